@@ -25,6 +25,8 @@ import type {
 import {
   startSyncCoordinator,
 } from "./api/sync";
+import { startChangeBridge } from "./api/windows";
+import { startCloseGuard } from "./api/closeGuard";
 import AppNavigation from "./components/AppNavigation";
 import AppRoutes from "./components/AppRoutes";
 import CommandPaletteManager from "./components/CommandPaletteManager";
@@ -34,13 +36,17 @@ import RightSidebar from "./components/RightSidebar";
 import StatusBar from "./components/StatusBar";
 import ToastViewport from "./components/ToastViewport";
 import UpdateBanner from "./components/UpdateBanner";
+import { startNativeMenu } from "./commands/nativeMenu";
 import type {
   RightSidebarMode,
 } from "./types/rightSidebar";
 import {
   normalizeRightSidebarMode,
 } from "./types/rightSidebar";
+import { useDeepLinks } from "./hooks/useDeepLinks";
+import { useReminderNotifications } from "./hooks/useReminderNotifications";
 import { useSessionRecovery } from "./hooks/useSessionRecovery";
+import { useTrayActions } from "./hooks/useTrayActions";
 
 import "./styles/app-shell.css";
 
@@ -102,6 +108,13 @@ const App: React.FC = () => {
   }, []);
 
   useEffect(() => startSyncCoordinator(), []);
+  useEffect(() => startChangeBridge(), []);
+  useEffect(() => startCloseGuard(), []);
+  useEffect(() => startNativeMenu(), []);
+
+  useReminderNotifications();
+  useTrayActions();
+  useDeepLinks();
 
   const handleSidebarModeChange = useCallback(
     async (mode: RightSidebarMode): Promise<void> => {
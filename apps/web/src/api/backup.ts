@@ -0,0 +1,82 @@
+// apps/web/src/api/backup.ts
+
+/*
+ * Whole-workspace backup and restore. Archives are written and read by the
+ * desktop crate; settings travel with them because they live in this
+ * webview's localStorage rather than the native store.
+ */
+
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+import { getSettingsSnapshot, replaceSettings } from "./settings";
+import type { AppSettings } from "./settings";
+
+export type WorkspaceCounts = {
+  tasks: number;
+  documents: number;
+  events: number;
+  pendingOps: number;
+  recoveryDrafts: number;
+};
+
+type ImportedWorkspace = {
+  counts: WorkspaceCounts;
+  settings: AppSettings | null;
+};
+
+const BACKUP_FILTERS = [
+  { name: "Pioneer backup", extensions: ["pioneer-backup", "zip"] },
+];
+
+export function isWorkspaceBackupSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+function defaultBackupFileName(): string {
+  const date = new Date().toISOString().slice(0, 10);
+  return `pioneer-workspace-${date}.pioneer-backup`;
+}
+
+/*
+ * Asks for a destination and writes the archive there. Resolves with null
+ * when the dialog is cancelled.
+ */
+export async function exportWorkspaceToFile(): Promise<WorkspaceCounts | null> {
+  const { save } = await import("@tauri-apps/api/dialog");
+  const path = await save({
+    defaultPath: defaultBackupFileName(),
+    filters: BACKUP_FILTERS,
+  });
+
+  if (!path) return null;
+
+  return invokeDesktop<WorkspaceCounts>("export_workspace", {
+    path,
+    settings: getSettingsSnapshot(),
+  });
+}
+
+/*
+ * Asks for an archive and replaces the workspace with it. The archive is
+ * fully validated before anything changes. Resolves with null when the
+ * dialog is cancelled.
+ */
+export async function importWorkspaceFromFile(): Promise<WorkspaceCounts | null> {
+  const { open } = await import("@tauri-apps/api/dialog");
+  const path = await open({
+    multiple: false,
+    directory: false,
+    filters: BACKUP_FILTERS,
+  });
+
+  if (!path || Array.isArray(path)) return null;
+
+  const imported = await invokeDesktop<ImportedWorkspace>("import_workspace", {
+    path,
+  });
+
+  if (imported.settings) {
+    await replaceSettings(imported.settings);
+  }
+
+  return imported.counts;
+}
@@ -0,0 +1,74 @@
+// apps/web/src/api/calendarFiles.ts
+
+/*
+ * iCalendar import and export. The desktop crate parses and writes the
+ * .ics files; imported events are created through createEvent so they
+ * queue and sync like events added by hand.
+ */
+
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+import { createEvent, fetchEvents } from "./events";
+import type { CalendarEvent, EventPayload } from "./events";
+
+export interface CalendarFileRange {
+  from: Date;
+  to: Date;
+}
+
+const ICS_FILTERS = [{ name: "iCalendar", extensions: ["ics"] }];
+
+export function isCalendarFileSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+/*
+ * Asks for an .ics file and creates its events. Recurring series are
+ * expanded within `range`. Resolves with null when the dialog is
+ * cancelled.
+ */
+export async function importEventsFromIcs(
+  range: CalendarFileRange
+): Promise<CalendarEvent[] | null> {
+  const { open } = await import("@tauri-apps/api/dialog");
+  const path = await open({ multiple: false, filters: ICS_FILTERS });
+
+  if (!path || Array.isArray(path)) return null;
+
+  const payloads = await invokeDesktop<EventPayload[]>("import_ics", {
+    path,
+    from: range.from.toISOString(),
+    to: range.to.toISOString(),
+  });
+
+  const created: CalendarEvent[] = [];
+
+  for (const payload of payloads) {
+    created.push(await createEvent(payload));
+  }
+
+  return created;
+}
+
+/*
+ * Asks for a destination and writes the events in `range` there.
+ * Resolves with the number of events written, or null when the dialog is
+ * cancelled.
+ */
+export async function exportEventsToIcs(
+  range: CalendarFileRange,
+  fileName: string
+): Promise<number | null> {
+  const from = range.from.toISOString();
+  const to = range.to.toISOString();
+  const events = await fetchEvents({ from, to });
+
+  const { save } = await import("@tauri-apps/api/dialog");
+  const path = await save({
+    defaultPath: `${fileName}.ics`,
+    filters: ICS_FILTERS,
+  });
+
+  if (!path) return null;
+
+  return invokeDesktop<number>("export_ics", { events, from, to, path });
+}
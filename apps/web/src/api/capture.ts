@@ -0,0 +1,58 @@
+// apps/web/src/api/capture.ts
+
+/*
+ * Quick capture. The desktop crate registers OS-wide shortcuts that open a
+ * small always-on-top capture window; the browser build has neither.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+
+export type CaptureKind = "task" | "note" | "event";
+
+/* Tauri accelerators such as "CmdOrCtrl+Shift+Space"; null is unset. */
+export type CaptureShortcuts = Record<CaptureKind, string | null>;
+
+export const CAPTURE_KIND_EVENT = "pioneer:capture-kind";
+
+export function isCaptureSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export function isCaptureKind(value: unknown): value is CaptureKind {
+  return value === "task" || value === "note" || value === "event";
+}
+
+export function getCaptureShortcuts(): Promise<CaptureShortcuts> {
+  return invokeDesktop<CaptureShortcuts>("get_capture_shortcuts");
+}
+
+/* Rejects with a readable message when a shortcut cannot be registered. */
+export function setCaptureShortcuts(
+  shortcuts: CaptureShortcuts
+): Promise<CaptureShortcuts> {
+  return invokeDesktop<CaptureShortcuts>("set_capture_shortcuts", {
+    shortcuts,
+  });
+}
+
+/*
+ * Hides the capture window. Pass what was saved so the other windows
+ * refresh and sync picks it up.
+ */
+export function finishCapture(
+  saved: CaptureKind | null,
+  showMain = false
+): Promise<void> {
+  return invokeDesktop<void>("finish_capture", { saved, showMain });
+}
+
+export function subscribeToCaptureKind(
+  listener: (kind: CaptureKind) => void
+): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+  return listenDesktop<CaptureKind>(CAPTURE_KIND_EVENT, listener);
+}
@@ -0,0 +1,79 @@
+// apps/web/src/api/closeGuard.ts
+
+/*
+ * Unsaved drafts, as seen by the desktop close guard. Before quitting, or
+ * closing a document window, the crate asks each window which drafts it
+ * holds and may ask it to save them; pages register theirs here.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+import { developerLogger } from "../developer/logger";
+
+const CLOSE_CHECK_EVENT = "pioneer:close-check";
+const SAVE_DRAFTS_EVENT = "pioneer:save-drafts";
+
+export interface UnsavedDraft {
+  /* Shown in the quit dialog, e.g. the document title. */
+  label: string;
+  /* Resolves to true once the draft is saved. */
+  save: () => Promise<boolean>;
+}
+
+interface DraftRequest {
+  requestId: number;
+}
+
+const drafts = new Map<string, UnsavedDraft>();
+
+export function setUnsavedDraft(
+  key: string,
+  draft: UnsavedDraft | null
+): void {
+  if (draft) {
+    drafts.set(key, draft);
+  } else {
+    drafts.delete(key);
+  }
+}
+
+function report(requestId: number): Promise<void> {
+  return invokeDesktop<void>("report_unsaved_drafts", {
+    requestId,
+    drafts: [...drafts.values()].map((draft) => draft.label),
+  });
+}
+
+async function saveDrafts(): Promise<void> {
+  await Promise.allSettled(
+    [...drafts.values()].map((draft) => draft.save())
+  );
+}
+
+export function startCloseGuard(): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+
+  const reportFailure = (error: unknown) => {
+    developerLogger.error(
+      "closeGuard",
+      "Unable to report unsaved drafts",
+      error
+    );
+  };
+
+  const unsubscribers = [
+    listenDesktop<DraftRequest>(CLOSE_CHECK_EVENT, ({ requestId }) => {
+      void report(requestId).catch(reportFailure);
+    }),
+    listenDesktop<DraftRequest>(SAVE_DRAFTS_EVENT, ({ requestId }) => {
+      void saveDrafts()
+        .then(() => report(requestId))
+        .catch(reportFailure);
+    }),
+  ];
+
+  return () => unsubscribers.forEach((unsubscribe) => unsubscribe());
+}
@@ -0,0 +1,33 @@
+// apps/web/src/api/deepLinks.ts
+
+/*
+ * `pioneer://` links. The desktop crate parses and validates them and hands
+ * over either a route for AppRoutes or the reason the link was refused.
+ * A link that launched the app is fetched once; later ones arrive as
+ * events.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+
+export type DeepLink =
+  | { kind: "open"; route: string }
+  | { kind: "invalid"; message: string };
+
+export const DEEP_LINK_EVENT = "pioneer:deep-link";
+
+/* The link the app was launched with, if any. Only returned once. */
+export async function takePendingDeepLink(): Promise<DeepLink | null> {
+  if (!isDesktopRuntime()) return null;
+  return invokeDesktop<DeepLink | null>("take_pending_deep_link");
+}
+
+export function subscribeToDeepLinks(
+  listener: (link: DeepLink) => void
+): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+  return listenDesktop<DeepLink>(DEEP_LINK_EVENT, listener);
+}
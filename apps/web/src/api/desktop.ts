@@ -0,0 +1,50 @@
+// apps/web/src/api/desktop.ts
+
+/*
+ * Thin bridge to the Rust commands registered in desktop/src-tauri. The
+ * Tauri API is imported lazily so Pages builds never load it.
+ */
+
+export function isDesktopRuntime(): boolean {
+  return (
+    typeof window !== "undefined" &&
+    "__TAURI_IPC__" in window
+  );
+}
+
+export async function invokeDesktop<T>(
+  command: string,
+  args?: Record<string, unknown>
+): Promise<T> {
+  const { invoke } = await import("@tauri-apps/api/tauri");
+  return invoke<T>(command, args);
+}
+
+/*
+ * Subscribes to an event emitted from Rust. The returned cleanup can be
+ * called before the subscription has finished registering.
+ */
+export function listenDesktop<T>(
+  event: string,
+  handler: (payload: T) => void
+): () => void {
+  let disposed = false;
+  let unlisten: (() => void) | null = null;
+
+  void import("@tauri-apps/api/event").then(async ({ listen }) => {
+    const stop = await listen<T>(event, (message) => {
+      handler(message.payload);
+    });
+
+    if (disposed) {
+      stop();
+    } else {
+      unlisten = stop;
+    }
+  });
+
+  return () => {
+    disposed = true;
+    unlisten?.();
+  };
+}
@@ -0,0 +1,53 @@
+// apps/web/src/api/documentExport.ts
+
+/*
+ * Native document export. The desktop crate converts the editor HTML and
+ * writes the file; the browser build falls back to the TXT and HTML
+ * downloads in utils/documentText.
+ */
+
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+import type { Document } from "./documents";
+import { sanitizeFilename } from "../utils/documentText";
+
+export type DocumentExportFormat = "markdown" | "html" | "text" | "pdf";
+
+const EXPORT_FILTERS: Record<
+  DocumentExportFormat,
+  { name: string; extensions: string[] }
+> = {
+  markdown: { name: "Markdown", extensions: ["md"] },
+  html: { name: "HTML", extensions: ["html"] },
+  text: { name: "Plain text", extensions: ["txt"] },
+  pdf: { name: "PDF", extensions: ["pdf"] },
+};
+
+export function isNativeDocumentExportSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+/*
+ * Asks for a destination and writes the document there. Resolves with
+ * false when the dialog is cancelled.
+ */
+export async function exportDocumentToFile(
+  document: Pick<Document, "title" | "content">,
+  format: DocumentExportFormat
+): Promise<boolean> {
+  const filter = EXPORT_FILTERS[format];
+  const { save } = await import("@tauri-apps/api/dialog");
+  const path = await save({
+    defaultPath: `${sanitizeFilename(document.title)}.${filter.extensions[0]}`,
+    filters: [filter],
+  });
+
+  if (!path) return false;
+
+  await invokeDesktop("export_document", {
+    document: { title: document.title, content: document.content },
+    format,
+    path,
+  });
+
+  return true;
+}
@@ -0,0 +1,49 @@
+// apps/web/src/api/documentImport.ts
+
+/*
+ * Native document import. The desktop crate converts Markdown, text and
+ * HTML files to editor HTML and queues them as offline creates, the same
+ * way createDocument does without a connection.
+ */
+
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+import type { Document } from "./documents";
+
+export interface DocumentImportFailure {
+  path: string;
+  message: string;
+}
+
+export interface DocumentImportResult {
+  documents: Document[];
+  failures: DocumentImportFailure[];
+}
+
+export function isDocumentImportSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+/*
+ * Asks for files and imports them. Resolves with null when the dialog is
+ * cancelled.
+ */
+export async function importDocumentsFromFiles(): Promise<DocumentImportResult | null> {
+  const { open } = await import("@tauri-apps/api/dialog");
+  const selected = await open({
+    multiple: true,
+    filters: [
+      {
+        name: "Documents",
+        extensions: ["md", "markdown", "txt", "html", "htm"],
+      },
+    ],
+  });
+
+  if (!selected) return null;
+
+  const paths = Array.isArray(selected) ? selected : [selected];
+
+  if (paths.length === 0) return null;
+
+  return invokeDesktop<DocumentImportResult>("import_documents", { paths });
+}
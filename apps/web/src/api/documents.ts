@@ -1,4 +1,5 @@
 // apps/web/src/api/documents.ts
+import { isDesktopRuntime } from "./desktop";
 import { http } from "./http";
 import { hasCloudSession } from "./session";
 import {
@@ -8,12 +9,16 @@ import {
   notifySyncStateChanged,
 } from "./syncSupport";
 import {
+  enqueueStoredOp,
+  mergeStoredRecord,
   migrateLegacyLocalStorage,
   readStoredDocumentQueue,
   readStoredDocuments,
+  removeStoredRecord,
   writeStoredDocumentQueue,
   writeStoredDocuments,
 } from "./storage";
+import { broadcastChange } from "./windows";
 
 export const DOCUMENTS_CHANGED_EVENT = "pioneer:documents-changed";
 import {
@@ -134,7 +139,11 @@ async function ensureDocumentStorageReady(): Promise<void> {
       await migrateLegacyLocalStorage();
 
       await refreshPendingDocumentSyncCount();
-    })();
+    })().catch((error) => {
+      // Reads fail while the desktop workspace is locked; retry next time.
+      storageInitialization = null;
+      throw error;
+    });
   }
 
   await storageInitialization;
@@ -153,6 +162,14 @@ async function readDocumentsCache(): Promise<Document[]> {
   );
 }
 
+function notifyDocumentsChanged(): void {
+  if (typeof window !== "undefined") {
+    window.dispatchEvent(new Event(DOCUMENTS_CHANGED_EVENT));
+  }
+
+  broadcastChange("documents");
+}
+
 async function writeDocumentsCache(
   documents: Document[]
 ): Promise<void> {
@@ -171,6 +188,14 @@ async function mergeDocumentIntoCache(
   document: Document
 ): Promise<void> {
   const normalized = normalizeDocument(document);
+
+  if (isDesktopRuntime()) {
+    await ensureDocumentStorageReady();
+    await mergeStoredRecord("documents", normalized);
+    notifyDocumentsChanged();
+    return;
+  }
+
   const documents = await readDocumentsCache();
 
   const index = documents.findIndex(
@@ -187,16 +212,25 @@ async function mergeDocumentIntoCache(
   }
 
   await writeDocumentsCache(documents);
+  broadcastChange("documents");
 }
 
 async function removeDocumentFromCache(
   id: string
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    await ensureDocumentStorageReady();
+    await removeStoredRecord("documents", id);
+    notifyDocumentsChanged();
+    return;
+  }
+
   const documents = await readDocumentsCache();
 
   await writeDocumentsCache(
     documents.filter((document) => document.id !== id)
   );
+  broadcastChange("documents");
 }
 
 
@@ -273,6 +307,27 @@ async function writeQueue(
   notifySyncStateChanged();
 }
 
+/*
+ * The desktop store coalesces the op with its queue itself, since the
+ * native sync worker edits that queue too. Returns false in the browser.
+ */
+async function enqueueNatively(
+  operation: DocumentOp
+): Promise<boolean> {
+  if (!isDesktopRuntime()) {
+    return false;
+  }
+
+  await ensureDocumentStorageReady();
+
+  pendingDocumentSyncCount = await enqueueStoredOp(
+    "documents",
+    operation
+  );
+  notifySyncStateChanged();
+  return true;
+}
+
 export function getPendingDocumentSyncCount(): number {
   return pendingDocumentSyncCount;
 }
@@ -290,6 +345,10 @@ export async function refreshPendingDocumentSyncCount(): Promise<number> {
 async function enqueueCreate(
   operation: CreateDocumentOp
 ): Promise<void> {
+  if (await enqueueNatively(operation)) {
+    return;
+  }
+
   const queue = await readQueue();
 
   queue.push(operation);
@@ -301,6 +360,18 @@ async function enqueueUpdate(
   patch: DocumentPatch
 ): Promise<void> {
   const normalizedPatch = normalizePatch(patch);
+
+  if (
+    await enqueueNatively({
+      kind: "update",
+      id,
+      patch: normalizedPatch,
+      timestamp: Date.now(),
+    })
+  ) {
+    return;
+  }
+
   const queue = await readQueue();
 
   const createIndex = queue.findIndex(
@@ -369,6 +440,16 @@ async function enqueueUpdate(
 async function enqueueDelete(
   id: string
 ): Promise<void> {
+  if (
+    await enqueueNatively({
+      kind: "delete",
+      id,
+      timestamp: Date.now(),
+    })
+  ) {
+    return;
+  }
+
   const queue = (await readQueue()).filter(
     (operation) => {
       if (
@@ -1,4 +1,5 @@
 // apps/web/src/api/events.ts
+import { isDesktopRuntime } from "./desktop";
 import { http } from "./http";
 import { hasCloudSession } from "./session";
 import {
@@ -8,12 +9,16 @@ import {
   notifySyncStateChanged,
 } from "./syncSupport";
 import {
+  enqueueStoredOp,
+  mergeStoredRecord,
   migrateLegacyLocalStorage,
   readStoredEventQueue,
   readStoredEvents,
+  removeStoredRecord,
   writeStoredEventQueue,
   writeStoredEvents,
 } from "./storage";
+import { broadcastChange } from "./windows";
 
 export type EventKind = string;
 export const EVENTS_CHANGED_EVENT =
@@ -111,6 +116,8 @@ function notifyEventsChanged(): void {
   if (hasBrowserWindow()) {
     window.dispatchEvent(new Event(EVENTS_CHANGED_EVENT));
   }
+
+  broadcastChange("events");
 }
 
 function normalizeEvent(raw: any): CalendarEvent {
@@ -178,7 +185,11 @@ async function ensureEventStorageReady(): Promise<void> {
     storageInitialization = (async () => {
       await migrateLegacyLocalStorage();
       await refreshPendingEventSyncCount();
-    })();
+    })().catch((error) => {
+      // Reads fail while the desktop workspace is locked; retry next time.
+      storageInitialization = null;
+      throw error;
+    });
   }
 
   await storageInitialization;
@@ -200,6 +211,13 @@ async function writeEventsCache(events: CalendarEvent[]): Promise<void> {
 
 async function mergeEventIntoCache(event: CalendarEvent): Promise<void> {
   const normalized = normalizeEvent(event);
+
+  if (isDesktopRuntime()) {
+    await ensureEventStorageReady();
+    await mergeStoredRecord("events", normalized);
+    return;
+  }
+
   const events = await readEventsCache();
 
   const index = events.findIndex((entry) => entry.id === normalized.id);
@@ -217,6 +235,12 @@ async function mergeEventIntoCache(event: CalendarEvent): Promise<void> {
 }
 
 async function removeEventFromCache(id: string): Promise<void> {
+  if (isDesktopRuntime()) {
+    await ensureEventStorageReady();
+    await removeStoredRecord("events", id);
+    return;
+  }
+
   const events = await readEventsCache();
 
   await writeEventsCache(events.filter((event) => event.id !== id));
@@ -247,6 +271,22 @@ async function writeQueue(queue: EventOp[]): Promise<void> {
   notifySyncStateChanged();
 }
 
+/*
+ * The desktop store coalesces the op with its queue itself, since the
+ * native sync worker edits that queue too. Returns false in the browser.
+ */
+async function enqueueNatively(operation: EventOp): Promise<boolean> {
+  if (!isDesktopRuntime()) {
+    return false;
+  }
+
+  await ensureEventStorageReady();
+
+  pendingEventSyncCount = await enqueueStoredOp("events", operation);
+  notifySyncStateChanged();
+  return true;
+}
+
 export function getPendingEventSyncCount(): number {
   return pendingEventSyncCount;
 }
@@ -260,9 +300,7 @@ export async function refreshPendingEventSyncCount(): Promise<number> {
 }
 
 async function enqueueCreate(event: CalendarEvent): Promise<void> {
-  const queue = await readQueue();
-
-  queue.push({
+  const operation: CreateEventOp = {
     kind: "create",
     tempId: event.id,
     payload: {
@@ -275,12 +313,30 @@ async function enqueueCreate(event: CalendarEvent): Promise<void> {
       urgency: event.urgency,
     },
     timestamp: Date.now(),
-  });
+  };
+
+  if (await enqueueNatively(operation)) {
+    return;
+  }
+
+  const queue = await readQueue();
+  queue.push(operation);
 
   await writeQueue(queue);
 }
 
 async function enqueueUpdate(id: string, patch: EventPatch): Promise<void> {
+  if (
+    await enqueueNatively({
+      kind: "update",
+      id,
+      patch,
+      timestamp: Date.now(),
+    })
+  ) {
+    return;
+  }
+
   const queue = await readQueue();
 
   const createIndex = queue.findIndex(
@@ -339,6 +395,10 @@ async function enqueueUpdate(id: string, patch: EventPatch): Promise<void> {
 }
 
 async function enqueueDelete(id: string): Promise<void> {
+  if (await enqueueNatively({ kind: "delete", id, timestamp: Date.now() })) {
+    return;
+  }
+
   const queue = (await readQueue()).filter((operation) => {
     if (operation.kind === "create" && operation.tempId === id) {
       return false;
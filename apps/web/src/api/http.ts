@@ -7,6 +7,7 @@ import axios, {
 } from "axios";
 
 import {
+  getCloudToken,
   hasCloudSession,
   invalidateCloudSession,
 } from "./session";
@@ -14,22 +15,29 @@ import {
   developerLogger,
 } from "../developer/logger";
 
-// For v1, the backend API base URL remains fixed for desktop and Pages builds.
-const API_BASE_URL = "https://pioneer-work-suite.onrender.com";
+// Pioneer Cloud. The Pages build always uses it; on desktop the active
+// server profile replaces it (see api/servers.ts).
+export const DEFAULT_API_BASE_URL = "https://pioneer-work-suite.onrender.com";
 
 export const http = axios.create({
-  baseURL: API_BASE_URL,
+  baseURL: DEFAULT_API_BASE_URL,
 });
 
+export function configureApiServer(
+  baseURL: string,
+  timeoutMs: number
+): void {
+  http.defaults.baseURL = baseURL;
+  http.defaults.timeout = timeoutMs;
+}
+
 // Attach Authorization header if a cloud token is present.
 http.interceptors.request.use((config: InternalAxiosRequestConfig) => {
-  if (typeof window !== "undefined") {
-    const token = window.localStorage.getItem("token");
+  const token = getCloudToken();
 
-    if (token) {
-      config.headers = config.headers ?? {};
-      config.headers.Authorization = `Bearer ${token}`;
-    }
+  if (token) {
+    config.headers = config.headers ?? {};
+    config.headers.Authorization = `Bearer ${token}`;
   }
 
   return config;
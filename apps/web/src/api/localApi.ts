@@ -0,0 +1,41 @@
+// apps/web/src/api/localApi.ts
+
+/*
+ * The API server built into the desktop app. When it is on, the crate adds
+ * a "This computer" server profile pointing at it; switching to that
+ * profile (api/servers.ts) runs the app with no cloud at all.
+ */
+
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+
+export const LOCAL_SERVER_ID = "this-computer";
+
+export type LocalApiSettings = {
+  enabled: boolean;
+  port: number;
+};
+
+export type LocalApiStatus = LocalApiSettings & {
+  running: boolean;
+  baseUrl: string;
+  /* Why an enabled server is not running, e.g. the port is taken. */
+  error: string | null;
+};
+
+export function isLocalApiSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export function getLocalApiStatus(): Promise<LocalApiStatus> {
+  return invokeDesktop<LocalApiStatus>("get_local_api_status");
+}
+
+/*
+ * Starts, moves or stops the server. Turning it off is refused while
+ * "This computer" is the active server.
+ */
+export function setLocalApiSettings(
+  settings: LocalApiSettings
+): Promise<LocalApiStatus> {
+  return invokeDesktop<LocalApiStatus>("set_local_api_settings", { settings });
+}
@@ -0,0 +1,73 @@
+// apps/web/src/api/network.ts
+
+/*
+ * Proxy and root certificate settings for the desktop crate's own
+ * requests: sync and server checks. After a restart the updater uses the
+ * proxy address too, without its sign-in or the imported certificates. The
+ * webview's requests follow the operating system's settings instead.
+ */
+
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+
+export type ProxyMode = "system" | "none" | "manual";
+
+export type ProxySettings = {
+  mode: ProxyMode;
+  /* http://, https://, socks5:// or socks5h://host:port. */
+  url: string;
+  username: string;
+  /* Comma-separated hosts, domains and CIDR ranges that skip the proxy. */
+  noProxy: string;
+};
+
+export type RootCertificate = {
+  id: string;
+  name: string;
+  fingerprint: string;
+  count: number;
+  importedAt: string;
+};
+
+export type NetworkSettings = {
+  proxy: ProxySettings;
+  hasProxyPassword: boolean;
+  certificates: RootCertificate[];
+};
+
+const PEM_FILTERS = [{ name: "PEM certificate", extensions: ["pem", "crt", "cer"] }];
+
+export function isNetworkSettingsSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export function getNetworkSettings(): Promise<NetworkSettings> {
+  return invokeDesktop<NetworkSettings>("get_network_settings");
+}
+
+/*
+ * Leave `password` undefined to keep the saved one; an empty string
+ * removes it.
+ */
+export function setProxySettings(
+  proxy: ProxySettings,
+  password?: string
+): Promise<NetworkSettings> {
+  return invokeDesktop<NetworkSettings>("set_proxy_settings", {
+    proxy,
+    password: password ?? null,
+  });
+}
+
+/* Asks for a PEM file to trust. Resolves with null when cancelled. */
+export async function importRootCertificate(): Promise<NetworkSettings | null> {
+  const { open } = await import("@tauri-apps/api/dialog");
+  const path = await open({ multiple: false, filters: PEM_FILTERS });
+
+  if (!path || Array.isArray(path)) return null;
+
+  return invokeDesktop<NetworkSettings>("import_root_certificate", { path });
+}
+
+export function removeRootCertificate(id: string): Promise<NetworkSettings> {
+  return invokeDesktop<NetworkSettings>("remove_root_certificate", { id });
+}
@@ -0,0 +1,75 @@
+// apps/web/src/api/reminders.ts
+
+/*
+ * Reminders for due tasks and upcoming events. The desktop crate plans and
+ * fires them, raising a native notification when the window is not
+ * focused; the browser build has none.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+
+export type ReminderKind = "task" | "event";
+
+export type ReminderState = "pending" | "snoozed" | "fired" | "dismissed";
+
+export type ReminderSettings = {
+  enabled: boolean;
+  /* "HH:MM" local time used for all-day events and tasks with a due date. */
+  allDayTime: string;
+  taskLeadMinutes: number[];
+  eventLeadMinutes: number[];
+};
+
+export type ReminderNotice = {
+  key: string;
+  kind: ReminderKind;
+  recordId: string;
+  title: string;
+  body: string;
+  dueAt: string;
+  fireAt: string;
+  state: ReminderState;
+  snoozedUntil: string | null;
+};
+
+export const REMINDER_EVENT = "pioneer:reminder";
+
+export function isReminderSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+/* Pending, snoozed and fired reminders, soonest first. */
+export function listReminders(): Promise<ReminderNotice[]> {
+  return invokeDesktop<ReminderNotice[]>("list_reminders");
+}
+
+export function snoozeReminder(key: string, minutes: number): Promise<void> {
+  return invokeDesktop<void>("snooze_reminder", { key, minutes });
+}
+
+export function dismissReminder(key: string): Promise<void> {
+  return invokeDesktop<void>("dismiss_reminder", { key });
+}
+
+export function getReminderSettings(): Promise<ReminderSettings> {
+  return invokeDesktop<ReminderSettings>("get_reminder_settings");
+}
+
+export function setReminderSettings(
+  settings: ReminderSettings
+): Promise<ReminderSettings> {
+  return invokeDesktop<ReminderSettings>("set_reminder_settings", {
+    settings,
+  });
+}
+
+export function subscribeToReminders(
+  listener: (notice: ReminderNotice) => void
+): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+  return listenDesktop<ReminderNotice>(REMINDER_EVENT, listener);
+}
@@ -0,0 +1,95 @@
+// apps/web/src/api/servers.ts
+
+/*
+ * API server profiles. The desktop crate keeps named servers (Pioneer
+ * Cloud and self-hosted deployments of apps/api) and which one is active;
+ * this module points `http` at it. The browser build always talks to
+ * Pioneer Cloud.
+ */
+
+import { configureApiServer } from "./http";
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+import { developerLogger } from "../developer/logger";
+
+const SERVER_CHANGED_EVENT = "pioneer:server-changed";
+
+export const DEFAULT_SERVER_ID = "pioneer-cloud";
+
+export type ServerProfile = {
+  id: string;
+  name: string;
+  baseUrl: string;
+  /* Full path to a PEM file with extra root certificates. */
+  caCertificate: string | null;
+  timeoutSeconds: number;
+};
+
+export type ServerSettings = {
+  active: string;
+  profiles: ServerProfile[];
+};
+
+export type ServerCheck = {
+  reachable: boolean;
+  status: number | null;
+  latencyMs: number;
+  message: string;
+};
+
+function applyServer(profile: ServerProfile): void {
+  configureApiServer(profile.baseUrl, profile.timeoutSeconds * 1000);
+}
+
+export function isServerProfileSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export function listServers(): Promise<ServerSettings> {
+  return invokeDesktop<ServerSettings>("list_servers");
+}
+
+export function saveServer(profile: ServerProfile): Promise<ServerSettings> {
+  return invokeDesktop<ServerSettings>("save_server", { profile });
+}
+
+export function deleteServer(id: string): Promise<ServerSettings> {
+  return invokeDesktop<ServerSettings>("delete_server", { id });
+}
+
+export function checkServer(profile: ServerProfile): Promise<ServerCheck> {
+  return invokeDesktop<ServerCheck>("check_server", { profile });
+}
+
+export function getActiveServer(): Promise<ServerProfile> {
+  return invokeDesktop<ServerProfile>("get_active_server");
+}
+
+/*
+ * Rejects with the health check's message when the server cannot be
+ * reached. The crate drops the sync session, since the token belonged to
+ * the previous server; callers sign out of it here as well.
+ */
+export function setActiveServer(id: string): Promise<ServerProfile> {
+  return invokeDesktop<ServerProfile>("set_active_server", { id });
+}
+
+/* Points `http` at the active server before the first render. */
+export async function hydrateApiServer(): Promise<void> {
+  if (!isDesktopRuntime()) return;
+
+  try {
+    applyServer(await getActiveServer());
+  } catch (error) {
+    developerLogger.error(
+      "servers",
+      "Unable to read the active server; using Pioneer Cloud",
+      error
+    );
+  }
+
+  listenDesktop<ServerProfile>(SERVER_CHANGED_EVENT, applyServer);
+}
@@ -1,5 +1,7 @@
 // apps/web/src/api/session.ts
 
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+
 type CloudUser = {
   id: string;
   email: string;
@@ -16,6 +18,18 @@ const USER_NAME_KEY = "userName";
 const USER_ROLE_KEY = "userRole";
 const CLOUD_RECONNECT_REQUIRED_KEY = "pioneer.cloud.reconnectRequired.v1";
 
+/*
+ * On desktop the token and user live in the native credential vault rather
+ * than localStorage. This cache is filled by hydrateDesktopSession() before
+ * the first render so the synchronous getters below keep working.
+ */
+type DesktopCloudSession = {
+  token: string;
+  user: CloudUser | null;
+};
+
+let desktopSession: DesktopCloudSession | null = null;
+
 export const SESSION_CHANGED_EVENT = "pioneer:session-changed";
 export const CLOUD_AUTH_REQUIRED_EVENT = "pioneer:cloud-auth-required";
 
@@ -31,6 +45,14 @@ function cleanName(name: string | null | undefined): string {
 function removeCloudCredentials(): void {
   if (!hasWindow()) return;
 
+  if (isDesktopRuntime()) {
+    desktopSession = null;
+
+    void invokeDesktop("vault_clear").catch((error) => {
+      console.error("Failed to clear the credential vault", error);
+    });
+  }
+
   window.localStorage.removeItem(TOKEN_KEY);
   window.localStorage.removeItem(USER_EMAIL_KEY);
   window.localStorage.removeItem(USER_NAME_KEY);
@@ -43,9 +65,9 @@ function notifySessionChanged(): void {
 }
 
 /*
- * This is intentionally name-only local access.
- * It is not secure authentication and will later be replaced
- * by a PIN, OS-account binding, encryption, or similar.
+ * Local access itself is name-only. On desktop the workspace can
+ * additionally be protected by a PIN (see api/workspaceLock.ts), which
+ * AppRoutes enforces with a lock screen.
  */
 function ensureLocalWorkspace(): void {
   if (!hasWindow()) return;
@@ -82,7 +104,104 @@ export function getLocalWorkspaceName(): string {
 }
 
 export function hasCloudSession(): boolean {
-  return hasWindow() && Boolean(window.localStorage.getItem(TOKEN_KEY));
+  return Boolean(getCloudToken());
+}
+
+export function getCloudToken(): string | null {
+  if (!hasWindow()) return null;
+
+  if (isDesktopRuntime()) {
+    return desktopSession?.token ?? null;
+  }
+
+  return window.localStorage.getItem(TOKEN_KEY);
+}
+
+function getCloudUserName(): string | null {
+  if (isDesktopRuntime()) {
+    return desktopSession?.user?.name ?? null;
+  }
+
+  return window.localStorage.getItem(USER_NAME_KEY);
+}
+
+/*
+ * Loads the cloud session from the credential vault. Tokens left in
+ * localStorage by earlier desktop builds are moved into the vault once and
+ * then removed from the webview profile.
+ */
+export async function hydrateDesktopSession(): Promise<void> {
+  if (!hasWindow() || !isDesktopRuntime()) return;
+
+  const legacyToken = window.localStorage.getItem(TOKEN_KEY);
+
+  try {
+    if (legacyToken) {
+      const user: CloudUser = {
+        id: "",
+        email: window.localStorage.getItem(USER_EMAIL_KEY) ?? "",
+        name: window.localStorage.getItem(USER_NAME_KEY) ?? "",
+        role: window.localStorage.getItem(USER_ROLE_KEY) ?? "",
+      };
+
+      await invokeDesktop("vault_store_token", { token: legacyToken, user });
+      desktopSession = { token: legacyToken, user };
+
+      window.localStorage.removeItem(TOKEN_KEY);
+      window.localStorage.removeItem(USER_EMAIL_KEY);
+      window.localStorage.removeItem(USER_NAME_KEY);
+      window.localStorage.removeItem(USER_ROLE_KEY);
+    } else {
+      desktopSession = await invokeDesktop<DesktopCloudSession | null>(
+        "vault_read_token"
+      );
+    }
+  } catch (error) {
+    // A locked or unreadable vault leaves the app in local-only mode.
+    console.error("Failed to read the credential vault", error);
+    desktopSession = null;
+  }
+
+  notifySessionChanged();
+}
+
+export type CredentialVaultStatus = {
+  exists: boolean;
+  keySource: "machine" | "passphrase" | null;
+  locked: boolean;
+};
+
+export function isCredentialVaultSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export function getCredentialVaultStatus(): Promise<CredentialVaultStatus> {
+  return invokeDesktop<CredentialVaultStatus>("vault_status");
+}
+
+/*
+ * Opens a vault protected by a passphrase for this run, which signs the app
+ * back in to the cloud.
+ */
+export async function unlockCredentialVault(passphrase: string): Promise<void> {
+  desktopSession = await invokeDesktop<DesktopCloudSession | null>(
+    "vault_unlock",
+    { passphrase }
+  );
+
+  notifySessionChanged();
+}
+
+/*
+ * Protects the saved cloud session with a passphrase, needed once per run,
+ * or binds it to this computer again when `passphrase` is null.
+ */
+export function setCredentialVaultPassphrase(
+  passphrase: string | null
+): Promise<CredentialVaultStatus> {
+  return invokeDesktop<CredentialVaultStatus>("vault_set_passphrase", {
+    passphrase,
+  });
 }
 
 export function isCloudReconnectRequired(): boolean {
@@ -96,21 +215,42 @@ export function getWorkspaceName(): string {
   if (!hasWindow()) return "Student";
 
   return hasCloudSession()
-    ? cleanName(window.localStorage.getItem(USER_NAME_KEY))
+    ? cleanName(getCloudUserName())
     : getLocalWorkspaceName();
 }
 
-export function connectCloudSession(user: CloudUser, token: string): void {
+/*
+ * Resolves once the session is saved. On desktop it rejects, leaving the
+ * app signed out, when the credential vault cannot take it, such as a
+ * vault protected by a passphrase that has not been unlocked this run.
+ */
+export async function connectCloudSession(
+  user: CloudUser,
+  token: string
+): Promise<void> {
   if (!hasWindow()) return;
 
+  if (isDesktopRuntime()) {
+    try {
+      await invokeDesktop("vault_store_token", { token, user });
+    } catch (error) {
+      console.error("Failed to save the cloud session to the vault", error);
+      throw new Error(
+        `Your session could not be saved on this computer. ${String(error)}`
+      );
+    }
+
+    desktopSession = { token, user };
+  } else {
+    window.localStorage.setItem(TOKEN_KEY, token);
+    window.localStorage.setItem(USER_EMAIL_KEY, user.email);
+    window.localStorage.setItem(USER_NAME_KEY, user.name);
+    window.localStorage.setItem(USER_ROLE_KEY, user.role);
+  }
+
   // Preserve a local identity for use after cloud disconnect.
   window.localStorage.setItem(LOCAL_WORKSPACE_ENABLED_KEY, "true");
   window.localStorage.setItem(LOCAL_PROFILE_NAME_KEY, cleanName(user.name));
-
-  window.localStorage.setItem(TOKEN_KEY, token);
-  window.localStorage.setItem(USER_EMAIL_KEY, user.email);
-  window.localStorage.setItem(USER_NAME_KEY, user.name);
-  window.localStorage.setItem(USER_ROLE_KEY, user.role);
   window.localStorage.removeItem(CLOUD_RECONNECT_REQUIRED_KEY);
 
   notifySessionChanged();
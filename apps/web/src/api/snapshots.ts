@@ -0,0 +1,61 @@
+// apps/web/src/api/snapshots.ts
+
+/*
+ * Scheduled snapshots of the local store. The desktop crate takes, prunes
+ * and restores them; the browser build has none.
+ */
+
+import type { WorkspaceCounts } from "./backup";
+import { invokeDesktop, isDesktopRuntime } from "./desktop";
+
+export type SnapshotFrequency = "off" | "hourly" | "daily";
+
+export type SnapshotSettings = {
+  frequency: SnapshotFrequency;
+  keepHourly: number;
+  keepDaily: number;
+};
+
+export type SnapshotInfo = {
+  id: string;
+  createdAt: string;
+  sizeBytes: number;
+};
+
+export function isSnapshotSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+/* Newest first. */
+export function listSnapshots(): Promise<SnapshotInfo[]> {
+  return invokeDesktop<SnapshotInfo[]>("list_snapshots");
+}
+
+export function createSnapshot(): Promise<SnapshotInfo> {
+  return invokeDesktop<SnapshotInfo>("create_snapshot");
+}
+
+/*
+ * Replaces the workspace with the snapshot. The current state is
+ * snapshotted first, so the restore itself can be undone. `pin` opens a
+ * snapshot taken under a PIN the workspace no longer has, e.g. after the
+ * local store was lost.
+ */
+export function restoreSnapshot(
+  id: string,
+  pin: string | null = null
+): Promise<WorkspaceCounts> {
+  return invokeDesktop<WorkspaceCounts>("restore_snapshot", { id, pin });
+}
+
+export function getSnapshotSettings(): Promise<SnapshotSettings> {
+  return invokeDesktop<SnapshotSettings>("get_snapshot_settings");
+}
+
+export function setSnapshotSettings(
+  settings: SnapshotSettings
+): Promise<SnapshotSettings> {
+  return invokeDesktop<SnapshotSettings>("set_snapshot_settings", {
+    settings,
+  });
+}
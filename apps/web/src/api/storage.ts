@@ -3,6 +3,10 @@
 import {
   developerLogger,
 } from "../developer/logger";
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+} from "./desktop";
 
 const DATABASE_NAME = "pioneer-work-suite";
 const DATABASE_VERSION = 2;
@@ -28,6 +32,9 @@ const LEGACY_DOCUMENTS_CACHE_KEY_V1 = "pioneer.documents.cache.v1";
 const LEGACY_DOCUMENTS_QUEUE_KEY_V1 = "pioneer.documents.queue.v1";
 
 const MIGRATION_FLAG_KEY = "localStorageMigrationComplete";
+const NATIVE_IMPORT_FLAG_KEY = "indexedDbImportComplete";
+
+const DOCUMENT_RECOVERY_PREFIX = "documentRecovery:";
 
 type StoreName =
   | typeof TASKS_STORE
@@ -262,7 +269,7 @@ function readLegacyJson<T>(key: string): T | null {
  * Calendar events were previously cloud-only, so there is no legacy event
  * cache to import.
  */
-export async function migrateLegacyLocalStorage(): Promise<void> {
+async function migrateLegacyLocalStorageIntoIndexedDb(): Promise<void> {
   if (!hasIndexedDb()) {
     return;
   }
@@ -326,19 +333,162 @@ export async function migrateLegacyLocalStorage(): Promise<void> {
   await setMetaValue(MIGRATION_FLAG_KEY, true);
 }
 
+/*
+ * One-time copy of the webview's IndexedDB data into the desktop SQLite
+ * store. IndexedDB is left untouched so an older build can still read it.
+ */
+async function importIndexedDbIntoNativeStorage(): Promise<void> {
+  const alreadyImported = await invokeDesktop<boolean | null>(
+    "read_storage_meta",
+    { key: NATIVE_IMPORT_FLAG_KEY }
+  );
+
+  if (alreadyImported) {
+    return;
+  }
+
+  if (hasIndexedDb()) {
+    await migrateLegacyLocalStorageIntoIndexedDb();
+
+    const [
+      tasks,
+      taskQueue,
+      documents,
+      documentQueue,
+      events,
+      eventQueue,
+      meta,
+    ] = await Promise.all([
+      getAll<{ id: string }>(TASKS_STORE),
+      getAll<{ id: number; value: unknown }>(TASK_QUEUE_STORE),
+      getAll<{ id: string }>(DOCUMENTS_STORE),
+      getAll<{ id: number; value: unknown }>(DOCUMENT_QUEUE_STORE),
+      getAll<{ id: string }>(EVENTS_STORE),
+      getAll<{ id: number; value: unknown }>(EVENT_QUEUE_STORE),
+      getAll<{ key: string; value: unknown }>(META_STORE),
+    ]);
+
+    const queueValues = (entries: { id: number; value: unknown }[]) =>
+      entries
+        .sort((a, b) => a.id - b.id)
+        .map((entry) => entry.value);
+
+    await invokeDesktop("import_stored_resource", {
+      resource: "tasks",
+      records: tasks,
+      queue: queueValues(taskQueue),
+    });
+    await invokeDesktop("import_stored_resource", {
+      resource: "documents",
+      records: documents,
+      queue: queueValues(documentQueue),
+    });
+    await invokeDesktop("import_stored_resource", {
+      resource: "events",
+      records: events,
+      queue: queueValues(eventQueue),
+    });
+
+    for (const entry of meta) {
+      if (entry.key.startsWith(DOCUMENT_RECOVERY_PREFIX)) {
+        await invokeDesktop("write_stored_document_recovery", {
+          documentId: entry.key.slice(DOCUMENT_RECOVERY_PREFIX.length),
+          value: entry.value,
+        });
+      }
+    }
+  }
+
+  await invokeDesktop("write_storage_meta", {
+    key: NATIVE_IMPORT_FLAG_KEY,
+    value: true,
+  });
+}
+
+let migration: Promise<void> | null = null;
+
+/*
+ * Prepares local storage before first use. The desktop build keeps its data
+ * in the native SQLite store; the browser build keeps using IndexedDB.
+ */
+export function migrateLegacyLocalStorage(): Promise<void> {
+  if (!migration) {
+    migration = (
+      isDesktopRuntime()
+        ? importIndexedDbIntoNativeStorage()
+        : migrateLegacyLocalStorageIntoIndexedDb()
+    ).catch((error) => {
+      migration = null;
+      developerLogger.error(
+        "storage",
+        "Unable to prepare local storage",
+        error
+      );
+      throw error;
+    });
+  }
+
+  return migration;
+}
+
+/*
+ * Single changes to the desktop store's caches and op queues. The native
+ * sync worker edits the same queues, so the desktop build never writes a
+ * whole queue back: each op is coalesced with the queue as it stands, in
+ * one transaction, and the new queue length comes back.
+ */
+
+export type StoredResource = "tasks" | "documents" | "events";
+
+const DESKTOP_QUEUE_WRITE_ERROR =
+  "The desktop queues take single ops; use enqueueStoredOp.";
+
+export function enqueueStoredOp<T>(
+  resource: StoredResource,
+  op: T
+): Promise<number> {
+  return invokeDesktop<number>("enqueue_stored_op", { resource, op });
+}
+
+export function mergeStoredRecord<T extends { id: string }>(
+  resource: StoredResource,
+  record: T
+): Promise<void> {
+  return invokeDesktop<void>("merge_stored_record", { resource, record });
+}
+
+export function removeStoredRecord(
+  resource: StoredResource,
+  id: string
+): Promise<void> {
+  return invokeDesktop<void>("remove_stored_record", { resource, id });
+}
+
 /* Tasks */
 
 export async function readStoredTasks<T>(): Promise<T[]> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T[]>("read_stored_tasks");
+  }
+
   return getAll<T>(TASKS_STORE);
 }
 
 export async function writeStoredTasks<T extends { id: string }>(
   tasks: T[]
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<void>("write_stored_tasks", { tasks });
+  }
+
   await replaceAll(TASKS_STORE, tasks);
 }
 
 export async function readStoredTaskQueue<T>(): Promise<T[]> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T[]>("read_stored_task_queue");
+  }
+
   const entries = await getAll<{ id: number; value: T }>(TASK_QUEUE_STORE);
 
   return entries
@@ -347,6 +497,10 @@ export async function readStoredTaskQueue<T>(): Promise<T[]> {
 }
 
 export async function writeStoredTaskQueue<T>(queue: T[]): Promise<void> {
+  if (isDesktopRuntime()) {
+    throw new Error(DESKTOP_QUEUE_WRITE_ERROR);
+  }
+
   await replaceAll(
     TASK_QUEUE_STORE,
     queue.map((value, index) => ({
@@ -359,16 +513,28 @@ export async function writeStoredTaskQueue<T>(queue: T[]): Promise<void> {
 /* Documents */
 
 export async function readStoredDocuments<T>(): Promise<T[]> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T[]>("read_stored_documents");
+  }
+
   return getAll<T>(DOCUMENTS_STORE);
 }
 
 export async function writeStoredDocuments<T extends { id: string }>(
   documents: T[]
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<void>("write_stored_documents", { documents });
+  }
+
   await replaceAll(DOCUMENTS_STORE, documents);
 }
 
 export async function readStoredDocumentQueue<T>(): Promise<T[]> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T[]>("read_stored_document_queue");
+  }
+
   const entries = await getAll<{ id: number; value: T }>(
     DOCUMENT_QUEUE_STORE
   );
@@ -381,6 +547,10 @@ export async function readStoredDocumentQueue<T>(): Promise<T[]> {
 export async function writeStoredDocumentQueue<T>(
   queue: T[]
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    throw new Error(DESKTOP_QUEUE_WRITE_ERROR);
+  }
+
   await replaceAll(
     DOCUMENT_QUEUE_STORE,
     queue.map((value, index) => ({
@@ -393,12 +563,18 @@ export async function writeStoredDocumentQueue<T>(
 /* Document recovery */
 
 function documentRecoveryKey(documentId: string): string {
-  return `documentRecovery:${documentId}`;
+  return `${DOCUMENT_RECOVERY_PREFIX}${documentId}`;
 }
 
 export async function readStoredDocumentRecovery<T>(
   documentId: string
 ): Promise<T | null> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T | null>("read_stored_document_recovery", {
+      documentId,
+    });
+  }
+
   return getMetaValue<T>(documentRecoveryKey(documentId));
 }
 
@@ -406,28 +582,53 @@ export async function writeStoredDocumentRecovery<T>(
   documentId: string,
   value: T
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<void>("write_stored_document_recovery", {
+      documentId,
+      value,
+    });
+  }
+
   await setMetaValue(documentRecoveryKey(documentId), value);
 }
 
 export async function deleteStoredDocumentRecovery(
   documentId: string
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<void>("delete_stored_document_recovery", {
+      documentId,
+    });
+  }
+
   await deleteMetaValue(documentRecoveryKey(documentId));
 }
 
 /* Calendar events */
 
 export async function readStoredEvents<T>(): Promise<T[]> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T[]>("read_stored_events");
+  }
+
   return getAll<T>(EVENTS_STORE);
 }
 
 export async function writeStoredEvents<T extends { id: string }>(
   events: T[]
 ): Promise<void> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<void>("write_stored_events", { events });
+  }
+
   await replaceAll(EVENTS_STORE, events);
 }
 
 export async function readStoredEventQueue<T>(): Promise<T[]> {
+  if (isDesktopRuntime()) {
+    return invokeDesktop<T[]>("read_stored_event_queue");
+  }
+
   const entries = await getAll<{ id: number; value: T }>(EVENT_QUEUE_STORE);
 
   return entries
@@ -436,6 +637,10 @@ export async function readStoredEventQueue<T>(): Promise<T[]> {
 }
 
 export async function writeStoredEventQueue<T>(queue: T[]): Promise<void> {
+  if (isDesktopRuntime()) {
+    throw new Error(DESKTOP_QUEUE_WRITE_ERROR);
+  }
+
   await replaceAll(
     EVENT_QUEUE_STORE,
     queue.map((value, index) => ({
@@ -444,4 +649,22 @@ export async function writeStoredEventQueue<T>(queue: T[]): Promise<void> {
     }))
   );
 }
+
+/* Location */
+
+/* Full path of the desktop app's database file; null in the browser. */
+export async function getStoragePath(): Promise<string | null> {
+  if (!isDesktopRuntime()) return null;
+  return invokeDesktop<string>("get_storage_path");
+}
+
+/* Encryption */
+
+/*
+ * Re-encrypts the local store under a fresh key. The current workspace PIN
+ * is required when one is set.
+ */
+export function rekeyLocalStore(pin: string | null): Promise<void> {
+  return invokeDesktop<void>("rekey_local_store", { pin });
+}
 
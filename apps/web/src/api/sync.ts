@@ -18,14 +18,22 @@ import {
 import {
   hasBrowserWindow,
   isBrowserOffline,
+  setDesktopOnline,
   SYNC_STATE_EVENT,
 } from "./syncSupport";
 import {
   CLOUD_AUTH_REQUIRED_EVENT,
   SESSION_CHANGED_EVENT,
+  getCloudToken,
   hasCloudSession,
+  invalidateCloudSession,
   isCloudReconnectRequired,
 } from "./session";
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
 import {
   developerLogger,
 } from "../developer/logger";
@@ -53,6 +61,21 @@ export interface SyncSnapshot {
 
 type SyncListener = (snapshot: SyncSnapshot) => void;
 
+/* Matches `NetworkStatus` in desktop/src-tauri/src/reachability/mod.rs. */
+interface NetworkStatus {
+  online: boolean | null;
+  reason:
+    | "checked"
+    | "reconnected"
+    | "disconnected"
+    | "resumed"
+    | "network-changed"
+    | null;
+  checkedAt: string | null;
+}
+
+const NETWORK_STATUS_EVENT = "pioneer:network-status";
+
 const listeners = new Set<SyncListener>();
 
 let isSyncing = false;
@@ -180,7 +203,93 @@ export async function refreshSyncStatus(): Promise<SyncSnapshot> {
   }
 }
 
+/*
+ * The desktop build drains the queues from Rust so uploads continue while
+ * the window is hidden; the webview only mirrors the native snapshots.
+ */
+async function syncAllNowOnDesktop(): Promise<SyncSnapshot> {
+  try {
+    return publish(await invokeDesktop<SyncSnapshot>("sync_now"));
+  } catch (error) {
+    developerLogger.error(
+      "sync",
+      "Unable to reach the native sync worker",
+      error
+    );
+    errorMessage = "Cloud synchronization failed. Local changes are safe.";
+    return publish(makeSnapshot());
+  }
+}
+
+function startNativeSyncBridge(): () => void {
+  const pushSession = () => {
+    void invokeDesktop("sync_set_session", {
+      token: getCloudToken(),
+      reconnectRequired: isCloudReconnectRequired(),
+    }).catch((error) => {
+      developerLogger.error(
+        "sync",
+        "Unable to hand the cloud session to the native sync worker",
+        error
+      );
+    });
+  };
+
+  const refresh = () => {
+    void invokeDesktop<SyncSnapshot>("get_sync_snapshot")
+      .then(publish)
+      .catch(() => undefined);
+  };
+
+  /*
+   * The crate probes the API server instead of trusting
+   * `navigator.onLine`; coming back, waking from sleep or moving to
+   * another network is a good moment to upload what queued up meanwhile.
+   */
+  const applyNetworkStatus = (status: NetworkStatus) => {
+    setDesktopOnline(status.online);
+
+    if (
+      status.online &&
+      (status.reason === "reconnected" ||
+        status.reason === "resumed" ||
+        status.reason === "network-changed")
+    ) {
+      void syncAllNow();
+    } else {
+      refresh();
+    }
+  };
+
+  const unsubscribers = [
+    listenDesktop<SyncSnapshot>("pioneer:sync-snapshot", publish),
+    listenDesktop<NetworkStatus>(NETWORK_STATUS_EVENT, applyNetworkStatus),
+    listenDesktop<string>(CLOUD_AUTH_REQUIRED_EVENT, (reason) => {
+      invalidateCloudSession(reason);
+    }),
+  ];
+
+  window.addEventListener(SESSION_CHANGED_EVENT, pushSession);
+  window.addEventListener(SYNC_STATE_EVENT, refresh);
+
+  pushSession();
+
+  void invokeDesktop<NetworkStatus>("get_network_status")
+    .then((status) => setDesktopOnline(status.online))
+    .catch(() => undefined);
+
+  return () => {
+    unsubscribers.forEach((unsubscribe) => unsubscribe());
+    window.removeEventListener(SESSION_CHANGED_EVENT, pushSession);
+    window.removeEventListener(SYNC_STATE_EVENT, refresh);
+  };
+}
+
 export async function syncAllNow(): Promise<SyncSnapshot> {
+  if (isDesktopRuntime()) {
+    return syncAllNowOnDesktop();
+  }
+
   if (syncPromise) {
     return syncPromise;
   }
@@ -260,6 +369,10 @@ export function startSyncCoordinator(
     return () => undefined;
   }
 
+  if (isDesktopRuntime()) {
+    return startNativeSyncBridge();
+  }
+
   let disposed = false;
 
   const refresh = () => {
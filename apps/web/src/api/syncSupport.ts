@@ -15,7 +15,22 @@ export function hasBrowserWindow(): boolean {
   return typeof window !== "undefined";
 }
 
+/*
+ * Whether the desktop crate's last probe reached the API server, or null
+ * before its first probe and in the browser, where `navigator.onLine` is
+ * used instead.
+ */
+let desktopOnline: boolean | null = null;
+
+export function setDesktopOnline(online: boolean | null): void {
+  desktopOnline = online;
+}
+
 export function isBrowserOffline(): boolean {
+  if (desktopOnline !== null) {
+    return !desktopOnline;
+  }
+
   return (
     typeof navigator !== "undefined" &&
     navigator.onLine === false
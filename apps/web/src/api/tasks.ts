@@ -1,4 +1,5 @@
 // apps/web/src/api/tasks.ts
+import { isDesktopRuntime } from "./desktop";
 import { http } from "./http";
 import { hasCloudSession } from "./session";
 import {
@@ -8,12 +9,16 @@ import {
   notifySyncStateChanged,
 } from "./syncSupport";
 import {
+  enqueueStoredOp,
+  mergeStoredRecord,
   migrateLegacyLocalStorage,
   readStoredTaskQueue,
   readStoredTasks,
+  removeStoredRecord,
   writeStoredTaskQueue,
   writeStoredTasks,
 } from "./storage";
+import { broadcastChange } from "./windows";
 
 export type TaskStatus = "todo" | "in_progress" | "done";
 export type TaskPriority = "critical" | "high" | "medium" | "low";
@@ -91,7 +96,11 @@ async function ensureTaskStorageReady(): Promise<void> {
     storageInitialization = (async () => {
       await migrateLegacyLocalStorage();
       await refreshPendingTaskSyncCount();
-    })();
+    })().catch((error) => {
+      // Reads fail while the desktop workspace is locked; retry next time.
+      storageInitialization = null;
+      throw error;
+    });
   }
 
   await storageInitialization;
@@ -158,6 +167,8 @@ function notifyTasksChanged(): void {
   if (hasBrowserWindow()) {
     window.dispatchEvent(new Event(TASKS_CHANGED_EVENT));
   }
+
+  broadcastChange("tasks");
 }
 
 function normalizeTaskPatch(patch: TaskPatch): TaskPatch {
@@ -287,6 +298,22 @@ async function writeQueue(queue: TaskOp[]): Promise<void> {
   notifySyncStateChanged();
 }
 
+/*
+ * The desktop store coalesces the op with its queue itself, since the
+ * native sync worker edits that queue too. Returns false in the browser.
+ */
+async function enqueueNatively(operation: TaskOp): Promise<boolean> {
+  if (!isDesktopRuntime()) {
+    return false;
+  }
+
+  await ensureTaskStorageReady();
+
+  pendingTaskSyncCount = await enqueueStoredOp("tasks", operation);
+  notifySyncStateChanged();
+  return true;
+}
+
 export function getPendingTaskSyncCount(): number {
   return pendingTaskSyncCount;
 }
@@ -300,6 +327,13 @@ export async function refreshPendingTaskSyncCount(): Promise<number> {
 
 async function mergeTaskIntoCache(task: Task): Promise<void> {
   const normalized = normalizeTask(task);
+
+  if (isDesktopRuntime()) {
+    await ensureTaskStorageReady();
+    await mergeStoredRecord("tasks", normalized);
+    return;
+  }
+
   const tasks = await readTasksCache();
 
   const index = tasks.findIndex((entry) => entry.id === normalized.id);
@@ -317,14 +351,18 @@ async function mergeTaskIntoCache(task: Task): Promise<void> {
 }
 
 async function removeTaskFromCache(id: string): Promise<void> {
+  if (isDesktopRuntime()) {
+    await ensureTaskStorageReady();
+    await removeStoredRecord("tasks", id);
+    return;
+  }
+
   const tasks = await readTasksCache();
   await writeTasksCache(tasks.filter((task) => task.id !== id));
 }
 
 async function enqueueCreate(task: Task): Promise<void> {
-  const queue = await readQueue();
-
-  queue.push({
+  const operation: CreateOp = {
     kind: "create",
     tempId: task.id,
     payload: {
@@ -336,7 +374,14 @@ async function enqueueCreate(task: Task): Promise<void> {
       dueDate: task.dueDate ?? null,
     },
     timestamp: Date.now(),
-  });
+  };
+
+  if (await enqueueNatively(operation)) {
+    return;
+  }
+
+  const queue = await readQueue();
+  queue.push(operation);
 
   await writeQueue(queue);
 }
@@ -346,6 +391,18 @@ async function enqueueUpdate(
   patch: TaskPatch
 ): Promise<void> {
   const normalizedPatch = normalizeTaskPatch(patch);
+
+  if (
+    await enqueueNatively({
+      kind: "update",
+      id,
+      patch: normalizedPatch,
+      timestamp: Date.now(),
+    })
+  ) {
+    return;
+  }
+
   const queue = await readQueue();
 
   const createIndex = queue.findIndex(
@@ -404,6 +461,10 @@ async function enqueueUpdate(
 }
 
 async function enqueueDelete(id: string): Promise<void> {
+  if (await enqueueNatively({ kind: "delete", id, timestamp: Date.now() })) {
+    return;
+  }
+
   const queue = (await readQueue()).filter((operation) => {
     if (operation.kind === "create" && operation.tempId === id) {
       return false;
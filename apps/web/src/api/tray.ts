@@ -0,0 +1,23 @@
+// apps/web/src/api/tray.ts
+
+/*
+ * Actions chosen from the system tray menu that the webview carries out.
+ * The desktop crate shows the main window before sending them; the browser
+ * build has no tray.
+ */
+
+import { isDesktopRuntime, listenDesktop } from "./desktop";
+
+export type TrayAction =
+  | { action: "quick-add-task" }
+  | { action: "open-document" }
+  | { action: "open"; route: string };
+
+export const TRAY_ACTION_EVENT = "pioneer:tray-action";
+
+export function subscribeToTrayActions(
+  listener: (action: TrayAction) => void
+): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+  return listenDesktop<TrayAction>(TRAY_ACTION_EVENT, listener);
+}
@@ -0,0 +1,80 @@
+// apps/web/src/api/windows.ts
+
+/*
+ * Pop-out windows. On desktop a document or the calendar can open in a
+ * native window of its own; each window loads this bundle separately, so
+ * local changes are announced to the other windows through the crate.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+import { developerLogger } from "../developer/logger";
+
+export type ChangedResource = "tasks" | "documents" | "events";
+
+/* TASKS_CHANGED_EVENT and friends, spelled out because those modules
+   import this one. */
+
+const CHANGED_EVENTS: Record<ChangedResource, string> = {
+  tasks: "pioneer:tasks-changed",
+  documents: "pioneer:documents-changed",
+  events: "pioneer:calendar-events-changed",
+};
+
+export function isPopoutSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export function openDocumentWindow(id: string): Promise<void> {
+  return invokeDesktop<void>("open_popout", {
+    popout: { kind: "document", id },
+  });
+}
+
+export function openCalendarWindow(): Promise<void> {
+  return invokeDesktop<void>("open_popout", {
+    popout: { kind: "calendar" },
+  });
+}
+
+/* Brings the main window forward at `route`. */
+export function showInMainWindow(route: string): Promise<void> {
+  return invokeDesktop<void>("show_in_main_window", { route });
+}
+
+/*
+ * Called after this window changed something. The event is also dispatched
+ * locally by the caller; here it only goes to the other windows.
+ */
+export function broadcastChange(resource: ChangedResource): void {
+  if (!isDesktopRuntime()) return;
+
+  void invokeDesktop<void>("broadcast_change", { resource }).catch(
+    (error) => {
+      developerLogger.error(
+        "windows",
+        `Unable to tell other windows that ${resource} changed`,
+        error
+      );
+    }
+  );
+}
+
+/*
+ * Re-dispatches change events from the crate, sent by the sync worker or
+ * by another window, as window events the pages already listen for.
+ */
+export function startChangeBridge(): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+
+  const unsubscribers = Object.values(CHANGED_EVENTS).map((eventName) =>
+    listenDesktop<null>(eventName, () => {
+      window.dispatchEvent(new Event(eventName));
+    })
+  );
+
+  return () => unsubscribers.forEach((unsubscribe) => unsubscribe());
+}
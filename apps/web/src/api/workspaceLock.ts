@@ -0,0 +1,132 @@
+// apps/web/src/api/workspaceLock.ts
+
+/*
+ * PIN lock for the local workspace. The PIN hash, attempt throttling and the
+ * idle timer all live in the desktop crate; the browser build has no lock.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "./desktop";
+
+export type WorkspaceLockReason = "startup" | "manual" | "idle";
+
+export type WorkspaceLockState = {
+  pinSet: boolean;
+  locked: boolean;
+  reason: WorkspaceLockReason | null;
+  failedAttempts: number;
+  retryAfterSeconds: number | null;
+  idleTimeoutSeconds: number;
+};
+
+export const WORKSPACE_LOCKED_EVENT = "pioneer:workspace-locked";
+export const WORKSPACE_UNLOCKED_EVENT = "pioneer:workspace-unlocked";
+
+const ACTIVITY_REPORT_INTERVAL_MS = 30_000;
+
+export const UNLOCKED_WORKSPACE_STATE: WorkspaceLockState = {
+  pinSet: false,
+  locked: false,
+  reason: null,
+  failedAttempts: 0,
+  retryAfterSeconds: null,
+  idleTimeoutSeconds: 0,
+};
+
+export function isWorkspaceLockSupported(): boolean {
+  return isDesktopRuntime();
+}
+
+export async function getWorkspaceLockState(): Promise<WorkspaceLockState> {
+  if (!isDesktopRuntime()) return UNLOCKED_WORKSPACE_STATE;
+  return invokeDesktop<WorkspaceLockState>("get_workspace_lock_state");
+}
+
+/*
+ * Passing null as the new PIN removes it. An existing PIN must be supplied
+ * as currentPin to change or remove it.
+ */
+export function setLocalPin(
+  pin: string | null,
+  currentPin: string | null = null
+): Promise<WorkspaceLockState> {
+  return invokeDesktop<WorkspaceLockState>("set_local_pin", {
+    pin,
+    currentPin,
+  });
+}
+
+/*
+ * Resolves with the new state; a wrong PIN leaves it locked. Rejects with a
+ * readable message while attempts are throttled.
+ */
+export function verifyLocalPin(pin: string): Promise<WorkspaceLockState> {
+  return invokeDesktop<WorkspaceLockState>("verify_local_pin", { pin });
+}
+
+export function lockWorkspace(): Promise<WorkspaceLockState> {
+  return invokeDesktop<WorkspaceLockState>("lock_workspace");
+}
+
+export function setIdleLockTimeout(
+  seconds: number
+): Promise<WorkspaceLockState> {
+  return invokeDesktop<WorkspaceLockState>("set_idle_lock_timeout", {
+    seconds,
+  });
+}
+
+export function subscribeToWorkspaceLock(
+  listener: (state: WorkspaceLockState) => void
+): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+
+  const stopLocked = listenDesktop<WorkspaceLockState>(
+    WORKSPACE_LOCKED_EVENT,
+    listener
+  );
+  const stopUnlocked = listenDesktop<WorkspaceLockState>(
+    WORKSPACE_UNLOCKED_EVENT,
+    listener
+  );
+
+  return () => {
+    stopLocked();
+    stopUnlocked();
+  };
+}
+
+/*
+ * Reports keyboard and pointer input to the idle timer, at most once every
+ * ACTIVITY_REPORT_INTERVAL_MS.
+ */
+export function startWorkspaceActivityReporting(): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+
+  let lastReportedAt = 0;
+
+  const report = () => {
+    const now = Date.now();
+    if (now - lastReportedAt < ACTIVITY_REPORT_INTERVAL_MS) return;
+
+    lastReportedAt = now;
+    void invokeDesktop("record_workspace_activity").catch((error) => {
+      console.error("Failed to report workspace activity", error);
+    });
+  };
+
+  const events = ["keydown", "pointerdown", "wheel"] as const;
+
+  events.forEach((name) => {
+    window.addEventListener(name, report, { passive: true });
+  });
+
+  return () => {
+    events.forEach((name) => {
+      window.removeEventListener(name, report);
+    });
+  };
+}
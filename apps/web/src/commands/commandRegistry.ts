@@ -255,6 +255,15 @@ class CommandRegistry {
       .map(({ definition }) => definition);
   }
 
+  getCommand(
+    id: string
+  ): CommandDefinition | null {
+    return (
+      this.commands.get(id)?.definition ??
+      null
+    );
+  }
+
   search(
     query: string
   ): CommandSearchResult[] {
@@ -20,6 +20,9 @@ export interface CommandDefinition {
 
   enabled?: boolean;
   disabledReason?: string;
+  /* Set on commands that toggle or pick an option; shown as a check mark
+     in the desktop menu bar. */
+  checked?: boolean;
   priority?: number;
 
   run: () => void | Promise<void>;
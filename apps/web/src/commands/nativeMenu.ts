@@ -0,0 +1,123 @@
+// apps/web/src/commands/nativeMenu.ts
+
+/*
+ * Mirrors the command registry into the desktop menu bar. The crate builds
+ * the menu at startup from menuCommands.json, which it embeds when it is
+ * compiled, so every command below has its item from the first start. This
+ * manifest then updates titles, enabled and checked state; choosing an item
+ * comes back as `pioneer:menu-command` and runs through the registry like
+ * the palette.
+ *
+ * A command that is missing from menuCommands.json only gets an item from
+ * the next start, so development builds warn about it.
+ */
+
+import {
+  invokeDesktop,
+  isDesktopRuntime,
+  listenDesktop,
+} from "../api/desktop";
+import { developerLogger } from "../developer/logger";
+import { commandRegistry } from "./commandRegistry";
+import type { CommandDefinition } from "./commandTypes";
+import menuCommands from "./menuCommands.json";
+
+export const MENU_COMMAND_EVENT = "pioneer:menu-command";
+
+/* Page commands re-register on every render; wait for them to settle. */
+const PUBLISH_DELAY_MS = 150;
+
+interface MenuCommandState {
+  id: string;
+  title: string;
+  category: CommandDefinition["category"];
+  shortcut: string[];
+  enabled: boolean;
+  checked: boolean | null;
+}
+
+function manifest(): MenuCommandState[] {
+  return commandRegistry
+    .getCommands()
+    .map((command) => ({
+      id: command.id,
+      title: command.title,
+      category: command.category,
+      shortcut: command.shortcut ?? [],
+      enabled: command.enabled !== false,
+      checked: command.checked ?? null,
+    }))
+    .sort((left, right) => left.id.localeCompare(right.id));
+}
+
+const catalogued = new Set(menuCommands.map((command) => command.id));
+const reportedMissing = new Set<string>();
+
+function reportMissing(commands: MenuCommandState[]): void {
+  for (const { id } of commands) {
+    if (catalogued.has(id) || reportedMissing.has(id)) continue;
+
+    reportedMissing.add(id);
+    developerLogger.warning(
+      "menu",
+      `Command ${id} is missing from menuCommands.json`
+    );
+  }
+}
+
+/* Returns a cleanup; does nothing outside the desktop app. */
+export function startNativeMenu(): () => void {
+  if (!isDesktopRuntime()) return () => undefined;
+
+  let published = "";
+  let timer: number | null = null;
+
+  function publish(): void {
+    timer = null;
+
+    const commands = manifest();
+    if (import.meta.env.DEV) reportMissing(commands);
+
+    const serialized = JSON.stringify(commands);
+    if (serialized === published) return;
+
+    published = serialized;
+    void invokeDesktop<void>("set_native_menu", { commands }).catch(
+      (error) => {
+        published = "";
+        developerLogger.error(
+          "menu",
+          "Unable to update the native menu",
+          error
+        );
+      }
+    );
+  }
+
+  function schedule(): void {
+    if (timer !== null) window.clearTimeout(timer);
+    timer = window.setTimeout(publish, PUBLISH_DELAY_MS);
+  }
+
+  const unsubscribe = commandRegistry.subscribe(schedule);
+  const unlisten = listenDesktop<string>(MENU_COMMAND_EVENT, (id) => {
+    const command = commandRegistry.getCommand(id);
+    if (!command) return;
+
+    void commandRegistry.execute(command).catch((error) => {
+      developerLogger.error(
+        "menu",
+        `Unable to run ${command.title}`,
+        error
+      );
+    });
+  });
+
+  schedule();
+
+  return () => {
+    if (timer !== null) window.clearTimeout(timer);
+    unsubscribe();
+    unlisten();
+  };
+}
@@ -177,6 +177,7 @@ export function useGlobalCommands({
           option.label.toLocaleLowerCase(),
         ],
         enabled: rightSidebarMode !== option.value,
+        checked: rightSidebarMode === option.value,
         disabledReason:
           `${option.label} is already shown in the right sidebar.`,
         run: () => onSetRightSidebarMode(option.value),
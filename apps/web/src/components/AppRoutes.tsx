@@ -17,9 +17,11 @@ import type {
 import type {
   RightSidebarMode,
 } from "../types/rightSidebar";
+import { useWorkspaceLock } from "../hooks/useWorkspaceLock";
 import LoginPage from "../pages/LoginPage";
 import RegisterPage from "../pages/RegisterPage";
 import PageLoadingFallback from "./PageLoadingFallback";
+import WorkspaceLockScreen from "./WorkspaceLockScreen";
 
 const CalendarPage = lazy(() => import("../pages/CalendarPage"));
 const DashboardPage = lazy(() => import("../pages/DashboardPage"));
@@ -53,6 +55,25 @@ const AppRoutes: React.FC<AppRoutesProps> = ({
   sidebarMode,
   onSidebarModeChange,
 }) => {
+  const workspaceLock = useWorkspaceLock();
+
+  /*
+   * On desktop the Rust side reports whether a PIN lock is active. Nothing
+   * from the workspace renders until that answer arrives.
+   */
+  if (!workspaceLock.loaded) {
+    return <PageLoadingFallback />;
+  }
+
+  if (workspaceLock.state.locked) {
+    return (
+      <WorkspaceLockScreen
+        state={workspaceLock.state}
+        onStateChange={workspaceLock.setState}
+      />
+    );
+  }
+
   return (
     <Suspense fallback={<PageLoadingFallback />}>
     <Routes>
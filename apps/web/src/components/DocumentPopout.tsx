@@ -0,0 +1,220 @@
+// apps/web/src/components/DocumentPopout.tsx
+
+import React, { useCallback, useEffect, useRef, useState } from "react";
+import ReactQuill from "react-quill";
+import "react-quill/dist/quill.snow.css";
+
+import {
+  DOCUMENTS_CHANGED_EVENT,
+  fetchDocuments,
+  updateDocument,
+} from "../api/documents";
+import type { Document } from "../api/documents";
+import { showInMainWindow } from "../api/windows";
+import { developerLogger } from "../developer/logger";
+import { useUnsavedDraft } from "../hooks/useUnsavedDraft";
+import Button from "./ui/Button";
+
+/* Edits are saved this long after the last keystroke. */
+const AUTOSAVE_DELAY_MS = 800;
+
+const QUILL_MODULES = {
+  toolbar: [
+    [{ header: [1, 2, 3, false] }],
+    ["bold", "italic", "underline", "strike"],
+    [{ background: [] }],
+    [{ list: "ordered" }, { list: "bullet" }],
+    [{ align: [] }],
+    ["blockquote", "code-block"],
+    ["link", "image"],
+    ["clean"],
+  ],
+};
+
+const QUILL_FORMATS = [
+  "header",
+  "bold",
+  "italic",
+  "underline",
+  "strike",
+  "background",
+  "list",
+  "bullet",
+  "align",
+  "blockquote",
+  "code-block",
+  "link",
+  "image",
+];
+
+interface DocumentPopoutProps {
+  id: string;
+}
+
+/*
+ * A single document in a window of its own. Changes saved in another window
+ * are picked up as long as there are no unsaved edits here.
+ */
+const DocumentPopout: React.FC<DocumentPopoutProps> = ({ id }) => {
+  const [document, setDocument] = useState<Document | null>(null);
+  const [loaded, setLoaded] = useState(false);
+  const [title, setTitle] = useState("");
+  const [content, setContent] = useState("");
+  const [editorRevision, setEditorRevision] = useState(0);
+  const [hasLocalChanges, setHasLocalChanges] = useState(false);
+  const [isSaving, setIsSaving] = useState(false);
+  const [saveError, setSaveError] = useState<string | null>(null);
+
+  const hasLocalChangesRef = useRef(false);
+  const contentRef = useRef("");
+
+  hasLocalChangesRef.current = hasLocalChanges;
+  contentRef.current = content;
+
+  const load = useCallback(async () => {
+    try {
+      const documents = await fetchDocuments();
+      const next = documents.find((item) => item.id === id) ?? null;
+
+      if (hasLocalChangesRef.current) return;
+
+      setDocument(next);
+      setTitle(next?.title ?? "");
+
+      if ((next?.content ?? "") !== contentRef.current) {
+        setContent(next?.content ?? "");
+        setEditorRevision((revision) => revision + 1);
+      }
+    } catch (error) {
+      developerLogger.error(
+        "documents",
+        "Unable to load the document for its window",
+        error
+      );
+    } finally {
+      setLoaded(true);
+    }
+  }, [id]);
+
+  useEffect(() => {
+    void load();
+
+    const handleChange = () => void load();
+    window.addEventListener(DOCUMENTS_CHANGED_EVENT, handleChange);
+    return () =>
+      window.removeEventListener(DOCUMENTS_CHANGED_EVENT, handleChange);
+  }, [load]);
+
+  useEffect(() => {
+    window.document.title = title.trim() || "Untitled document";
+  }, [title]);
+
+  const save = useCallback(async (): Promise<boolean> => {
+    if (!document) return false;
+
+    setIsSaving(true);
+
+    try {
+      const saved = await updateDocument(document.id, { title, content });
+      setDocument(saved);
+      setHasLocalChanges(false);
+      setSaveError(null);
+      return true;
+    } catch (error) {
+      developerLogger.error(
+        "documents",
+        "Unable to save the document from its window",
+        error
+      );
+      setSaveError("Unable to save. Changes are kept in this window.");
+      return false;
+    } finally {
+      setIsSaving(false);
+    }
+  }, [content, document, title]);
+
+  useEffect(() => {
+    if (!hasLocalChanges) return;
+
+    const timeout = window.setTimeout(() => void save(), AUTOSAVE_DELAY_MS);
+    return () => window.clearTimeout(timeout);
+  }, [hasLocalChanges, save]);
+
+  useUnsavedDraft(
+    `document-${id}`,
+    hasLocalChanges ? title.trim() || "Untitled document" : null,
+    save
+  );
+
+  if (!loaded) {
+    return <div className="document-popout__message">Loading…</div>;
+  }
+
+  if (!document) {
+    return (
+      <div className="document-popout__message">
+        <p>This document was deleted or is not available.</p>
+        <Button onClick={() => void showInMainWindow("/documents")}>
+          Show documents
+        </Button>
+      </div>
+    );
+  }
+
+  let status = "Saved";
+  if (saveError) status = saveError;
+  else if (isSaving) status = "Saving…";
+  else if (hasLocalChanges) status = "Unsaved changes";
+
+  return (
+    <div className="document-popout">
+      <header className="document-popout__header">
+        <input
+          className="document-popout__title"
+          value={title}
+          placeholder="Untitled document"
+          aria-label="Document title"
+          onChange={(event) => {
+            setTitle(event.target.value);
+            setHasLocalChanges(true);
+          }}
+        />
+        <span
+          className={
+            saveError
+              ? "document-popout__status document-popout__status--error"
+              : "document-popout__status"
+          }
+        >
+          {status}
+        </span>
+        <Button
+          tone="quiet"
+          onClick={() =>
+            void showInMainWindow(
+              `/documents?document=${encodeURIComponent(document.id)}`
+            )
+          }
+        >
+          Show in main window
+        </Button>
+      </header>
+
+      <ReactQuill
+        key={`${document.id}-${editorRevision}`}
+        className="document-popout__editor"
+        defaultValue={content}
+        onChange={(html, _delta, source) => {
+          setContent(html);
+          if (source === "user") setHasLocalChanges(true);
+        }}
+        placeholder="Start writing your document here..."
+        theme="snow"
+        modules={QUILL_MODULES}
+        formats={QUILL_FORMATS}
+      />
+    </div>
+  );
+};
+
+export default DocumentPopout;
@@ -13,13 +13,25 @@ import "../styles/global-search.css";
 
 const GLOBAL_SEARCH_OPEN_EVENT = "pioneer:open-global-search";
 
-export function openGlobalSearch(): void {
+type SearchFilter = "all" | SearchResultKind;
+
+function requestGlobalSearch(filter: SearchFilter): void {
   if (typeof window !== "undefined") {
-    window.dispatchEvent(new Event(GLOBAL_SEARCH_OPEN_EVENT));
+    window.dispatchEvent(
+      new CustomEvent<SearchFilter>(GLOBAL_SEARCH_OPEN_EVENT, {
+        detail: filter,
+      })
+    );
   }
 }
 
-type SearchFilter = "all" | SearchResultKind;
+export function openGlobalSearch(): void {
+  requestGlobalSearch("all");
+}
+
+export function openDocumentSearch(): void {
+  requestGlobalSearch("document");
+}
 
 const EMPTY: SearchSnapshot = {
   results: [],
@@ -50,8 +62,11 @@ const GlobalSearch: React.FC = () => {
   );
 
   useEffect(() => {
-    const show = () => {
+    const show = (event: Event) => {
+      const requested = (event as CustomEvent<SearchFilter>).detail;
+
       setOpen(true);
+      setFilter(requested ?? "all");
       setError(null);
       window.setTimeout(
         () => inputRef.current?.focus(),
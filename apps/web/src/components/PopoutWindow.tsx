@@ -0,0 +1,78 @@
+// apps/web/src/components/PopoutWindow.tsx
+
+import React, { Suspense, lazy, useEffect } from "react";
+import {
+  Route,
+  Routes,
+  useLocation,
+  useNavigate,
+  useParams,
+} from "react-router-dom";
+
+import { startCloseGuard } from "../api/closeGuard";
+import { showInMainWindow, startChangeBridge } from "../api/windows";
+import { useWorkspaceLock } from "../hooks/useWorkspaceLock";
+import DocumentPopout from "./DocumentPopout";
+import PageLoadingFallback from "./PageLoadingFallback";
+import ToastViewport from "./ToastViewport";
+import WorkspaceLockScreen from "./WorkspaceLockScreen";
+import "../styles/popout.css";
+
+const CalendarPage = lazy(() => import("../pages/CalendarPage"));
+
+const DocumentRoute: React.FC = () => {
+  const { id = "" } = useParams();
+  return <DocumentPopout key={id} id={id} />;
+};
+
+/* Anything else a pop-out links to opens in the main window. */
+const ForwardToMainWindow: React.FC = () => {
+  const location = useLocation();
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    void showInMainWindow(`${location.pathname}${location.search}`);
+    navigate(-1);
+  }, [location.pathname, location.search, navigate]);
+
+  return null;
+};
+
+/*
+ * The shell of a pop-out window: one document or the calendar, without the
+ * navigation, sidebars or status bar of the main window.
+ */
+const PopoutWindow: React.FC = () => {
+  const workspaceLock = useWorkspaceLock();
+
+  useEffect(() => startChangeBridge(), []);
+  useEffect(() => startCloseGuard(), []);
+
+  if (!workspaceLock.loaded) {
+    return <PageLoadingFallback />;
+  }
+
+  if (workspaceLock.state.locked) {
+    return (
+      <WorkspaceLockScreen
+        state={workspaceLock.state}
+        onStateChange={workspaceLock.setState}
+      />
+    );
+  }
+
+  return (
+    <div className="popout-window">
+      <Suspense fallback={<PageLoadingFallback />}>
+        <Routes>
+          <Route path="/popout/document/:id" element={<DocumentRoute />} />
+          <Route path="/popout/calendar" element={<CalendarPage />} />
+          <Route path="*" element={<ForwardToMainWindow />} />
+        </Routes>
+      </Suspense>
+      <ToastViewport />
+    </div>
+  );
+};
+
+export default PopoutWindow;
@@ -0,0 +1,322 @@
+// apps/web/src/components/QuickCaptureWindow.tsx
+import React, { useEffect, useRef, useState } from "react";
+
+import {
+  type CaptureKind,
+  finishCapture,
+  isCaptureKind,
+  subscribeToCaptureKind,
+} from "../api/capture";
+import { createDocument } from "../api/documents";
+import { createEvent } from "../api/events";
+import { createTask } from "../api/tasks";
+import {
+  getWorkspaceLockState,
+  subscribeToWorkspaceLock,
+} from "../api/workspaceLock";
+import { developerLogger } from "../developer/logger";
+import { plainTextToHtml } from "../utils/documentText";
+import { getLocalDateKey } from "../utils/taskDates";
+import Button from "./ui/Button";
+import "../styles/quick-capture.css";
+
+type TaskDue = "none" | "today" | "tomorrow";
+
+const KINDS: Array<[CaptureKind, string]> = [
+  ["task", "Task"],
+  ["note", "Note"],
+  ["event", "Event"],
+];
+
+/* Minutes; 0 is all day. */
+const EVENT_LENGTHS: Array<[number, string]> = [
+  [30, "30 minutes"],
+  [60, "1 hour"],
+  [120, "2 hours"],
+  [0, "All day"],
+];
+
+function initialKind(): CaptureKind {
+  const query = window.location.hash.split("?")[1] ?? "";
+  const kind = new URLSearchParams(query).get("kind");
+  return isCaptureKind(kind) ? kind : "task";
+}
+
+function nextHalfHour(): string {
+  const date = new Date();
+  date.setMinutes(date.getMinutes() < 30 ? 30 : 60, 0, 0);
+  return `${String(date.getHours()).padStart(2, "0")}:${String(date.getMinutes()).padStart(2, "0")}`;
+}
+
+function dueDateFor(due: TaskDue): string | null {
+  if (due === "none") return null;
+
+  const date = new Date();
+  if (due === "tomorrow") date.setDate(date.getDate() + 1);
+  return getLocalDateKey(date);
+}
+
+function eventTimes(
+  day: string,
+  time: string,
+  minutes: number
+): { start: Date; end: Date } | null {
+  const [year, month, date] = day.split("-").map(Number);
+  if (!year || !month || !date) return null;
+
+  const start = new Date(year, month - 1, date);
+
+  if (minutes === 0) {
+    return { start, end: new Date(year, month - 1, date + 1) };
+  }
+
+  const [hours, mins] = time.split(":").map(Number);
+  if (Number.isNaN(hours) || Number.isNaN(mins)) return null;
+
+  start.setHours(hours, mins, 0, 0);
+  return { start, end: new Date(start.getTime() + minutes * 60_000) };
+}
+
+/*
+ * The always-on-top window opened by the global quick capture shortcuts.
+ * It saves through the same APIs as the main window, so captures land in
+ * the local store and op queues even while the main window is hidden.
+ */
+const QuickCaptureWindow: React.FC = () => {
+  const titleRef = useRef<HTMLInputElement | null>(null);
+
+  const [kind, setKind] = useState<CaptureKind>(initialKind);
+  const [locked, setLocked] = useState(false);
+  const [title, setTitle] = useState("");
+  const [taskDue, setTaskDue] = useState<TaskDue>("none");
+  const [noteBody, setNoteBody] = useState("");
+  const [eventDay, setEventDay] = useState(() => getLocalDateKey());
+  const [eventTime, setEventTime] = useState(nextHalfHour);
+  const [eventLength, setEventLength] = useState(30);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    void getWorkspaceLockState()
+      .then((state) => setLocked(state.locked))
+      .catch(() => undefined);
+
+    return subscribeToWorkspaceLock((state) => setLocked(state.locked));
+  }, []);
+
+  useEffect(
+    () =>
+      subscribeToCaptureKind((requested) => {
+        setKind(requested);
+        setError(null);
+        setEventDay(getLocalDateKey());
+        setEventTime(nextHalfHour());
+        window.setTimeout(() => titleRef.current?.focus(), 0);
+      }),
+    []
+  );
+
+  useEffect(() => {
+    titleRef.current?.focus();
+  }, [kind]);
+
+  function reset(): void {
+    setTitle("");
+    setTaskDue("none");
+    setNoteBody("");
+    setError(null);
+  }
+
+  async function close(saved: CaptureKind | null): Promise<void> {
+    try {
+      await finishCapture(saved);
+    } catch (closeError) {
+      developerLogger.error(
+        "capture",
+        "Unable to close the quick capture window",
+        closeError
+      );
+    }
+  }
+
+  async function save(): Promise<void> {
+    const trimmed = title.trim();
+
+    if (!trimmed) {
+      setError(kind === "task" ? "Give the task a title." : "Add a title first.");
+      return;
+    }
+
+    setSaving(true);
+    setError(null);
+
+    try {
+      if (kind === "task") {
+        await createTask(trimmed, { dueDate: dueDateFor(taskDue) });
+      } else if (kind === "note") {
+        await createDocument(trimmed, plainTextToHtml(noteBody));
+      } else {
+        const times = eventTimes(eventDay, eventTime, eventLength);
+
+        if (!times) {
+          setError("Choose a valid date and time.");
+          return;
+        }
+
+        await createEvent({
+          title: trimmed,
+          description: "",
+          start: times.start.toISOString(),
+          end: times.end.toISOString(),
+          allDay: eventLength === 0,
+          kind: "event",
+          urgency: null,
+        });
+      }
+
+      reset();
+      await close(kind);
+    } catch (saveError) {
+      developerLogger.error("capture", `Unable to save the ${kind}`, saveError);
+      setError(`Unable to save the ${kind}. Your text is still here.`);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  function handleKeyDown(event: React.KeyboardEvent): void {
+    if (event.key === "Escape") {
+      event.preventDefault();
+      void close(null);
+    } else if (
+      event.key === "Enter" &&
+      (event.metaKey || event.ctrlKey || !(event.target instanceof HTMLTextAreaElement))
+    ) {
+      event.preventDefault();
+      void save();
+    }
+  }
+
+  if (locked) {
+    return (
+      <main className="quick-capture quick-capture--locked">
+        <p>Pioneer Work Suite is locked. Unlock it to capture.</p>
+        <div className="quick-capture__actions">
+          <Button onClick={() => void close(null)}>Cancel</Button>
+          <Button tone="primary" onClick={() => void finishCapture(null, true)}>
+            Unlock
+          </Button>
+        </div>
+      </main>
+    );
+  }
+
+  return (
+    <main className="quick-capture" onKeyDown={handleKeyDown}>
+      <div className="quick-capture__kinds" role="radiogroup" aria-label="Capture">
+        {KINDS.map(([value, label]) => (
+          <button
+            key={value}
+            type="button"
+            role="radio"
+            aria-checked={kind === value}
+            className={kind === value ? "is-active" : undefined}
+            onClick={() => setKind(value)}
+          >
+            {label}
+          </button>
+        ))}
+      </div>
+
+      <input
+        ref={titleRef}
+        className="quick-capture__title"
+        aria-label="Title"
+        placeholder={
+          kind === "task"
+            ? "What needs doing?"
+            : kind === "note"
+              ? "Note title"
+              : "Event title"
+        }
+        value={title}
+        disabled={saving}
+        onChange={(event) => setTitle(event.target.value)}
+      />
+
+      {kind === "task" && (
+        <label className="quick-capture__field">
+          <span>Due</span>
+          <select
+            value={taskDue}
+            disabled={saving}
+            onChange={(event) => setTaskDue(event.target.value as TaskDue)}
+          >
+            <option value="none">No due date</option>
+            <option value="today">Today</option>
+            <option value="tomorrow">Tomorrow</option>
+          </select>
+        </label>
+      )}
+
+      {kind === "note" && (
+        <textarea
+          className="quick-capture__body"
+          aria-label="Note"
+          placeholder="Write something…"
+          value={noteBody}
+          disabled={saving}
+          onChange={(event) => setNoteBody(event.target.value)}
+        />
+      )}
+
+      {kind === "event" && (
+        <div className="quick-capture__row">
+          <input
+            type="date"
+            aria-label="Date"
+            value={eventDay}
+            disabled={saving}
+            onChange={(event) => setEventDay(event.target.value)}
+          />
+          <input
+            type="time"
+            aria-label="Start time"
+            value={eventTime}
+            disabled={saving || eventLength === 0}
+            onChange={(event) => setEventTime(event.target.value)}
+          />
+          <select
+            aria-label="Length"
+            value={eventLength}
+            disabled={saving}
+            onChange={(event) => setEventLength(Number(event.target.value))}
+          >
+            {EVENT_LENGTHS.map(([minutes, label]) => (
+              <option key={minutes} value={minutes}>
+                {label}
+              </option>
+            ))}
+          </select>
+        </div>
+      )}
+
+      {error && (
+        <p className="quick-capture__error" role="alert">
+          {error}
+        </p>
+      )}
+
+      <div className="quick-capture__actions">
+        <Button disabled={saving} onClick={() => void close(null)}>
+          Cancel
+        </Button>
+        <Button tone="primary" disabled={saving} onClick={() => void save()}>
+          {saving ? "Saving…" : "Save"}
+        </Button>
+      </div>
+    </main>
+  );
+};
+
+export default QuickCaptureWindow;
@@ -0,0 +1,134 @@
+// apps/web/src/components/WorkspaceLockScreen.tsx
+
+import React, { useEffect, useRef, useState } from "react";
+
+import { getLocalWorkspaceName } from "../api/session";
+import { verifyLocalPin } from "../api/workspaceLock";
+import type { WorkspaceLockState } from "../api/workspaceLock";
+import Button from "./ui/Button";
+
+interface WorkspaceLockScreenProps {
+  state: WorkspaceLockState;
+  onStateChange: (state: WorkspaceLockState) => void;
+}
+
+function describeReason(state: WorkspaceLockState): string {
+  switch (state.reason) {
+    case "idle":
+      return "The workspace was locked after a period of inactivity.";
+    case "manual":
+      return "The workspace was locked.";
+    default:
+      return "Enter your PIN to open the workspace.";
+  }
+}
+
+const WorkspaceLockScreen: React.FC<WorkspaceLockScreenProps> = ({
+  state,
+  onStateChange,
+}) => {
+  const inputRef = useRef<HTMLInputElement>(null);
+  const [pin, setPin] = useState("");
+  const [isSubmitting, setIsSubmitting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    inputRef.current?.focus();
+  }, []);
+
+  async function handleSubmit(
+    event: React.FormEvent<HTMLFormElement>
+  ): Promise<void> {
+    event.preventDefault();
+    if (!pin) return;
+
+    setIsSubmitting(true);
+    setError(null);
+
+    try {
+      const nextState = await verifyLocalPin(pin);
+
+      if (nextState.locked) {
+        setError(
+          nextState.retryAfterSeconds
+            ? `Incorrect PIN. Try again in ${nextState.retryAfterSeconds} seconds.`
+            : "Incorrect PIN."
+        );
+      }
+
+      onStateChange(nextState);
+    } catch (error: unknown) {
+      setError(String(error));
+    } finally {
+      setPin("");
+      setIsSubmitting(false);
+      inputRef.current?.focus();
+    }
+  }
+
+  return (
+    <div
+      role="dialog"
+      aria-modal="true"
+      aria-labelledby="workspace-lock-title"
+      style={{
+        maxWidth: 360,
+        width: "100%",
+        margin: "80px auto",
+      }}
+    >
+      <h2 id="workspace-lock-title" style={{ marginTop: 0 }}>
+        {getLocalWorkspaceName()}
+      </h2>
+
+      <p
+        style={{
+          fontSize: 13,
+          color: "var(--text-muted)",
+        }}
+      >
+        {describeReason(state)}
+      </p>
+
+      <form
+        onSubmit={(event) => void handleSubmit(event)}
+        style={{
+          display: "flex",
+          flexDirection: "column",
+          gap: 10,
+        }}
+      >
+        <input
+          ref={inputRef}
+          type="password"
+          inputMode="numeric"
+          autoComplete="current-password"
+          aria-label="PIN"
+          placeholder="PIN"
+          value={pin}
+          disabled={isSubmitting}
+          onChange={(event) => setPin(event.target.value)}
+        />
+
+        {error && (
+          <p
+            role="alert"
+            style={{
+              margin: 0,
+              fontSize: 12,
+              color: "var(--danger, #f87171)",
+            }}
+          >
+            {error}
+          </p>
+        )}
+
+        <Button tone="primary" type="submit" disabled={isSubmitting || !pin}>
+          {isSubmitting ? "Checking…" : "Unlock"}
+        </Button>
+      </form>
+    </div>
+  );
+};
+
+export default WorkspaceLockScreen;
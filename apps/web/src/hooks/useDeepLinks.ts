@@ -0,0 +1,48 @@
+import { useCallback, useEffect } from "react";
+import { useNavigate } from "react-router-dom";
+
+import {
+  subscribeToDeepLinks,
+  takePendingDeepLink,
+  type DeepLink,
+} from "../api/deepLinks";
+import { developerLogger } from "../developer/logger";
+import { toast } from "../toasts/toastStore";
+
+/*
+ * Routes `pioneer://` links from the desktop crate. The workspace lock
+ * screen still comes first; the route is already in place once it is
+ * unlocked.
+ */
+export function useDeepLinks(): void {
+  const navigate = useNavigate();
+
+  const follow = useCallback(
+    (link: DeepLink) => {
+      if (link.kind === "open") {
+        navigate(link.route);
+      } else {
+        toast.error("Unable to open link", {
+          description: link.message,
+        });
+      }
+    },
+    [navigate]
+  );
+
+  useEffect(() => {
+    void takePendingDeepLink()
+      .then((link) => {
+        if (link) follow(link);
+      })
+      .catch((error) => {
+        developerLogger.error(
+          "deep-links",
+          "Unable to read the link the app was opened with",
+          error
+        );
+      });
+
+    return subscribeToDeepLinks(follow);
+  }, [follow]);
+}
@@ -0,0 +1,43 @@
+import { useEffect } from "react";
+
+import { developerLogger } from "../developer/logger";
+import {
+  snoozeReminder,
+  subscribeToReminders,
+} from "../api/reminders";
+import { toast } from "../toasts/toastStore";
+
+const REMINDER_TOAST_DURATION_MS = 15_000;
+const SNOOZE_MINUTES = 10;
+
+/*
+ * Shows reminders fired by the desktop crate as toasts. The native
+ * notification cannot carry actions, so snoozing happens here.
+ */
+export function useReminderNotifications(): void {
+  useEffect(
+    () =>
+      subscribeToReminders((notice) => {
+        toast.info(notice.title, {
+          description: notice.body,
+          duration: REMINDER_TOAST_DURATION_MS,
+          action: {
+            label: `Snooze ${SNOOZE_MINUTES} min`,
+            run: () => {
+              void snoozeReminder(notice.key, SNOOZE_MINUTES).catch(
+                (error) => {
+                  developerLogger.error(
+                    "reminders",
+                    "Failed to snooze reminder",
+                    error
+                  );
+                  toast.error("Couldn't snooze reminder");
+                }
+              );
+            },
+          },
+        });
+      }),
+    []
+  );
+}
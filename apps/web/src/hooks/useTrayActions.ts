@@ -0,0 +1,27 @@
+import { useEffect } from "react";
+import { useNavigate } from "react-router-dom";
+
+import { subscribeToTrayActions } from "../api/tray";
+import { openDocumentSearch } from "../components/GlobalSearch";
+
+export function useTrayActions(): void {
+  const navigate = useNavigate();
+
+  useEffect(
+    () =>
+      subscribeToTrayActions((tray) => {
+        switch (tray.action) {
+          case "quick-add-task":
+            navigate("/tasks?create=1");
+            break;
+          case "open-document":
+            openDocumentSearch();
+            break;
+          case "open":
+            navigate(tray.route);
+            break;
+        }
+      }),
+    [navigate]
+  );
+}
@@ -0,0 +1,27 @@
+import { useEffect, useRef } from "react";
+
+import { setUnsavedDraft } from "../api/closeGuard";
+
+/*
+ * Registers a draft with the desktop close guard while `label` is set.
+ * `save` may change on every render; the latest one is used.
+ */
+export function useUnsavedDraft(
+  key: string,
+  label: string | null,
+  save: () => Promise<boolean>
+): void {
+  const saveRef = useRef(save);
+  saveRef.current = save;
+
+  useEffect(() => {
+    if (label === null) return;
+
+    setUnsavedDraft(key, {
+      label,
+      save: () => saveRef.current(),
+    });
+
+    return () => setUnsavedDraft(key, null);
+  }, [key, label]);
+}
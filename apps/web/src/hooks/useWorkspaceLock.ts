@@ -0,0 +1,42 @@
+import { useEffect, useState } from "react";
+
+import {
+  UNLOCKED_WORKSPACE_STATE,
+  getWorkspaceLockState,
+  startWorkspaceActivityReporting,
+  subscribeToWorkspaceLock,
+} from "../api/workspaceLock";
+import type { WorkspaceLockState } from "../api/workspaceLock";
+
+export function useWorkspaceLock() {
+  const [state, setState] = useState<WorkspaceLockState>(
+    UNLOCKED_WORKSPACE_STATE
+  );
+  const [loaded, setLoaded] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+
+    void getWorkspaceLockState()
+      .then((nextState) => {
+        if (!cancelled) setState(nextState);
+      })
+      .catch((error) => {
+        console.error("Failed to read the workspace lock state", error);
+      })
+      .finally(() => {
+        if (!cancelled) setLoaded(true);
+      });
+
+    const stopListening = subscribeToWorkspaceLock(setState);
+    const stopReporting = startWorkspaceActivityReporting();
+
+    return () => {
+      cancelled = true;
+      stopListening();
+      stopReporting();
+    };
+  }, []);
+
+  return { state, loaded, setState };
+}
@@ -10,9 +10,14 @@ import {
   getSettingsSnapshot,
   subscribeToSettings,
 } from "./api/settings";
+import { hydrateApiServer } from "./api/servers";
+import { hydrateDesktopSession } from "./api/session";
+import { hydrateSessionRecovery } from "./recovery/sessionRecovery";
 import {
   installDeveloperLogging,
 } from "./developer/logger";
+import PopoutWindow from "./components/PopoutWindow";
+import QuickCaptureWindow from "./components/QuickCaptureWindow";
 import ApplicationErrorBoundary from "./components/recovery/ApplicationErrorBoundary";
 
 import "./styles/global.css";
@@ -41,18 +46,53 @@ if (!container) {
   throw new Error("Root element #root not found");
 }
 
-ReactDOM.createRoot(container).render(
-  <React.StrictMode>
-    <HashRouter
-      future={{
-        v7_startTransition: true,
-        v7_relativeSplatPath: true,
-      }}
-    >
-      <ApplicationErrorBoundary>
-        <App />
-      </ApplicationErrorBoundary>
-    </HashRouter>
-  </React.StrictMode>
-);
+/*
+ * On desktop the cloud session is read from the native credential vault,
+ * and the active API server and the previous session's state from the
+ * crate, all asynchronously, so the first render waits for them. In the
+ * browser this resolves immediately.
+ */
+/*
+ * The desktop quick capture window loads the same bundle at #/capture and
+ * renders only the capture form, without the app shell.
+ */
+const isQuickCaptureWindow = window.location.hash.startsWith("#/capture");
+
+/*
+ * Pop-out windows (#/popout/...) show a single document or the calendar
+ * in a shell of their own.
+ */
+const isPopoutWindow = window.location.hash.startsWith("#/popout/");
+
+void Promise.allSettled([
+  hydrateApiServer(),
+  hydrateDesktopSession(),
+  hydrateSessionRecovery(),
+]).finally(() => {
+  if (isQuickCaptureWindow) {
+    ReactDOM.createRoot(container).render(
+      <React.StrictMode>
+        <ApplicationErrorBoundary>
+          <QuickCaptureWindow />
+        </ApplicationErrorBoundary>
+      </React.StrictMode>
+    );
+    return;
+  }
+
+  ReactDOM.createRoot(container).render(
+    <React.StrictMode>
+      <HashRouter
+        future={{
+          v7_startTransition: true,
+          v7_relativeSplatPath: true,
+        }}
+      >
+        <ApplicationErrorBoundary>
+          {isPopoutWindow ? <PopoutWindow /> : <App />}
+        </ApplicationErrorBoundary>
+      </HashRouter>
+    </React.StrictMode>
+  );
+});
 
@@ -1,6 +1,6 @@
 // apps/web/src/pages/CalendarPage.tsx
 import React, { useEffect, useMemo, useState } from "react";
-import { useNavigate } from "react-router-dom";
+import { useLocation, useNavigate } from "react-router-dom";
 import { fetchTasks, Task } from "../api/tasks";
 import {
   deleteEvent,
@@ -18,6 +18,15 @@ import {
 import {
   TASK_PRIORITY_RANK,
 } from "../utils/taskPriority";
+import {
+  exportEventsToIcs,
+  importEventsFromIcs,
+  isCalendarFileSupported,
+} from "../api/calendarFiles";
+import {
+  isPopoutSupported,
+  openCalendarWindow,
+} from "../api/windows";
 import { toast } from "../toasts/toastStore";
 import { useConfirmation } from "../hooks/useConfirmation";
 
@@ -134,6 +143,7 @@ const weekdayLabels = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
 const CalendarPage: React.FC = () => {
   const { confirm, confirmationDialog } = useConfirmation();
   const navigate = useNavigate();
+  const location = useLocation();
   const [currentMonth, setCurrentMonth] = useState<Date>(() =>
     startOfMonth(new Date())
   );
@@ -154,6 +164,20 @@ const CalendarPage: React.FC = () => {
   const [creatingEvent, setCreatingEvent] = useState(false);
   const [deletingEventIds, setDeletingEventIds] =
     useState<Set<string>>(() => new Set());
+  const [transferringFile, setTransferringFile] = useState(false);
+
+  /* `?date=YYYY-MM-DD`, e.g. from a `pioneer://calendar/...` link. */
+  useEffect(() => {
+    const requested = new URLSearchParams(location.search).get("date");
+    const [year, month, day] = (requested ?? "").split("-").map(Number);
+    if (!year || !month || !day) return;
+
+    const date = new Date(year, month - 1, day);
+    if (date.getMonth() !== month - 1) return;
+
+    setCurrentMonth(startOfMonth(date));
+    setSelectedDay(date);
+  }, [location.search]);
 
   const today = useMemo(() => {
     const d = new Date();
@@ -389,6 +413,71 @@ const CalendarPage: React.FC = () => {
     }
   }
 
+  async function handleImportIcs() {
+    try {
+      setTransferringFile(true);
+
+      // Recurring events are added for a year from the displayed month.
+      const created = await importEventsFromIcs({
+        from: monthRange.from,
+        to: addMonths(monthRange.from, 12),
+      });
+
+      if (!created) return;
+
+      setEvents((prev) => [...prev, ...created]);
+      toast.success(
+        created.length === 1
+          ? "1 event imported"
+          : `${created.length} events imported`
+      );
+    } catch (importError) {
+      console.error("Error importing calendar:", importError);
+      toast.error("Unable to import calendar", {
+        description: String(importError),
+      });
+    } finally {
+      setTransferringFile(false);
+    }
+  }
+
+  async function handleExportIcs() {
+    try {
+      setTransferringFile(true);
+
+      const month = monthRange.from;
+      const exported = await exportEventsToIcs(
+        monthRange,
+        `calendar-${month.getFullYear()}-${String(
+          month.getMonth() + 1
+        ).padStart(2, "0")}`
+      );
+
+      if (exported === null) return;
+
+      toast.success("Calendar exported", {
+        description:
+          exported === 1 ? "1 event" : `${exported} events`,
+      });
+    } catch (exportError) {
+      console.error("Error exporting calendar:", exportError);
+      toast.error("Unable to export calendar");
+    } finally {
+      setTransferringFile(false);
+    }
+  }
+
+  async function handleOpenInWindow() {
+    try {
+      await openCalendarWindow();
+    } catch (windowError) {
+      console.error("Error opening calendar window:", windowError);
+      toast.error("Unable to open the calendar in a new window");
+    }
+  }
+
+  const isPopout = location.pathname.startsWith("/popout/");
+
   const selectedDayKey = selectedDay
     ? getLocalDateKey(selectedDay)
     : null;
@@ -486,6 +575,59 @@ const CalendarPage: React.FC = () => {
           >
             Next
           </button>
+          {isCalendarFileSupported() && (
+            <>
+              <button
+                type="button"
+                onClick={() => void handleImportIcs()}
+                disabled={transferringFile}
+                style={{
+                  padding: "6px 10px",
+                  borderRadius: 999,
+                  border: "1px solid var(--border-strong)",
+                  background: "transparent",
+                  color: "var(--text)",
+                  fontSize: 13,
+                  cursor: transferringFile ? "wait" : "pointer",
+                }}
+              >
+                Import .ics
+              </button>
+              <button
+                type="button"
+                onClick={() => void handleExportIcs()}
+                disabled={transferringFile}
+                style={{
+                  padding: "6px 10px",
+                  borderRadius: 999,
+                  border: "1px solid var(--border-strong)",
+                  background: "transparent",
+                  color: "var(--text)",
+                  fontSize: 13,
+                  cursor: transferringFile ? "wait" : "pointer",
+                }}
+              >
+                Export month
+              </button>
+            </>
+          )}
+          {isPopoutSupported() && !isPopout && (
+            <button
+              type="button"
+              onClick={() => void handleOpenInWindow()}
+              style={{
+                padding: "6px 10px",
+                borderRadius: 999,
+                border: "1px solid var(--border-strong)",
+                background: "transparent",
+                color: "var(--text)",
+                fontSize: 13,
+                cursor: "pointer",
+              }}
+            >
+              New window
+            </button>
+          )}
         </div>
 
         <div style={{ fontSize: 15, fontWeight: 600 }}>
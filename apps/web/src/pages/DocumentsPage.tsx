@@ -6,6 +6,7 @@ import React, {
   useRef,
   useState,
 } from "react";
+import { useLocation } from "react-router-dom";
 import ReactQuill from "react-quill";
 import "react-quill/dist/quill.snow.css";
 
@@ -19,6 +20,10 @@ import {
   fetchDocuments,
   updateDocument,
 } from "../api/documents";
+import {
+  isPopoutSupported,
+  openDocumentWindow,
+} from "../api/windows";
 import {
   useCommands,
 } from "../commands/useCommands";
@@ -34,6 +39,7 @@ import type {
 import { useStatusBarItems } from "../hooks/useStatusBarItems";
 import { useAppSettings } from "../hooks/useAppSettings";
 import { useConfirmation } from "../hooks/useConfirmation";
+import { useUnsavedDraft } from "../hooks/useUnsavedDraft";
 import type { StatusBarItem } from "../status/statusRegistry";
 import { toast } from "../toasts/toastStore";
 import { developerLogger } from "../developer/logger";
@@ -43,6 +49,15 @@ import {
   writeDocumentRecoveryDraft,
 } from "../recovery/documentRecovery";
 import type { DocumentRecoveryDraft } from "../recovery/documentRecovery";
+import {
+  type DocumentExportFormat,
+  exportDocumentToFile,
+  isNativeDocumentExportSupported,
+} from "../api/documentExport";
+import {
+  importDocumentsFromFiles,
+  isDocumentImportSupported,
+} from "../api/documentImport";
 import {
   calculateDocumentStatistics,
   exportDocumentAsHtml,
@@ -57,6 +72,19 @@ import {
 import "../styles/documents.css";
 
 const LAST_DOC_KEY = "suite:lastDocumentId";
+
+const EXPORT_FORMAT_LABELS: Record<DocumentExportFormat, string> = {
+  markdown: "Markdown",
+  html: "HTML",
+  text: "TXT",
+  pdf: "PDF",
+};
+
+// The browser build can only download TXT and HTML.
+const EXPORT_FORMATS: DocumentExportFormat[] =
+  isNativeDocumentExportSupported()
+    ? ["markdown", "html", "text", "pdf"]
+    : ["text", "html"];
 const RECENT_DOCUMENT_LIMIT = 12;
 
 type LibraryView =
@@ -137,7 +165,9 @@ const DocumentsPage: React.FC = () => {
   const quillRef = useRef<any>(null);
   const fileInputRef =
     useRef<HTMLInputElement | null>(null);
+  const location = useLocation();
   const createQueryHandledRef = useRef(false);
+  const followedSearchRef = useRef(location.search);
   const savePromiseRef =
     useRef<Promise<boolean> | null>(null);
 
@@ -168,6 +198,8 @@ const DocumentsPage: React.FC = () => {
     useState(false);
   const [duplicating, setDuplicating] =
     useState(false);
+  const [importing, setImporting] =
+    useState(false);
   const [deletingId, setDeletingId] =
     useState<string | null>(null);
   const [lastSavedAt, setLastSavedAt] =
@@ -595,6 +627,14 @@ const DocumentsPage: React.FC = () => {
       selectedDocument,
     ]);
 
+  useUnsavedDraft(
+    "documents",
+    hasLocalChanges && selectedDocument
+      ? editTitle.trim() || "Untitled document"
+      : null,
+    saveCurrentDocument
+  );
+
   async function saveBeforeLeaving(): Promise<boolean> {
     if (!hasLocalChanges) {
       return true;
@@ -624,9 +664,7 @@ const DocumentsPage: React.FC = () => {
         setDocuments(sorted);
 
         const searchParams =
-          typeof window !== "undefined"
-            ? new URLSearchParams(window.location.search)
-            : new URLSearchParams();
+          new URLSearchParams(location.search);
 
         const createRequested =
           searchParams.get("create") === "1";
@@ -1007,6 +1045,40 @@ const DocumentsPage: React.FC = () => {
     setSelectedDocumentState(next);
   }
 
+  /*
+   * A link to a document while the page is already open, e.g. from search
+   * or a `pioneer://` URL. The first load handles the link the page was
+   * opened with.
+   */
+  useEffect(() => {
+    if (
+      listLoading ||
+      location.search === followedSearchRef.current
+    ) {
+      return;
+    }
+
+    const requestedId =
+      new URLSearchParams(location.search).get(
+        "document"
+      );
+
+    if (
+      requestedId &&
+      !documents.some(
+        (document) => document.id === requestedId
+      )
+    ) {
+      return;
+    }
+
+    followedSearchRef.current = location.search;
+
+    if (requestedId) {
+      void handleSelectDocument(requestedId);
+    }
+  }, [documents, listLoading, location.search]);
+
   async function handleCreateDocument(): Promise<void> {
     if (creating) {
       return;
@@ -1111,6 +1183,34 @@ const DocumentsPage: React.FC = () => {
     }
   }
 
+  async function handleOpenInWindow(): Promise<void> {
+    if (!selectedDocument) {
+      return;
+    }
+
+    const canLeave =
+      await saveBeforeLeaving();
+
+    if (!canLeave) {
+      return;
+    }
+
+    try {
+      await openDocumentWindow(
+        selectedDocument.id
+      );
+    } catch (error) {
+      developerLogger.error(
+        "documents",
+        "Unable to open document window",
+        error
+      );
+      toast.error(
+        "Unable to open the document in a new window"
+      );
+    }
+  }
+
   async function handleTogglePinned(
     document: Document
   ): Promise<void> {
@@ -1323,39 +1423,108 @@ const DocumentsPage: React.FC = () => {
     }
   }
 
-  function handleExportText(): void {
-    if (!selectedDocument) return;
+  async function handleImport(): Promise<void> {
+    setImporting(true);
 
     try {
-      exportDocumentAsText(editTitle, editContent);
-      toast.success("TXT export complete", {
-        description: editTitle || "Untitled document",
-      });
+      const result =
+        await importDocumentsFromFiles();
+
+      if (!result) return;
+
+      const { documents: imported, failures } =
+        result;
+
+      if (imported.length > 0) {
+        const importedIds = new Set(
+          imported.map((document) => document.id)
+        );
+
+        setDocuments((current) =>
+          sortDocumentsByPinnedThenUpdated([
+            ...imported,
+            ...current.filter(
+              (document) =>
+                !importedIds.has(document.id)
+            ),
+          ])
+        );
+      }
+
+      for (const failure of failures) {
+        developerLogger.warning(
+          "documents.import",
+          `Unable to import ${failure.path}`,
+          failure.message
+        );
+      }
+
+      if (failures.length === 0) {
+        toast.success(
+          imported.length === 1
+            ? "Document imported"
+            : `${imported.length} documents imported`,
+          {
+            description:
+              imported.length === 1
+                ? imported[0].title
+                : undefined,
+          }
+        );
+      } else if (imported.length > 0) {
+        toast.warning(
+          `${imported.length} imported, ${failures.length} skipped`,
+          { description: failures[0].message }
+        );
+      } else {
+        toast.error("Import failed", {
+          description: failures[0].message,
+        });
+      }
     } catch (error) {
       developerLogger.error(
-        "documents.export",
-        "Unable to export a document as text",
+        "documents.import",
+        "Unable to import documents",
         error
       );
-      toast.error("TXT export failed");
+      toast.error("Import failed");
+    } finally {
+      setImporting(false);
     }
   }
 
-  function handleExportHtml(): void {
+  async function handleExport(
+    format: DocumentExportFormat
+  ): Promise<void> {
     if (!selectedDocument) return;
 
+    const label = EXPORT_FORMAT_LABELS[format];
+
     try {
-      exportDocumentAsHtml(editTitle, editContent);
-      toast.success("HTML export complete", {
+      if (isNativeDocumentExportSupported()) {
+        const saved = await exportDocumentToFile(
+          { title: editTitle, content: editContent },
+          format
+        );
+        if (!saved) return;
+      } else if (format === "html") {
+        exportDocumentAsHtml(editTitle, editContent);
+      } else {
+        exportDocumentAsText(editTitle, editContent);
+      }
+
+      toast.success(`${label} export complete`, {
         description: editTitle || "Untitled document",
       });
     } catch (error) {
       developerLogger.error(
         "documents.export",
-        "Unable to export a document as HTML",
+        `Unable to export a document as ${label}`,
         error
       );
-      toast.error("HTML export failed");
+      toast.error(`${label} export failed`, {
+        description: String(error),
+      });
     }
   }
 
@@ -1696,40 +1865,46 @@ const DocumentsPage: React.FC = () => {
           run: () =>
             handleDuplicate(),
         },
-        {
-          id: "documents-export-text",
-          title:
-            "Export current document as TXT",
-          category: "Documents",
-          description:
-            "Download a plain-text copy",
-          keywords: [
-            "download",
-            "text",
-          ],
-          enabled:
-            Boolean(selectedDocument),
-          disabledReason:
-            "Select a document first.",
-          run: handleExportText,
-        },
-        {
-          id: "documents-export-html",
-          title:
-            "Export current document as HTML",
+        ...(isPopoutSupported()
+          ? [
+              {
+                id: "documents-open-window",
+                title: "Open current document in new window",
+                category: "Documents",
+                description:
+                  "Edit the active document in a separate window",
+                keywords: [
+                  "pop out",
+                  "window",
+                  "detach",
+                ],
+                enabled: Boolean(selectedDocument),
+                disabledReason:
+                  "Select a document first.",
+                run: () =>
+                  handleOpenInWindow(),
+              },
+            ]
+          : []),
+        ...EXPORT_FORMATS.map((format) => ({
+          id: `documents-export-${format}`,
+          title: `Export current document as ${EXPORT_FORMAT_LABELS[format]}`,
           category: "Documents",
-          description:
-            "Download a formatted HTML copy",
+          description: isNativeDocumentExportSupported()
+            ? `Save a ${EXPORT_FORMAT_LABELS[format]} copy`
+            : `Download a ${EXPORT_FORMAT_LABELS[format]} copy`,
           keywords: [
             "download",
-            "web",
+            "save",
+            format,
           ],
           enabled:
             Boolean(selectedDocument),
           disabledReason:
             "Select a document first.",
-          run: handleExportHtml,
-        },
+          run: () =>
+            void handleExport(format),
+        })),
         {
           id: "documents-toggle-pin",
           title: selectedDocument?.isPinned
@@ -1787,6 +1962,8 @@ const DocumentsPage: React.FC = () => {
             "Show the complete document library",
           enabled:
             libraryView !== "all",
+          checked:
+            libraryView === "all",
           disabledReason:
             "The complete library is already shown.",
           run: () =>
@@ -1800,6 +1977,8 @@ const DocumentsPage: React.FC = () => {
             "Show recently edited documents",
           enabled:
             libraryView !== "recent",
+          checked:
+            libraryView === "recent",
           disabledReason:
             "Recent documents are already shown.",
           run: () =>
@@ -1813,6 +1992,8 @@ const DocumentsPage: React.FC = () => {
             "Filter the library to pinned documents",
           enabled:
             libraryView !== "pinned",
+          checked:
+            libraryView === "pinned",
           disabledReason:
             "Pinned documents are already shown.",
           run: () =>
@@ -1827,6 +2008,8 @@ const DocumentsPage: React.FC = () => {
             "Filter the library to favorite documents",
           enabled:
             libraryView !== "favorites",
+          checked:
+            libraryView === "favorites",
           disabledReason:
             "Favorite documents are already shown.",
           run: () =>
@@ -1905,18 +2088,30 @@ const DocumentsPage: React.FC = () => {
           </p>
         </div>
 
-        <button
-          className="documents-v2-new-button"
-          type="button"
-          onClick={() =>
-            void handleCreateDocument()
-          }
-          disabled={creating}
-        >
-          {creating
-            ? "Creating…"
-            : "New document"}
-        </button>
+        <div className="documents-v2-header-actions">
+          {isDocumentImportSupported() && (
+            <button
+              className="documents-v2-import-button"
+              type="button"
+              onClick={() => void handleImport()}
+              disabled={importing}
+            >
+              {importing ? "Importing…" : "Import…"}
+            </button>
+          )}
+          <button
+            className="documents-v2-new-button"
+            type="button"
+            onClick={() =>
+              void handleCreateDocument()
+            }
+            disabled={creating}
+          >
+            {creating
+              ? "Creating…"
+              : "New document"}
+          </button>
+        </div>
       </header>
 
       <div className="documents-v2-layout">
@@ -2218,24 +2413,34 @@ const DocumentsPage: React.FC = () => {
                         : "Duplicate"}
                     </button>
 
+                    {isPopoutSupported() && (
+                      <button
+                        type="button"
+                        onClick={() =>
+                          void handleOpenInWindow()
+                        }
+                      >
+                        New window
+                      </button>
+                    )}
+
                     <div className="documents-v2-export-menu">
                       <details>
                         <summary>
                           Export
                         </summary>
                         <div>
-                          <button
-                            type="button"
-                            onClick={handleExportText}
-                          >
-                            Export TXT
-                          </button>
-                          <button
-                            type="button"
-                            onClick={handleExportHtml}
-                          >
-                            Export HTML
-                          </button>
+                          {EXPORT_FORMATS.map((format) => (
+                            <button
+                              key={format}
+                              type="button"
+                              onClick={() =>
+                                void handleExport(format)
+                              }
+                            >
+                              Export {EXPORT_FORMAT_LABELS[format]}
+                            </button>
+                          ))}
                         </div>
                       </details>
                     </div>
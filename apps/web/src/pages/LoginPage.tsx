@@ -61,7 +61,7 @@ const LoginPage: React.FC = () => {
         password
       );
 
-      connectCloudSession(user, token);
+      await connectCloudSession(user, token);
       openConfiguredStartupPage();
     } catch (error: unknown) {
       console.error("Login error:", error);
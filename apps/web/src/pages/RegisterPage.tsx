@@ -69,7 +69,7 @@ const RegisterPage: React.FC = () => {
         password
       );
 
-      connectCloudSession(user, token);
+      await connectCloudSession(user, token);
       openConfiguredStartupPage();
     } catch (error: unknown) {
       console.error("Register error:", error);
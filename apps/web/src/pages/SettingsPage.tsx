@@ -1,8 +1,79 @@
 import React, { useEffect, useState } from "react";
 
+import {
+  exportWorkspaceToFile,
+  importWorkspaceFromFile,
+  isWorkspaceBackupSupported,
+} from "../api/backup";
+import {
+  type CaptureKind,
+  type CaptureShortcuts,
+  getCaptureShortcuts,
+  isCaptureSupported,
+  setCaptureShortcuts,
+} from "../api/capture";
 import { fetchDocuments, refreshPendingDocumentSyncCount } from "../api/documents";
 import { fetchEvents, refreshPendingEventSyncCount } from "../api/events";
-import { getWorkspaceName, hasCloudSession } from "../api/session";
+import {
+  type ReminderNotice,
+  type ReminderSettings,
+  dismissReminder,
+  getReminderSettings,
+  isReminderSupported,
+  listReminders,
+  setReminderSettings,
+  snoozeReminder,
+} from "../api/reminders";
+import {
+  type ServerProfile,
+  type ServerSettings,
+  DEFAULT_SERVER_ID,
+  checkServer,
+  deleteServer,
+  isServerProfileSupported,
+  listServers,
+  saveServer,
+  setActiveServer,
+} from "../api/servers";
+import {
+  type LocalApiStatus,
+  LOCAL_SERVER_ID,
+  getLocalApiStatus,
+  isLocalApiSupported,
+  setLocalApiSettings,
+} from "../api/localApi";
+import {
+  type NetworkSettings,
+  type ProxyMode,
+  type ProxySettings,
+  getNetworkSettings,
+  importRootCertificate,
+  isNetworkSettingsSupported,
+  removeRootCertificate,
+  setProxySettings,
+} from "../api/network";
+import {
+  type CredentialVaultStatus,
+  SESSION_CHANGED_EVENT,
+  disconnectCloudSession,
+  getCredentialVaultStatus,
+  getWorkspaceName,
+  hasCloudSession,
+  isCredentialVaultSupported,
+  setCredentialVaultPassphrase,
+  unlockCredentialVault,
+} from "../api/session";
+import {
+  type SnapshotFrequency,
+  type SnapshotInfo,
+  type SnapshotSettings,
+  createSnapshot,
+  getSnapshotSettings,
+  isSnapshotSupported,
+  listSnapshots,
+  restoreSnapshot,
+  setSnapshotSettings,
+} from "../api/snapshots";
 import {
   type AccentPreference,
   type AppSettingsPatch,
@@ -20,7 +91,16 @@ import {
   resetSettings,
   updateSettings,
 } from "../api/settings";
+import { getStoragePath, rekeyLocalStore } from "../api/storage";
 import { fetchTasks, refreshPendingTaskSyncCount } from "../api/tasks";
+import {
+  type WorkspaceLockState,
+  getWorkspaceLockState,
+  isWorkspaceLockSupported,
+  lockWorkspace,
+  setIdleLockTimeout,
+  setLocalPin,
+} from "../api/workspaceLock";
 import DeveloperConsole from "../components/developer/DeveloperConsole";
 import { openShortcutReference } from "../components/KeyboardShortcutsManager";
 import Button from "../components/ui/Button";
@@ -98,12 +178,101 @@ const Toggle: React.FC<ToggleProps> = ({ checked, label, disabled, onChange }) =
   </label>
 );
 
+const EMPTY_SERVER_DRAFT: ServerProfile = {
+  id: "",
+  name: "",
+  baseUrl: "",
+  caCertificate: null,
+  timeoutSeconds: 30,
+};
+
+function describeServer(profile: ServerProfile): string {
+  const details = [profile.baseUrl, `${profile.timeoutSeconds} s timeout`];
+  if (profile.caCertificate) details.push("custom CA");
+  return details.join(" · ");
+}
+
+const IDLE_LOCK_OPTIONS = [
+  { value: 0, label: "Never" },
+  { value: 5 * 60, label: "5 minutes" },
+  { value: 15 * 60, label: "15 minutes" },
+  { value: 30 * 60, label: "30 minutes" },
+  { value: 60 * 60, label: "1 hour" },
+];
+
+const KEEP_HOURLY_OPTIONS = [0, 6, 12, 24, 48];
+const KEEP_DAILY_OPTIONS = [0, 3, 7, 14, 30];
+
+const TASK_LEAD_OPTIONS = [
+  { value: 0, label: "When due" },
+  { value: 60, label: "1 hour before" },
+  { value: 24 * 60, label: "1 day before" },
+];
+
+const EVENT_LEAD_OPTIONS = [
+  { value: 0, label: "At start" },
+  { value: 5, label: "5 minutes before" },
+  { value: 15, label: "15 minutes before" },
+  { value: 30, label: "30 minutes before" },
+  { value: 60, label: "1 hour before" },
+  { value: 24 * 60, label: "1 day before" },
+];
+
+const UPCOMING_REMINDER_LIMIT = 10;
+
+const CAPTURE_SHORTCUT_ROWS: Array<[CaptureKind, string, string]> = [
+  ["task", "Capture a task", "Opens the quick capture window for a new task."],
+  ["note", "Capture a note", "Opens it for a new document."],
+  ["event", "Capture an event", "Opens it for a new calendar event."],
+];
+
+function leadValue(leads: number[]): string {
+  return leads.length === 0 ? "off" : String(leads[0]);
+}
+
+function parseLead(value: string): number[] {
+  return value === "off" ? [] : [Number(value)];
+}
+
+function formatSnapshotSize(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${Math.max(1, Math.round(bytes / 1024))} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 const SettingsPage: React.FC = () => {
   const settings = useAppSettings();
   const { confirm, confirmationDialog } = useConfirmation();
   const [saving, setSaving] = useState(false);
   const [resetting, setResetting] = useState(false);
   const [diagnostics, setDiagnostics] = useState(EMPTY_DIAGNOSTICS);
+  const [lockState, setLockState] = useState<WorkspaceLockState | null>(null);
+  const [currentPin, setCurrentPin] = useState("");
+  const [newPin, setNewPin] = useState("");
+  const [savingPin, setSavingPin] = useState(false);
+  const [rekeying, setRekeying] = useState(false);
+  const [vaultStatus, setVaultStatus] = useState<CredentialVaultStatus | null>(null);
+  const [vaultPassphrase, setVaultPassphrase] = useState("");
+  const [vaultBusy, setVaultBusy] = useState(false);
+  const [backupBusy, setBackupBusy] = useState(false);
+  const [snapshotSettings, setSnapshotSettingsState] = useState<SnapshotSettings | null>(null);
+  const [snapshots, setSnapshots] = useState<SnapshotInfo[]>([]);
+  const [storagePath, setStoragePath] = useState<string | null>(null);
+  const [snapshotBusy, setSnapshotBusy] = useState(false);
+  const [snapshotPin, setSnapshotPin] = useState("");
+  const [reminderSettings, setReminderSettingsState] = useState<ReminderSettings | null>(null);
+  const [reminders, setReminders] = useState<ReminderNotice[]>([]);
+  const [captureShortcuts, setCaptureShortcutsState] = useState<CaptureShortcuts | null>(null);
+  const [captureDraft, setCaptureDraft] = useState<CaptureShortcuts | null>(null);
+  const [savingCapture, setSavingCapture] = useState(false);
+  const [serverSettings, setServerSettings] = useState<ServerSettings | null>(null);
+  const [serverDraft, setServerDraft] = useState<ServerProfile>(EMPTY_SERVER_DRAFT);
+  const [serverBusy, setServerBusy] = useState(false);
+  const [localApi, setLocalApi] = useState<LocalApiStatus | null>(null);
+  const [localApiPort, setLocalApiPort] = useState("");
+  const [networkSettings, setNetworkSettings] = useState<NetworkSettings | null>(null);
+  const [proxyDraft, setProxyDraft] = useState<ProxySettings | null>(null);
+  const [proxyPassword, setProxyPassword] = useState("");
+  const [networkBusy, setNetworkBusy] = useState(false);
 
   async function applySettings(patch: AppSettingsPatch): Promise<void> {
     setSaving(true);
@@ -176,6 +345,445 @@ const SettingsPage: React.FC = () => {
     void loadDiagnostics();
   }, []);
 
+  useEffect(() => {
+    if (!isWorkspaceLockSupported()) return;
+    void getWorkspaceLockState().then(setLockState).catch((error) => {
+      console.error("Unable to read the workspace lock state:", error);
+    });
+  }, []);
+
+  useEffect(() => {
+    if (!isCredentialVaultSupported()) return;
+
+    const loadVaultStatus = () => {
+      void getCredentialVaultStatus().then(setVaultStatus).catch((error) => {
+        console.error("Unable to read the credential vault:", error);
+      });
+    };
+
+    loadVaultStatus();
+    window.addEventListener(SESSION_CHANGED_EVENT, loadVaultStatus);
+    return () => window.removeEventListener(SESSION_CHANGED_EVENT, loadVaultStatus);
+  }, []);
+
+  async function handleUnlockVault(): Promise<void> {
+    setVaultBusy(true);
+    try {
+      await unlockCredentialVault(vaultPassphrase);
+      setVaultPassphrase("");
+      setVaultStatus(await getCredentialVaultStatus());
+      toast.success("Signed in again");
+    } catch (error) {
+      console.error("Unable to unlock the credential vault:", error);
+      toast.error(String(error));
+    } finally {
+      setVaultBusy(false);
+    }
+  }
+
+  async function saveVaultPassphrase(passphrase: string | null): Promise<void> {
+    setVaultBusy(true);
+    try {
+      setVaultStatus(await setCredentialVaultPassphrase(passphrase));
+      setVaultPassphrase("");
+      toast.success(passphrase ? "Passphrase saved" : "Passphrase removed");
+    } catch (error) {
+      console.error("Unable to update the vault passphrase:", error);
+      toast.error(String(error));
+    } finally {
+      setVaultBusy(false);
+    }
+  }
+
+  async function loadSnapshots(): Promise<void> {
+    try {
+      const [nextSettings, nextSnapshots] = await Promise.all([getSnapshotSettings(), listSnapshots()]);
+      setSnapshotSettingsState(nextSettings);
+      setSnapshots(nextSnapshots);
+    } catch (error) {
+      console.error("Unable to read snapshots:", error);
+    }
+  }
+
+  useEffect(() => {
+    if (!isSnapshotSupported()) return;
+    void loadSnapshots();
+  }, []);
+
+  async function applySnapshotSettings(patch: Partial<SnapshotSettings>): Promise<void> {
+    if (!snapshotSettings) return;
+    try {
+      setSnapshotSettingsState(await setSnapshotSettings({ ...snapshotSettings, ...patch }));
+      setSnapshots(await listSnapshots());
+      toast.success("Settings saved");
+    } catch (error) {
+      console.error("Unable to save snapshot settings:", error);
+      toast.error(String(error));
+    }
+  }
+
+  async function handleCreateSnapshot(): Promise<void> {
+    setSnapshotBusy(true);
+    try {
+      await createSnapshot();
+      setSnapshots(await listSnapshots());
+      toast.success("Snapshot taken");
+    } catch (error) {
+      console.error("Unable to take a snapshot:", error);
+      toast.error(String(error));
+    } finally {
+      setSnapshotBusy(false);
+    }
+  }
+
+  async function handleRestoreSnapshot(snapshot: SnapshotInfo): Promise<void> {
+    const accepted = await confirm({
+      title: "Restore this snapshot?",
+      description: `Every task, document, calendar event, and pending change on this device is replaced by the snapshot from ${new Date(snapshot.createdAt).toLocaleString()}. A snapshot of the current state is taken first.`,
+      confirmLabel: "Restore",
+      dangerous: true,
+    });
+    if (!accepted) return;
+
+    setSnapshotBusy(true);
+    try {
+      const counts = await restoreSnapshot(snapshot.id, snapshotPin || null);
+      setSnapshotPin("");
+      toast.success(`Restored ${counts.tasks} tasks, ${counts.documents} documents, ${counts.events} events`);
+      setSnapshots(await listSnapshots());
+      await loadDiagnostics();
+    } catch (error) {
+      console.error("Unable to restore the snapshot:", error);
+      toast.error(String(error));
+    } finally {
+      setSnapshotBusy(false);
+    }
+  }
+
+  async function loadReminders(): Promise<void> {
+    try {
+      const [nextSettings, nextReminders] = await Promise.all([getReminderSettings(), listReminders()]);
+      setReminderSettingsState(nextSettings);
+      setReminders(nextReminders);
+    } catch (error) {
+      console.error("Unable to read reminders:", error);
+    }
+  }
+
+  useEffect(() => {
+    if (!isReminderSupported()) return;
+    void loadReminders();
+  }, []);
+
+  async function applyReminderSettings(patch: Partial<ReminderSettings>): Promise<void> {
+    if (!reminderSettings) return;
+    try {
+      setReminderSettingsState(await setReminderSettings({ ...reminderSettings, ...patch }));
+      setReminders(await listReminders());
+      toast.success("Settings saved");
+    } catch (error) {
+      console.error("Unable to save reminder settings:", error);
+      toast.error(String(error));
+    }
+  }
+
+  async function handleReminderAction(action: () => Promise<void>): Promise<void> {
+    try {
+      await action();
+      setReminders(await listReminders());
+    } catch (error) {
+      console.error("Unable to update the reminder:", error);
+      toast.error(String(error));
+    }
+  }
+
+  useEffect(() => {
+    if (!isCaptureSupported()) return;
+    void getCaptureShortcuts()
+      .then((shortcuts) => {
+        setCaptureShortcutsState(shortcuts);
+        setCaptureDraft(shortcuts);
+      })
+      .catch((error) => {
+        console.error("Unable to read quick capture shortcuts:", error);
+      });
+  }, []);
+
+  async function handleSaveCaptureShortcuts(): Promise<void> {
+    if (!captureDraft) return;
+    setSavingCapture(true);
+    try {
+      const saved = await setCaptureShortcuts(captureDraft);
+      setCaptureShortcutsState(saved);
+      setCaptureDraft(saved);
+      toast.success("Settings saved");
+    } catch (error) {
+      console.error("Unable to save quick capture shortcuts:", error);
+      toast.error(String(error));
+    } finally {
+      setSavingCapture(false);
+    }
+  }
+
+  useEffect(() => {
+    if (!isServerProfileSupported()) return;
+    void listServers()
+      .then(setServerSettings)
+      .catch((error) => {
+        console.error("Unable to read the server profiles:", error);
+      });
+  }, []);
+
+  useEffect(() => {
+    if (!isLocalApiSupported()) return;
+    void getLocalApiStatus()
+      .then((status) => {
+        setLocalApi(status);
+        setLocalApiPort(String(status.port));
+      })
+      .catch((error) => {
+        console.error("Unable to read the local server status:", error);
+      });
+  }, []);
+
+  useEffect(() => {
+    void getStoragePath()
+      .then(setStoragePath)
+      .catch((error) => {
+        console.error("Unable to read the storage path:", error);
+      });
+  }, []);
+
+  useEffect(() => {
+    if (!isNetworkSettingsSupported()) return;
+    void getNetworkSettings()
+      .then((network) => {
+        setNetworkSettings(network);
+        setProxyDraft(network.proxy);
+      })
+      .catch((error) => {
+        console.error("Unable to read the network settings:", error);
+      });
+  }, []);
+
+  async function handleCheckServer(profile: ServerProfile): Promise<void> {
+    setServerBusy(true);
+    try {
+      const check = await checkServer(profile);
+      if (check.reachable) {
+        toast.success(check.message, { description: `Answered in ${check.latencyMs} ms` });
+      } else {
+        toast.error("Server not reachable", { description: check.message });
+      }
+    } catch (error) {
+      console.error("Unable to check the server:", error);
+      toast.error(String(error));
+    } finally {
+      setServerBusy(false);
+    }
+  }
+
+  async function handleAddServer(): Promise<void> {
+    setServerBusy(true);
+    try {
+      setServerSettings(await saveServer({ ...serverDraft, id: `server-${Date.now().toString(36)}` }));
+      setServerDraft(EMPTY_SERVER_DRAFT);
+      toast.success("Server added");
+    } catch (error) {
+      console.error("Unable to add the server:", error);
+      toast.error(String(error));
+    } finally {
+      setServerBusy(false);
+    }
+  }
+
+  async function handleDeleteServer(profile: ServerProfile): Promise<void> {
+    setServerBusy(true);
+    try {
+      setServerSettings(await deleteServer(profile.id));
+    } catch (error) {
+      console.error("Unable to remove the server:", error);
+      toast.error(String(error));
+    } finally {
+      setServerBusy(false);
+    }
+  }
+
+  async function handleActivateServer(id: string): Promise<void> {
+    const profile = serverSettings?.profiles.find((candidate) => candidate.id === id);
+    if (!profile || id === serverSettings?.active) return;
+
+    const accepted = await confirm({
+      title: `Switch to ${profile.name}?`,
+      description:
+        "You will be signed out of the current server. Local tasks, documents, and events stay on this device, and pending changes upload to the new server once you sign in there.",
+      confirmLabel: "Switch server",
+    });
+    if (!accepted) return;
+
+    setServerBusy(true);
+    try {
+      await setActiveServer(id);
+      disconnectCloudSession();
+      setServerSettings(await listServers());
+      toast.success(`Using ${profile.name}`, { description: "Sign in to this server to sync." });
+    } catch (error) {
+      console.error("Unable to switch servers:", error);
+      toast.error("Unable to switch servers", { description: String(error) });
+    } finally {
+      setServerBusy(false);
+    }
+  }
+
+  async function handleLocalApiChange(enabled: boolean, port: number): Promise<void> {
+    setServerBusy(true);
+    try {
+      const status = await setLocalApiSettings({ enabled, port });
+      setLocalApi(status);
+      setLocalApiPort(String(status.port));
+      setServerSettings(await listServers());
+      if (enabled) {
+        toast.success("Server on this computer is running", {
+          description: `Switch to "This computer" to use it. It listens on ${status.baseUrl}.`,
+        });
+      }
+    } catch (error) {
+      console.error("Unable to change the local server:", error);
+      toast.error("Unable to change the local server", { description: String(error) });
+      setLocalApi(await getLocalApiStatus().catch(() => localApi));
+    } finally {
+      setServerBusy(false);
+    }
+  }
+
+  const activeServer = serverSettings?.profiles.find((profile) => profile.id === serverSettings.active);
+
+  async function handleSaveProxy(draft: ProxySettings): Promise<void> {
+    setNetworkBusy(true);
+    try {
+      // An untouched password field keeps the saved password.
+      const network = await setProxySettings(draft, proxyPassword || undefined);
+      setNetworkSettings(network);
+      setProxyDraft(network.proxy);
+      setProxyPassword("");
+      toast.success("Proxy settings saved", {
+        description: "Sync uses them right away; app updates after a restart.",
+      });
+    } catch (error) {
+      console.error("Unable to save the proxy settings:", error);
+      toast.error("Unable to save the proxy settings", { description: String(error) });
+    } finally {
+      setNetworkBusy(false);
+    }
+  }
+
+  async function handleImportCertificate(): Promise<void> {
+    setNetworkBusy(true);
+    try {
+      const network = await importRootCertificate();
+      if (!network) return;
+      setNetworkSettings(network);
+      toast.success("Root certificate imported");
+    } catch (error) {
+      console.error("Unable to import the root certificate:", error);
+      toast.error("Unable to import the root certificate", { description: String(error) });
+    } finally {
+      setNetworkBusy(false);
+    }
+  }
+
+  async function handleRemoveCertificate(id: string): Promise<void> {
+    setNetworkBusy(true);
+    try {
+      setNetworkSettings(await removeRootCertificate(id));
+    } catch (error) {
+      console.error("Unable to remove the root certificate:", error);
+      toast.error(String(error));
+    } finally {
+      setNetworkBusy(false);
+    }
+  }
+
+  async function handleExportWorkspace(): Promise<void> {
+    setBackupBusy(true);
+    try {
+      const counts = await exportWorkspaceToFile();
+      if (counts) {
+        toast.success(`Backup saved: ${counts.tasks} tasks, ${counts.documents} documents, ${counts.events} events`);
+      }
+    } catch (error) {
+      console.error("Unable to export the workspace:", error);
+      toast.error(String(error));
+    } finally {
+      setBackupBusy(false);
+    }
+  }
+
+  async function handleImportWorkspace(): Promise<void> {
+    const accepted = await confirm({
+      title: "Restore from a backup?",
+      description:
+        "Every task, document, calendar event, pending change, and setting on this device is replaced by the backup's contents. A snapshot of the current tasks, documents, and events is taken first.",
+      confirmLabel: "Choose backup",
+      dangerous: true,
+    });
+    if (!accepted) return;
+
+    setBackupBusy(true);
+    try {
+      const counts = await importWorkspaceFromFile();
+      if (counts) {
+        toast.success(`Restored ${counts.tasks} tasks, ${counts.documents} documents, ${counts.events} events`);
+        await loadDiagnostics();
+        if (isSnapshotSupported()) setSnapshots(await listSnapshots());
+      }
+    } catch (error) {
+      console.error("Unable to import the workspace:", error);
+      toast.error(String(error));
+    } finally {
+      setBackupBusy(false);
+    }
+  }
+
+  async function savePin(pin: string | null): Promise<void> {
+    setSavingPin(true);
+    try {
+      setLockState(await setLocalPin(pin, lockState?.pinSet ? currentPin : null));
+      setCurrentPin("");
+      setNewPin("");
+      toast.success(pin ? "PIN saved" : "PIN removed");
+    } catch (error) {
+      console.error("Unable to update the PIN:", error);
+      toast.error(String(error));
+    } finally {
+      setSavingPin(false);
+    }
+  }
+
+  async function handleRekeyLocalStore(): Promise<void> {
+    setRekeying(true);
+    try {
+      await rekeyLocalStore(lockState?.pinSet ? currentPin : null);
+      setCurrentPin("");
+      toast.success("Local data re-encrypted");
+    } catch (error) {
+      console.error("Unable to re-encrypt the local data:", error);
+      toast.error(String(error));
+    } finally {
+      setRekeying(false);
+    }
+  }
+
+  async function applyIdleLockTimeout(seconds: number): Promise<void> {
+    try {
+      setLockState(await setIdleLockTimeout(seconds));
+      toast.success("Settings saved");
+    } catch (error) {
+      console.error("Unable to save the idle lock timeout:", error);
+      toast.error("Unable to save settings");
+    }
+  }
+
   const totalPending =
     diagnostics.pendingTasks + diagnostics.pendingDocuments + diagnostics.pendingEvents;
 
@@ -301,6 +909,77 @@ const SettingsPage: React.FC = () => {
         </SettingRow>
       </Card>
 
+      {lockState && (
+        <Card aria-labelledby="settings-security">
+          <SectionHeader
+            headingId="settings-security"
+            eyebrow="Security"
+            title="Workspace PIN"
+            description="Require a PIN to open this workspace on this computer. Local data is encrypted with it, and repeated wrong attempts are delayed."
+            actions={lockState.pinSet ? <Button onClick={() => void lockWorkspace()}>Lock now</Button> : undefined}
+          />
+          {lockState.pinSet && (
+            <SettingRow title="Current PIN" description="Required to change or remove the PIN.">
+              <input type="password" inputMode="numeric" autoComplete="current-password" aria-label="Current PIN" value={currentPin} disabled={savingPin} onChange={(event) => setCurrentPin(event.target.value)} />
+            </SettingRow>
+          )}
+          <SettingRow title={lockState.pinSet ? "New PIN" : "PIN"} description="At least 4 characters.">
+            <input type="password" inputMode="numeric" autoComplete="new-password" aria-label={lockState.pinSet ? "New PIN" : "PIN"} value={newPin} disabled={savingPin} onChange={(event) => setNewPin(event.target.value)} />
+          </SettingRow>
+          <div className="settings-reset">
+            <div><strong>{lockState.pinSet ? "Change PIN" : "Set PIN"}</strong><span>The workspace locks on startup once a PIN is set.</span></div>
+            <div>
+              {lockState.pinSet && <Button tone="danger" disabled={savingPin || !currentPin} onClick={() => void savePin(null)}>Remove PIN</Button>}
+              <Button tone="primary" disabled={savingPin || newPin.length < 4 || (lockState.pinSet && !currentPin)} onClick={() => void savePin(newPin)}>{savingPin ? "Saving…" : "Save PIN"}</Button>
+            </div>
+          </div>
+          <SettingRow title="Lock when idle" description="Lock the workspace after this long without keyboard or pointer input.">
+            <select value={lockState.idleTimeoutSeconds} disabled={!lockState.pinSet} onChange={(event) => void applyIdleLockTimeout(Number(event.target.value))}>
+              {IDLE_LOCK_OPTIONS.map((option) => <option key={option.value} value={option.value}>{option.label}</option>)}
+            </select>
+          </SettingRow>
+          <div className="settings-reset">
+            <div><strong>Re-encrypt local data</strong><span>{lockState.pinSet ? "Replaces the key your local data is encrypted with. Enter the current PIN first." : "Replaces the key your local data is encrypted with, for example after a copy of your files may have been taken."}</span></div>
+            <div><Button disabled={savingPin || rekeying || (lockState.pinSet && !currentPin)} onClick={() => void handleRekeyLocalStore()}>{rekeying ? "Re-encrypting…" : "Re-encrypt"}</Button></div>
+          </div>
+        </Card>
+      )}
+
+      {vaultStatus?.exists && (
+        <Card aria-labelledby="settings-vault">
+          <SectionHeader
+            headingId="settings-vault"
+            eyebrow="Security"
+            title="Saved sign-in"
+            description="Your cloud sign-in is kept encrypted on this computer. A passphrase keeps it closed until you enter it after each start, even to someone with a copy of your files."
+          />
+          {vaultStatus.locked ? (
+            <>
+              <SettingRow title="Passphrase" description="Enter it to sign in to the cloud for this session.">
+                <input type="password" autoComplete="current-password" aria-label="Vault passphrase" value={vaultPassphrase} disabled={vaultBusy} onChange={(event) => setVaultPassphrase(event.target.value)} />
+              </SettingRow>
+              <div className="settings-reset">
+                <div><strong>Unlock sign-in</strong><span>Syncing resumes once the sign-in is unlocked.</span></div>
+                <div><Button tone="primary" disabled={vaultBusy || !vaultPassphrase} onClick={() => void handleUnlockVault()}>{vaultBusy ? "Unlocking…" : "Unlock"}</Button></div>
+              </div>
+            </>
+          ) : (
+            <>
+              <SettingRow title={vaultStatus.keySource === "passphrase" ? "New passphrase" : "Passphrase"} description="At least 8 characters.">
+                <input type="password" autoComplete="new-password" aria-label="New vault passphrase" value={vaultPassphrase} disabled={vaultBusy} onChange={(event) => setVaultPassphrase(event.target.value)} />
+              </SettingRow>
+              <div className="settings-reset">
+                <div><strong>{vaultStatus.keySource === "passphrase" ? "Change passphrase" : "Protect with a passphrase"}</strong><span>{vaultStatus.keySource === "passphrase" ? "Removing it ties the sign-in to this computer instead." : "Without one, the sign-in is tied to this computer."}</span></div>
+                <div>
+                  {vaultStatus.keySource === "passphrase" && <Button tone="danger" disabled={vaultBusy} onClick={() => void saveVaultPassphrase(null)}>Remove passphrase</Button>}
+                  <Button tone="primary" disabled={vaultBusy || vaultPassphrase.length < 8} onClick={() => void saveVaultPassphrase(vaultPassphrase)}>{vaultBusy ? "Saving…" : "Save passphrase"}</Button>
+                </div>
+              </div>
+            </>
+          )}
+        </Card>
+      )}
+
       <Card aria-labelledby="settings-shortcuts">
         <SectionHeader
           headingId="settings-shortcuts"
@@ -316,7 +995,7 @@ const SettingsPage: React.FC = () => {
           headingId="settings-data"
           eyebrow="Data"
           title="Local workspace"
-          description="Local data remains available without the backend. Backup and restore arrive in 0.2.0."
+          description={isWorkspaceBackupSupported() ? "Local data remains available without the backend. Back it up to a single file or restore an earlier backup." : "Local data remains available without the backend."}
           actions={<Button disabled={diagnostics.loading} onClick={() => void loadDiagnostics()}>{diagnostics.loading ? "Refreshing…" : "Refresh"}</Button>}
         />
         {diagnostics.error && <p className="settings-error" role="alert">{diagnostics.error}</p>}
@@ -326,8 +1005,215 @@ const SettingsPage: React.FC = () => {
           <Diagnostic label="Events" value={diagnostics.events} />
           <Diagnostic label="Pending sync" value={totalPending} />
         </div>
+        {storagePath && (
+          <SettingRow title="Data file" description="Where this computer keeps the encrypted local workspace. Copy it only while the app is closed.">
+            <input type="text" readOnly aria-label="Local data file" value={storagePath} onFocus={(event) => event.target.select()} />
+          </SettingRow>
+        )}
+        {isWorkspaceBackupSupported() && (
+          <div className="settings-reset">
+            <div><strong>Backup and restore</strong><span>Backups include tasks, documents, events, pending changes, recovery drafts, and settings. They are not encrypted.</span></div>
+            <div>
+              <Button disabled={backupBusy} onClick={() => void handleImportWorkspace()}>Restore…</Button>
+              <Button tone="primary" disabled={backupBusy} onClick={() => void handleExportWorkspace()}>{backupBusy ? "Working…" : "Back up…"}</Button>
+            </div>
+          </div>
+        )}
       </Card>
 
+      {serverSettings && (
+        <Card aria-labelledby="settings-server">
+          <SectionHeader
+            headingId="settings-server"
+            eyebrow="Sync"
+            title="Server"
+            description="The Pioneer API this app signs in to and syncs with. Add your own deployment of the Pioneer API to use it instead of Pioneer Cloud."
+          />
+          <SettingRow title="Active server" description="Servers are checked before switching to them.">
+            <select value={serverSettings.active} disabled={serverBusy} onChange={(event) => void handleActivateServer(event.target.value)}>
+              {serverSettings.profiles.map((profile) => <option key={profile.id} value={profile.id}>{profile.name}</option>)}
+            </select>
+          </SettingRow>
+          {serverSettings.profiles.map((profile) => (
+            <SettingRow key={profile.id} title={profile.name} description={describeServer(profile)}>
+              <Button disabled={serverBusy} onClick={() => void handleCheckServer(profile)}>Check</Button>
+              {profile.id !== DEFAULT_SERVER_ID && profile.id !== LOCAL_SERVER_ID && <Button tone="danger" disabled={serverBusy || profile.id === serverSettings.active} onClick={() => void handleDeleteServer(profile)}>Remove</Button>}
+            </SettingRow>
+          ))}
+          {localApi && (
+            <>
+              <SettingRow
+                title="Server on this computer"
+                description={localApi.error ?? "Serves the Pioneer API from this device, so the app works with no cloud. Accounts and data stay on this computer."}
+              >
+                <Toggle checked={localApi.enabled} disabled={serverBusy || (localApi.enabled && serverSettings.active === LOCAL_SERVER_ID)} label={localApi.running ? "Running" : localApi.enabled ? "Stopped" : "Off"} onChange={(value) => void handleLocalApiChange(value, localApi.port)} />
+              </SettingRow>
+              <SettingRow title="Port" description="The port it listens on at 127.0.0.1.">
+                <input type="number" min={1024} max={65535} aria-label="Local server port" value={localApiPort} disabled={serverBusy} onChange={(event) => setLocalApiPort(event.target.value)} />
+                <Button disabled={serverBusy || Number(localApiPort) === localApi.port} onClick={() => void handleLocalApiChange(localApi.enabled, Number(localApiPort))}>Apply</Button>
+              </SettingRow>
+            </>
+          )}
+          <SettingRow title="Name" description="How the server is listed.">
+            <input type="text" aria-label="Server name" placeholder="Campus server" value={serverDraft.name} disabled={serverBusy} onChange={(event) => setServerDraft({ ...serverDraft, name: event.target.value })} />
+          </SettingRow>
+          <SettingRow title="URL" description="Where the API is served, e.g. https://pioneer.example.edu.">
+            <input type="url" aria-label="Server URL" placeholder="https://" value={serverDraft.baseUrl} disabled={serverBusy} onChange={(event) => setServerDraft({ ...serverDraft, baseUrl: event.target.value })} />
+          </SettingRow>
+          <SettingRow title="CA certificate" description="Full path to a PEM file, for servers with a certificate from a private CA. Optional.">
+            <input type="text" aria-label="CA certificate path" placeholder="None" value={serverDraft.caCertificate ?? ""} disabled={serverBusy} onChange={(event) => setServerDraft({ ...serverDraft, caCertificate: event.target.value || null })} />
+          </SettingRow>
+          <SettingRow title="Timeout" description="Seconds to wait for the server before a request fails.">
+            <input type="number" min={1} max={300} aria-label="Server timeout" value={serverDraft.timeoutSeconds} disabled={serverBusy} onChange={(event) => setServerDraft({ ...serverDraft, timeoutSeconds: Number(event.target.value) })} />
+          </SettingRow>
+          <div className="settings-reset">
+            <div><strong>Add server</strong><span>Check it first to make sure the URL, certificate, and timeout work.</span></div>
+            <div>
+              <Button disabled={serverBusy || !serverDraft.baseUrl} onClick={() => void handleCheckServer({ ...serverDraft, name: serverDraft.name || serverDraft.baseUrl })}>Check</Button>
+              <Button tone="primary" disabled={serverBusy || !serverDraft.name.trim() || !serverDraft.baseUrl} onClick={() => void handleAddServer()}>Add server</Button>
+            </div>
+          </div>
+        </Card>
+      )}
+
+      {networkSettings && proxyDraft && (
+        <Card aria-labelledby="settings-network">
+          <SectionHeader
+            headingId="settings-network"
+            eyebrow="Sync"
+            title="Network"
+            description="For school and office networks that require a proxy or inspect HTTPS traffic. Sync uses all of these. App updates use the proxy address after a restart, but not the proxy sign-in or imported certificates."
+          />
+          <SettingRow title="Proxy" description="The system setting follows your operating system and the HTTPS_PROXY environment variable.">
+            <select value={proxyDraft.mode} disabled={networkBusy} onChange={(event) => setProxyDraft({ ...proxyDraft, mode: event.target.value as ProxyMode })}>
+              <option value="system">System</option>
+              <option value="none">No proxy</option>
+              <option value="manual">Manual</option>
+            </select>
+          </SettingRow>
+          {proxyDraft.mode === "manual" && (
+            <>
+              <SettingRow title="Proxy address" description="An http://, https://, socks5:// or socks5h:// address with its port.">
+                <input type="url" aria-label="Proxy address" placeholder="http://proxy.example.edu:3128" value={proxyDraft.url} disabled={networkBusy} onChange={(event) => setProxyDraft({ ...proxyDraft, url: event.target.value })} />
+              </SettingRow>
+              <SettingRow title="Username" description="Only if the proxy asks you to sign in.">
+                <input type="text" aria-label="Proxy username" autoComplete="off" value={proxyDraft.username} disabled={networkBusy} onChange={(event) => setProxyDraft({ ...proxyDraft, username: event.target.value })} />
+              </SettingRow>
+              <SettingRow title="Password" description={networkSettings.hasProxyPassword ? "Saved and encrypted on this computer. Type a new one to replace it. Used by sync only." : "Encrypted on this computer. Used by sync only."}>
+                <input type="password" aria-label="Proxy password" autoComplete="new-password" placeholder={networkSettings.hasProxyPassword ? "Saved" : ""} value={proxyPassword} disabled={networkBusy || !proxyDraft.username.trim()} onChange={(event) => setProxyPassword(event.target.value)} />
+              </SettingRow>
+              <SettingRow title="Bypass proxy for" description="Comma-separated hosts, domains such as .example.edu, or ranges such as 10.0.0.0/8.">
+                <input type="text" aria-label="Hosts that bypass the proxy" placeholder="localhost, 127.0.0.1" value={proxyDraft.noProxy} disabled={networkBusy} onChange={(event) => setProxyDraft({ ...proxyDraft, noProxy: event.target.value })} />
+              </SettingRow>
+            </>
+          )}
+          <div className="settings-reset">
+            <div><strong>Apply proxy settings</strong><span>Then check the active server to make sure it can be reached.</span></div>
+            <div>
+              <Button disabled={networkBusy || serverBusy || !activeServer} onClick={() => activeServer && void handleCheckServer(activeServer)}>Check server</Button>
+              <Button tone="primary" disabled={networkBusy} onClick={() => void handleSaveProxy(proxyDraft)}>Save</Button>
+            </div>
+          </div>
+          {networkSettings.certificates.map((certificate) => (
+            <SettingRow key={certificate.id} title={certificate.name || "Root certificate"} description={`SHA-256 ${certificate.fingerprint}`}>
+              <Button tone="danger" disabled={networkBusy} onClick={() => void handleRemoveCertificate(certificate.id)}>Remove</Button>
+            </SettingRow>
+          ))}
+          <SettingRow title="Root certificates" description="If sync reports a failed secure connection, import the root certificate your network's IT team provides, as a PEM file. App updates trust only the certificates your operating system trusts.">
+            <Button disabled={networkBusy} onClick={() => void handleImportCertificate()}>Import certificate…</Button>
+          </SettingRow>
+        </Card>
+      )}
+
+      {reminderSettings && (
+        <Card aria-labelledby="settings-reminders">
+          <SectionHeader
+            headingId="settings-reminders"
+            eyebrow="Notifications"
+            title="Reminders"
+            description="Notifications for tasks that are due and events that are about to start. They show while Pioneer is running, even in the background."
+          />
+          <SettingRow title="Reminders" description="Turn every task and event reminder on or off.">
+            <Toggle checked={reminderSettings.enabled} label={reminderSettings.enabled ? "On" : "Off"} onChange={(value) => void applyReminderSettings({ enabled: value })} />
+          </SettingRow>
+          <SettingRow title="Tasks" description="When to be reminded of a task with a due date.">
+            <select value={leadValue(reminderSettings.taskLeadMinutes)} disabled={!reminderSettings.enabled} onChange={(event) => void applyReminderSettings({ taskLeadMinutes: parseLead(event.target.value) })}>
+              <option value="off">Never</option>
+              {TASK_LEAD_OPTIONS.map((option) => <option key={option.value} value={option.value}>{option.label}</option>)}
+            </select>
+          </SettingRow>
+          <SettingRow title="Events" description="When to be reminded of an upcoming event.">
+            <select value={leadValue(reminderSettings.eventLeadMinutes)} disabled={!reminderSettings.enabled} onChange={(event) => void applyReminderSettings({ eventLeadMinutes: parseLead(event.target.value) })}>
+              <option value="off">Never</option>
+              {EVENT_LEAD_OPTIONS.map((option) => <option key={option.value} value={option.value}>{option.label}</option>)}
+            </select>
+          </SettingRow>
+          <SettingRow title="All-day time" description="Time of day used for tasks and all-day events, which have no time of their own.">
+            <input type="time" value={reminderSettings.allDayTime} disabled={!reminderSettings.enabled} onChange={(event) => { if (event.target.value) void applyReminderSettings({ allDayTime: event.target.value }); }} />
+          </SettingRow>
+          {reminderSettings.enabled && reminders.length === 0 && <p className="settings-empty">No upcoming reminders.</p>}
+          {reminders.slice(0, UPCOMING_REMINDER_LIMIT).map((reminder) => (
+            <SettingRow key={reminder.key} title={reminder.title} description={`${new Date(reminder.snoozedUntil ?? reminder.fireAt).toLocaleString()} · ${reminder.body}`}>
+              <Button onClick={() => void handleReminderAction(() => snoozeReminder(reminder.key, 60))}>Snooze 1 hour</Button>
+              <Button onClick={() => void handleReminderAction(() => dismissReminder(reminder.key))}>Dismiss</Button>
+            </SettingRow>
+          ))}
+        </Card>
+      )}
+
+      {captureShortcuts && captureDraft && (
+        <Card aria-labelledby="settings-capture">
+          <SectionHeader
+            headingId="settings-capture"
+            eyebrow="Keyboard"
+            title="Quick capture"
+            description="Shortcuts that work in every app and open a small window for jotting something down. Use names like CmdOrCtrl+Shift+Space; leave one empty to turn it off."
+            actions={<Button tone="primary" disabled={savingCapture || JSON.stringify(captureDraft) === JSON.stringify(captureShortcuts)} onClick={() => void handleSaveCaptureShortcuts()}>{savingCapture ? "Saving…" : "Save shortcuts"}</Button>}
+          />
+          {CAPTURE_SHORTCUT_ROWS.map(([kind, title, description]) => (
+            <SettingRow key={kind} title={title} description={description}>
+              <input type="text" aria-label={`${title} shortcut`} placeholder="Off" value={captureDraft[kind] ?? ""} disabled={savingCapture} onChange={(event) => setCaptureDraft({ ...captureDraft, [kind]: event.target.value || null })} />
+            </SettingRow>
+          ))}
+        </Card>
+      )}
+
+      {snapshotSettings && (
+        <Card aria-labelledby="settings-snapshots">
+          <SectionHeader
+            headingId="settings-snapshots"
+            eyebrow="Data"
+            title="Snapshots"
+            description="Automatic copies of the local workspace, kept encrypted on this computer. Restoring one replaces the current workspace."
+            actions={<Button disabled={snapshotBusy} onClick={() => void handleCreateSnapshot()}>{snapshotBusy ? "Working…" : "Take snapshot"}</Button>}
+          />
+          <SettingRow title="Frequency" description="How often a snapshot is taken while the workspace is unlocked.">
+            <select value={snapshotSettings.frequency} onChange={(event) => void applySnapshotSettings({ frequency: event.target.value as SnapshotFrequency })}>
+              <option value="off">Off</option><option value="hourly">Hourly</option><option value="daily">Daily</option>
+            </select>
+          </SettingRow>
+          <SettingRow title="Hourly snapshots" description="Keep the latest snapshot of this many recent hours.">
+            <select value={snapshotSettings.keepHourly} onChange={(event) => void applySnapshotSettings({ keepHourly: Number(event.target.value) })}>
+              {KEEP_HOURLY_OPTIONS.map((count) => <option key={count} value={count}>{count === 0 ? "None" : count}</option>)}
+            </select>
+          </SettingRow>
+          <SettingRow title="Daily snapshots" description="Keep the latest snapshot of this many recent days.">
+            <select value={snapshotSettings.keepDaily} onChange={(event) => void applySnapshotSettings({ keepDaily: Number(event.target.value) })}>
+              {KEEP_DAILY_OPTIONS.map((count) => <option key={count} value={count}>{count === 0 ? "None" : count}</option>)}
+            </select>
+          </SettingRow>
+          <SettingRow title="Earlier PIN" description="Only needed to restore a snapshot taken under a PIN this workspace no longer has, for example after its local data was lost.">
+            <input type="password" inputMode="numeric" autoComplete="off" aria-label="Earlier PIN" value={snapshotPin} disabled={snapshotBusy} onChange={(event) => setSnapshotPin(event.target.value)} />
+          </SettingRow>
+          {snapshots.length === 0 && <p className="settings-empty">No snapshots yet.</p>}
+          {snapshots.map((snapshot) => (
+            <SettingRow key={snapshot.id} title={new Date(snapshot.createdAt).toLocaleString()} description={formatSnapshotSize(snapshot.sizeBytes)}>
+              <Button disabled={snapshotBusy} onClick={() => void handleRestoreSnapshot(snapshot)}>Restore</Button>
+            </SettingRow>
+          ))}
+        </Card>
+      )}
+
       <Card aria-labelledby="settings-about">
         <SectionHeader headingId="settings-about" eyebrow="About" title="Pioneer Work Suite" description={`Version ${APP_VERSION} · ${getWorkspaceName()} · ${hasCloudSession() ? "Cloud connected" : "Local only"}`} />
         <SettingRow title="Developer tools" description="Show diagnostics and the local application console.">
@@ -1,6 +1,6 @@
 // apps/web/src/pages/TasksPage.tsx
 import React, { useEffect, useMemo, useRef, useState } from "react";
-import { useNavigate } from "react-router-dom";
+import { useLocation, useNavigate } from "react-router-dom";
 
 import {
   createTask,
@@ -128,9 +128,10 @@ const TasksPage: React.FC<TasksPageProps> = ({
   const settings = useAppSettings();
   const { confirm, confirmationDialog } = useConfirmation();
   const navigate = useNavigate();
+  const location = useLocation();
   const newTitleRef = useRef<HTMLInputElement>(null);
   const [tasks, setTasks] = useState<Task[]>([]);
-  const [loading, setLoading] = useState(false);
+  const [loading, setLoading] = useState(true);
   const [savingNewTask, setSavingNewTask] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [newTitle, setNewTitle] = useState("");
@@ -164,24 +165,6 @@ const TasksPage: React.FC<TasksPageProps> = ({
         const loaded = await fetchTasks();
         if (cancelled) return;
         setTasks(loaded);
-
-        const parameters = new URLSearchParams(window.location.search);
-        const requestedId = parameters.get("task");
-
-        if (requestedId) {
-          setFilter("all");
-          setSearchTargetId(requestedId);
-          window.requestAnimationFrame(() =>
-            document
-              .getElementById(`task-card-${requestedId}`)
-              ?.scrollIntoView({ behavior: "smooth", block: "center" })
-          );
-          window.setTimeout(() => setSearchTargetId(null), 2_600);
-        }
-
-        if (parameters.get("create") === "1" && !archivedOnly) {
-          window.requestAnimationFrame(() => newTitleRef.current?.focus());
-        }
       } catch (loadError) {
         console.error("Error loading tasks:", loadError);
         if (!cancelled) setError("Unable to load tasks.");
@@ -195,6 +178,37 @@ const TasksPage: React.FC<TasksPageProps> = ({
     };
   }, [archivedOnly]);
 
+  /*
+   * Links from search, the tray and `pioneer://` URLs arrive as query
+   * parameters, possibly while the page is already open.
+   */
+  useEffect(() => {
+    if (loading) return;
+
+    const parameters = new URLSearchParams(location.search);
+    const requestedId = parameters.get("task");
+
+    if (requestedId) {
+      setFilter("all");
+      setSearchTargetId(requestedId);
+      window.requestAnimationFrame(() =>
+        document
+          .getElementById(`task-card-${requestedId}`)
+          ?.scrollIntoView({ behavior: "smooth", block: "center" })
+      );
+      const timer = window.setTimeout(() => setSearchTargetId(null), 2_600);
+      return () => window.clearTimeout(timer);
+    }
+
+    if (parameters.get("create") === "1" && !archivedOnly) {
+      const requestedTitle = parameters.get("title");
+      if (requestedTitle) setNewTitle(requestedTitle);
+      window.requestAnimationFrame(() => newTitleRef.current?.focus());
+    }
+
+    return undefined;
+  }, [archivedOnly, loading, location.search]);
+
   function replaceTask(updated: Task): void {
     setTasks((current) =>
       current.map((task) => (task.id === updated.id ? updated : task))
@@ -478,6 +492,7 @@ const TasksPage: React.FC<TasksPageProps> = ({
         title: `Tasks: Show ${label}`,
         category: "Tasks" as const,
         enabled: !archivedOnly && filter !== value,
+        checked: !archivedOnly && filter === value,
         disabledReason: archivedOnly
           ? "Filters are unavailable in the archive."
           : `${label} is already selected.`,
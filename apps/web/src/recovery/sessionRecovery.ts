@@ -1,3 +1,4 @@
+import { invokeDesktop, isDesktopRuntime } from "../api/desktop";
 import { developerLogger } from "../developer/logger";
 
 const SESSION_RECOVERY_KEY = "pioneer.session-recovery.v1";
@@ -14,9 +15,21 @@ export interface SessionRecoveryStart {
   previousSessionInterrupted: boolean;
 }
 
+/*
+ * On desktop the crate keeps the session marker and marks it ended from its
+ * exit hook, which also covers a webview that was killed before `pagehide`.
+ */
+interface DesktopPreviousSession {
+  interrupted: boolean;
+  path: string | null;
+  startedAt: string | null;
+  lastSeenAt: string | null;
+}
+
 let currentRecord: SessionRecoveryRecord | null = null;
 let startResult: SessionRecoveryStart | null = null;
 let storageFailureReported = false;
+let desktopPrevious: DesktopPreviousSession | null = null;
 
 function isRecoverablePath(path: string): boolean {
   return (
@@ -82,9 +95,53 @@ function writeRecord(record: SessionRecoveryRecord): void {
   }
 }
 
+/* Asks the crate about the previous session before the first render. */
+export async function hydrateSessionRecovery(): Promise<void> {
+  if (!isDesktopRuntime()) return;
+
+  try {
+    desktopPrevious = await invokeDesktop<DesktopPreviousSession>(
+      "get_previous_session_state"
+    );
+  } catch (error) {
+    reportStorageFailure(
+      "Unable to read the previous session from the desktop app",
+      error
+    );
+  }
+}
+
+function beginDesktopSessionRecovery(
+  previous: DesktopPreviousSession
+): SessionRecoveryStart {
+  const recoveredPath =
+    previous.interrupted && previous.path && isRecoverablePath(previous.path)
+      ? previous.path
+      : null;
+
+  return {
+    recoveredPath,
+    previousSessionInterrupted: previous.interrupted,
+  };
+}
+
 export function beginSessionRecovery(): SessionRecoveryStart {
   if (startResult) return startResult;
 
+  if (desktopPrevious) {
+    startResult = beginDesktopSessionRecovery(desktopPrevious);
+
+    if (startResult.recoveredPath) {
+      developerLogger.info(
+        "recovery.session",
+        "Restoring the page from an interrupted session",
+        { path: startResult.recoveredPath }
+      );
+    }
+
+    return startResult;
+  }
+
   const previous = readRecord();
   const interrupted = Boolean(previous && !previous.cleanExit);
   const recoveredPath =
@@ -118,6 +175,16 @@ export function beginSessionRecovery(): SessionRecoveryStart {
 export function updateSessionRecoveryPath(path: string): void {
   if (!isRecoverablePath(path)) return;
 
+  if (desktopPrevious) {
+    void invokeDesktop<void>("set_session_path", { path }).catch((error) => {
+      reportStorageFailure(
+        "Unable to report the current page to the desktop app",
+        error
+      );
+    });
+    return;
+  }
+
   writeRecord({
     schemaVersion: 1,
     path,
@@ -127,6 +194,9 @@ export function updateSessionRecoveryPath(path: string): void {
 }
 
 export function markSessionRecoveryCleanExit(): void {
+  // The crate marks the session ended when the app exits.
+  if (desktopPrevious) return;
+
   const record = currentRecord ?? readRecord();
   if (!record) return;
 
@@ -201,6 +201,23 @@ export function formatDocumentDate(
   );
 }
 
+/*
+ * Editor HTML for plain text: blank lines separate paragraphs and single
+ * line breaks are kept.
+ */
+export function plainTextToHtml(text: string): string {
+  return text
+    .replace(/\r\n/g, "\n")
+    .split(/\n\s*\n/)
+    .map((paragraph) => paragraph.trim())
+    .filter(Boolean)
+    .map(
+      (paragraph) =>
+        `<p>${paragraph.split("\n").map(escapeHtml).join("<br>")}</p>`
+    )
+    .join("");
+}
+
 function escapeHtml(value: string): string {
   return value
     .replace(/&/g, "&amp;")
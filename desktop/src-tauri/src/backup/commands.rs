@@ -0,0 +1,64 @@
+// desktop/src-tauri/src/backup/commands.rs
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use super::{WorkspaceBackup, WorkspaceCounts};
+use crate::error::Result;
+use crate::snapshots::Snapshots;
+use crate::storage::LocalStore;
+use crate::sync::{engine::Resource, SyncService};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedWorkspace {
+    pub counts: WorkspaceCounts,
+    /// The archived `AppSettings`, for the webview to apply.
+    pub settings: Option<Value>,
+}
+
+/// Writes a backup of the whole workspace to `path`. The webview passes its
+/// current settings along, since those live in its own storage.
+#[tauri::command]
+pub async fn export_workspace(
+    app: AppHandle,
+    store: State<'_, LocalStore>,
+    path: PathBuf,
+    settings: Option<Value>,
+) -> Result<WorkspaceCounts> {
+    let backup = WorkspaceBackup {
+        contents: store.read_contents()?,
+        settings,
+    };
+
+    let manifest = super::write_archive(&path, &backup, &app.package_info().version.to_string())?;
+    Ok(manifest.counts)
+}
+
+/// Replaces the workspace with the backup at `path` once it has been fully
+/// validated, after snapshotting the current state.
+#[tauri::command]
+pub async fn import_workspace(
+    app: AppHandle,
+    snapshots: State<'_, Snapshots>,
+    store: State<'_, LocalStore>,
+    path: PathBuf,
+) -> Result<ImportedWorkspace> {
+    let (_, backup) = super::read_archive(&path)?;
+
+    snapshots.take_before_replacing(&store, None)?;
+    store.replace_contents(&backup.contents)?;
+
+    for resource in Resource::ALL {
+        let _ = app.emit_all(resource.changed_event(), ());
+    }
+    app.state::<SyncService>().wake();
+
+    Ok(ImportedWorkspace {
+        counts: WorkspaceCounts::of(&backup.contents),
+        settings: backup.settings,
+    })
+}
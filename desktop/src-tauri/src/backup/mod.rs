@@ -0,0 +1,506 @@
+// desktop/src-tauri/src/backup/mod.rs
+
+/*
+ * Whole-workspace backups.
+ *
+ * A backup is a zip archive with one JSON file per store, the webview's
+ * `AppSettings`, and a manifest listing every file with its size and SHA-256
+ * checksum. Restores read and validate the complete archive before anything
+ * in the local store is replaced.
+ *
+ * Restores migrate in two steps. Each archive format has a decoder that
+ * upgrades into the current `WorkspaceBackup` (format 1 is the only one so
+ * far), and the archived `AppSettings` are brought up to the
+ * `schemaVersion` the webview writes, one version at a time. Archives and
+ * settings from a newer version of the app are refused.
+ *
+ * Archives hold plaintext JSON so they stay readable without this computer
+ * or the workspace PIN; the save dialog in Settings says so.
+ */
+
+pub mod commands;
+
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::{Error, Result};
+use crate::files;
+use crate::storage::WorkspaceContents;
+use crate::sync::engine::QueueOp;
+
+pub const BACKUP_FORMAT: &str = "pioneer-workspace-backup";
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// `AppSettings.schemaVersion` in apps/web/src/api/settings.ts.
+pub const SETTINGS_SCHEMA_VERSION: u64 = 1;
+
+/// `SETTINGS_MIGRATIONS[n]` upgrades settings of schemaVersion `n`.
+const SETTINGS_MIGRATIONS: [fn(&mut Map<String, Value>); SETTINGS_SCHEMA_VERSION as usize] = [
+    // Settings saved before they had a schemaVersion already used the
+    // version 1 fields.
+    |_| {},
+];
+
+const MANIFEST_PATH: &str = "manifest.json";
+
+const TASKS_PATH: &str = "tasks.json";
+const DOCUMENTS_PATH: &str = "documents.json";
+const EVENTS_PATH: &str = "events.json";
+const TASK_QUEUE_PATH: &str = "queues/tasks.json";
+const DOCUMENT_QUEUE_PATH: &str = "queues/documents.json";
+const EVENT_QUEUE_PATH: &str = "queues/events.json";
+const RECOVERY_DRAFTS_PATH: &str = "recovery-drafts.json";
+const SETTINGS_PATH: &str = "settings.json";
+
+// Guards against archives that expand to something absurd.
+const MAX_ENTRY_SIZE: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceCounts {
+    pub tasks: usize,
+    pub documents: usize,
+    pub events: usize,
+    pub pending_ops: usize,
+    pub recovery_drafts: usize,
+}
+
+impl WorkspaceCounts {
+    pub fn of(contents: &WorkspaceContents) -> Self {
+        Self {
+            tasks: contents.tasks.len(),
+            documents: contents.documents.len(),
+            events: contents.events.len(),
+            pending_ops: contents.task_queue.len()
+                + contents.document_queue.len()
+                + contents.event_queue.len(),
+            recovery_drafts: contents.recovery_drafts.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub counts: WorkspaceCounts,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// What a backup carries: the local store plus the webview's settings.
+pub struct WorkspaceBackup {
+    pub contents: WorkspaceContents,
+    pub settings: Option<Value>,
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidBackup(message.into())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Writes `backup` to `path` as a new archive, replacing any existing file
+/// only once the archive is complete.
+pub fn write_archive(path: &Path, backup: &WorkspaceBackup, app_version: &str) -> Result<Manifest> {
+    let contents = &backup.contents;
+
+    let mut payloads = vec![
+        (TASKS_PATH, serde_json::to_vec_pretty(&contents.tasks)?),
+        (
+            DOCUMENTS_PATH,
+            serde_json::to_vec_pretty(&contents.documents)?,
+        ),
+        (EVENTS_PATH, serde_json::to_vec_pretty(&contents.events)?),
+        (
+            TASK_QUEUE_PATH,
+            serde_json::to_vec_pretty(&contents.task_queue)?,
+        ),
+        (
+            DOCUMENT_QUEUE_PATH,
+            serde_json::to_vec_pretty(&contents.document_queue)?,
+        ),
+        (
+            EVENT_QUEUE_PATH,
+            serde_json::to_vec_pretty(&contents.event_queue)?,
+        ),
+        (
+            RECOVERY_DRAFTS_PATH,
+            serde_json::to_vec_pretty(&contents.recovery_drafts)?,
+        ),
+    ];
+
+    if let Some(settings) = &backup.settings {
+        payloads.push((SETTINGS_PATH, serde_json::to_vec_pretty(settings)?));
+    }
+
+    let manifest = Manifest {
+        format: BACKUP_FORMAT.to_string(),
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: app_version.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        counts: WorkspaceCounts::of(contents),
+        entries: payloads
+            .iter()
+            .map(|(path, bytes)| ManifestEntry {
+                path: path.to_string(),
+                size: bytes.len() as u64,
+                sha256: sha256_hex(bytes),
+            })
+            .collect(),
+    };
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(MANIFEST_PATH, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+    for (path, bytes) in &payloads {
+        zip.start_file(*path, options)?;
+        zip.write_all(bytes)?;
+    }
+
+    let archive = zip.finish()?.into_inner();
+    files::write_atomic(path, &archive)?;
+
+    Ok(manifest)
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, path: &str) -> Result<Option<Vec<u8>>> {
+    let entry = match archive.by_name(path) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut bytes = Vec::new();
+    entry.take(MAX_ENTRY_SIZE + 1).read_to_end(&mut bytes)?;
+
+    if bytes.len() as u64 > MAX_ENTRY_SIZE {
+        return Err(invalid(format!("{path} is too large.")));
+    }
+
+    Ok(Some(bytes))
+}
+
+/// Reads a listed file and checks it against its manifest entry.
+fn read_checked(
+    archive: &mut ZipArchive<File>,
+    manifest: &Manifest,
+    path: &str,
+) -> Result<Option<Vec<u8>>> {
+    let Some(entry) = manifest.entries.iter().find(|entry| entry.path == path) else {
+        return Ok(None);
+    };
+
+    let bytes = read_entry(archive, path)?
+        .ok_or_else(|| invalid(format!("{path} is listed in the manifest but missing.")))?;
+
+    if bytes.len() as u64 != entry.size || sha256_hex(&bytes) != entry.sha256 {
+        return Err(invalid(format!("{path} does not match its checksum.")));
+    }
+
+    Ok(Some(bytes))
+}
+
+fn parse<T: serde::de::DeserializeOwned>(path: &str, bytes: &[u8]) -> Result<T> {
+    serde_json::from_slice(bytes).map_err(|error| invalid(format!("{path} is not valid: {error}")))
+}
+
+fn validate_records(path: &str, records: &[Value]) -> Result<()> {
+    let mut ids = std::collections::HashSet::new();
+
+    for record in records {
+        let id = record
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid(format!("{path} has a record without a string id.")))?;
+
+        if !ids.insert(id) {
+            return Err(invalid(format!("{path} lists the record {id} twice.")));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_queue(path: &str, queue: &[Value]) -> Result<()> {
+    for op in queue {
+        serde_json::from_value::<QueueOp>(op.clone())
+            .map_err(|error| invalid(format!("{path} has an unreadable queued change: {error}")))?;
+    }
+
+    Ok(())
+}
+
+/// Brings `settings` up to `SETTINGS_SCHEMA_VERSION`.
+fn migrate_settings(settings: &mut Value) -> Result<()> {
+    let settings = settings
+        .as_object_mut()
+        .ok_or_else(|| invalid("settings.json does not hold settings."))?;
+
+    let version = match settings.get("schemaVersion") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| invalid("settings.json has an unreadable schemaVersion."))?,
+    };
+
+    if version > SETTINGS_SCHEMA_VERSION {
+        return Err(invalid(format!(
+            "its settings were saved by a newer version of Pioneer (schema {version})."
+        )));
+    }
+
+    for migrate in &SETTINGS_MIGRATIONS[version as usize..] {
+        migrate(settings);
+    }
+    settings.insert("schemaVersion".to_string(), SETTINGS_SCHEMA_VERSION.into());
+
+    Ok(())
+}
+
+/// Decodes a format version 1 archive, the current format.
+fn decode_v1(archive: &mut ZipArchive<File>, manifest: &Manifest) -> Result<WorkspaceBackup> {
+    let mut required = |path: &str| -> Result<Vec<u8>> {
+        read_checked(archive, manifest, path)?
+            .ok_or_else(|| invalid(format!("{path} is missing from the manifest.")))
+    };
+
+    let contents = WorkspaceContents {
+        tasks: parse(TASKS_PATH, &required(TASKS_PATH)?)?,
+        documents: parse(DOCUMENTS_PATH, &required(DOCUMENTS_PATH)?)?,
+        events: parse(EVENTS_PATH, &required(EVENTS_PATH)?)?,
+        task_queue: parse(TASK_QUEUE_PATH, &required(TASK_QUEUE_PATH)?)?,
+        document_queue: parse(DOCUMENT_QUEUE_PATH, &required(DOCUMENT_QUEUE_PATH)?)?,
+        event_queue: parse(EVENT_QUEUE_PATH, &required(EVENT_QUEUE_PATH)?)?,
+        recovery_drafts: parse(RECOVERY_DRAFTS_PATH, &required(RECOVERY_DRAFTS_PATH)?)?,
+    };
+
+    let settings = read_checked(archive, manifest, SETTINGS_PATH)?
+        .map(|bytes| parse::<Value>(SETTINGS_PATH, &bytes))
+        .transpose()?;
+
+    Ok(WorkspaceBackup { contents, settings })
+}
+
+/// Opens, verifies and decodes the archive at `path`, migrating what an
+/// older version wrote. Nothing is written.
+pub fn read_archive(path: &Path) -> Result<(Manifest, WorkspaceBackup)> {
+    let mut archive = ZipArchive::new(File::open(path)?)
+        .map_err(|_| invalid("the file is not a Pioneer backup archive."))?;
+
+    let manifest: Manifest = parse(
+        MANIFEST_PATH,
+        &read_entry(&mut archive, MANIFEST_PATH)?
+            .ok_or_else(|| invalid("the archive has no manifest."))?,
+    )?;
+
+    if manifest.format != BACKUP_FORMAT {
+        return Err(invalid("the file is not a Pioneer backup archive."));
+    }
+
+    // Decoders for older formats upgrade into the current shape.
+    let mut backup = match manifest.format_version {
+        1 => decode_v1(&mut archive, &manifest)?,
+        version if version > BACKUP_FORMAT_VERSION => {
+            return Err(invalid(format!(
+                "it was made by a newer version of Pioneer (format {version})."
+            )))
+        }
+        version => return Err(invalid(format!("format {version} is not supported."))),
+    };
+
+    let contents = &backup.contents;
+
+    validate_records(TASKS_PATH, &contents.tasks)?;
+    validate_records(DOCUMENTS_PATH, &contents.documents)?;
+    validate_records(EVENTS_PATH, &contents.events)?;
+    validate_queue(TASK_QUEUE_PATH, &contents.task_queue)?;
+    validate_queue(DOCUMENT_QUEUE_PATH, &contents.document_queue)?;
+    validate_queue(EVENT_QUEUE_PATH, &contents.event_queue)?;
+
+    if let Some(settings) = &mut backup.settings {
+        migrate_settings(settings)?;
+    }
+
+    Ok((manifest, backup))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample() -> WorkspaceBackup {
+        WorkspaceBackup {
+            contents: WorkspaceContents {
+                tasks: vec![json!({ "id": "t1", "title": "Essay draft" })],
+                documents: vec![json!({ "id": "d1", "title": "Notes" })],
+                task_queue: vec![json!(QueueOp::Delete {
+                    id: "t2".to_string(),
+                    timestamp: 1,
+                })],
+                ..WorkspaceContents::default()
+            },
+            settings: Some(json!({ "schemaVersion": 1 })),
+        }
+    }
+
+    /// Rewrites the archive at `path`, passing the manifest and each file
+    /// through `edit` first.
+    fn rewrite(path: &Path, edit: impl Fn(&str, &mut Vec<u8>)) {
+        let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).unwrap();
+            let name = entry.name().to_string();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            edit(&name, &mut bytes);
+
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(&bytes).unwrap();
+        }
+
+        std::fs::write(path, zip.finish().unwrap().into_inner()).unwrap();
+    }
+
+    fn error_of(path: &Path) -> String {
+        match read_archive(path) {
+            Err(Error::InvalidBackup(message)) => message,
+            Err(error) => panic!("unexpected error: {error}"),
+            Ok(_) => panic!("the archive was accepted"),
+        }
+    }
+
+    #[test]
+    fn archives_read_back_what_was_written() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("workspace.zip");
+        let backup = sample();
+
+        let written = write_archive(&path, &backup, "1.2.3").unwrap();
+        let (manifest, read) = read_archive(&path).unwrap();
+
+        assert_eq!(manifest.format_version, BACKUP_FORMAT_VERSION);
+        assert_eq!(manifest.app_version, "1.2.3");
+        assert_eq!(manifest.entries.len(), written.entries.len());
+        assert_eq!(manifest.counts.pending_ops, 1);
+        assert_eq!(read.contents, backup.contents);
+        assert_eq!(read.settings, backup.settings);
+    }
+
+    #[test]
+    fn altered_files_are_rejected() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("workspace.zip");
+        write_archive(&path, &sample(), "1.2.3").unwrap();
+
+        // Same length, different bytes.
+        rewrite(&path, |name, bytes| {
+            if name == TASKS_PATH {
+                let at = bytes.iter().position(|&byte| byte == b'1').unwrap();
+                bytes[at] = b'9';
+            }
+        });
+        assert_eq!(error_of(&path), "tasks.json does not match its checksum.");
+
+        write_archive(&path, &sample(), "1.2.3").unwrap();
+        rewrite(&path, |name, bytes| {
+            if name == EVENTS_PATH {
+                bytes.push(b'\n');
+            }
+        });
+        assert_eq!(error_of(&path), "events.json does not match its checksum.");
+    }
+
+    #[test]
+    fn archives_from_a_newer_format_are_rejected() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("workspace.zip");
+        write_archive(&path, &sample(), "1.2.3").unwrap();
+
+        rewrite(&path, |name, bytes| {
+            if name == MANIFEST_PATH {
+                let mut manifest: Value = serde_json::from_slice(bytes).unwrap();
+                manifest["formatVersion"] = json!(BACKUP_FORMAT_VERSION + 1);
+                *bytes = serde_json::to_vec(&manifest).unwrap();
+            }
+        });
+
+        assert!(error_of(&path).contains("made by a newer version"));
+    }
+
+    #[test]
+    fn duplicate_records_are_rejected() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("workspace.zip");
+
+        let mut backup = sample();
+        backup
+            .contents
+            .documents
+            .push(json!({ "id": "d1", "title": "Copy" }));
+        write_archive(&path, &backup, "1.2.3").unwrap();
+        assert_eq!(error_of(&path), "documents.json lists the record d1 twice.");
+
+        // Settings are optional.
+        let mut backup = sample();
+        backup.settings = None;
+        write_archive(&path, &backup, "1.2.3").unwrap();
+        assert!(read_archive(&path).unwrap().1.settings.is_none());
+    }
+
+    #[test]
+    fn older_settings_are_migrated_and_newer_ones_refused() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("workspace.zip");
+        let with_settings = |settings: Value| {
+            let mut backup = sample();
+            backup.settings = Some(settings);
+            write_archive(&path, &backup, "0.1.0").unwrap();
+        };
+
+        // From before settings carried a schemaVersion.
+        with_settings(json!({ "appearance": { "theme": "light" } }));
+        assert_eq!(
+            read_archive(&path).unwrap().1.settings,
+            Some(json!({ "schemaVersion": 1, "appearance": { "theme": "light" } }))
+        );
+
+        with_settings(json!({ "schemaVersion": SETTINGS_SCHEMA_VERSION + 1 }));
+        assert!(error_of(&path).contains("saved by a newer version"));
+
+        with_settings(json!({ "schemaVersion": "one" }));
+        assert_eq!(
+            error_of(&path),
+            "settings.json has an unreadable schemaVersion."
+        );
+
+        with_settings(json!([]));
+        assert_eq!(error_of(&path), "settings.json does not hold settings.");
+    }
+}
@@ -0,0 +1,23 @@
+// desktop/src-tauri/src/blocking.rs
+
+/*
+ * Argon2 derivations and whole-store re-encryption take a noticeable
+ * moment. Commands that do either run the work here, on a blocking thread,
+ * rather than stall the async runtime and the windows waiting on it.
+ */
+
+use std::io;
+
+use tauri::AppHandle;
+
+use crate::error::{Error, Result};
+
+/// Runs `work` on a blocking thread and waits for its result.
+pub async fn run<T: Send + 'static>(
+    app: AppHandle,
+    work: impl FnOnce(&AppHandle) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tauri::async_runtime::spawn_blocking(move || work(&app))
+        .await
+        .map_err(|error| Error::Io(io::Error::other(error.to_string())))?
+}
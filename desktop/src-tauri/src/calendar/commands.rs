@@ -0,0 +1,44 @@
+// desktop/src-tauri/src/calendar/commands.rs
+
+use std::fs;
+use std::path::PathBuf;
+
+use super::{export, import, CalendarEvent, DateRange, EventPayload};
+use crate::error::{Error, Result};
+use crate::files;
+
+const MAX_IMPORT_SIZE: u64 = 20 * 1024 * 1024;
+
+/// Reads the .ics file at `path` into event payloads. Recurring series are
+/// expanded between `from` and `to`; the webview creates the events.
+#[tauri::command]
+pub async fn import_ics(path: PathBuf, from: String, to: String) -> Result<Vec<EventPayload>> {
+    let range = DateRange::parse(&from, &to)?;
+
+    if fs::metadata(&path)?.len() > MAX_IMPORT_SIZE {
+        return Err(Error::InvalidInput(
+            "This calendar file is too large to import.".into(),
+        ));
+    }
+
+    let bytes = fs::read(&path)?;
+    let source = String::from_utf8_lossy(&bytes);
+
+    Ok(import::import(&source, &range))
+}
+
+/// Writes the `events` that fall between `from` and `to` to `path`, chosen
+/// by the webview through a save dialog. Returns how many were written.
+#[tauri::command]
+pub async fn export_ics(
+    events: Vec<CalendarEvent>,
+    from: String,
+    to: String,
+    path: PathBuf,
+) -> Result<usize> {
+    let range = DateRange::parse(&from, &to)?;
+    let (file, count) = export::export(&events, &range);
+
+    files::write_atomic(&path, file.as_bytes())?;
+    Ok(count)
+}
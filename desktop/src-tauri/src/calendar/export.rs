@@ -0,0 +1,236 @@
+// desktop/src-tauri/src/calendar/export.rs
+
+/*
+ * Events to a VCALENDAR.
+ *
+ * Timed events are written in UTC and all-day events as DATE values in
+ * local time. Urgency maps to PRIORITY and the event kind to
+ * X-PIONEER-KIND, which imports read back.
+ */
+
+use chrono::{DateTime, Duration, Local, Utc};
+
+use super::ics::{escape_text, write_line};
+use super::{priority, CalendarEvent, DateRange};
+use crate::crypto;
+
+const PRODUCT_ID: &str = "-//Pioneer Work Suite//Desktop//EN";
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn write_event(
+    output: &mut String,
+    event: &CalendarEvent,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    stamp: &str,
+) {
+    let payload = &event.payload;
+    let uid = match event.id.trim() {
+        "" => hex(&crypto::random_bytes::<16>()),
+        id => id.to_string(),
+    };
+
+    write_line(output, "BEGIN:VEVENT");
+    write_line(
+        output,
+        &format!("UID:{}@pioneer-work-suite", escape_text(&uid)),
+    );
+    write_line(output, &format!("DTSTAMP:{stamp}"));
+
+    if payload.all_day {
+        let first = start.with_timezone(&Local).date_naive();
+        let last = end.with_timezone(&Local).date_naive();
+        let end_date = if last > first {
+            last
+        } else {
+            first + Duration::days(1)
+        };
+
+        write_line(
+            output,
+            &format!("DTSTART;VALUE=DATE:{}", first.format("%Y%m%d")),
+        );
+        write_line(
+            output,
+            &format!("DTEND;VALUE=DATE:{}", end_date.format("%Y%m%d")),
+        );
+    } else {
+        write_line(output, &format!("DTSTART:{}", format_utc(start)));
+        write_line(output, &format!("DTEND:{}", format_utc(end.max(start))));
+    }
+
+    let title = match payload.title.trim() {
+        "" => "Untitled event",
+        title => title,
+    };
+    write_line(output, &format!("SUMMARY:{}", escape_text(title)));
+
+    if !payload.description.trim().is_empty() {
+        write_line(
+            output,
+            &format!("DESCRIPTION:{}", escape_text(&payload.description)),
+        );
+    }
+    if let Some(priority) = payload.urgency.as_deref().and_then(priority) {
+        write_line(output, &format!("PRIORITY:{priority}"));
+    }
+    if !payload.kind.is_empty() {
+        write_line(
+            output,
+            &format!("X-PIONEER-KIND:{}", escape_text(&payload.kind)),
+        );
+    }
+
+    for (name, value) in [
+        ("CREATED", &event.created_at),
+        ("LAST-MODIFIED", &event.updated_at),
+    ] {
+        if let Some(time) = value.as_deref().and_then(parse_time) {
+            write_line(output, &format!("{name}:{}", format_utc(time)));
+        }
+    }
+
+    write_line(output, "END:VEVENT");
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Writes the events that touch `range` as an iCalendar file. Returns the
+/// file and the number of events in it.
+pub fn export(events: &[CalendarEvent], range: &DateRange) -> (String, usize) {
+    let mut selected: Vec<(&CalendarEvent, DateTime<Utc>, DateTime<Utc>)> = events
+        .iter()
+        .filter_map(|event| {
+            let start = parse_time(&event.payload.start)?;
+            let end = parse_time(&event.payload.end)?;
+            range.touches(start, end).then_some((event, start, end))
+        })
+        .collect();
+    selected.sort_by_key(|(_, start, _)| *start);
+
+    let stamp = format_utc(Utc::now());
+    let mut output = String::new();
+
+    write_line(&mut output, "BEGIN:VCALENDAR");
+    write_line(&mut output, "VERSION:2.0");
+    write_line(&mut output, &format!("PRODID:{PRODUCT_ID}"));
+    write_line(&mut output, "CALSCALE:GREGORIAN");
+    write_line(&mut output, "METHOD:PUBLISH");
+
+    for (event, start, end) in &selected {
+        write_event(&mut output, event, *start, *end, &stamp);
+    }
+
+    write_line(&mut output, "END:VCALENDAR");
+
+    (output, selected.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{import, EventPayload};
+    use super::*;
+
+    fn event(id: &str, start: &str, end: &str, all_day: bool) -> CalendarEvent {
+        CalendarEvent {
+            id: id.to_string(),
+            payload: EventPayload {
+                title: format!("Event {id}"),
+                description: "Bring notes, slides; snacks".to_string(),
+                start: start.to_string(),
+                end: end.to_string(),
+                all_day,
+                kind: "meeting".to_string(),
+                urgency: Some("high".to_string()),
+            },
+            created_at: Some("2024-01-01T08:00:00.000Z".to_string()),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn exports_the_range_and_imports_back() {
+        let range = DateRange::parse("2024-03-01T00:00:00Z", "2024-04-01T00:00:00Z").unwrap();
+        let events = [
+            event(
+                "b",
+                "2024-03-12T15:00:00.000Z",
+                "2024-03-12T16:30:00.000Z",
+                false,
+            ),
+            event(
+                "a",
+                "2024-03-02T09:00:00.000Z",
+                "2024-03-02T09:30:00.000Z",
+                false,
+            ),
+            event(
+                "c",
+                "2024-05-01T09:00:00.000Z",
+                "2024-05-01T10:00:00.000Z",
+                false,
+            ),
+        ];
+        let (file, count) = export(&events, &range);
+
+        assert_eq!(count, 2);
+        assert!(file.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:"));
+        assert!(file.contains("UID:a@pioneer-work-suite\r\n"));
+        assert!(file.contains("DTSTART:20240302T090000Z\r\n"));
+        assert!(file.contains("DESCRIPTION:Bring notes\\, slides\\; snacks\r\n"));
+        assert!(file.contains("CREATED:20240101T080000Z\r\n"));
+        assert!(file.ends_with("END:VCALENDAR\r\n"));
+        assert!(file.split("\r\n").all(|line| line.len() <= 75));
+
+        let imported = import::import(&file, &range);
+        let expected: Vec<EventPayload> = [&events[1], &events[0]]
+            .iter()
+            .map(|event| event.payload.clone())
+            .collect();
+        assert_eq!(imported, expected);
+    }
+
+    #[test]
+    fn all_day_events_round_trip_as_dates() {
+        let start = Local::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+        let start = start
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc);
+        let end = (start.with_timezone(&Local).date_naive() + Duration::days(2))
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc);
+        let range = DateRange {
+            from: start - Duration::days(1),
+            to: end + Duration::days(1),
+        };
+        let iso = |time: DateTime<Utc>| super::super::format_iso(time);
+        let events = [event("day", &iso(start), &iso(end), true)];
+
+        let (file, _) = export(&events, &range);
+        let first = start.with_timezone(&Local).date_naive();
+        assert!(file.contains(&format!(
+            "DTSTART;VALUE=DATE:{}\r\n",
+            first.format("%Y%m%d")
+        )));
+
+        let imported = import::import(&file, &range);
+        assert_eq!(imported, [events[0].payload.clone()]);
+    }
+}
@@ -0,0 +1,271 @@
+// desktop/src-tauri/src/calendar/ics.rs
+
+/*
+ * iCalendar content lines (RFC 5545 section 3.1).
+ *
+ * Reading unfolds continuation lines and splits each line into a name,
+ * parameters and a raw value, then groups lines into a tree of components.
+ * Writing escapes text values and folds lines at 75 octets.
+ */
+
+/// One `NAME;PARAM=value:VALUE` line. Names and parameter names are
+/// uppercased; values are kept as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A `BEGIN:NAME` ... `END:NAME` block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    pub fn properties<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> + 'a {
+        self.properties
+            .iter()
+            .filter(move |property| property.name == name)
+    }
+
+    /// Every nested component called `name`, at any depth.
+    pub fn descendants<'a>(&'a self, name: &str) -> Vec<&'a Component> {
+        let mut found = Vec::new();
+
+        for component in &self.components {
+            if component.name == name {
+                found.push(component);
+            }
+            found.extend(component.descendants(name));
+        }
+
+        found
+    }
+}
+
+fn unfold(source: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in source.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        match line.strip_prefix([' ', '\t']) {
+            Some(continuation) if !lines.is_empty() => {
+                lines.last_mut().unwrap().push_str(continuation);
+            }
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+fn parse_line(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut value_start = None;
+
+    for (index, character) in line.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                sections.push(&line[start..index]);
+                start = index + 1;
+            }
+            ':' if !quoted => {
+                sections.push(&line[start..index]);
+                value_start = Some(index + 1);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let value = &line[value_start?..];
+    let (name, params) = sections.split_first()?;
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let params = params
+        .iter()
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            Some((key.to_ascii_uppercase(), value.to_string()))
+        })
+        .collect();
+
+    Some(Property {
+        name: name.to_ascii_uppercase(),
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Parses `source` into its top-level components, usually one `VCALENDAR`.
+/// Malformed lines are skipped and unclosed components are closed at the
+/// end, as most calendar apps do.
+pub fn parse(source: &str) -> Vec<Component> {
+    let mut stack = vec![Component::default()];
+
+    for line in unfold(source) {
+        let Some(property) = parse_line(&line) else {
+            continue;
+        };
+
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.trim().to_ascii_uppercase(),
+                ..Component::default()
+            }),
+            "END" if stack.len() > 1 => {
+                let component = stack.pop().unwrap();
+                stack.last_mut().unwrap().components.push(component);
+            }
+            "END" => {}
+            _ => stack.last_mut().unwrap().properties.push(property),
+        }
+    }
+
+    while stack.len() > 1 {
+        let component = stack.pop().unwrap();
+        stack.last_mut().unwrap().components.push(component);
+    }
+
+    stack.pop().unwrap().components
+}
+
+/// Decodes a TEXT value.
+pub fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            text.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+
+    text
+}
+
+/// Encodes a TEXT value.
+pub fn escape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+
+    for character in value.replace("\r\n", "\n").chars() {
+        match character {
+            '\\' => text.push_str("\\\\"),
+            ';' => text.push_str("\\;"),
+            ',' => text.push_str("\\,"),
+            '\n' => text.push_str("\\n"),
+            '\r' => {}
+            other => text.push(other),
+        }
+    }
+
+    text
+}
+
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Appends `line` to `output`, folded at 75 octets without splitting a
+/// UTF-8 sequence, and terminated with CRLF.
+pub fn write_line(output: &mut String, line: &str) {
+    let mut width = 0;
+
+    for character in line.chars() {
+        let length = character.len_utf8();
+
+        if width + length > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            // The leading space counts towards the continuation line.
+            width = 1;
+        }
+
+        output.push(character);
+        width += length;
+    }
+
+    output.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_folded_lines_with_quoted_parameters() {
+        let source = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Quarterly \r\n review\r\n\
+                      ATTENDEE;CN=\"Doe; Jane\";ROLE=CHAIR:mailto:jane@example.com\r\n\
+                      END:VEVENT\r\nEND:VCALENDAR\r\n";
+        let calendars = parse(source);
+        let event = &calendars[0].components[0];
+
+        assert_eq!(calendars[0].name, "VCALENDAR");
+        assert_eq!(event.name, "VEVENT");
+        assert_eq!(event.property("SUMMARY").unwrap().value, "Quarterly review");
+
+        let attendee = event.property("ATTENDEE").unwrap();
+        assert_eq!(attendee.param("CN"), Some("Doe; Jane"));
+        assert_eq!(attendee.param("ROLE"), Some("CHAIR"));
+        assert_eq!(attendee.value, "mailto:jane@example.com");
+    }
+
+    #[test]
+    fn text_escaping_round_trips() {
+        let text = "Agenda; budget, hiring\nC:\\notes";
+
+        assert_eq!(
+            escape_text(text),
+            "Agenda\\; budget\\, hiring\\nC:\\\\notes"
+        );
+        assert_eq!(unescape_text(&escape_text(text)), text);
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_octets_on_character_boundaries() {
+        let mut output = String::new();
+        write_line(&mut output, &format!("SUMMARY:{}", "é".repeat(60)));
+
+        for line in output.split("\r\n").filter(|line| !line.is_empty()) {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{} octets", line.len());
+        }
+
+        let unfolded = &unfold(&output)[0];
+        assert_eq!(unfolded, &format!("SUMMARY:{}", "é".repeat(60)));
+    }
+}
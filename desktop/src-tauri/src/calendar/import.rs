@@ -0,0 +1,669 @@
+// desktop/src-tauri/src/calendar/import.rs
+
+/*
+ * VEVENTs to event payloads.
+ *
+ * TZIDs resolve to IANA zones, including Outlook's Windows names and the
+ * path-style ids some exporters prefix; an unknown TZID falls back to the
+ * standard offset of its VTIMEZONE, then to local time. Recurring series
+ * are expanded within the import range, minus EXDATEs and occurrences
+ * replaced by RECURRENCE-ID overrides, plus RDATEs. Events that are not
+ * recurring are imported whatever their date.
+ */
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Utc,
+};
+use chrono_tz::Tz;
+
+use super::ics::{self, Component, Property};
+use super::recurrence::Rule;
+use super::{format_iso, urgency, DateRange, EventPayload};
+
+/// Occurrences generated for one series at most.
+const MAX_OCCURRENCES: usize = 5_000;
+
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("UTC", "UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("FLE Standard Time", "Europe/Kiev"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Zone {
+    Utc,
+    Named(Tz),
+    Fixed(FixedOffset),
+    Local,
+}
+
+fn earliest<T: TimeZone>(zone: &T, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => {
+            Some(time.with_timezone(&Utc))
+        }
+        LocalResult::None => None,
+    }
+}
+
+impl Zone {
+    fn to_utc(self, local: NaiveDateTime) -> DateTime<Utc> {
+        let resolve = |local| match self {
+            Zone::Utc => Some(Utc.from_utc_datetime(&local)),
+            Zone::Named(tz) => earliest(&tz, local),
+            Zone::Fixed(offset) => earliest(&offset, local),
+            Zone::Local => earliest(&chrono::Local, local),
+        };
+
+        // A time skipped by a DST change is moved past the gap.
+        resolve(local)
+            .or_else(|| resolve(local + Duration::hours(1)))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+    }
+}
+
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+    let (sign, digits) = match value.trim().split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    let number = |range: std::ops::Range<usize>| -> Option<i32> {
+        digits.get(range).map_or(Some(0), |part| part.parse().ok())
+    };
+
+    if digits.len() != 4 && digits.len() != 6 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (number(0..2)? * 3600 + number(2..4)? * 60 + number(4..6)?))
+}
+
+fn resolve_tzid(tzid: &str, calendar: &Component) -> Zone {
+    let tzid = tzid.trim();
+
+    if let Ok(tz) = Tz::from_str(tzid) {
+        return Zone::Named(tz);
+    }
+
+    // e.g. `/mozilla.org/20050126_1/America/New_York`.
+    let segments: Vec<&str> = tzid.split('/').filter(|part| !part.is_empty()).collect();
+    for start in 1..segments.len() {
+        if let Ok(tz) = Tz::from_str(&segments[start..].join("/")) {
+            return Zone::Named(tz);
+        }
+    }
+
+    if let Some((_, name)) = WINDOWS_ZONES
+        .iter()
+        .find(|(windows, _)| windows.eq_ignore_ascii_case(tzid))
+    {
+        if let Ok(tz) = Tz::from_str(name) {
+            return Zone::Named(tz);
+        }
+    }
+
+    calendar
+        .descendants("VTIMEZONE")
+        .into_iter()
+        .find(|zone| {
+            zone.property("TZID")
+                .is_some_and(|property| property.value.trim() == tzid)
+        })
+        .and_then(|zone| {
+            let standard = zone
+                .components
+                .iter()
+                .filter(|part| part.name == "STANDARD");
+            let latest = standard.max_by_key(|part| {
+                part.property("DTSTART")
+                    .map(|start| start.value.clone())
+                    .unwrap_or_default()
+            })?;
+            parse_offset(&latest.property("TZOFFSETTO")?.value)
+        })
+        .map(Zone::Fixed)
+        .unwrap_or(Zone::Local)
+}
+
+/// A DATE or DATE-TIME value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum When {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime, Zone),
+}
+
+impl When {
+    fn parse(value: &str, tzid: Option<&str>, calendar: &Component) -> Option<When> {
+        let value = value.trim();
+
+        if value.len() == 8 {
+            return NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .map(When::Date);
+        }
+
+        let (value, zone) = match value.strip_suffix(['Z', 'z']) {
+            Some(value) => (value, Zone::Utc),
+            None => match tzid {
+                Some(tzid) => (value, resolve_tzid(tzid, calendar)),
+                None => (value, Zone::Local),
+            },
+        };
+        let local = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+
+        Some(When::DateTime(local, zone))
+    }
+
+    fn from_property(property: &Property, calendar: &Component) -> Option<When> {
+        When::parse(&property.value, property.param("TZID"), calendar)
+    }
+
+    fn date(self) -> NaiveDate {
+        match self {
+            When::Date(date) => date,
+            When::DateTime(local, _) => local.date(),
+        }
+    }
+
+    fn instant(self) -> DateTime<Utc> {
+        match self {
+            When::Date(date) => Zone::Local.to_utc(date.and_time(NaiveTime::MIN)),
+            When::DateTime(local, zone) => zone.to_utc(local),
+        }
+    }
+
+    /// The same wall-clock time on `date`.
+    fn on(self, date: NaiveDate) -> When {
+        match self {
+            When::Date(_) => When::Date(date),
+            When::DateTime(local, zone) => When::DateTime(date.and_time(local.time()), zone),
+        }
+    }
+}
+
+/// Parses an ISO 8601 duration such as `PT1H30M` or `-P1D`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut in_time = false;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = after;
+            continue;
+        }
+
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let number: i64 = rest[..digits].parse().ok()?;
+        let unit = rest[digits..].chars().next()?;
+
+        total += match (unit, in_time) {
+            ('W', false) => Duration::weeks(number),
+            ('D', false) => Duration::days(number),
+            ('H', true) => Duration::hours(number),
+            ('M', true) => Duration::minutes(number),
+            ('S', true) => Duration::seconds(number),
+            _ => return None,
+        };
+        rest = &rest[digits + 1..];
+    }
+
+    Some(if negative { -total } else { total })
+}
+
+fn text(event: &Component, name: &str) -> Option<String> {
+    event
+        .property(name)
+        .map(|property| ics::unescape_text(&property.value).trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn is_cancelled(event: &Component) -> bool {
+    event
+        .property("STATUS")
+        .is_some_and(|status| status.value.trim().eq_ignore_ascii_case("CANCELLED"))
+}
+
+/// The parts of a VEVENT shared by all of its occurrences.
+struct Details {
+    title: String,
+    description: String,
+    kind: String,
+    urgency: Option<String>,
+}
+
+impl Details {
+    fn read(event: &Component) -> Details {
+        let mut description = text(event, "DESCRIPTION").unwrap_or_default();
+
+        if let Some(location) = text(event, "LOCATION") {
+            if !description.is_empty() {
+                description.push_str("\n\n");
+            }
+            description.push_str("Location: ");
+            description.push_str(&location);
+        }
+
+        Details {
+            title: text(event, "SUMMARY").unwrap_or_else(|| "Untitled event".to_string()),
+            description,
+            kind: text(event, "X-PIONEER-KIND").unwrap_or_else(|| "event".to_string()),
+            urgency: event
+                .property("PRIORITY")
+                .and_then(|priority| priority.value.trim().parse().ok())
+                .and_then(urgency)
+                .map(str::to_string),
+        }
+    }
+
+    fn payload(&self, start: When, length: Length) -> EventPayload {
+        let (start_time, end_time) = match (start, length) {
+            (When::Date(date), Length::Days(days)) => (
+                start.instant(),
+                When::Date(date + Duration::days(days.max(1))).instant(),
+            ),
+            (_, Length::Exact(duration)) => {
+                let start = start.instant();
+                (start, start + duration.max(Duration::zero()))
+            }
+            (When::DateTime(..), Length::Days(days)) => {
+                let start = start.instant();
+                (start, start + Duration::days(days.max(0)))
+            }
+        };
+
+        EventPayload {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            start: format_iso(start_time),
+            end: format_iso(end_time),
+            all_day: matches!(start, When::Date(_)),
+            kind: self.kind.clone(),
+            urgency: self.urgency.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Length {
+    /// Whole days, for DATE starts.
+    Days(i64),
+    Exact(Duration),
+}
+
+fn length(event: &Component, start: When, calendar: &Component) -> Length {
+    let end = event
+        .property("DTEND")
+        .and_then(|end| When::from_property(end, calendar));
+
+    match (start, end) {
+        (When::Date(start), Some(When::Date(end))) => Length::Days((end - start).num_days()),
+        (_, Some(end)) => Length::Exact(end.instant() - start.instant()),
+        (_, None) => match event
+            .property("DURATION")
+            .and_then(|duration| parse_duration(&duration.value))
+        {
+            Some(duration) if matches!(start, When::Date(_)) => Length::Days(duration.num_days()),
+            Some(duration) => Length::Exact(duration),
+            // RFC 5545: a DATE start lasts one day, a DATE-TIME start none.
+            None if matches!(start, When::Date(_)) => Length::Days(1),
+            None => Length::Exact(Duration::zero()),
+        },
+    }
+}
+
+fn values<'a>(
+    event: &'a Component,
+    name: &'a str,
+    calendar: &'a Component,
+) -> impl Iterator<Item = When> + 'a {
+    event.properties(name).flat_map(move |property| {
+        property
+            .value
+            .split(',')
+            .filter_map(|value| When::parse(value, property.param("TZID"), calendar))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// A recurring VEVENT.
+struct Series<'a> {
+    event: &'a Component,
+    calendar: &'a Component,
+    uid: String,
+    start: When,
+    length: Length,
+    details: Details,
+}
+
+impl Series<'_> {
+    /// Appends the occurrences that touch `range`.
+    fn expand(
+        &self,
+        rule: &Rule,
+        replaced: &HashSet<(String, DateTime<Utc>)>,
+        range: &DateRange,
+        output: &mut Vec<EventPayload>,
+    ) {
+        let Series {
+            event,
+            calendar,
+            ref uid,
+            start,
+            length,
+            ref details,
+        } = *self;
+
+        let until = rule.until.as_deref().and_then(|until| {
+            match (When::parse(until, None, calendar)?, start) {
+                // A floating UNTIL is in the start's zone.
+                (When::DateTime(local, Zone::Local), When::DateTime(_, zone)) => {
+                    Some(When::DateTime(local, zone))
+                }
+                (until, _) => Some(until),
+            }
+        });
+        let excluded: HashSet<DateTime<Utc>> = values(event, "EXDATE", calendar)
+            .map(When::instant)
+            .collect();
+        let is_wanted = |occurrence: When| {
+            let instant = occurrence.instant();
+            !excluded.contains(&instant) && !replaced.contains(&(uid.to_string(), instant))
+        };
+        let emit = |occurrence: When, output: &mut Vec<EventPayload>| {
+            let payload = details.payload(occurrence, length);
+            let touches = DateTime::parse_from_rfc3339(&payload.start)
+                .ok()
+                .zip(DateTime::parse_from_rfc3339(&payload.end).ok())
+                .is_some_and(|(start, end)| {
+                    range.touches(start.with_timezone(&Utc), end.with_timezone(&Utc))
+                });
+
+            if touches {
+                output.push(payload);
+            }
+        };
+
+        let mut seen = HashSet::new();
+
+        for date in rule.dates(start.date()).take(MAX_OCCURRENCES) {
+            let occurrence = start.on(date);
+            let instant = occurrence.instant();
+
+            let past_until = match until {
+                Some(When::Date(until)) => date > until,
+                Some(until) => instant > until.instant(),
+                None => false,
+            };
+            if past_until || instant >= range.to {
+                break;
+            }
+
+            seen.insert(instant);
+            if is_wanted(occurrence) {
+                emit(occurrence, output);
+            }
+        }
+
+        for occurrence in values(event, "RDATE", calendar) {
+            let occurrence = match (occurrence, start) {
+                // A bare RDATE date keeps the series' time of day.
+                (When::Date(date), When::DateTime(..)) => start.on(date),
+                (occurrence, _) => occurrence,
+            };
+
+            if seen.insert(occurrence.instant()) && is_wanted(occurrence) {
+                emit(occurrence, output);
+            }
+        }
+    }
+}
+
+/// Converts the events in `source`, expanding recurring series within
+/// `range`, ordered by start.
+pub fn import(source: &str, range: &DateRange) -> Vec<EventPayload> {
+    let mut output = Vec::new();
+
+    for calendar in ics::parse(source) {
+        let events = if calendar.name == "VEVENT" {
+            vec![&calendar]
+        } else {
+            calendar.descendants("VEVENT")
+        };
+
+        // Occurrences moved or cancelled by a RECURRENCE-ID override.
+        let replaced: HashSet<(String, DateTime<Utc>)> = events
+            .iter()
+            .filter_map(|event| {
+                let uid = event.property("UID")?.value.trim().to_string();
+                let id = When::from_property(event.property("RECURRENCE-ID")?, &calendar)?;
+                Some((uid, id.instant()))
+            })
+            .collect();
+
+        for event in events {
+            let Some(start) = event
+                .property("DTSTART")
+                .and_then(|start| When::from_property(start, &calendar))
+            else {
+                continue;
+            };
+
+            if is_cancelled(event) {
+                continue;
+            }
+
+            let details = Details::read(event);
+            let length = length(event, start, &calendar);
+            let is_override = event.property("RECURRENCE-ID").is_some();
+            let rule = event
+                .property("RRULE")
+                .filter(|_| !is_override)
+                .and_then(|rule| Rule::parse(&rule.value));
+
+            match rule {
+                Some(rule) => {
+                    let series = Series {
+                        event,
+                        calendar: &calendar,
+                        uid: event
+                            .property("UID")
+                            .map(|uid| uid.value.trim().to_string())
+                            .unwrap_or_default(),
+                        start,
+                        length,
+                        details,
+                    };
+                    series.expand(&rule, &replaced, range, &mut output);
+                }
+                None => {
+                    let payload = details.payload(start, length);
+                    let in_range = !is_override || {
+                        let end = match length {
+                            Length::Days(days) => {
+                                When::Date(start.date() + Duration::days(days.max(1))).instant()
+                            }
+                            Length::Exact(duration) => start.instant() + duration,
+                        };
+                        range.touches(start.instant(), end)
+                    };
+
+                    if in_range {
+                        output.push(payload);
+                    }
+                }
+            }
+        }
+    }
+
+    output.sort_by(|a, b| a.start.cmp(&b.start));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(from: &str, to: &str) -> DateRange {
+        DateRange::parse(from, to).unwrap()
+    }
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{events}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn zoned_times_convert_to_utc_across_dst() {
+        let source = calendar(
+            "BEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Standup\r\n\
+             DTSTART;TZID=America/New_York:20240308T090000\r\n\
+             DURATION:PT15M\r\nRRULE:FREQ=DAILY;COUNT=3\r\nEND:VEVENT\r\n",
+        );
+        let events = import(
+            &source,
+            &range("2024-03-01T00:00:00Z", "2024-04-01T00:00:00Z"),
+        );
+        let starts: Vec<&str> = events.iter().map(|event| event.start.as_str()).collect();
+
+        assert_eq!(
+            starts,
+            [
+                "2024-03-08T14:00:00.000Z",
+                "2024-03-09T14:00:00.000Z",
+                "2024-03-10T13:00:00.000Z",
+            ]
+        );
+        assert_eq!(events[0].end, "2024-03-08T14:15:00.000Z");
+        assert_eq!(events[0].title, "Standup");
+        assert!(!events[0].all_day);
+    }
+
+    #[test]
+    fn windows_and_prefixed_tzids_resolve() {
+        let source = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;TZID=W. Europe Standard Time:20240115T100000\r\n\
+             DTEND;TZID=\"/mozilla.org/20050126_1/Europe/Berlin\":20240115T110000\r\n\
+             END:VEVENT\r\n",
+        );
+        let events = import(
+            &source,
+            &range("2024-01-01T00:00:00Z", "2024-02-01T00:00:00Z"),
+        );
+
+        assert_eq!(events[0].start, "2024-01-15T09:00:00.000Z");
+        assert_eq!(events[0].end, "2024-01-15T10:00:00.000Z");
+    }
+
+    #[test]
+    fn unknown_tzids_use_the_vtimezone_offset() {
+        let source = calendar(
+            "BEGIN:VTIMEZONE\r\nTZID:Custom\r\nBEGIN:STANDARD\r\nDTSTART:19700101T000000\r\n\
+             TZOFFSETTO:+0530\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n\
+             BEGIN:VEVENT\r\nDTSTART;TZID=Custom:20240115T100000\r\nEND:VEVENT\r\n",
+        );
+        let events = import(
+            &source,
+            &range("2024-01-01T00:00:00Z", "2024-02-01T00:00:00Z"),
+        );
+
+        assert_eq!(events[0].start, "2024-01-15T04:30:00.000Z");
+    }
+
+    #[test]
+    fn exdates_and_overrides_replace_occurrences() {
+        let source = calendar(
+            "BEGIN:VEVENT\r\nUID:series\r\nSUMMARY:Review\r\nDTSTART:20240101T150000Z\r\n\
+             DTEND:20240101T160000Z\r\nRRULE:FREQ=WEEKLY;UNTIL=20240129T150000Z\r\n\
+             EXDATE:20240108T150000Z\r\nPRIORITY:1\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:series\r\nSUMMARY:Review (moved)\r\n\
+             RECURRENCE-ID:20240115T150000Z\r\nDTSTART:20240116T150000Z\r\n\
+             DTEND:20240116T160000Z\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:series\r\nRECURRENCE-ID:20240122T150000Z\r\n\
+             DTSTART:20240122T150000Z\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n",
+        );
+        let events = import(
+            &source,
+            &range("2024-01-01T00:00:00Z", "2024-03-01T00:00:00Z"),
+        );
+        let summary: Vec<(&str, &str)> = events
+            .iter()
+            .map(|event| (event.start.as_str(), event.title.as_str()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("2024-01-01T15:00:00.000Z", "Review"),
+                ("2024-01-16T15:00:00.000Z", "Review (moved)"),
+                ("2024-01-29T15:00:00.000Z", "Review"),
+            ]
+        );
+        assert_eq!(events[0].urgency.as_deref(), Some("critical"));
+    }
+
+    #[test]
+    fn expansion_stops_at_the_range_but_single_events_do_not() {
+        let source = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240101\r\nRRULE:FREQ=MONTHLY\r\n\
+             SUMMARY:Rent\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20200101\r\nSUMMARY:Old\r\n\
+             DESCRIPTION:Line one\\nLine two\r\nLOCATION:Room 4\\, east\r\nEND:VEVENT\r\n",
+        );
+        let events = import(
+            &source,
+            &range("2024-02-15T00:00:00Z", "2024-05-15T00:00:00Z"),
+        );
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].title, "Old");
+        assert_eq!(
+            events[0].description,
+            "Line one\nLine two\n\nLocation: Room 4, east"
+        );
+        assert!(events.iter().all(|event| event.all_day));
+        assert_eq!(
+            events[1].start,
+            format_iso(When::Date(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).instant())
+        );
+    }
+
+    #[test]
+    fn durations_parse() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1W"), Some(Duration::days(7)));
+        assert_eq!(parse_duration("-P1DT2H"), Some(-Duration::hours(26)));
+        assert_eq!(parse_duration("1H"), None);
+    }
+}
@@ -0,0 +1,120 @@
+// desktop/src-tauri/src/calendar/mod.rs
+
+/*
+ * iCalendar interop for calendar events.
+ *
+ * Imports turn the VEVENTs of an .ics file into the webview's
+ * `EventPayload`s, expanding recurring series within a range; the webview
+ * creates them through `createEvent` so they queue and sync like events
+ * added by hand. Exports write the events of a range as a VCALENDAR.
+ *
+ * All-day events follow the web client: they start at local midnight and
+ * end at local midnight after their last day.
+ */
+
+pub mod commands;
+pub mod export;
+pub mod ics;
+pub mod import;
+pub mod recurrence;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// The webview's `EventPayload`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPayload {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub all_day: bool,
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub urgency: Option<String>,
+}
+
+fn default_kind() -> String {
+    "event".to_string()
+}
+
+/// The fields of the webview's `CalendarEvent` that exports need.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    #[serde(default)]
+    pub id: String,
+    #[serde(flatten)]
+    pub payload: EventPayload,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// A half-open `[from, to)` range, as the webview passes to `fetchEvents`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl DateRange {
+    pub fn parse(from: &str, to: &str) -> Result<DateRange> {
+        let parse = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|_| Error::InvalidInput(format!("{value} is not a valid date.")))
+        };
+        let range = DateRange {
+            from: parse(from)?,
+            to: parse(to)?,
+        };
+
+        if range.to <= range.from {
+            return Err(Error::InvalidInput(
+                "The end of the date range must be after its start.".into(),
+            ));
+        }
+
+        Ok(range)
+    }
+
+    /// Matches `eventTouchesRange` in apps/web/src/api/events.ts.
+    pub fn touches(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        end >= self.from && start < self.to
+    }
+}
+
+/// The ISO format the web client stores, e.g. `2024-01-01T09:00:00.000Z`.
+fn format_iso(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Maps the webview's urgency to an iCalendar PRIORITY (1 is highest).
+fn priority(urgency: &str) -> Option<u8> {
+    Some(match urgency {
+        "critical" => 1,
+        "high" => 3,
+        "medium" => 5,
+        "low" => 7,
+        _ => return None,
+    })
+}
+
+fn urgency(priority: u8) -> Option<&'static str> {
+    Some(match priority {
+        1 => "critical",
+        2..=4 => "high",
+        5 => "medium",
+        6..=9 => "low",
+        _ => return None,
+    })
+}
@@ -0,0 +1,425 @@
+// desktop/src-tauri/src/calendar/recurrence.rs
+
+/*
+ * RRULE expansion (RFC 5545 section 3.3.10).
+ *
+ * Supports DAILY, WEEKLY, MONTHLY and YEARLY rules with INTERVAL, COUNT,
+ * UNTIL, BYDAY (with ordinals), BYMONTHDAY, BYMONTH, BYSETPOS and WKST.
+ * Rules work on dates: every occurrence keeps the start's wall-clock time,
+ * which is what the sub-daily BY parts would otherwise vary, so rules that
+ * use them (or a sub-daily FREQ) are not expanded.
+ */
+
+use std::collections::VecDeque;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    /// The raw UNTIL value; it is a DATE or DATE-TIME in the start's zone,
+    /// so the caller resolves it.
+    pub until: Option<String>,
+    pub by_day: Vec<(Option<i32>, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    Some(match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn numbers<T: std::str::FromStr>(value: &str) -> Option<Vec<T>> {
+    value
+        .split(',')
+        .map(|part| part.trim().trim_start_matches('+').parse().ok())
+        .collect()
+}
+
+impl Rule {
+    /// Parses an RRULE value. Returns `None` for rules this module cannot
+    /// expand faithfully.
+    pub fn parse(value: &str) -> Option<Rule> {
+        let mut frequency = None;
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            let value = value.trim().to_ascii_uppercase();
+
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|n| *n > 0)?,
+                "COUNT" => rule.count = Some(value.parse().ok()?),
+                "UNTIL" => rule.until = Some(value),
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(|token| {
+                            let token = token.trim();
+                            // Not a char boundary in tokens like `1€`.
+                            let (ordinal, day) =
+                                token.split_at_checked(token.len().checked_sub(2)?)?;
+                            let ordinal = match ordinal {
+                                "" => None,
+                                number => Some(number.trim_start_matches('+').parse().ok()?),
+                            };
+                            Some((ordinal, weekday(day)?))
+                        })
+                        .collect::<Option<_>>()?
+                }
+                "BYMONTHDAY" => rule.by_month_day = numbers(&value)?,
+                "BYMONTH" => rule.by_month = numbers(&value)?,
+                "BYSETPOS" => rule.by_set_pos = numbers(&value)?,
+                "WKST" => rule.week_start = weekday(&value)?,
+                "BYHOUR" | "BYMINUTE" | "BYSECOND" | "BYYEARDAY" | "BYWEEKNO" => return None,
+                // Unknown parts are ignored, per the RFC's extension rules.
+                _ => {}
+            }
+        }
+
+        rule.frequency = frequency?;
+        Some(rule)
+    }
+
+    /// The occurrence dates for a series starting on `start`, in order.
+    /// `start` is always the first occurrence. COUNT is applied, UNTIL is
+    /// left to the caller, and unbounded rules run until the iterator is
+    /// dropped.
+    pub fn dates(&self, start: NaiveDate) -> Dates<'_> {
+        Dates {
+            rule: self,
+            start,
+            period: 0,
+            empty_periods: 0,
+            pending: VecDeque::from([start]),
+            emitted: 0,
+        }
+    }
+
+    fn period_start(&self, start: NaiveDate, period: u32) -> Option<NaiveDate> {
+        let step = period.checked_mul(self.interval)?;
+
+        match self.frequency {
+            Frequency::Daily => start.checked_add_signed(Duration::days(step.into())),
+            Frequency::Weekly => {
+                let offset = (7 + start.weekday().num_days_from_monday()
+                    - self.week_start.num_days_from_monday())
+                    % 7;
+                (start - Duration::days(offset.into()))
+                    .checked_add_signed(Duration::weeks(step.into()))
+            }
+            Frequency::Monthly => start.with_day(1)?.checked_add_months(Months::new(step)),
+            Frequency::Yearly => {
+                NaiveDate::from_ymd_opt(start.year().checked_add(step as i32)?, 1, 1)
+            }
+        }
+    }
+
+    fn matches_weekday(&self, date: NaiveDate, first: NaiveDate, last: NaiveDate) -> bool {
+        self.by_day.iter().any(|(ordinal, weekday)| {
+            if date.weekday() != *weekday {
+                return false;
+            }
+
+            match ordinal {
+                None => true,
+                Some(n) if *n > 0 => (date - first).num_days() / 7 + 1 == i64::from(*n),
+                Some(n) => -((last - date).num_days() / 7 + 1) == i64::from(*n),
+            }
+        })
+    }
+
+    fn month_days(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        let last = first + Months::new(1) - Duration::days(1);
+
+        let mut days: Vec<NaiveDate> = if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|day| match *day {
+                    day if day > 0 => first.with_day(day as u32),
+                    day if day < 0 => last.checked_add_signed(Duration::days(i64::from(day + 1))),
+                    _ => None,
+                })
+                .filter(|date| date.month() == month)
+                .collect()
+        } else if !self.by_day.is_empty() {
+            first.iter_days().take_while(|date| *date <= last).collect()
+        } else {
+            first.with_day(default_day).into_iter().collect()
+        };
+
+        if !self.by_day.is_empty() {
+            days.retain(|date| self.matches_weekday(*date, first, last));
+        }
+
+        days
+    }
+
+    fn candidates(&self, start: NaiveDate, period_start: NaiveDate) -> Vec<NaiveDate> {
+        let in_months =
+            |date: &NaiveDate| self.by_month.is_empty() || self.by_month.contains(&date.month());
+
+        let mut dates: Vec<NaiveDate> = match self.frequency {
+            Frequency::Daily => {
+                let date = period_start;
+                let day_matches = self.by_month_day.is_empty() || {
+                    let last = date.with_day(1).unwrap() + Months::new(1) - Duration::days(1);
+                    let from_end = date.day() as i32 - last.day() as i32 - 1;
+                    self.by_month_day
+                        .iter()
+                        .any(|day| *day == date.day() as i32 || *day == from_end)
+                };
+                let weekday_matches = self.by_day.is_empty()
+                    || self
+                        .by_day
+                        .iter()
+                        .any(|(_, weekday)| *weekday == date.weekday());
+
+                vec![date]
+                    .into_iter()
+                    .filter(|date| in_months(date) && day_matches && weekday_matches)
+                    .collect()
+            }
+            Frequency::Weekly => period_start
+                .iter_days()
+                .take(7)
+                .filter(|date| {
+                    let weekday_matches = if self.by_day.is_empty() {
+                        date.weekday() == start.weekday()
+                    } else {
+                        self.by_day
+                            .iter()
+                            .any(|(_, weekday)| *weekday == date.weekday())
+                    };
+                    weekday_matches && in_months(date)
+                })
+                .collect(),
+            Frequency::Monthly => {
+                if in_months(&period_start) {
+                    self.month_days(period_start.year(), period_start.month(), start.day())
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Yearly => {
+                let year = period_start.year();
+
+                if self.by_month.is_empty()
+                    && self.by_month_day.is_empty()
+                    && !self.by_day.is_empty()
+                {
+                    // BYDAY ordinals count within the whole year.
+                    let last = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+                    period_start
+                        .iter_days()
+                        .take_while(|date| *date <= last)
+                        .filter(|date| self.matches_weekday(*date, period_start, last))
+                        .collect()
+                } else {
+                    let months: Vec<u32> = if !self.by_month.is_empty() {
+                        self.by_month.clone()
+                    } else if !self.by_month_day.is_empty() {
+                        (1..=12).collect()
+                    } else {
+                        vec![start.month()]
+                    };
+
+                    months
+                        .into_iter()
+                        .flat_map(|month| self.month_days(year, month, start.day()))
+                        .collect()
+                }
+            }
+        };
+
+        dates.sort();
+        dates.dedup();
+
+        if !self.by_set_pos.is_empty() {
+            let total = dates.len() as i32;
+            let mut selected: Vec<NaiveDate> = self
+                .by_set_pos
+                .iter()
+                .filter_map(|position| {
+                    let index = if *position > 0 {
+                        position - 1
+                    } else {
+                        total + position
+                    };
+                    (0..total).contains(&index).then(|| dates[index as usize])
+                })
+                .collect();
+            selected.sort();
+            selected.dedup();
+            dates = selected;
+        }
+
+        dates.retain(|date| *date > start);
+        dates
+    }
+}
+
+/// Periods in a row without an occurrence before a rule is treated as
+/// exhausted, e.g. BYMONTHDAY=30 with BYMONTH=2.
+const MAX_EMPTY_PERIODS: u32 = 1_000;
+
+pub struct Dates<'a> {
+    rule: &'a Rule,
+    start: NaiveDate,
+    period: u32,
+    empty_periods: u32,
+    pending: VecDeque<NaiveDate>,
+    emitted: u32,
+}
+
+impl Iterator for Dates<'_> {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        if self.rule.count.is_some_and(|count| self.emitted >= count) {
+            return None;
+        }
+
+        while self.pending.is_empty() {
+            if self.empty_periods >= MAX_EMPTY_PERIODS {
+                return None;
+            }
+
+            let period_start = self.rule.period_start(self.start, self.period)?;
+            self.period += 1;
+
+            let dates = self.rule.candidates(self.start, period_start);
+            if dates.is_empty() {
+                self.empty_periods += 1;
+            } else {
+                self.empty_periods = 0;
+                self.pending.extend(dates);
+            }
+        }
+
+        self.emitted += 1;
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn expand(rule: &str, start: NaiveDate, limit: usize) -> Vec<String> {
+        Rule::parse(rule)
+            .unwrap()
+            .dates(start)
+            .take(limit)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn weekly_by_day_counts_the_start() {
+        assert_eq!(
+            expand("FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4", date(2024, 1, 1), 10),
+            ["2024-01-01", "2024-01-03", "2024-01-08", "2024-01-10"]
+        );
+        assert_eq!(
+            expand("FREQ=WEEKLY;INTERVAL=2;BYDAY=FR", date(2024, 1, 5), 3),
+            ["2024-01-05", "2024-01-19", "2024-02-02"]
+        );
+    }
+
+    #[test]
+    fn monthly_rules_handle_ordinals_and_short_months() {
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYDAY=-1FR", date(2024, 1, 26), 3),
+            ["2024-01-26", "2024-02-23", "2024-03-29"]
+        );
+        assert_eq!(
+            expand("FREQ=MONTHLY", date(2024, 1, 31), 3),
+            ["2024-01-31", "2024-03-31", "2024-05-31"]
+        );
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+                date(2024, 5, 31),
+                3
+            ),
+            ["2024-05-31", "2024-06-28", "2024-07-31"]
+        );
+    }
+
+    #[test]
+    fn yearly_rules_expand_months_and_leap_days() {
+        assert_eq!(
+            expand("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH", date(2024, 11, 28), 2),
+            ["2024-11-28", "2025-11-27"]
+        );
+        assert_eq!(
+            expand("FREQ=YEARLY", date(2024, 2, 29), 2),
+            ["2024-02-29", "2028-02-29"]
+        );
+    }
+
+    #[test]
+    fn impossible_rules_end_instead_of_spinning() {
+        assert_eq!(
+            expand("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", date(2024, 1, 1), 5),
+            ["2024-01-01"]
+        );
+        assert!(Rule::parse("FREQ=HOURLY").is_none());
+        assert!(Rule::parse("FREQ=DAILY;BYHOUR=9,17").is_none());
+    }
+
+    #[test]
+    fn malformed_multibyte_days_are_rejected() {
+        assert!(Rule::parse("FREQ=MONTHLY;BYDAY=1€").is_none());
+        assert!(Rule::parse("FREQ=WEEKLY;BYDAY=MO,€").is_none());
+        assert!(Rule::parse("FREQ=WEEKLY;BYDAY=ÄMO").is_none());
+    }
+}
@@ -0,0 +1,42 @@
+// desktop/src-tauri/src/capture/commands.rs
+
+use tauri::{AppHandle, Manager, State};
+
+use super::{Capture, CaptureKind, CaptureShortcuts, CAPTURE_WINDOW_LABEL};
+use crate::error::Result;
+use crate::sync::SyncService;
+use crate::tray;
+
+#[tauri::command]
+pub fn get_capture_shortcuts(capture: State<'_, Capture>) -> CaptureShortcuts {
+    capture.shortcuts()
+}
+
+#[tauri::command]
+pub async fn set_capture_shortcuts(
+    app: AppHandle,
+    capture: State<'_, Capture>,
+    shortcuts: CaptureShortcuts,
+) -> Result<CaptureShortcuts> {
+    capture.set_shortcuts(&app, shortcuts)?;
+    Ok(capture.shortcuts())
+}
+
+/// Hides the capture window. `saved` names what was just captured so the
+/// other windows refresh and the change starts uploading; `show_main` brings
+/// the main window forward, e.g. to unlock the workspace.
+#[tauri::command]
+pub fn finish_capture(app: AppHandle, saved: Option<CaptureKind>, show_main: bool) {
+    if let Some(window) = app.get_window(CAPTURE_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+
+    if let Some(kind) = saved {
+        let _ = app.emit_all(kind.resource().changed_event(), ());
+        app.state::<SyncService>().wake();
+    }
+
+    if show_main {
+        tray::show_main_window(&app);
+    }
+}
@@ -0,0 +1,245 @@
+// desktop/src-tauri/src/capture/mod.rs
+
+/*
+ * Quick capture from anywhere.
+ *
+ * OS-wide shortcuts open a small always-on-top window where a task, note or
+ * event can be jotted down without bringing the main window forward. The
+ * capture window is an ordinary webview, so it saves through the same
+ * `createTask`, `createDocument` and `createEvent` calls, and therefore the
+ * same local store and op queues, as the main window. When it is done it
+ * calls `finish_capture`, which tells every window what changed and wakes
+ * the sync worker.
+ *
+ * The shortcuts are Tauri accelerators such as `CmdOrCtrl+Shift+Space`,
+ * kept in the app config directory.
+ */
+
+pub mod commands;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, GlobalShortcutManager, Manager, WindowBuilder, WindowUrl};
+
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+use crate::sync::engine::Resource;
+
+pub const CAPTURE_SETTINGS_FILE_NAME: &str = "capture-settings.json";
+
+pub const CAPTURE_WINDOW_LABEL: &str = "capture";
+
+/// Sent to an open capture window when a shortcut asks for another kind.
+pub const CAPTURE_KIND_EVENT: &str = "pioneer:capture-kind";
+
+const WINDOW_WIDTH: f64 = 480.0;
+const WINDOW_HEIGHT: f64 = 300.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureKind {
+    Task,
+    Note,
+    Event,
+}
+
+impl CaptureKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            CaptureKind::Task => "task",
+            CaptureKind::Note => "note",
+            CaptureKind::Event => "event",
+        }
+    }
+
+    /// Notes are captured as documents.
+    pub fn resource(self) -> Resource {
+        match self {
+            CaptureKind::Task => Resource::Tasks,
+            CaptureKind::Note => Resource::Documents,
+            CaptureKind::Event => Resource::Events,
+        }
+    }
+}
+
+/// One optional accelerator per kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureShortcuts {
+    #[serde(default)]
+    pub task: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+impl Default for CaptureShortcuts {
+    fn default() -> Self {
+        Self {
+            task: Some("CmdOrCtrl+Shift+Space".to_string()),
+            note: None,
+            event: None,
+        }
+    }
+}
+
+impl CaptureShortcuts {
+    fn entries(&self) -> impl Iterator<Item = (CaptureKind, &str)> {
+        [
+            (CaptureKind::Task, &self.task),
+            (CaptureKind::Note, &self.note),
+            (CaptureKind::Event, &self.event),
+        ]
+        .into_iter()
+        .filter_map(|(kind, accelerator)| Some((kind, accelerator.as_deref()?)))
+    }
+
+    /// Trims the accelerators, treating blank ones as unset, and rejects the
+    /// same accelerator for two kinds.
+    fn normalized(self) -> Result<Self> {
+        let clean = |accelerator: Option<String>| {
+            accelerator
+                .map(|accelerator| accelerator.trim().to_string())
+                .filter(|accelerator| !accelerator.is_empty())
+        };
+        let shortcuts = Self {
+            task: clean(self.task),
+            note: clean(self.note),
+            event: clean(self.event),
+        };
+
+        let accelerators: Vec<String> = shortcuts
+            .entries()
+            .map(|(_, accelerator)| accelerator.to_lowercase())
+            .collect();
+
+        for (index, accelerator) in accelerators.iter().enumerate() {
+            if accelerators[..index].contains(accelerator) {
+                return Err(Error::InvalidInput(
+                    "Each quick capture shortcut must be different.".to_string(),
+                ));
+            }
+        }
+
+        Ok(shortcuts)
+    }
+}
+
+pub struct Capture {
+    settings_path: PathBuf,
+    shortcuts: Mutex<CaptureShortcuts>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Capture {
+    pub fn open(settings_path: impl Into<PathBuf>) -> Result<Self> {
+        let settings_path = settings_path.into();
+
+        let shortcuts = if settings_path.exists() {
+            serde_json::from_slice(&fs::read(&settings_path)?)?
+        } else {
+            CaptureShortcuts::default()
+        };
+
+        Ok(Self {
+            settings_path,
+            shortcuts: Mutex::new(shortcuts),
+        })
+    }
+
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Self::open(paths::app_config_dir(app)?.join(CAPTURE_SETTINGS_FILE_NAME))
+    }
+
+    pub fn shortcuts(&self) -> CaptureShortcuts {
+        lock(&self.shortcuts).clone()
+    }
+
+    /// Registers `shortcuts` in place of the current ones and saves them. If
+    /// any of them cannot be registered the current ones stay in effect.
+    pub fn set_shortcuts(&self, app: &AppHandle, shortcuts: CaptureShortcuts) -> Result<()> {
+        let shortcuts = shortcuts.normalized()?;
+        let mut current = lock(&self.shortcuts);
+
+        if let Err(error) = register(app, &shortcuts) {
+            let _ = register(app, &current);
+            return Err(error);
+        }
+
+        files::write_atomic(&self.settings_path, &serde_json::to_vec_pretty(&shortcuts)?)?;
+        *current = shortcuts;
+        Ok(())
+    }
+}
+
+fn register_one(app: &AppHandle, kind: CaptureKind, accelerator: &str) -> Result<()> {
+    let handle = app.clone();
+
+    app.global_shortcut_manager()
+        .register(accelerator, move || open_window(&handle, kind))
+        .map_err(|_| {
+            Error::InvalidInput(format!(
+                "{accelerator} could not be used as a shortcut. It may be taken by another app."
+            ))
+        })
+}
+
+/// Replaces every global shortcut of the app with `shortcuts`. Stops at the
+/// first one that cannot be registered.
+fn register(app: &AppHandle, shortcuts: &CaptureShortcuts) -> Result<()> {
+    let _ = app.global_shortcut_manager().unregister_all();
+
+    for (kind, accelerator) in shortcuts.entries() {
+        register_one(app, kind, accelerator)?;
+    }
+
+    Ok(())
+}
+
+/// Shows the capture window for `kind`, creating it on first use.
+pub fn open_window(app: &AppHandle, kind: CaptureKind) {
+    if let Some(window) = app.get_window(CAPTURE_WINDOW_LABEL) {
+        let _ = window.emit(CAPTURE_KIND_EVENT, kind);
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+
+    let url = WindowUrl::App(format!("index.html#/capture?kind={}", kind.as_str()).into());
+
+    let window = WindowBuilder::new(app, CAPTURE_WINDOW_LABEL, url)
+        .title("Quick capture")
+        .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build();
+
+    // Windows without a menu of their own get the app menu bar.
+    if let Ok(window) = window {
+        let _ = window.menu_handle().hide();
+    }
+}
+
+/// Registers the saved shortcuts. One that cannot be registered, say
+/// because another app holds it, does not stop startup; it can be changed
+/// in Settings.
+pub fn start(app: &AppHandle) {
+    let shortcuts = app.state::<Capture>().shortcuts();
+
+    for (kind, accelerator) in shortcuts.entries() {
+        let _ = register_one(app, kind, accelerator);
+    }
+}
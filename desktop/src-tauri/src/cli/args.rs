@@ -0,0 +1,382 @@
+// desktop/src-tauri/src/cli/args.rs
+
+/*
+ * The command line, parsed without touching the store. Options take the
+ * usual `--name value` or `--name=value` forms; `--` ends them.
+ */
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use crate::documents::ExportFormat;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    AddTask(NewTask),
+    ListTasks {
+        status: Option<&'static str>,
+        /// Includes archived tasks.
+        all: bool,
+        json: bool,
+    },
+    ListDocuments {
+        json: bool,
+    },
+    ExportDocument {
+        id: String,
+        format: ExportFormat,
+        /// Standard output when `None`.
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewTask {
+    pub title: String,
+    pub description: String,
+    pub status: &'static str,
+    pub priority: &'static str,
+    pub tags: Vec<String>,
+    pub due_date: Option<NaiveDate>,
+    pub json: bool,
+}
+
+struct Options {
+    positional: Vec<String>,
+    named: Vec<(String, String)>,
+    switches: Vec<String>,
+}
+
+impl Options {
+    /// Splits `args` into positional arguments, the options named in
+    /// `valued` and the flags named in `switches`.
+    fn split(args: &[String], valued: &[&str], switches: &[&str]) -> Result<Self> {
+        let mut options = Self {
+            positional: Vec::new(),
+            named: Vec::new(),
+            switches: Vec::new(),
+        };
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                options.positional.extend(args.cloned());
+                break;
+            }
+
+            let Some(option) = arg.strip_prefix("--") else {
+                options.positional.push(arg.clone());
+                continue;
+            };
+            let (name, inline_value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (option, None),
+            };
+
+            if switches.contains(&name) && inline_value.is_none() {
+                options.switches.push(name.to_string());
+            } else if valued.contains(&name) {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| invalid(format!("--{name} needs a value.")))?;
+                options.named.push((name.to_string(), value));
+            } else {
+                return Err(invalid(format!("Unknown option --{name}.")));
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// The last value given for `name`.
+    fn value<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        self.values(name).last()
+    }
+
+    fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.named
+            .iter()
+            .filter(move |(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|switch| switch == name)
+    }
+
+    fn no_positional(&self) -> Result<()> {
+        match self.positional.first() {
+            Some(extra) => Err(invalid(format!("Unexpected argument \"{extra}\"."))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidInput(message)
+}
+
+fn parse_status(value: &str) -> Result<&'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "todo" => Ok("todo"),
+        "in_progress" | "in-progress" => Ok("in_progress"),
+        "done" => Ok("done"),
+        _ => Err(invalid(format!(
+            "Unknown status \"{value}\". Use todo, in_progress or done."
+        ))),
+    }
+}
+
+fn parse_priority(value: &str) -> Result<&'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "critical" => Ok("critical"),
+        "high" => Ok("high"),
+        "medium" => Ok("medium"),
+        "low" => Ok("low"),
+        _ => Err(invalid(format!(
+            "Unknown priority \"{value}\". Use critical, high, medium or low."
+        ))),
+    }
+}
+
+/// `today`, `tomorrow`, a weekday (the next one, never today) or a
+/// YYYY-MM-DD date.
+fn parse_due(value: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let value = value.trim().to_lowercase();
+
+    match value.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today + Days::new(1)),
+        _ => {}
+    }
+
+    if let Ok(weekday) = value.parse::<Weekday>() {
+        let ahead =
+            (weekday.num_days_from_monday() + 6 - today.weekday().num_days_from_monday()) % 7 + 1;
+        return Ok(today + Days::new(u64::from(ahead)));
+    }
+
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| {
+        invalid(format!(
+            "Unknown due date \"{value}\". Use today, tomorrow, a weekday or YYYY-MM-DD."
+        ))
+    })
+}
+
+fn parse_format(value: &str) -> Result<ExportFormat> {
+    match value.trim().to_lowercase().as_str() {
+        "md" | "markdown" => Ok(ExportFormat::Markdown),
+        "html" | "htm" => Ok(ExportFormat::Html),
+        "txt" | "text" => Ok(ExportFormat::Text),
+        "pdf" => Ok(ExportFormat::Pdf),
+        _ => Err(invalid(format!(
+            "Unknown format \"{value}\". Use md, html, txt or pdf."
+        ))),
+    }
+}
+
+fn format_for_path(path: &Path) -> Option<ExportFormat> {
+    parse_format(path.extension()?.to_str()?).ok()
+}
+
+fn parse_task(args: &[String], today: NaiveDate) -> Result<Command> {
+    let (action, rest) = args.split_first().unzip();
+
+    match action.map(String::as_str) {
+        Some("add") => {
+            let options = Options::split(
+                rest.unwrap_or_default(),
+                &["due", "priority", "status", "description", "tag"],
+                &["json"],
+            )?;
+            let title = options.positional.join(" ").trim().to_string();
+
+            if title.is_empty() {
+                return Err(invalid("A task needs a title.".to_string()));
+            }
+
+            let mut tags: Vec<String> = Vec::new();
+            for tag in options.values("tag").map(str::trim) {
+                if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+
+            Ok(Command::AddTask(NewTask {
+                title,
+                description: options
+                    .value("description")
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+                status: options.value("status").map_or(Ok("todo"), parse_status)?,
+                priority: options
+                    .value("priority")
+                    .map_or(Ok("medium"), parse_priority)?,
+                tags,
+                due_date: options
+                    .value("due")
+                    .map(|due| parse_due(due, today))
+                    .transpose()?,
+                json: options.switch("json"),
+            }))
+        }
+        Some("list") => {
+            let options = Options::split(rest.unwrap_or_default(), &["status"], &["all", "json"])?;
+            options.no_positional()?;
+
+            Ok(Command::ListTasks {
+                status: options.value("status").map(parse_status).transpose()?,
+                all: options.switch("all"),
+                json: options.switch("json"),
+            })
+        }
+        _ => Err(invalid("Use `task add` or `task list`.".to_string())),
+    }
+}
+
+fn parse_doc(args: &[String]) -> Result<Command> {
+    let (action, rest) = args.split_first().unzip();
+
+    match action.map(String::as_str) {
+        Some("list") => {
+            let options = Options::split(rest.unwrap_or_default(), &[], &["json"])?;
+            options.no_positional()?;
+
+            Ok(Command::ListDocuments {
+                json: options.switch("json"),
+            })
+        }
+        Some("export") => {
+            let mut options = Options::split(rest.unwrap_or_default(), &["format", "output"], &[])?;
+
+            if options.positional.is_empty() {
+                return Err(invalid("Which document? Pass its id.".to_string()));
+            }
+            let id = options.positional.remove(0);
+            options.no_positional()?;
+
+            let output = options.value("output").map(PathBuf::from);
+            let format = match options.value("format") {
+                Some(format) => parse_format(format)?,
+                None => output
+                    .as_deref()
+                    .and_then(format_for_path)
+                    .unwrap_or(ExportFormat::Markdown),
+            };
+
+            Ok(Command::ExportDocument { id, format, output })
+        }
+        _ => Err(invalid("Use `doc list` or `doc export`.".to_string())),
+    }
+}
+
+/// The command in `args`, or `None` when they are not a command line for
+/// this mode and the app should start as usual. Relative due dates are
+/// resolved against `today`.
+pub fn parse(args: &[String], today: NaiveDate) -> Option<Result<Command>> {
+    let (first, rest) = args.split_first()?;
+
+    match first.as_str() {
+        "task" | "tasks" => Some(parse_task(rest, today)),
+        "doc" | "docs" => Some(parse_doc(rest)),
+        "help" | "--help" | "-h" => Some(Ok(Command::Help)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 14).unwrap()
+    }
+
+    fn parse_line(line: &str) -> Option<Result<Command>> {
+        let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        parse(&args, today())
+    }
+
+    fn date(day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+    }
+
+    #[test]
+    fn adds_tasks_with_options_in_either_form() {
+        let Some(Ok(Command::AddTask(task))) = parse_line(
+            "task add Essay draft --due tomorrow --priority=HIGH --tag school --tag school",
+        ) else {
+            panic!("expected a task");
+        };
+
+        assert_eq!(task.title, "Essay draft");
+        assert_eq!(task.due_date, date(15));
+        assert_eq!(task.priority, "high");
+        assert_eq!(task.status, "todo");
+        assert_eq!(task.tags, vec!["school"]);
+        assert!(!task.json);
+
+        let due = |when: &str| match parse_line(&format!("task add x --due {when}")) {
+            Some(Ok(Command::AddTask(task))) => task.due_date,
+            _ => None,
+        };
+        assert_eq!(due("friday"), date(16));
+        assert_eq!(due("wed"), date(21));
+        assert_eq!(due("2026-12-01"), NaiveDate::from_ymd_opt(2026, 12, 1));
+        assert_eq!(due("someday"), None);
+    }
+
+    #[test]
+    fn rejects_what_it_does_not_understand() {
+        assert!(matches!(parse_line("task add"), Some(Err(_))));
+        assert!(matches!(
+            parse_line("task add x --priority urgent"),
+            Some(Err(_))
+        ));
+        assert!(matches!(parse_line("task list --colour"), Some(Err(_))));
+        assert!(matches!(parse_line("task list --status"), Some(Err(_))));
+        assert!(matches!(parse_line("task remove 1"), Some(Err(_))));
+
+        // Anything else starts the app, including forwarded deep links.
+        assert!(parse_line("").is_none());
+        assert!(parse_line("pioneer://tasks/1").is_none());
+    }
+
+    #[test]
+    fn exports_take_the_format_from_the_output_file_by_default() {
+        assert_eq!(
+            parse_line("doc export abc --output notes.pdf")
+                .unwrap()
+                .unwrap(),
+            Command::ExportDocument {
+                id: "abc".to_string(),
+                format: ExportFormat::Pdf,
+                output: Some(PathBuf::from("notes.pdf")),
+            }
+        );
+        assert_eq!(
+            parse_line("doc export abc --format txt --output notes.pdf")
+                .unwrap()
+                .unwrap(),
+            Command::ExportDocument {
+                id: "abc".to_string(),
+                format: ExportFormat::Text,
+                output: Some(PathBuf::from("notes.pdf")),
+            }
+        );
+        assert!(matches!(
+            parse_line("doc export abc"),
+            Some(Ok(Command::ExportDocument {
+                format: ExportFormat::Markdown,
+                output: None,
+                ..
+            }))
+        ));
+        assert!(matches!(parse_line("doc export"), Some(Err(_))));
+    }
+}
@@ -0,0 +1,278 @@
+// desktop/src-tauri/src/cli/mod.rs
+
+/*
+ * Command-line mode for scripting.
+ *
+ * `pioneer-work-suite task add "Essay draft" --due tomorrow` and friends run
+ * headless and exit instead of starting the app. (Packages install the
+ * binary as `pioneer-work-suite`; alias it to `pioneer` for short.) They
+ * open the same local store as the app and make the same changes the
+ * webview makes offline: a new task gets an `offline-task-` id in the cache
+ * and a `create` op in the queue, which the sync worker uploads.
+ *
+ * The app may be running at the same time. Its webview and sync worker
+ * never write back a cache or queue they read earlier: every change is
+ * applied to the rows as they stand, in one transaction (see
+ * `sync::changes`), and the command's change is made the same way, so
+ * neither side can drop the other's. Afterwards the running app is told
+ * over the single-instance channel which stores changed, so its views
+ * reload and the sync worker wakes up. Without a running app the ops wait
+ * for its next start.
+ *
+ * A workspace with a PIN needs it here too, from `PIONEER_PIN` or standard
+ * input, and wrong guesses count towards the same lockout.
+ *
+ * On Windows the release binary has no console of its own, so it attaches
+ * to the one it was started from; the shell does not wait for it, though,
+ * and its prompt may come back before the output.
+ */
+
+mod args;
+mod tasks;
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use chrono::{Local, Utc};
+use serde_json::Value;
+use tauri::Config;
+use zeroize::Zeroizing;
+
+use crate::documents::{self, Document};
+use crate::error::{Error, Result};
+use crate::files;
+use crate::instance;
+use crate::lock::WorkspaceLock;
+use crate::storage::{LocalStore, RecordStore, UnlockSecret};
+use crate::sync::engine::Resource;
+use args::Command;
+
+const PIN_VARIABLE: &str = "PIONEER_PIN";
+
+const USAGE: &str = "\
+Usage:
+  {program} task add <title> [--due <when>] [--priority <priority>]
+        [--status <status>] [--description <text>] [--tag <tag>]... [--json]
+  {program} task list [--status <status>] [--all] [--json]
+  {program} doc list [--json]
+  {program} doc export <id> [--format md|html|txt|pdf] [--output <file>]
+
+<when> is today, tomorrow, a weekday or YYYY-MM-DD. Priorities are
+critical, high, medium (the default) and low; statuses are todo (the
+default), in_progress and done. `task list` leaves out archived tasks
+unless --all is given. Exports go to standard output unless --output is
+given, in the format its extension names.
+
+A workspace protected by a PIN reads it from PIONEER_PIN or standard input.";
+
+/// Parses `args` as a command line for this mode; `None` starts the app.
+pub fn parse(args: &[String]) -> Option<Result<Command>> {
+    args::parse(args, Local::now().date_naive())
+}
+
+fn program() -> String {
+    std::env::args()
+        .next()
+        .as_deref()
+        .map(Path::new)
+        .and_then(Path::file_stem)
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "pioneer-work-suite".to_string())
+}
+
+fn usage() -> String {
+    USAGE.replace("{program}", &program())
+}
+
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails harmlessly when there is no parent console, or when this is a
+    // debug build that already has one.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Runs `command` and returns the process exit code.
+pub fn run(config: &Config, command: Result<Command>) -> i32 {
+    #[cfg(windows)]
+    attach_console();
+
+    let command = match command {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{error}\n\n{}", usage());
+            return 2;
+        }
+    };
+
+    match execute(config, command) {
+        Ok(()) => 0,
+        // The reader went away, as with `| head`.
+        Err(Error::Io(error)) if error.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(error) => {
+            eprintln!("{}: {error}", program());
+            1
+        }
+    }
+}
+
+fn execute(config: &Config, command: Command) -> Result<()> {
+    let mut out = io::stdout().lock();
+
+    match command {
+        Command::Help => writeln!(out, "{}", usage())?,
+        Command::AddTask(task) => {
+            let json = task.json;
+            let task = tasks::add(&open_store(config)?, task, Utc::now())?;
+
+            // Nothing is listening when the app is not running.
+            let _ = instance::notify_changed(config, &[Resource::Tasks]);
+
+            if json {
+                write_json(&mut out, &task)?;
+            } else {
+                writeln!(out, "{}", text(&task["id"]))?;
+            }
+        }
+        Command::ListTasks { status, all, json } => {
+            let tasks: Vec<Value> = open_store(config)?
+                .read_records(RecordStore::Tasks)?
+                .into_iter()
+                .filter(|task| all || task["archivedAt"].is_null())
+                .filter(|task| status.is_none_or(|status| task["status"] == status))
+                .collect();
+
+            if json {
+                write_json(&mut out, &tasks)?;
+            } else {
+                let rows = tasks.iter().map(|task| {
+                    [
+                        text(&task["id"]),
+                        text(&task["status"]),
+                        text(&task["priority"]),
+                        text(&task["dueDate"]),
+                        text(&task["title"]),
+                    ]
+                });
+                write_rows(&mut out, rows.collect())?;
+            }
+        }
+        Command::ListDocuments { json } => {
+            let documents = open_store(config)?.read_records(RecordStore::Documents)?;
+
+            if json {
+                write_json(&mut out, &documents)?;
+            } else {
+                let rows = documents.iter().map(|document| {
+                    [
+                        text(&document["id"]),
+                        text(&document["updatedAt"]).chars().take(10).collect(),
+                        text(&document["title"]),
+                    ]
+                });
+                write_rows(&mut out, rows.collect())?;
+            }
+        }
+        Command::ExportDocument { id, format, output } => {
+            let document = open_store(config)?
+                .read_records(RecordStore::Documents)?
+                .into_iter()
+                .find(|document| document["id"] == id.as_str())
+                .ok_or_else(|| Error::InvalidInput(format!("No document has the id \"{id}\".")))?;
+            let document: Document = serde_json::from_value(document)?;
+            let bytes = documents::export(&document, format)?;
+
+            match output {
+                Some(path) => files::write_atomic(&path, &bytes)?,
+                None => out.write_all(&bytes)?,
+            }
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn read_pin() -> Result<Zeroizing<String>> {
+    if let Ok(pin) = std::env::var(PIN_VARIABLE) {
+        return Ok(Zeroizing::new(pin));
+    }
+
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        eprint!("PIN: ");
+    }
+
+    let mut pin = Zeroizing::new(String::new());
+    stdin.lock().read_line(&mut pin)?;
+    let length = pin.trim_end_matches(['\r', '\n']).len();
+    pin.truncate(length);
+
+    Ok(pin)
+}
+
+/// Opens and unlocks the app's local store.
+fn open_store(config: &Config) -> Result<LocalStore> {
+    let store = LocalStore::open_for_config(config)?;
+    let lock = WorkspaceLock::open_for_config(config, store.requires_pin()?)?;
+
+    if !lock.state().pin_set {
+        store.unlock(UnlockSecret::Machine)?;
+        return Ok(store);
+    }
+
+    let pin = read_pin()?;
+    if lock.verify(&pin, |pin| store.pin_unlocks(pin))?.locked {
+        return Err(Error::InvalidInput("Incorrect PIN.".to_string()));
+    }
+    store.unlock(UnlockSecret::Pin(&pin))?;
+
+    Ok(store)
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "-".to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn write_json(out: &mut impl Write, value: &impl serde::Serialize) -> Result<()> {
+    let mut json = serde_json::to_vec_pretty(value)?;
+    json.push(b'\n');
+    out.write_all(&json)?;
+    Ok(())
+}
+
+/// Writes `rows` as columns padded to line up, the last one unpadded.
+fn write_rows<const N: usize>(out: &mut impl Write, rows: Vec<[String; N]>) -> Result<()> {
+    let mut widths = [0; N];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    for row in rows {
+        let mut line = String::new();
+        for (column, cell) in row.iter().enumerate() {
+            if column + 1 == N {
+                line.push_str(cell);
+            } else {
+                line.push_str(&format!("{cell:<width$}  ", width = widths[column]));
+            }
+        }
+        writeln!(out, "{line}")?;
+    }
+
+    Ok(())
+}
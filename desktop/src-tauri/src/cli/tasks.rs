@@ -0,0 +1,140 @@
+// desktop/src-tauri/src/cli/tasks.rs
+
+/*
+ * `task add` against the local store. The record and its `create` op go
+ * through `sync::changes` in one transaction, like any edit from the
+ * webview, so a running app can keep writing the same cache and queue.
+ */
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use super::args::NewTask;
+use crate::error::Result;
+use crate::storage::LocalStore;
+use crate::sync::changes;
+use crate::sync::engine::{QueueOp, Resource};
+
+/// Adds `task` the way `createTask` in apps/web/src/api/tasks.ts does
+/// offline. Returns the task record.
+pub fn add(store: &LocalStore, task: NewTask, now: DateTime<Utc>) -> Result<Value> {
+    let timestamp = now.timestamp_millis();
+    let created_at = now.to_rfc3339_opts(SecondsFormat::Millis, true);
+    let id = Resource::Tasks.offline_id(timestamp);
+
+    let payload = json!({
+        "title": task.title,
+        "description": task.description,
+        "status": task.status,
+        "priority": task.priority,
+        "tags": task.tags,
+        "dueDate": task.due_date.map(|date| date.format("%Y-%m-%d").to_string()),
+    });
+
+    let mut record = payload.clone();
+    record["id"] = json!(id);
+    record["createdAt"] = json!(created_at);
+    record["updatedAt"] = json!(created_at);
+    record["completedAt"] = json!((task.status == "done").then_some(&created_at));
+    record["archivedAt"] = Value::Null;
+
+    let op = QueueOp::Create {
+        temp_id: id,
+        payload,
+        timestamp,
+    };
+
+    store.update_records_and_queue(
+        Resource::Tasks.record_store(),
+        Resource::Tasks.queue_store(),
+        |tasks, queue| {
+            changes::merge_record(tasks, record.clone());
+            changes::enqueue_op(queue, Resource::Tasks, op);
+        },
+    )?;
+
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::storage::UnlockSecret;
+
+    fn open_store(directory: &tempfile::TempDir) -> LocalStore {
+        let store = LocalStore::open(directory.path().join("store.sqlite3")).unwrap();
+        store.unlock(UnlockSecret::Pin("test")).unwrap();
+        store
+    }
+
+    fn new_task(title: &str) -> NewTask {
+        NewTask {
+            title: title.to_string(),
+            description: String::new(),
+            status: "todo",
+            priority: "medium",
+            tags: Vec::new(),
+            due_date: None,
+            json: false,
+        }
+    }
+
+    #[test]
+    fn tasks_added_while_the_app_writes_are_kept() {
+        let directory = tempfile::tempdir().unwrap();
+        let app = open_store(&directory);
+        changes::save_record(
+            &app,
+            Resource::Tasks,
+            json!({ "id": "t1", "title": "Read" }),
+        )
+        .unwrap();
+
+        // The webview's list from before the command ran.
+        let stale = app.read_records(Resource::Tasks.record_store()).unwrap();
+
+        let cli = {
+            let directory = directory.path().to_path_buf();
+            thread::spawn(move || {
+                let store = LocalStore::open(directory.join("store.sqlite3")).unwrap();
+                store.unlock(UnlockSecret::Pin("test")).unwrap();
+                add(&store, new_task("Essay draft"), Utc::now()).unwrap()
+            })
+        };
+
+        while !cli.is_finished() {
+            changes::replace_records(&app, Resource::Tasks, stale.clone()).unwrap();
+            changes::enqueue(
+                &app,
+                Resource::Tasks,
+                QueueOp::Update {
+                    id: "t1".to_string(),
+                    patch: json!({ "status": "done" }),
+                    timestamp: 1,
+                },
+            )
+            .unwrap();
+
+            // Leave gaps the way a person typing does, or the command could
+            // wait out the store's busy timeout.
+            thread::sleep(Duration::from_millis(2));
+        }
+        let task = cli.join().unwrap();
+        changes::replace_records(&app, Resource::Tasks, stale).unwrap();
+
+        let tasks = app.read_records(Resource::Tasks.record_store()).unwrap();
+        let queue = app.read_queue(Resource::Tasks.queue_store()).unwrap();
+
+        assert!(tasks.contains(&task));
+        assert_eq!(queue.len(), 2);
+        assert!(queue
+            .iter()
+            .any(|op| op["kind"] == "create" && op["tempId"] == task["id"]));
+        assert!(queue
+            .iter()
+            .any(|op| op["kind"] == "update" && op["id"] == "t1"));
+    }
+}
@@ -0,0 +1,12 @@
+// desktop/src-tauri/src/close_guard/commands.rs
+
+use tauri::State;
+
+use super::CloseGuard;
+
+/// Answers a `pioneer:close-check` or `pioneer:save-drafts` request with
+/// the titles of the drafts that are still unsaved.
+#[tauri::command]
+pub fn report_unsaved_drafts(guard: State<'_, CloseGuard>, request_id: u64, drafts: Vec<String>) {
+    guard.answer(request_id, drafts);
+}
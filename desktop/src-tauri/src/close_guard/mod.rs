@@ -0,0 +1,317 @@
+// desktop/src-tauri/src/close_guard/mod.rs
+
+/*
+ * Quitting without losing work.
+ *
+ * Quit from the tray or the app menu, and closing a document window, come
+ * through here instead of going straight ahead. The windows involved are
+ * asked for their unsaved drafts with `pioneer:close-check` and answer with
+ * `report_unsaved_drafts`; a quit also counts the ops still waiting to
+ * upload. When nothing would be lost the app quits at once. Otherwise a
+ * native dialog warns first and can save the drafts (`pioneer:save-drafts`,
+ * answered the same way) and run a sync pass before quitting.
+ *
+ * Tauri's message dialogs have at most two buttons, so the choice between
+ * "Sync and quit", "Quit anyway" and "Cancel" takes two steps.
+ *
+ * Closing the main window only hides it (see tray), which loses nothing.
+ *
+ * `AppHandle::exit` ends the process without a `RunEvent::Exit`, so what
+ * has to happen on the way out runs in `shutdown` first.
+ */
+
+pub mod commands;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::api::dialog::{MessageDialogBuilder, MessageDialogButtons, MessageDialogKind};
+use tauri::{AppHandle, GlobalWindowEvent, Manager, Window, WindowEvent};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::capture::CAPTURE_WINDOW_LABEL;
+use crate::popout::DOCUMENT_LABEL_PREFIX;
+use crate::session;
+use crate::sync;
+use crate::window_state;
+
+pub const CLOSE_CHECK_EVENT: &str = "pioneer:close-check";
+pub const SAVE_DRAFTS_EVENT: &str = "pioneer:save-drafts";
+
+/// Windows that do not answer in time, e.g. because they are still loading,
+/// are taken to have no drafts.
+const ANSWER_TIMEOUT: Duration = Duration::from_millis(1500);
+const SAVE_TIMEOUT: Duration = Duration::from_secs(5);
+/// "Sync and quit" gives up on the upload after this; the ops stay queued
+/// for the next launch.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(15);
+/// Drafts named in the dialog before the rest are counted.
+const LISTED_DRAFTS: usize = 3;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DraftRequest {
+    request_id: u64,
+}
+
+#[derive(Default)]
+pub struct CloseGuard {
+    next_request: AtomicU64,
+    waiting: Mutex<HashMap<u64, mpsc::UnboundedSender<Vec<String>>>>,
+    /// Set while a check or dialog is in progress, so a second Quit does not
+    /// stack another dialog on top.
+    busy: AtomicBool,
+    /// Windows cleared to close, whose next close request goes through.
+    closing: Mutex<HashSet<String>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl CloseGuard {
+    /// Hands a window's answer to the check or save it belongs to. Answers
+    /// that arrive after the timeout are dropped.
+    pub fn answer(&self, request_id: u64, drafts: Vec<String>) {
+        if let Some(sender) = lock(&self.waiting).get(&request_id) {
+            let _ = sender.send(drafts);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Cancel,
+    Save,
+    Discard,
+}
+
+/// Sends `event` to `windows` and gathers the drafts they report.
+async fn ask(app: &AppHandle, windows: &[Window], event: &str, limit: Duration) -> Vec<String> {
+    let guard = app.state::<CloseGuard>();
+    let request_id = guard.next_request.fetch_add(1, Ordering::Relaxed);
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    lock(&guard.waiting).insert(request_id, sender);
+
+    let mut expected = 0;
+    for window in windows {
+        if window.emit(event, DraftRequest { request_id }).is_ok() {
+            expected += 1;
+        }
+    }
+
+    let mut drafts = Vec::new();
+    let _ = tokio::time::timeout(limit, async {
+        for _ in 0..expected {
+            match receiver.recv().await {
+                Some(answer) => drafts.extend(answer),
+                None => break,
+            }
+        }
+    })
+    .await;
+
+    lock(&guard.waiting).remove(&request_id);
+    drafts
+}
+
+async fn confirm(
+    parent: Option<&Window>,
+    title: &str,
+    message: &str,
+    ok: &str,
+    cancel: &str,
+) -> bool {
+    let (sender, receiver) = oneshot::channel();
+
+    let mut dialog = MessageDialogBuilder::new(title, message)
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelWithLabels(
+            ok.to_string(),
+            cancel.to_string(),
+        ));
+    if let Some(parent) = parent {
+        dialog = dialog.parent(parent);
+    }
+    dialog.show(move |confirmed| {
+        let _ = sender.send(confirmed);
+    });
+
+    receiver.await.unwrap_or(false)
+}
+
+fn describe(drafts: &[String], pending: usize) -> String {
+    let mut lines = Vec::new();
+
+    if !drafts.is_empty() {
+        let mut names: Vec<String> = drafts
+            .iter()
+            .take(LISTED_DRAFTS)
+            .map(|draft| format!("“{draft}”"))
+            .collect();
+        if drafts.len() > LISTED_DRAFTS {
+            names.push(format!("{} more", drafts.len() - LISTED_DRAFTS));
+        }
+        lines.push(format!("Unsaved changes in {}.", names.join(", ")));
+    }
+
+    match pending {
+        0 => {}
+        1 => lines.push("1 change has not been uploaded yet.".to_string()),
+        count => lines.push(format!("{count} changes have not been uploaded yet.")),
+    }
+
+    lines.join("\n")
+}
+
+/// Asks what to do about `drafts` and `pending` ops before quitting, or
+/// before closing `window` when one is given.
+async fn choose(window: Option<&Window>, drafts: &[String], pending: usize) -> Choice {
+    let (title, close, save, discard, question) = match window {
+        None => (
+            "Quit Pioneer?",
+            "Quit",
+            "Sync and quit",
+            "Quit anyway",
+            "Save your drafts and upload waiting changes before quitting?",
+        ),
+        Some(_) => (
+            "Close this window?",
+            "Close",
+            "Save and close",
+            "Close anyway",
+            "Save your changes before closing?",
+        ),
+    };
+
+    if !confirm(window, title, &describe(drafts, pending), close, "Cancel").await {
+        return Choice::Cancel;
+    }
+
+    if confirm(window, title, question, save, discard).await {
+        Choice::Save
+    } else {
+        Choice::Discard
+    }
+}
+
+async fn guard(app: AppHandle, window: Option<Window>) {
+    let windows: Vec<Window> = match &window {
+        Some(window) => vec![window.clone()],
+        None => app
+            .windows()
+            .into_values()
+            .filter(|window| window.label() != CAPTURE_WINDOW_LABEL)
+            .collect(),
+    };
+
+    let drafts = ask(&app, &windows, CLOSE_CHECK_EVENT, ANSWER_TIMEOUT).await;
+
+    // Ops only leave the queue while signed in and online; warning about
+    // them otherwise would mean warning on every quit.
+    let pending = match &window {
+        None => {
+            let snapshot = sync::current_snapshot(&app);
+            if snapshot.cloud_connected && snapshot.online {
+                snapshot.pending_total
+            } else {
+                0
+            }
+        }
+        Some(_) => 0,
+    };
+
+    if !drafts.is_empty() || pending > 0 {
+        match choose(window.as_ref(), &drafts, pending).await {
+            Choice::Cancel => return,
+            Choice::Discard => {}
+            Choice::Save => {
+                if !drafts.is_empty() {
+                    let unsaved = ask(&app, &windows, SAVE_DRAFTS_EVENT, SAVE_TIMEOUT).await;
+
+                    if !unsaved.is_empty()
+                        && !confirm(
+                            window.as_ref(),
+                            "Some changes were not saved",
+                            &describe(&unsaved, 0),
+                            if window.is_some() {
+                                "Close anyway"
+                            } else {
+                                "Quit anyway"
+                            },
+                            "Cancel",
+                        )
+                        .await
+                    {
+                        return;
+                    }
+                }
+
+                if window.is_none() {
+                    let _ = tokio::time::timeout(SYNC_TIMEOUT, sync::sync_now(&app)).await;
+                }
+            }
+        }
+    }
+
+    match window {
+        Some(window) => {
+            lock(&app.state::<CloseGuard>().closing).insert(window.label().to_string());
+            let _ = window.close();
+        }
+        None => {
+            shutdown(&app);
+            app.exit(0);
+        }
+    }
+}
+
+/// Saves the window positions and marks the session as ended cleanly.
+pub fn shutdown(app: &AppHandle) {
+    window_state::save(app);
+    session::end(app);
+}
+
+fn start_guard(app: &AppHandle, window: Option<Window>) {
+    if app.state::<CloseGuard>().busy.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        guard(app.clone(), window).await;
+        app.state::<CloseGuard>()
+            .busy
+            .store(false, Ordering::SeqCst);
+    });
+}
+
+/// Quits once nothing would be lost, or the user has said so.
+pub fn request_quit(app: &AppHandle) {
+    start_guard(app, None);
+}
+
+pub fn handle_window_event(event: GlobalWindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event.event() else {
+        return;
+    };
+    let window = event.window();
+    if !window.label().starts_with(DOCUMENT_LABEL_PREFIX) {
+        return;
+    }
+    let Some(guard) = window.try_state::<CloseGuard>() else {
+        return;
+    };
+
+    if lock(&guard.closing).remove(window.label()) {
+        return;
+    }
+
+    api.prevent_close();
+    start_guard(&window.app_handle(), Some(window.clone()));
+}
@@ -0,0 +1,189 @@
+// desktop/src-tauri/src/crypto.rs
+
+/*
+ * Small wrapper around the primitives used for secrets at rest:
+ * Argon2id turns a passphrase or machine secret into a key (or a PHC hash
+ * string for verifying a PIN), and XChaCha20-Poly1305 seals data with a
+ * random nonce stored in front of the ciphertext.
+ */
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::error::{Error, Result};
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+
+const NONCE_LEN: usize = 24;
+
+// OWASP's minimum recommendation for Argon2id: 19 MiB, two passes.
+const ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const ARGON2_ITERATIONS: u32 = 2;
+const ARGON2_PARALLELISM: u32 = 1;
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey([u8; KEY_LEN]);
+
+impl SecretKey {
+    pub fn generate() -> Self {
+        Self(random_bytes())
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| Error::Crypto("An encryption key has the wrong length."))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+/// A secret tied to this computer, for keys that must work without asking
+/// the user for anything.
+pub fn machine_secret() -> Result<Zeroizing<Vec<u8>>> {
+    let machine_id = machine_uid::get()
+        .map_err(|_| Error::Crypto("Unable to read this computer's machine id."))?;
+
+    Ok(Zeroizing::new(machine_id.trim().as_bytes().to_vec()))
+}
+
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn argon2() -> Argon2<'static> {
+    let params = Params::new(
+        ARGON2_MEMORY_KIB,
+        ARGON2_ITERATIONS,
+        ARGON2_PARALLELISM,
+        Some(KEY_LEN),
+    )
+    .expect("static Argon2 parameters are valid");
+
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+pub fn derive_key(secret: &[u8], salt: &[u8]) -> Result<SecretKey> {
+    let mut key = [0u8; KEY_LEN];
+
+    argon2()
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|_| Error::Crypto("Unable to derive an encryption key."))?;
+
+    Ok(SecretKey(key))
+}
+
+/// Hashes `secret` into a self-describing PHC string with a fresh salt.
+pub fn hash_secret(secret: &[u8]) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    argon2()
+        .hash_password(secret, &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| Error::Crypto("Unable to hash the secret."))
+}
+
+pub fn verify_secret(secret: &[u8], hash: &str) -> Result<bool> {
+    let hash =
+        PasswordHash::new(hash).map_err(|_| Error::Crypto("The stored secret hash is damaged."))?;
+
+    Ok(argon2().verify_password(secret, &hash).is_ok())
+}
+
+/// Encrypts `plaintext`, binding it to `context` so a sealed value cannot be
+/// swapped into a different slot without failing authentication.
+pub fn seal(key: &SecretKey, plaintext: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+    let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: context,
+            },
+        )
+        .map_err(|_| Error::Crypto("Unable to encrypt local data."))?;
+
+    let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+
+    Ok(sealed)
+}
+
+pub fn open(key: &SecretKey, sealed: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(Error::Crypto("Encrypted data is truncated."));
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
+
+    cipher
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: context,
+            },
+        )
+        .map_err(|_| {
+            Error::Crypto(
+                "Encrypted data could not be read. The key is wrong or the data is damaged.",
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_data_opens_only_with_its_key_and_context() {
+        let key = SecretKey::generate();
+        let sealed = seal(&key, b"bearer token", b"slot-a").unwrap();
+
+        assert_eq!(open(&key, &sealed, b"slot-a").unwrap(), b"bearer token");
+        assert_ne!(seal(&key, b"bearer token", b"slot-a").unwrap(), sealed);
+
+        assert!(open(&SecretKey::generate(), &sealed, b"slot-a").is_err());
+        assert!(open(&key, &sealed, b"slot-b").is_err());
+        assert!(open(&key, &sealed[..NONCE_LEN - 1], b"slot-a").is_err());
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&key, &tampered, b"slot-a").is_err());
+    }
+
+    #[test]
+    fn keys_and_hashes_follow_the_secret() {
+        let salt = random_bytes::<SALT_LEN>();
+        let key = derive_key(b"correct horse", &salt).unwrap();
+
+        assert_eq!(
+            derive_key(b"correct horse", &salt).unwrap().as_bytes(),
+            key.as_bytes()
+        );
+        assert_ne!(
+            derive_key(b"battery staple", &salt).unwrap().as_bytes(),
+            key.as_bytes()
+        );
+
+        let hash = hash_secret(b"2468").unwrap();
+        assert!(verify_secret(b"2468", &hash).unwrap());
+        assert!(!verify_secret(b"1357", &hash).unwrap());
+        assert!(verify_secret(b"2468", "not a hash").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+// desktop/src-tauri/src/deep_link/apple_events.rs
+
+/*
+ * macOS delivers `pioneer://` links as `kAEGetURL` Apple events, both to a
+ * running app and to one the link launches, rather than as arguments. Tauri 1
+ * does not pass these on, so a handler is registered with the shared
+ * NSAppleEventManager. It must be in place before the app finishes launching
+ * or the link that launched it is lost; `start` runs from `setup`, before the
+ * event loop, which is early enough.
+ */
+
+use std::ffi::{c_char, CStr};
+use std::sync::OnceLock;
+
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use tauri::AppHandle;
+
+// Four-character codes from the Apple Event Manager headers.
+const INTERNET_EVENT_CLASS: u32 = u32::from_be_bytes(*b"GURL");
+const GET_URL_EVENT: u32 = u32::from_be_bytes(*b"GURL");
+const DIRECT_OBJECT_KEYWORD: u32 = u32::from_be_bytes(*b"----");
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+pub(super) fn listen(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+
+    let Some(class) = handler_class() else {
+        return;
+    };
+
+    unsafe {
+        let handler: *mut Object = msg_send![class, new];
+        let manager: *mut Object = msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+        let _: () = msg_send![
+            manager,
+            setEventHandler: handler
+            andSelector: sel!(handleGetURLEvent:withReplyEvent:)
+            forEventClass: INTERNET_EVENT_CLASS
+            andEventID: GET_URL_EVENT
+        ];
+    }
+}
+
+fn handler_class() -> Option<&'static Class> {
+    let mut decl = ClassDecl::new("PioneerDeepLinkHandler", class!(NSObject))?;
+
+    unsafe {
+        decl.add_method(
+            sel!(handleGetURLEvent:withReplyEvent:),
+            handle_get_url as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+        );
+    }
+
+    Some(decl.register())
+}
+
+extern "C" fn handle_get_url(_: &Object, _: Sel, event: *mut Object, _reply: *mut Object) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+
+    if let Some(link) = unsafe { url_of(event) } {
+        super::open(app, &link);
+    }
+}
+
+unsafe fn url_of(event: *mut Object) -> Option<String> {
+    if event.is_null() {
+        return None;
+    }
+
+    let descriptor: *mut Object =
+        msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT_KEYWORD];
+    if descriptor.is_null() {
+        return None;
+    }
+
+    let string: *mut Object = msg_send![descriptor, stringValue];
+    if string.is_null() {
+        return None;
+    }
+
+    let utf8: *const c_char = msg_send![string, UTF8String];
+    if utf8.is_null() {
+        return None;
+    }
+
+    Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+}
@@ -0,0 +1,11 @@
+// desktop/src-tauri/src/deep_link/commands.rs
+
+use tauri::State;
+
+use super::{DeepLink, DeepLinks};
+
+/// The link the app was launched with, once.
+#[tauri::command]
+pub fn take_pending_deep_link(deep_links: State<'_, DeepLinks>) -> Option<DeepLink> {
+    deep_links.take_pending()
+}
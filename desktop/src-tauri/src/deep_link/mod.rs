@@ -0,0 +1,202 @@
+// desktop/src-tauri/src/deep_link/mod.rs
+
+/*
+ * `pioneer://` links.
+ *
+ * On Windows and Linux the app registers itself as the handler for the
+ * scheme on startup, for the current user, so a link clicked in another app
+ * launches it with the URL as an argument. On macOS the bundle declares the
+ * scheme in Info.plist and links arrive as Apple events (see
+ * `apple_events`). Links are parsed and checked here (see `route`), and
+ * the webview only ever receives a route it can navigate to, or a message
+ * saying why the link was refused.
+ *
+ * Every link goes through `open`. Until the webview asks for the pending
+ * link with `take_pending_deep_link` it is not listening yet, so a link that
+ * arrives before then, usually the one that launched the app, is kept for it
+ * instead of being sent.
+ */
+
+#[cfg(target_os = "macos")]
+mod apple_events;
+
+pub mod commands;
+pub mod route;
+
+use std::sync::{Mutex, MutexGuard};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::error::Result;
+use crate::tray;
+use route::SCHEME;
+
+pub const DEEP_LINK_EVENT: &str = "pioneer:deep-link";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum DeepLink {
+    Open { route: String },
+    Invalid { message: String },
+}
+
+impl DeepLink {
+    pub fn from_url(link: &str) -> Self {
+        match route::parse(link) {
+            Ok(route) => DeepLink::Open { route },
+            Err(error) => DeepLink::Invalid {
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DeepLinks {
+    pending: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    link: Option<DeepLink>,
+    taken: bool,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl DeepLinks {
+    pub fn take_pending(&self) -> Option<DeepLink> {
+        let mut pending = lock(&self.pending);
+        pending.taken = true;
+        pending.link.take()
+    }
+}
+
+/// Returns the first command line argument that is a `pioneer:` URL.
+pub fn find_link(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let prefix = format!("{SCHEME}:");
+
+    args.into_iter().find(|arg| {
+        arg.get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(&prefix))
+    })
+}
+
+fn send(app: &AppHandle, link: DeepLink) {
+    tray::show_main_window(app);
+
+    if let Some(window) = app.get_window("main") {
+        let _ = window.emit(DEEP_LINK_EVENT, link);
+    }
+}
+
+/// Sends a link to the main window and brings it to the front, or keeps it
+/// as the pending link if the webview has not asked for that yet.
+pub fn open(app: &AppHandle, link: &str) {
+    let link = DeepLink::from_url(link);
+
+    {
+        let deep_links = app.state::<DeepLinks>();
+        let mut pending = lock(&deep_links.pending);
+        if !pending.taken {
+            pending.link = Some(link);
+            return;
+        }
+    }
+
+    send(app, link);
+}
+
+/// Like `open`, for a route that came from one of the app's own windows.
+pub fn open_route(app: &AppHandle, route: String) {
+    send(app, DeepLink::Open { route });
+}
+
+#[cfg(windows)]
+fn register_scheme() -> Result<()> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let exe = std::env::current_exe()?;
+    let exe = exe.display();
+
+    let (key, _) =
+        RegKey::predef(HKEY_CURRENT_USER).create_subkey(format!("Software\\Classes\\{SCHEME}"))?;
+    key.set_value("", &"URL:Pioneer Work Suite")?;
+    key.set_value("URL Protocol", &"")?;
+
+    let (icon, _) = key.create_subkey("DefaultIcon")?;
+    icon.set_value("", &format!("\"{exe}\",0"))?;
+
+    let (command, _) = key.create_subkey("shell\\open\\command")?;
+    command.set_value("", &format!("\"{exe}\" \"%1\""))?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn register_scheme() -> Result<()> {
+    use std::fs;
+    use std::process::Command;
+
+    use crate::error::Error;
+    use crate::files;
+
+    const DESKTOP_FILE_NAME: &str = "pioneer-work-suite-url-handler.desktop";
+
+    let exe = std::env::current_exe()?;
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Pioneer Work Suite\n\
+         Exec=\"{}\" %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{SCHEME};\n",
+        exe.display()
+    );
+
+    let path = tauri::api::path::data_dir()
+        .ok_or(Error::AppDataDirUnavailable)?
+        .join("applications")
+        .join(DESKTOP_FILE_NAME);
+
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == entry) {
+        return Ok(());
+    }
+
+    files::write_atomic(&path, entry.as_bytes())?;
+    Command::new("xdg-mime")
+        .args([
+            "default",
+            DESKTOP_FILE_NAME,
+            &format!("x-scheme-handler/{SCHEME}"),
+        ])
+        .status()?;
+
+    Ok(())
+}
+
+// macOS learns of the scheme from Info.plist when the bundle is installed.
+#[cfg(not(any(windows, target_os = "linux")))]
+fn register_scheme() -> Result<()> {
+    Ok(())
+}
+
+/// Claims the scheme and keeps a link the app was launched with. Failing to
+/// register is not fatal; links then simply do not reach the app.
+pub fn start(app: &AppHandle) {
+    let _ = register_scheme();
+
+    #[cfg(target_os = "macos")]
+    apple_events::listen(app);
+
+    if let Some(link) = find_link(std::env::args().skip(1)) {
+        open(app, &link);
+    }
+}
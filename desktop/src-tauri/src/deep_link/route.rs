@@ -0,0 +1,150 @@
+// desktop/src-tauri/src/deep_link/route.rs
+
+/*
+ * Turns a `pioneer://` URL into a webview route.
+ *
+ * The host names the area and the path the item:
+ *
+ *   pioneer://tasks                  /tasks
+ *   pioneer://tasks/<id>             /tasks?task=<id>
+ *   pioneer://tasks/new?title=...    /tasks?create=1&title=...
+ *   pioneer://documents/<id>         /documents?document=<id>
+ *   pioneer://documents/new          /documents?create=1
+ *   pioneer://calendar/<YYYY-MM-DD>  /calendar?date=<YYYY-MM-DD>
+ *   pioneer://dashboard, mail, settings
+ *
+ * Links come from other apps, so anything else is rejected rather than
+ * passed through to the router.
+ */
+
+use chrono::NaiveDate;
+use tauri::Url;
+
+use crate::error::{Error, Result};
+
+pub const SCHEME: &str = "pioneer";
+
+const MAX_ID_LENGTH: usize = 128;
+const MAX_TITLE_LENGTH: usize = 500;
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidInput(format!("This Pioneer link cannot be opened: {message}"))
+}
+
+fn id(value: &str) -> Result<&str> {
+    let valid = !value.is_empty()
+        && value.len() <= MAX_ID_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(value)
+    } else {
+        Err(invalid("the item id is not valid."))
+    }
+}
+
+fn query(pairs: &[(&str, &str)]) -> String {
+    let mut serializer = Url::parse("pioneer://query").expect("static URL");
+    serializer.query_pairs_mut().extend_pairs(pairs);
+    serializer.query().unwrap_or_default().to_string()
+}
+
+pub fn parse(link: &str) -> Result<String> {
+    let url = Url::parse(link.trim()).map_err(|_| invalid("it is not a valid URL."))?;
+
+    if url.scheme() != SCHEME {
+        return Err(invalid("it is not a pioneer:// link."));
+    }
+
+    let area = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    let path: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+
+    let route = match (area.as_str(), path.as_slice()) {
+        ("dashboard", []) => "/dashboard".to_string(),
+        ("mail", []) => "/mail".to_string(),
+        ("settings", []) => "/settings".to_string(),
+        ("tasks", []) => "/tasks".to_string(),
+        ("tasks", ["archive"]) => "/tasks/archive".to_string(),
+        ("tasks", ["new"]) => {
+            let title = url
+                .query_pairs()
+                .find(|(key, _)| key == "title")
+                .map(|(_, value)| value.trim().to_string())
+                .unwrap_or_default();
+
+            if title.chars().count() > MAX_TITLE_LENGTH {
+                return Err(invalid("the task title is too long."));
+            }
+
+            if title.is_empty() {
+                format!("/tasks?{}", query(&[("create", "1")]))
+            } else {
+                format!("/tasks?{}", query(&[("create", "1"), ("title", &title)]))
+            }
+        }
+        ("tasks", [task]) => format!("/tasks?{}", query(&[("task", id(task)?)])),
+        ("documents", []) => "/documents".to_string(),
+        ("documents", ["new"]) => format!("/documents?{}", query(&[("create", "1")])),
+        ("documents", [document]) => {
+            format!("/documents?{}", query(&[("document", id(document)?)]))
+        }
+        ("calendar", []) => "/calendar".to_string(),
+        ("calendar", [day]) => {
+            let day = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .map_err(|_| invalid("the date should look like 2024-05-31."))?;
+            format!("/calendar?{}", query(&[("date", &day.to_string())]))
+        }
+        _ => return Err(invalid("Pioneer does not know this kind of link.")),
+    };
+
+    Ok(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_known_links() {
+        assert_eq!(parse("pioneer://tasks").unwrap(), "/tasks");
+        assert_eq!(parse("pioneer://Tasks/").unwrap(), "/tasks");
+        assert_eq!(
+            parse("pioneer://tasks/abc-123").unwrap(),
+            "/tasks?task=abc-123"
+        );
+        assert_eq!(
+            parse("pioneer://tasks/new?title=Call%20Sam%20%26%20Jo").unwrap(),
+            "/tasks?create=1&title=Call+Sam+%26+Jo"
+        );
+        assert_eq!(parse("pioneer://tasks/new").unwrap(), "/tasks?create=1");
+        assert_eq!(
+            parse("pioneer://documents/d_9").unwrap(),
+            "/documents?document=d_9"
+        );
+        assert_eq!(
+            parse("pioneer://calendar/2024-02-29").unwrap(),
+            "/calendar?date=2024-02-29"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_malformed_links() {
+        for link in [
+            "https://tasks/1",
+            "pioneer://tasks/1/2",
+            "pioneer://tasks/..%2Fsettings",
+            "pioneer://documents/a%20b",
+            "pioneer://calendar/2023-02-29",
+            "pioneer://login",
+            "pioneer://",
+            "not a link",
+        ] {
+            assert!(parse(link).is_err(), "{link} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// desktop/src-tauri/src/documents/blocks.rs
+
+/*
+ * A flat block model of the editor's HTML.
+ *
+ * Every export format renders from this model rather than from the HTML
+ * itself. It covers what the Quill editor produces: headings, paragraphs,
+ * nested and checklist items (nested `<ul>/<ol>`, Quill 1's `ql-indent-N`
+ * and `data-checked`, and Quill 2's `data-list`),
+ * quotes, code blocks, rules, and inline bold, italic, strike, code,
+ * links, images and line breaks. Anything else is read for its text.
+ */
+
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::NodeRef;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub strike: bool,
+    pub code: bool,
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    Text(String, Style),
+    Break,
+    Image { src: String, alt: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Bullet,
+    Ordered,
+    Checked(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    Paragraph(Vec<Inline>),
+    ListItem {
+        kind: ListKind,
+        /// Position within its run of ordered items, starting at 1.
+        number: usize,
+        depth: usize,
+        content: Vec<Inline>,
+    },
+    Quote(Vec<Inline>),
+    Code(String),
+    Rule,
+}
+
+/// Plain text of a run of inlines, with breaks as newlines.
+pub fn inline_text(content: &[Inline]) -> String {
+    content
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text, _) => text.as_str(),
+            Inline::Break => "\n",
+            Inline::Image { alt, .. } => alt.as_str(),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Container {
+    Paragraph,
+    Heading(u8),
+    ListItem(ListKind, usize),
+    Quote,
+}
+
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    inlines: Vec<Inline>,
+    containers: Vec<Container>,
+    lists: Vec<ListKind>,
+    // Newer Quill versions emit one `div.ql-code-block` per line.
+    in_code_run: bool,
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+
+    for character in text.chars() {
+        if character.is_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(character);
+            in_space = false;
+        }
+    }
+
+    collapsed
+}
+
+/// Trims the whitespace HTML would not render: at either end of a block and
+/// around line breaks.
+fn trim_inlines(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut trimmed: Vec<Inline> = Vec::with_capacity(inlines.len());
+
+    for inline in inlines {
+        match inline {
+            Inline::Text(text, style) => {
+                let at_line_start = matches!(trimmed.last(), None | Some(Inline::Break))
+                    || matches!(trimmed.last(), Some(Inline::Text(previous, _)) if previous.ends_with(' '));
+                let text = if at_line_start && !style.code {
+                    text.trim_start().to_string()
+                } else {
+                    text
+                };
+
+                if !text.is_empty() {
+                    trimmed.push(Inline::Text(text, style));
+                }
+            }
+            Inline::Break => {
+                if let Some(Inline::Text(previous, _)) = trimmed.last_mut() {
+                    previous.truncate(previous.trim_end().len());
+                }
+                trimmed.push(Inline::Break);
+            }
+            image => trimmed.push(image),
+        }
+    }
+
+    if let Some(Inline::Text(last, _)) = trimmed.last_mut() {
+        last.truncate(last.trim_end().len());
+    }
+
+    trimmed.retain(|inline| !matches!(inline, Inline::Text(text, _) if text.is_empty()));
+
+    while matches!(trimmed.last(), Some(Inline::Break)) {
+        trimmed.pop();
+    }
+
+    trimmed
+}
+
+fn list_kind(element: &str, data_list: Option<&str>) -> ListKind {
+    match data_list {
+        Some("ordered") => ListKind::Ordered,
+        Some("checked") => ListKind::Checked(true),
+        Some("unchecked") => ListKind::Checked(false),
+        Some(_) => ListKind::Bullet,
+        None if element == "ol" => ListKind::Ordered,
+        None => ListKind::Bullet,
+    }
+}
+
+fn indent_level(class: Option<&str>) -> usize {
+    class
+        .unwrap_or_default()
+        .split_whitespace()
+        .find_map(|class| class.strip_prefix("ql-indent-"))
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(0)
+}
+
+impl Builder {
+    fn flush(&mut self) {
+        let content = trim_inlines(std::mem::take(&mut self.inlines));
+
+        if content.is_empty() {
+            return;
+        }
+
+        let block = match self
+            .containers
+            .last()
+            .copied()
+            .unwrap_or(Container::Paragraph)
+        {
+            Container::Paragraph => Block::Paragraph(content),
+            Container::Heading(level) => Block::Heading { level, content },
+            Container::ListItem(kind, depth) => Block::ListItem {
+                kind,
+                number: 0,
+                depth,
+                content,
+            },
+            Container::Quote => Block::Quote(content),
+        };
+
+        self.push(block);
+    }
+
+    fn push(&mut self, block: Block) {
+        self.in_code_run = false;
+        self.blocks.push(block);
+    }
+
+    fn within(&mut self, node: &NodeRef, style: &Style, container: Container) {
+        self.flush();
+        self.containers.push(container);
+        self.children(node, style);
+        self.flush();
+        self.containers.pop();
+    }
+
+    fn children(&mut self, node: &NodeRef, style: &Style) {
+        for child in node.children() {
+            self.node(&child, style);
+        }
+    }
+
+    /// Paragraph-like elements keep the container they sit in, so a `<p>`
+    /// inside a quote or list item stays part of it.
+    fn inherited(&self) -> Container {
+        match self.containers.last() {
+            Some(container @ (Container::Quote | Container::ListItem(..))) => *container,
+            _ => Container::Paragraph,
+        }
+    }
+
+    fn node(&mut self, node: &NodeRef, style: &Style) {
+        if let Some(text) = node.as_text() {
+            let text = collapse_whitespace(&text.borrow());
+            if !text.is_empty() {
+                self.inlines.push(Inline::Text(text, style.clone()));
+            }
+            return;
+        }
+
+        let Some(element) = node.as_element() else {
+            self.children(node, style);
+            return;
+        };
+
+        let attributes = element.attributes.borrow();
+        let name = &*element.name.local;
+
+        match name {
+            "head" | "script" | "style" | "template" | "noscript" | "title" => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse().unwrap_or(1);
+                self.within(node, style, Container::Heading(level));
+            }
+            "blockquote" => self.within(node, style, Container::Quote),
+            "pre" => {
+                self.flush();
+                let code = node.text_contents();
+                self.push(Block::Code(code.trim_end_matches('\n').to_string()));
+            }
+            "div"
+                if attributes.get("class").is_some_and(|class| {
+                    class
+                        .split_whitespace()
+                        .any(|class| class == "ql-code-block")
+                }) =>
+            {
+                self.flush();
+                let line = node.text_contents();
+
+                match self.blocks.last_mut() {
+                    Some(Block::Code(code)) if self.in_code_run => {
+                        code.push('\n');
+                        code.push_str(&line);
+                    }
+                    _ => self.push(Block::Code(line)),
+                }
+
+                self.in_code_run = true;
+            }
+            "ul" | "ol" => {
+                self.flush();
+                // Quill 1 marks checklists on the list itself.
+                let kind = match attributes.get("data-checked") {
+                    Some(checked) if name == "ul" => ListKind::Checked(checked == "true"),
+                    _ => list_kind(name, None),
+                };
+                self.lists.push(kind);
+                self.children(node, style);
+                self.lists.pop();
+            }
+            "li" => {
+                let kind = match attributes.get("data-list") {
+                    Some(data_list) => list_kind(name, Some(data_list)),
+                    None => self.lists.last().copied().unwrap_or(ListKind::Bullet),
+                };
+                let depth =
+                    self.lists.len().saturating_sub(1) + indent_level(attributes.get("class"));
+
+                self.within(node, style, Container::ListItem(kind, depth));
+            }
+            "p" | "div" | "section" | "article" | "header" | "footer" | "tr" | "dt" | "dd"
+            | "figure" | "figcaption" => {
+                let container = self.inherited();
+                self.within(node, style, container);
+            }
+            "hr" => {
+                self.flush();
+                self.push(Block::Rule);
+            }
+            "br" => self.inlines.push(Inline::Break),
+            "img" => self.inlines.push(Inline::Image {
+                src: attributes.get("src").unwrap_or_default().to_string(),
+                alt: attributes.get("alt").unwrap_or_default().to_string(),
+            }),
+            "td" | "th" => {
+                self.inlines
+                    .push(Inline::Text(" ".to_string(), style.clone()));
+                self.children(node, style);
+            }
+            _ => {
+                let mut style = style.clone();
+
+                match name {
+                    "strong" | "b" => style.bold = true,
+                    "em" | "i" => style.italic = true,
+                    "s" | "strike" | "del" => style.strike = true,
+                    "code" | "kbd" | "samp" => style.code = true,
+                    "a" => style.link = attributes.get("href").map(str::to_string),
+                    _ => {}
+                }
+
+                self.children(node, &style);
+            }
+        }
+    }
+}
+
+/// Numbers ordered items within each run at each depth. A run ends at any
+/// block that is not a deeper list item.
+pub fn number_lists(blocks: &mut [Block]) {
+    let mut counters: Vec<usize> = Vec::new();
+
+    for block in blocks {
+        let Block::ListItem {
+            kind,
+            number,
+            depth,
+            ..
+        } = block
+        else {
+            counters.clear();
+            continue;
+        };
+
+        counters.resize(*depth + 1, 0);
+
+        if *kind == ListKind::Ordered {
+            counters[*depth] += 1;
+            *number = counters[*depth];
+        } else {
+            counters[*depth] = 0;
+        }
+    }
+}
+
+/// Parses editor HTML into blocks.
+pub fn parse_html(html: &str) -> Vec<Block> {
+    let document = kuchikiki::parse_html().one(html);
+    let mut builder = Builder::default();
+
+    builder.node(&document, &Style::default());
+    builder.flush();
+
+    number_lists(&mut builder.blocks);
+    builder.blocks
+}
@@ -0,0 +1,76 @@
+// desktop/src-tauri/src/documents/commands.rs
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use super::{import, Document, ExportFormat};
+use crate::error::Result;
+use crate::files;
+use crate::storage::LocalStore;
+use crate::sync::{engine::Resource, SyncService};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentImport {
+    /// The new document records, for the webview to merge into its list.
+    pub documents: Vec<Value>,
+    pub failures: Vec<ImportFailure>,
+}
+
+/// Writes `document` to `path`, chosen by the webview through a save dialog.
+#[tauri::command]
+pub async fn export_document(
+    document: Document,
+    format: ExportFormat,
+    path: PathBuf,
+) -> Result<()> {
+    files::write_atomic(&path, &super::export(&document, format)?)
+}
+
+/// Imports Markdown, text and HTML files as new documents. Files that
+/// cannot be read are reported and skipped; the rest are added together.
+#[tauri::command]
+pub async fn import_documents(
+    app: AppHandle,
+    store: State<'_, LocalStore>,
+    paths: Vec<PathBuf>,
+) -> Result<DocumentImport> {
+    let mut converted = Vec::new();
+    let mut failures = Vec::new();
+
+    for path in paths {
+        match import::convert_file(&path) {
+            Ok(file) => converted.push(file),
+            Err(error) => failures.push(ImportFailure {
+                path: path.display().to_string(),
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    let documents = if converted.is_empty() {
+        Vec::new()
+    } else {
+        import::insert_documents(&store, converted)?
+    };
+
+    if !documents.is_empty() {
+        let _ = app.emit_all(Resource::Documents.changed_event(), ());
+        app.state::<SyncService>().wake();
+    }
+
+    Ok(DocumentImport {
+        documents,
+        failures,
+    })
+}
@@ -0,0 +1,248 @@
+// desktop/src-tauri/src/documents/import.rs
+
+/*
+ * Imports Markdown, text and HTML files as documents.
+ *
+ * Files are converted to the editor's HTML and added the way `createDocument`
+ * in apps/web/src/api/documents.ts adds a document offline: a record with an
+ * `offline-doc-` id in the cache plus a `create` op in the queue, so the
+ * sync worker uploads them later.
+ */
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{SecondsFormat, Utc};
+use kuchikiki::traits::TendrilSink;
+use serde_json::{json, Value};
+
+use super::blocks::{self, inline_text, Block, Inline, Style};
+use super::markdown;
+use super::render;
+use crate::error::{Error, Result};
+use crate::storage::{LocalStore, QueueStore, RecordStore};
+use crate::sync::engine::{QueueOp, Resource};
+
+const MAX_IMPORT_SIZE: u64 = 20 * 1024 * 1024;
+
+const UNTITLED: &str = "Untitled document";
+
+/// A converted file, ready to become a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedFile {
+    pub title: String,
+    pub content: String,
+}
+
+/// Uses the first heading as the title. It is removed from the content
+/// when it opens the document, so the title is not shown twice.
+fn take_heading_title(blocks: &mut Vec<Block>) -> Option<String> {
+    let index = blocks
+        .iter()
+        .position(|block| matches!(block, Block::Heading { .. }))?;
+
+    let Block::Heading { content, .. } = &blocks[index] else {
+        return None;
+    };
+    let title = inline_text(content).trim().to_string();
+
+    if title.is_empty() {
+        return None;
+    }
+
+    if index == 0 {
+        blocks.remove(0);
+    }
+
+    Some(title)
+}
+
+pub fn from_markdown(source: &str, fallback_title: &str) -> ImportedFile {
+    let (front_matter_title, body) = markdown::split_front_matter(source);
+    let mut blocks = markdown::parse_markdown(body);
+
+    let title = front_matter_title
+        .or_else(|| take_heading_title(&mut blocks))
+        .unwrap_or_else(|| fallback_title.to_string());
+
+    ImportedFile {
+        title,
+        content: render::to_editor_html(&blocks),
+    }
+}
+
+pub fn from_html(source: &str, fallback_title: &str) -> ImportedFile {
+    let document = kuchikiki::parse_html().one(source);
+    let page_title = document
+        .select_first("title")
+        .ok()
+        .map(|title| title.text_contents().trim().to_string())
+        .filter(|title| !title.is_empty());
+
+    let mut blocks = blocks::parse_html(source);
+    let title = page_title
+        .or_else(|| take_heading_title(&mut blocks))
+        .unwrap_or_else(|| fallback_title.to_string());
+
+    ImportedFile {
+        title,
+        content: render::to_editor_html(&blocks),
+    }
+}
+
+/// Text files become one paragraph per blank-line separated block, keeping
+/// their line breaks. The file name is the title.
+pub fn from_text(source: &str, fallback_title: &str) -> ImportedFile {
+    let normalized = source.replace("\r\n", "\n");
+    let mut blocks = Vec::new();
+
+    for paragraph in normalized.split("\n\n") {
+        let mut content = Vec::new();
+
+        for line in paragraph.lines().filter(|line| !line.trim().is_empty()) {
+            if !content.is_empty() {
+                content.push(Inline::Break);
+            }
+            content.push(Inline::Text(line.trim_end().to_string(), Style::default()));
+        }
+
+        if !content.is_empty() {
+            blocks.push(Block::Paragraph(content));
+        }
+    }
+
+    ImportedFile {
+        title: fallback_title.to_string(),
+        content: render::to_editor_html(&blocks),
+    }
+}
+
+/// Reads and converts the file at `path`, choosing the format by extension.
+pub fn convert_file(path: &Path) -> Result<ImportedFile> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let convert: fn(&str, &str) -> ImportedFile = match extension.as_str() {
+        "md" | "markdown" | "mdown" | "mkd" => from_markdown,
+        "txt" | "text" => from_text,
+        "html" | "htm" => from_html,
+        _ => {
+            return Err(Error::InvalidInput(format!(
+                "{name} is not a Markdown, text or HTML file."
+            )))
+        }
+    };
+
+    if fs::metadata(path)?.len() > MAX_IMPORT_SIZE {
+        return Err(Error::InvalidInput(format!(
+            "{name} is too large to import."
+        )));
+    }
+
+    let bytes = fs::read(path)?;
+    let source = String::from_utf8_lossy(&bytes);
+    let source = source.strip_prefix('\u{feff}').unwrap_or(&source);
+
+    let fallback_title = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().trim().to_string())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| UNTITLED.to_string());
+
+    Ok(convert(source, &fallback_title))
+}
+
+/// Adds `files` as new documents with queued creates, in one transaction.
+/// Returns the document records.
+pub fn insert_documents(store: &LocalStore, files: Vec<ImportedFile>) -> Result<Vec<Value>> {
+    let now = Utc::now();
+    let timestamp = now.timestamp_millis();
+    let created_at = now.to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    let mut documents = Vec::with_capacity(files.len());
+    let mut ops = Vec::with_capacity(files.len());
+
+    for file in files {
+        let id = Resource::Documents.offline_id(timestamp);
+        let title = match file.title.trim() {
+            "" => UNTITLED.to_string(),
+            title => title.to_string(),
+        };
+        let payload = json!({
+            "title": title,
+            "content": file.content,
+            "isPinned": false,
+            "isFavorite": false,
+        });
+
+        documents.push(json!({
+            "id": id,
+            "title": title,
+            "content": payload["content"],
+            "isPinned": false,
+            "isFavorite": false,
+            "createdAt": created_at,
+            "updatedAt": created_at,
+        }));
+        ops.push(serde_json::to_value(QueueOp::Create {
+            temp_id: id,
+            payload,
+            timestamp,
+        })?);
+    }
+
+    store.update_records_and_queue(
+        RecordStore::Documents,
+        QueueStore::Documents,
+        |records, queue| {
+            records.splice(0..0, documents.iter().cloned());
+            queue.extend(ops);
+        },
+    )?;
+
+    Ok(documents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_titles_come_from_front_matter_then_the_first_heading() {
+        let from_front_matter = from_markdown("---\ntitle: Plan\n---\n# Goals\n\nShip.\n", "notes");
+        assert_eq!(from_front_matter.title, "Plan");
+        assert_eq!(from_front_matter.content, "<h1>Goals</h1><p>Ship.</p>");
+
+        let from_heading = from_markdown("# Goals\n\nShip.\n", "notes");
+        assert_eq!(from_heading.title, "Goals");
+        assert_eq!(from_heading.content, "<p>Ship.</p>");
+
+        let from_file_name = from_markdown("Ship.\n", "notes");
+        assert_eq!(from_file_name.title, "notes");
+    }
+
+    #[test]
+    fn html_titles_come_from_the_title_element() {
+        let file = from_html(
+            "<html><head><title>Minutes</title></head><body><h2>Agenda</h2><p>One</p></body></html>",
+            "page",
+        );
+
+        assert_eq!(file.title, "Minutes");
+        assert_eq!(file.content, "<h2>Agenda</h2><p>One</p>");
+    }
+
+    #[test]
+    fn text_keeps_paragraphs_and_line_breaks() {
+        let file = from_text("First\r\nline\r\n\r\nSecond <b>\n", "todo");
+
+        assert_eq!(file.title, "todo");
+        assert_eq!(file.content, "<p>First<br>line</p><p>Second &lt;b&gt;</p>");
+    }
+}
@@ -0,0 +1,857 @@
+// desktop/src-tauri/src/documents/markdown.rs
+
+/*
+ * Markdown to the block model.
+ *
+ * Covers the CommonMark constructs that notes are usually written with:
+ * ATX and setext headings, paragraphs with soft and hard breaks, nested
+ * bullet, ordered and task lists, block quotes, fenced and indented code,
+ * rules, and inline emphasis, strikethrough, code, links, autolinks and
+ * images. YAML front-matter is split off so its `title` can name the
+ * document. Raw HTML is kept as text.
+ */
+
+use super::blocks::{Block, Inline, ListKind, Style};
+
+/// Splits leading `---` front-matter from `source` and returns its
+/// `title`, if any, with the rest of the document.
+pub fn split_front_matter(source: &str) -> (Option<String>, &str) {
+    let Some(rest) = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))
+    else {
+        return (None, source);
+    };
+
+    let mut offset = 0;
+
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+
+        if trimmed == "---" || trimmed == "..." {
+            let title = rest[..offset].lines().find_map(|line| {
+                let value = line.strip_prefix("title:")?.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .or_else(|| {
+                        value
+                            .strip_prefix('\'')
+                            .and_then(|value| value.strip_suffix('\''))
+                    })
+                    .unwrap_or(value);
+
+                (!value.is_empty()).then(|| value.to_string())
+            });
+
+            return (title, &rest[offset + line.len()..]);
+        }
+
+        offset += line.len();
+    }
+
+    // An unterminated block is not front-matter.
+    (None, source)
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|character| matches!(character, ' ' | '\t'))
+        .map(|character| if character == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+fn atx_heading(line: &str) -> Option<(u8, &str)> {
+    if indentation(line) > 3 {
+        return None;
+    }
+
+    let trimmed = line.trim_start();
+    let level = trimmed
+        .chars()
+        .take_while(|character| *character == '#')
+        .count();
+
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    let rest = &trimmed[level..];
+
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    // An optional closing sequence of `#`s.
+    let text = rest.trim();
+    let text = match text.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with([' ', '\t']) => stripped.trim_end(),
+        _ => text,
+    };
+
+    Some((level as u8, text))
+}
+
+fn is_rule(line: &str) -> bool {
+    if indentation(line) > 3 {
+        return false;
+    }
+
+    let compact: String = line
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect();
+
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|character| character == *marker))
+}
+
+fn setext_level(line: &str) -> Option<u8> {
+    if indentation(line) > 3 {
+        return None;
+    }
+
+    let trimmed = line.trim();
+
+    if !trimmed.is_empty() && trimmed.chars().all(|character| character == '=') {
+        Some(1)
+    } else if !trimmed.is_empty() && trimmed.chars().all(|character| character == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn fence(line: &str) -> Option<(char, usize)> {
+    if indentation(line) > 3 {
+        return None;
+    }
+
+    let trimmed = line.trim_start();
+    let marker = trimmed
+        .chars()
+        .next()
+        .filter(|marker| matches!(marker, '`' | '~'))?;
+    let length = trimmed
+        .chars()
+        .take_while(|character| *character == marker)
+        .count();
+
+    // Backtick fences cannot have backticks in their info string.
+    let valid = length >= 3 && (marker == '~' || !trimmed[length..].contains('`'));
+    valid.then_some((marker, length))
+}
+
+struct ListMarker<'a> {
+    indent: usize,
+    ordered: bool,
+    /// Column where the item's content starts.
+    content_indent: usize,
+    content: &'a str,
+}
+
+fn list_marker(line: &str) -> Option<ListMarker<'_>> {
+    let indent = indentation(line);
+    let trimmed = line.trim_start();
+
+    let (ordered, marker_length) = match trimmed.chars().next()? {
+        '-' | '*' | '+' => (false, 1),
+        character if character.is_ascii_digit() => {
+            let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+            if digits > 9 || !trimmed[digits..].starts_with(['.', ')']) {
+                return None;
+            }
+            (true, digits + 1)
+        }
+        _ => return None,
+    };
+
+    let after = &trimmed[marker_length..];
+
+    if !after.is_empty() && !after.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    let content = after.trim_start();
+    let spacing = (after.len() - content.len()).clamp(1, 4);
+
+    Some(ListMarker {
+        indent,
+        ordered,
+        content_indent: indent + marker_length + spacing,
+        content,
+    })
+}
+
+fn quote_content(line: &str) -> Option<&str> {
+    if indentation(line) > 3 {
+        return None;
+    }
+
+    let rest = line.trim_start().strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+fn strip_indent(line: &str, columns: usize) -> &str {
+    let mut removed = 0;
+
+    for (index, character) in line.char_indices() {
+        if removed >= columns {
+            return &line[index..];
+        }
+
+        match character {
+            ' ' => removed += 1,
+            '\t' => removed += 4,
+            _ => return &line[index..],
+        }
+    }
+
+    ""
+}
+
+enum Open {
+    Paragraph(Vec<String>),
+    Item {
+        kind: ListKind,
+        depth: usize,
+        lines: Vec<String>,
+    },
+}
+
+#[derive(Default)]
+struct Parser {
+    blocks: Vec<Block>,
+    open: Option<Open>,
+    // Content columns of the open list levels, outermost first.
+    lists: Vec<usize>,
+}
+
+impl Parser {
+    fn close(&mut self) {
+        match self.open.take() {
+            Some(Open::Paragraph(lines)) => {
+                self.blocks
+                    .push(Block::Paragraph(parse_inlines(lines.join("\n").trim_end())));
+            }
+            Some(Open::Item { kind, depth, lines }) => self.blocks.push(Block::ListItem {
+                kind,
+                number: 0,
+                depth,
+                content: parse_inlines(lines.join("\n").trim_end()),
+            }),
+            None => {}
+        }
+    }
+
+    /// Closes everything, including open lists.
+    fn end_lists(&mut self) {
+        self.close();
+        self.lists.clear();
+    }
+
+    fn list_item(&mut self, marker: ListMarker<'_>) {
+        self.close();
+
+        while self
+            .lists
+            .last()
+            .is_some_and(|content_indent| marker.indent < *content_indent)
+        {
+            self.lists.pop();
+        }
+
+        let depth = self.lists.len();
+        self.lists.push(marker.content_indent);
+
+        let (kind, content) = if let Some(rest) = marker.content.strip_prefix("[ ] ") {
+            (ListKind::Checked(false), rest)
+        } else if let Some(rest) = marker
+            .content
+            .strip_prefix("[x] ")
+            .or_else(|| marker.content.strip_prefix("[X] "))
+        {
+            (ListKind::Checked(true), rest)
+        } else if marker.ordered {
+            (ListKind::Ordered, marker.content)
+        } else {
+            (ListKind::Bullet, marker.content)
+        };
+
+        self.open = Some(Open::Item {
+            kind,
+            depth,
+            lines: vec![content.to_string()],
+        });
+    }
+
+    fn parse(mut self, source: &str) -> Vec<Block> {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut index = 0;
+
+        while index < lines.len() {
+            let line = lines[index];
+            index += 1;
+
+            if line.trim().is_empty() {
+                // A blank line ends a paragraph or item, but a list only
+                // ends at the next line that is not part of it.
+                self.close();
+                continue;
+            }
+
+            if let Some((marker, length)) = fence(line) {
+                self.end_lists();
+                let indent = indentation(line);
+                let mut code = Vec::new();
+
+                while index < lines.len() {
+                    let line = lines[index];
+                    index += 1;
+
+                    if fence(line).is_some_and(|(closing, closing_length)| {
+                        closing == marker
+                            && closing_length >= length
+                            && line.trim_start()[closing_length..].trim().is_empty()
+                    }) {
+                        break;
+                    }
+
+                    code.push(strip_indent(line, indent));
+                }
+
+                self.blocks.push(Block::Code(code.join("\n")));
+                continue;
+            }
+
+            if let Some((level, text)) = atx_heading(line) {
+                self.end_lists();
+                self.blocks.push(Block::Heading {
+                    level,
+                    content: parse_inlines(text),
+                });
+                continue;
+            }
+
+            if let Some(level) = setext_level(line) {
+                if let Some(Open::Paragraph(lines)) = &self.open {
+                    let content = parse_inlines(lines.join("\n").trim_end());
+                    self.open = None;
+                    self.blocks.push(Block::Heading { level, content });
+                    continue;
+                }
+            }
+
+            if is_rule(line) {
+                self.end_lists();
+                self.blocks.push(Block::Rule);
+                continue;
+            }
+
+            if let Some(content) = quote_content(line) {
+                self.end_lists();
+                let mut quoted = vec![content];
+
+                while let Some(content) = lines.get(index).and_then(|line| quote_content(line)) {
+                    quoted.push(content);
+                    index += 1;
+                }
+
+                for paragraph in quoted.split(|line| line.trim().is_empty()) {
+                    if !paragraph.is_empty() {
+                        self.blocks
+                            .push(Block::Quote(parse_inlines(&paragraph.join("\n"))));
+                    }
+                }
+                continue;
+            }
+
+            if let Some(marker) = list_marker(line) {
+                // A numbered line only starts a list after a blank line,
+                // so prose that begins with a year is left alone.
+                let interrupts = marker.ordered && matches!(self.open, Some(Open::Paragraph(_)));
+                if !interrupts && !marker.content.is_empty() {
+                    self.list_item(marker);
+                    continue;
+                }
+            }
+
+            let indent = indentation(line);
+
+            match &mut self.open {
+                Some(Open::Paragraph(lines)) => lines.push(line.trim_start().to_string()),
+                Some(Open::Item { lines, .. }) => lines.push(line.trim_start().to_string()),
+                None if indent >= 4 && self.lists.is_empty() => {
+                    let mut code = vec![strip_indent(line, 4)];
+
+                    while let Some(line) = lines.get(index) {
+                        if line.trim().is_empty() || indentation(line) >= 4 {
+                            code.push(strip_indent(line, 4));
+                            index += 1;
+                        } else {
+                            break;
+                        }
+                    }
+
+                    while code.last().is_some_and(|line| line.trim().is_empty()) {
+                        code.pop();
+                    }
+
+                    self.blocks.push(Block::Code(code.join("\n")));
+                }
+                None if self
+                    .lists
+                    .last()
+                    .is_some_and(|content_indent| indent >= *content_indent) =>
+                {
+                    // A later paragraph of a list item; kept as its own item
+                    // text at the same depth.
+                    let depth = self.lists.len() - 1;
+                    self.open = Some(Open::Item {
+                        kind: ListKind::Bullet,
+                        depth,
+                        lines: vec![line.trim_start().to_string()],
+                    });
+                }
+                None => {
+                    self.lists.clear();
+                    self.open = Some(Open::Paragraph(vec![line.trim_start().to_string()]));
+                }
+            }
+        }
+
+        self.end_lists();
+        self.blocks
+    }
+}
+
+/// Parses Markdown into blocks.
+pub fn parse_markdown(source: &str) -> Vec<Block> {
+    let mut blocks = Parser::default().parse(source);
+    super::blocks::number_lists(&mut blocks);
+    blocks
+}
+
+/* Inlines */
+
+struct Inlines<'a> {
+    source: &'a str,
+    output: Vec<Inline>,
+    text: String,
+    style: Style,
+}
+
+/// Finds the closing `delimiter` for a run opened just before `from`: the
+/// next one not preceded by whitespace. For single `*`/`_`, doubled
+/// delimiters belong to strong emphasis and are skipped.
+fn closing(source: &str, from: usize, delimiter: &str) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let single = delimiter.len() == 1;
+    let mut index = from;
+
+    while index < source.len() {
+        if bytes[index] == b'\\' {
+            index += 2;
+            continue;
+        }
+
+        if bytes[index] == b'`' {
+            // Code spans take precedence over emphasis.
+            let run = source[index..]
+                .bytes()
+                .take_while(|byte| *byte == b'`')
+                .count();
+            let fence = &source[index..index + run];
+            match source[index + run..].find(fence) {
+                Some(end) => index += run + end + run,
+                None => index += run,
+            }
+            continue;
+        }
+
+        if source[index..].starts_with(delimiter) {
+            let doubled = single && source[index + 1..].starts_with(delimiter);
+
+            if doubled {
+                index += 2;
+                continue;
+            }
+
+            let after_whitespace = source[..index]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace);
+
+            if index > from && !after_whitespace {
+                return Some(index);
+            }
+        }
+
+        index += source[index..].chars().next().map_or(1, char::len_utf8);
+    }
+
+    None
+}
+
+/// Finds the `]` matching the `[` just before `from`.
+fn closing_bracket(source: &str, from: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+
+    for (offset, character) in source[from..].char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some(from + offset),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parses `(destination "title")` at `from`, returning the destination and
+/// the index after the closing parenthesis.
+fn link_destination(source: &str, from: usize) -> Option<(String, usize)> {
+    let rest = source[from..].strip_prefix('(')?;
+    let rest_start = from + 1;
+
+    let (destination, after) = if let Some(bracketed) = rest.strip_prefix('<') {
+        let end = bracketed.find('>')?;
+        (bracketed[..end].to_string(), rest_start + 1 + end + 1)
+    } else {
+        let mut depth = 0;
+        let mut end = rest.len();
+
+        for (offset, character) in rest.char_indices() {
+            match character {
+                '(' => depth += 1,
+                ')' if depth == 0 => {
+                    end = offset;
+                    break;
+                }
+                ')' => depth -= 1,
+                character if character.is_whitespace() => {
+                    end = offset;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        (rest[..end].to_string(), rest_start + end)
+    };
+
+    // Skip an optional title.
+    let close = source[after..].find(')')?;
+    let between = source[after..after + close].trim();
+
+    let titled = between.is_empty()
+        || (between.len() >= 2
+            && matches!(
+                (between.chars().next(), between.chars().next_back()),
+                (Some('"'), Some('"')) | (Some('\''), Some('\'')) | (Some('('), Some(')'))
+            ));
+
+    titled.then_some((destination, after + close + 1))
+}
+
+fn autolink(source: &str, from: usize) -> Option<(String, usize)> {
+    let rest = source[from..].strip_prefix('<')?;
+    let end = rest.find('>')?;
+    let target = &rest[..end];
+
+    let is_url = target.split_once(':').is_some_and(|(scheme, rest)| {
+        scheme.len() >= 2
+            && scheme
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || "+.-".contains(character))
+            && !rest.is_empty()
+    });
+    let is_email = target.contains('@') && !target.contains(char::is_whitespace);
+
+    if target.contains(char::is_whitespace) || !(is_url || is_email) {
+        return None;
+    }
+
+    let href = if is_url {
+        target.to_string()
+    } else {
+        format!("mailto:{target}")
+    };
+
+    Some((href, from + 1 + end + 1))
+}
+
+impl<'a> Inlines<'a> {
+    fn new(source: &'a str, style: Style) -> Self {
+        Self {
+            source,
+            output: Vec::new(),
+            text: String::new(),
+            style,
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            self.output.push(Inline::Text(
+                std::mem::take(&mut self.text),
+                self.style.clone(),
+            ));
+        }
+    }
+
+    fn nested(&mut self, source: &str, style: Style) {
+        self.flush();
+        let inner = Inlines::new(source, style).parse();
+        self.output.extend(inner);
+    }
+
+    fn parse(mut self) -> Vec<Inline> {
+        let source = self.source;
+        let mut index = 0;
+
+        while index < source.len() {
+            let rest = &source[index..];
+            let character = rest.chars().next().unwrap_or_default();
+
+            match character {
+                '\\' => {
+                    let next = rest[1..].chars().next();
+
+                    match next {
+                        Some('\n') => {
+                            self.flush();
+                            self.output.push(Inline::Break);
+                            index += 2;
+                        }
+                        Some(next) if next.is_ascii_punctuation() => {
+                            self.text.push(next);
+                            index += 2;
+                        }
+                        _ => {
+                            self.text.push('\\');
+                            index += 1;
+                        }
+                    }
+                    continue;
+                }
+                '\n' => {
+                    let hard = self.text.ends_with("  ");
+                    let trimmed = self.text.trim_end_matches(' ').len();
+                    self.text.truncate(trimmed);
+
+                    if hard {
+                        self.flush();
+                        self.output.push(Inline::Break);
+                    } else {
+                        self.text.push(' ');
+                    }
+                    index += 1;
+                    continue;
+                }
+                '`' => {
+                    let run = rest.bytes().take_while(|byte| *byte == b'`').count();
+                    let fence = &rest[..run];
+
+                    if let Some(end) = rest[run..].find(fence) {
+                        let code = rest[run..run + end].replace('\n', " ");
+                        let code = match code
+                            .strip_prefix(' ')
+                            .and_then(|code| code.strip_suffix(' '))
+                        {
+                            Some(inner) if !inner.trim().is_empty() => inner.to_string(),
+                            _ => code,
+                        };
+
+                        self.flush();
+                        self.output.push(Inline::Text(
+                            code,
+                            Style {
+                                code: true,
+                                ..self.style.clone()
+                            },
+                        ));
+                        index += run + end + run;
+                    } else {
+                        self.text.push_str(fence);
+                        index += run;
+                    }
+                    continue;
+                }
+                '!' if rest.starts_with("![") => {
+                    if let Some(end) = closing_bracket(source, index + 2) {
+                        if let Some((src, after)) = link_destination(source, end + 1) {
+                            self.flush();
+                            self.output.push(Inline::Image {
+                                src,
+                                alt: source[index + 2..end].to_string(),
+                            });
+                            index = after;
+                            continue;
+                        }
+                    }
+                }
+                '[' if self.style.link.is_none() => {
+                    if let Some(end) = closing_bracket(source, index + 1) {
+                        if let Some((href, after)) = link_destination(source, end + 1) {
+                            let style = Style {
+                                link: Some(href),
+                                ..self.style.clone()
+                            };
+                            self.nested(&source[index + 1..end], style);
+                            index = after;
+                            continue;
+                        }
+                    }
+                }
+                '<' => {
+                    if let Some((href, after)) = autolink(source, index) {
+                        let label = href.strip_prefix("mailto:").unwrap_or(&href).to_string();
+
+                        self.flush();
+                        self.output.push(Inline::Text(
+                            label,
+                            Style {
+                                link: Some(href),
+                                ..self.style.clone()
+                            },
+                        ));
+                        index = after;
+                        continue;
+                    }
+                }
+                '*' | '_' | '~' => {
+                    let double = rest[1..].starts_with(character);
+                    let delimiter = &rest[..if double { 2 } else { 1 }];
+                    let opens = rest[delimiter.len()..]
+                        .chars()
+                        .next()
+                        .is_some_and(|next| !next.is_whitespace());
+                    // `_` inside a word, as in snake_case, is not emphasis.
+                    let intraword = character == '_'
+                        && source[..index]
+                            .chars()
+                            .next_back()
+                            .is_some_and(char::is_alphanumeric);
+
+                    if opens && !intraword && (character != '~' || double) {
+                        let from = index + delimiter.len();
+
+                        if let Some(end) = closing(source, from, delimiter) {
+                            let mut style = self.style.clone();
+
+                            match (character, double) {
+                                ('~', _) => style.strike = true,
+                                (_, true) => style.bold = true,
+                                (_, false) => style.italic = true,
+                            }
+
+                            self.nested(&source[from..end], style);
+                            index = end + delimiter.len();
+                            continue;
+                        }
+                    }
+
+                    self.text.push_str(delimiter);
+                    index += delimiter.len();
+                    continue;
+                }
+                _ => {}
+            }
+
+            self.text.push(character);
+            index += character.len_utf8();
+        }
+
+        self.flush();
+        self.output
+    }
+}
+
+/// Parses inline Markdown.
+pub fn parse_inlines(source: &str) -> Vec<Inline> {
+    Inlines::new(source.trim(), Style::default()).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::render::to_editor_html;
+
+    fn html(markdown: &str) -> String {
+        to_editor_html(&parse_markdown(markdown))
+    }
+
+    #[test]
+    fn front_matter_title_is_split_off() {
+        let (title, rest) =
+            split_front_matter("---\ntitle: \"Trip notes\"\ntags: [a]\n---\n# Day one\n");
+
+        assert_eq!(title.as_deref(), Some("Trip notes"));
+        assert_eq!(rest, "# Day one\n");
+        assert_eq!(split_front_matter("---\nno end\n").0, None);
+    }
+
+    #[test]
+    fn converts_blocks_to_editor_html() {
+        let markdown = concat!(
+            "# Title\n\n",
+            "Setext\n======\n\n",
+            "A paragraph\nthat wraps.  \nNew line.\n\n",
+            "- one\n",
+            "  - nested\n",
+            "- [x] done\n\n",
+            "1. first\n",
+            "2. second\n\n",
+            "> quoted\n\n",
+            "```rust\nfn main() {}\n```\n\n",
+            "---\n",
+        );
+
+        assert_eq!(
+            html(markdown),
+            concat!(
+                "<h1>Title</h1>",
+                "<h1>Setext</h1>",
+                "<p>A paragraph that wraps.<br>New line.</p>",
+                "<ul><li>one</li><li class=\"ql-indent-1\">nested</li></ul>",
+                "<ul data-checked=\"true\"><li>done</li></ul>",
+                "<ol><li>first</li><li>second</li></ol>",
+                "<blockquote>quoted</blockquote>",
+                "<pre class=\"ql-syntax\" spellcheck=\"false\">fn main() {}</pre>",
+                "<p><br></p>",
+            )
+        );
+    }
+
+    #[test]
+    fn converts_inline_formatting() {
+        assert_eq!(
+            html("**bold** and *it* with `a<b` ~~gone~~ [link](https://x.test \"t\") snake_case_name"),
+            concat!(
+                "<p><strong>bold</strong> and <em>it</em> with <code>a&lt;b</code> ",
+                "<s>gone</s> <a href=\"https://x.test\" rel=\"noopener noreferrer\" target=\"_blank\">link</a> ",
+                "snake_case_name</p>",
+            )
+        );
+        assert_eq!(
+            html("![chart](img/chart.png) <https://a.test> \\*literal\\*"),
+            concat!(
+                "<p><img src=\"img/chart.png\" alt=\"chart\"> ",
+                "<a href=\"https://a.test\" rel=\"noopener noreferrer\" target=\"_blank\">https://a.test</a> ",
+                "*literal*</p>",
+            )
+        );
+    }
+
+    #[test]
+    fn unsafe_links_are_dropped() {
+        assert_eq!(html("[x](javascript:alert(1))"), "<p>x</p>");
+    }
+}
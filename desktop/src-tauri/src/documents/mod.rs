@@ -0,0 +1,55 @@
+// desktop/src-tauri/src/documents/mod.rs
+
+/*
+ * Document conversion.
+ *
+ * Documents are edited as Quill HTML in the webview. Exports parse that HTML
+ * once into a block model (`blocks`) and render Markdown, plain text or PDF
+ * from it; HTML exports wrap the original markup in a standalone page.
+ * Imports go the other way, parsing Markdown, text or HTML files into the
+ * same model and rendering the editor's HTML from it.
+ */
+
+pub mod blocks;
+pub mod commands;
+pub mod import;
+pub mod markdown;
+pub mod pdf;
+pub mod render;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// The fields of the webview's `Document` that exports need.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Text,
+    Pdf,
+}
+
+/// Renders `document` in `format`. Only PDF exports can fail, for text the
+/// PDF fonts cannot show.
+pub fn export(document: &Document, format: ExportFormat) -> Result<Vec<u8>> {
+    let title = &document.title;
+    let blocks = || blocks::parse_html(&document.content);
+
+    Ok(match format {
+        ExportFormat::Html => render::to_html(title, &document.content).into_bytes(),
+        ExportFormat::Markdown => render::to_markdown(title, &blocks()).into_bytes(),
+        ExportFormat::Text => render::to_text(title, &blocks()).into_bytes(),
+        ExportFormat::Pdf => pdf::to_pdf(title, &blocks())?,
+    })
+}
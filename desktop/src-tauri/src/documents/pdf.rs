@@ -0,0 +1,741 @@
+// desktop/src-tauri/src/documents/pdf.rs
+
+/*
+ * A small PDF renderer for documents.
+ *
+ * Pages are A4 and use the standard Helvetica and Courier fonts, which every
+ * PDF reader provides, so nothing has to be embedded. Text is encoded as
+ * WinAnsi, and a document with a character outside it is refused rather
+ * than exported with the character missing. Images are shown by their alt
+ * text.
+ */
+
+use super::blocks::{inline_text, Block, Inline, ListKind, Style};
+use crate::error::{Error, Result};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const LINE_HEIGHT: f32 = 1.45;
+const BLOCK_GAP: f32 = 8.0;
+const LIST_INDENT: f32 = 18.0;
+const QUOTE_INDENT: f32 = 14.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Mono,
+}
+
+impl Font {
+    const ALL: [Font; 5] = [
+        Font::Regular,
+        Font::Bold,
+        Font::Italic,
+        Font::BoldItalic,
+        Font::Mono,
+    ];
+
+    fn of(style: &Style, bold: bool) -> Self {
+        match (style.code, style.bold || bold, style.italic) {
+            (true, _, _) => Font::Mono,
+            (false, true, true) => Font::BoldItalic,
+            (false, true, false) => Font::Bold,
+            (false, false, true) => Font::Italic,
+            (false, false, false) => Font::Regular,
+        }
+    }
+
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+            Font::BoldItalic => "F4",
+            Font::Mono => "F5",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Italic => "Helvetica-Oblique",
+            Font::BoldItalic => "Helvetica-BoldOblique",
+            Font::Mono => "Courier",
+        }
+    }
+
+    /// Advance width of a WinAnsi byte in thousandths of the font size.
+    fn width(self, byte: u8) -> u16 {
+        let table = match self {
+            Font::Mono => return 600,
+            Font::Regular | Font::Italic => &HELVETICA_WIDTHS,
+            Font::Bold | Font::BoldItalic => &HELVETICA_BOLD_WIDTHS,
+        };
+
+        match byte {
+            32..=126 => table[(byte - 32) as usize],
+            _ => 556,
+        }
+    }
+}
+
+// Widths of ASCII 32..=126 from the standard Helvetica font metrics.
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// The WinAnsi (Windows-1252) byte for `character`, if it has one.
+fn win_ansi_byte(character: char) -> Option<u8> {
+    let byte = match character {
+        ' '..='~' => character as u8,
+        '\u{a0}'..='\u{ff}' => character as u32 as u8,
+        '€' => 0x80,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '™' => 0x99,
+        '\t' => b' ',
+        _ => return None,
+    };
+
+    Some(byte)
+}
+
+/// Encodes `text` as WinAnsi. `to_pdf` has already checked that every
+/// character has a byte.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars().filter_map(win_ansi_byte).collect()
+}
+
+/// The first character of the document that WinAnsi cannot encode. Line
+/// breaks are laid out rather than encoded.
+fn unsupported_character(title: &str, blocks: &[Block]) -> Option<char> {
+    let texts = blocks.iter().map(|block| match block {
+        Block::Heading { content, .. }
+        | Block::Paragraph(content)
+        | Block::ListItem { content, .. }
+        | Block::Quote(content) => inline_text(content),
+        Block::Code(code) => code.clone(),
+        Block::Rule => String::new(),
+    });
+
+    std::iter::once(title.to_string())
+        .chain(texts)
+        .find_map(|text| {
+            text.chars()
+                .find(|character| *character != '\n' && win_ansi_byte(*character).is_none())
+        })
+}
+
+fn text_width(bytes: &[u8], font: Font, size: f32) -> f32 {
+    bytes
+        .iter()
+        .map(|byte| f32::from(font.width(*byte)))
+        .sum::<f32>()
+        * size
+        / 1000.0
+}
+
+struct Run {
+    x: f32,
+    font: Font,
+    bytes: Vec<u8>,
+    link: bool,
+}
+
+struct Line {
+    size: f32,
+    runs: Vec<Run>,
+}
+
+/// A word or space with the font it is set in.
+struct Piece {
+    bytes: Vec<u8>,
+    font: Font,
+    link: bool,
+    space: bool,
+}
+
+fn pieces(content: &[Inline], bold: bool) -> Vec<Option<Piece>> {
+    let mut pieces = Vec::new();
+
+    for inline in content {
+        let (text, style) = match inline {
+            Inline::Text(text, style) => (text.clone(), style.clone()),
+            Inline::Break => {
+                pieces.push(None);
+                continue;
+            }
+            Inline::Image { alt, .. } if !alt.is_empty() => (
+                format!("[Image: {alt}]"),
+                Style {
+                    italic: true,
+                    ..Style::default()
+                },
+            ),
+            Inline::Image { .. } => (
+                "[Image]".to_string(),
+                Style {
+                    italic: true,
+                    ..Style::default()
+                },
+            ),
+        };
+
+        let font = Font::of(&style, bold);
+        let link = style.link.is_some();
+        let mut word = String::new();
+
+        for character in text.chars() {
+            if character == ' ' {
+                if !word.is_empty() {
+                    pieces.push(Some(Piece {
+                        bytes: win_ansi(&std::mem::take(&mut word)),
+                        font,
+                        link,
+                        space: false,
+                    }));
+                }
+                pieces.push(Some(Piece {
+                    bytes: vec![b' '],
+                    font,
+                    link,
+                    space: true,
+                }));
+            } else {
+                word.push(character);
+            }
+        }
+
+        if !word.is_empty() {
+            pieces.push(Some(Piece {
+                bytes: win_ansi(&word),
+                font,
+                link,
+                space: false,
+            }));
+        }
+    }
+
+    pieces
+}
+
+/// Breaks `content` into lines no wider than `width`. Words wider than a
+/// whole line are split by character.
+fn wrap(content: &[Inline], size: f32, width: f32, bold: bool) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut runs: Vec<Run> = Vec::new();
+    let mut x = 0.0;
+
+    let finish = |lines: &mut Vec<Line>, runs: &mut Vec<Run>, x: &mut f32| {
+        while runs.last().is_some_and(|run| run.bytes == b" ") {
+            runs.pop();
+        }
+        lines.push(Line {
+            size,
+            runs: std::mem::take(runs),
+        });
+        *x = 0.0;
+    };
+
+    for piece in pieces(content, bold) {
+        let Some(piece) = piece else {
+            finish(&mut lines, &mut runs, &mut x);
+            continue;
+        };
+
+        // Leading spaces are dropped, except for indentation in code.
+        if piece.space && x == 0.0 && piece.font != Font::Mono {
+            continue;
+        }
+
+        let mut bytes = piece.bytes;
+
+        loop {
+            let piece_width = text_width(&bytes, piece.font, size);
+
+            if x + piece_width <= width || (x == 0.0 && bytes.len() <= 1) {
+                runs.push(Run {
+                    x,
+                    font: piece.font,
+                    bytes,
+                    link: piece.link,
+                });
+                x += piece_width;
+                break;
+            }
+
+            if x > 0.0 {
+                finish(&mut lines, &mut runs, &mut x);
+                if piece.space {
+                    break;
+                }
+                continue;
+            }
+
+            // A single word wider than the line.
+            let mut fitted = 1;
+            while fitted < bytes.len()
+                && text_width(&bytes[..fitted + 1], piece.font, size) <= width
+            {
+                fitted += 1;
+            }
+
+            let rest = bytes.split_off(fitted);
+            runs.push(Run {
+                x,
+                font: piece.font,
+                bytes,
+                link: piece.link,
+            });
+            finish(&mut lines, &mut runs, &mut x);
+            bytes = rest;
+        }
+    }
+
+    if !runs.is_empty() || lines.is_empty() {
+        finish(&mut lines, &mut runs, &mut x);
+    }
+
+    lines
+}
+
+struct Page {
+    content: String,
+}
+
+struct Layout {
+    pages: Vec<Page>,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Page {
+                content: String::new(),
+            }],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn page(&mut self) -> &mut String {
+        &mut self
+            .pages
+            .last_mut()
+            .expect("layout always has a page")
+            .content
+    }
+
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < MARGIN && self.y < PAGE_HEIGHT - MARGIN {
+            self.pages.push(Page {
+                content: String::new(),
+            });
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        if self.y < PAGE_HEIGHT - MARGIN {
+            self.y -= height;
+        }
+    }
+
+    fn line(&mut self, line: &Line, left: f32) {
+        let height = line.size * LINE_HEIGHT;
+        self.ensure(height);
+
+        let baseline = self.y - line.size;
+
+        for run in &line.runs {
+            let hex: String = run.bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+            let color = if run.link {
+                "0.1 0.25 0.7"
+            } else {
+                "0.09 0.1 0.17"
+            };
+
+            let text = format!(
+                "BT {color} rg /{} {:.1} Tf {:.2} {:.2} Td <{hex}> Tj ET\n",
+                run.font.resource(),
+                line.size,
+                left + run.x,
+                baseline,
+            );
+            self.page().push_str(&text);
+        }
+
+        self.y -= height;
+    }
+
+    fn marker(&mut self, marker: &str, x: f32, size: f32) {
+        let hex: String = win_ansi(marker)
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect();
+        let baseline = self.y - size;
+        let text = format!(
+            "BT 0.09 0.1 0.17 rg /{} {size:.1} Tf {x:.2} {baseline:.2} Td <{hex}> Tj ET\n",
+            Font::Regular.resource(),
+        );
+
+        self.page().push_str(&text);
+    }
+
+    fn paragraph(&mut self, content: &[Inline], size: f32, left: f32, bold: bool) {
+        for line in wrap(content, size, CONTENT_WIDTH - (left - MARGIN), bold) {
+            self.line(&line, left);
+        }
+    }
+
+    fn rule(&mut self, x: f32, y_offset: f32, width: f32, gray: f32) {
+        let y = self.y - y_offset;
+        let path = format!(
+            "{gray:.2} G 0.75 w {x:.2} {y:.2} m {:.2} {y:.2} l S\n",
+            x + width
+        );
+        self.page().push_str(&path);
+    }
+}
+
+fn heading_size(level: u8) -> f32 {
+    match level {
+        1 => 20.0,
+        2 => 16.0,
+        3 => 14.0,
+        _ => 12.0,
+    }
+}
+
+fn list_marker(kind: ListKind, number: usize) -> String {
+    match kind {
+        ListKind::Bullet => "•".to_string(),
+        ListKind::Ordered => format!("{number}."),
+        ListKind::Checked(true) => "[x]".to_string(),
+        ListKind::Checked(false) => "[ ]".to_string(),
+    }
+}
+
+fn pdf_string(text: &str) -> String {
+    let mut escaped = String::from("(");
+
+    for byte in win_ansi(text) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                escaped.push('\\');
+                escaped.push(byte as char);
+            }
+            32..=126 => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+
+    escaped.push(')');
+    escaped
+}
+
+/// Renders the document as a PDF file, or refuses if it has text the
+/// standard fonts cannot show.
+pub fn to_pdf(title: &str, blocks: &[Block]) -> Result<Vec<u8>> {
+    let title = title.trim();
+    if let Some(character) = unsupported_character(title, blocks) {
+        return Err(Error::InvalidInput(format!(
+            "PDF export only supports Western European text and cannot show \"{character}\" \
+             (U+{:04X}). Export the document as HTML or Markdown instead.",
+            u32::from(character)
+        )));
+    }
+
+    let mut layout = Layout::new();
+
+    if !title.is_empty() {
+        let content = [Inline::Text(title.to_string(), Style::default())];
+        layout.paragraph(&content, 22.0, MARGIN, true);
+        layout.gap(BLOCK_GAP * 1.5);
+    }
+
+    for block in blocks {
+        match block {
+            Block::Heading { level, content } => {
+                let size = heading_size(*level);
+                layout.gap(size * 0.4);
+                layout.ensure(size * LINE_HEIGHT * 2.0);
+                layout.paragraph(content, size, MARGIN, true);
+            }
+            Block::Paragraph(content) => layout.paragraph(content, BODY_SIZE, MARGIN, false),
+            Block::ListItem {
+                kind,
+                number,
+                depth,
+                content,
+            } => {
+                let left = MARGIN + LIST_INDENT * (*depth as f32 + 1.0);
+                layout.ensure(BODY_SIZE * LINE_HEIGHT);
+                layout.marker(
+                    &list_marker(*kind, *number),
+                    left - LIST_INDENT + 4.0,
+                    BODY_SIZE,
+                );
+                layout.paragraph(content, BODY_SIZE, left, false);
+            }
+            Block::Quote(content) => {
+                let top = layout.pages.len();
+                let start = layout.y;
+                layout.paragraph(content, BODY_SIZE, MARGIN + QUOTE_INDENT, false);
+
+                // The bar is only drawn when the quote did not span a page.
+                if layout.pages.len() == top {
+                    let path = format!(
+                        "0.67 0.7 0.84 RG 2 w {MARGIN:.2} {start:.2} m {MARGIN:.2} {:.2} l S\n",
+                        layout.y
+                    );
+                    layout.page().push_str(&path);
+                }
+            }
+            Block::Code(code) => {
+                let style = Style {
+                    code: true,
+                    ..Style::default()
+                };
+
+                for line in code.split('\n') {
+                    let content = [Inline::Text(line.to_string(), style.clone())];
+                    layout.paragraph(&content, CODE_SIZE, MARGIN + 12.0, false);
+                }
+            }
+            Block::Rule => {
+                layout.ensure(BLOCK_GAP * 2.0);
+                layout.rule(MARGIN, BLOCK_GAP, CONTENT_WIDTH, 0.75);
+                layout.gap(BLOCK_GAP * 2.0);
+                continue;
+            }
+        }
+
+        layout.gap(if matches!(block, Block::ListItem { .. }) {
+            BLOCK_GAP / 2.0
+        } else {
+            BLOCK_GAP
+        });
+    }
+
+    Ok(write_pdf(title, &layout.pages))
+}
+
+fn write_pdf(title: &str, pages: &[Page]) -> Vec<u8> {
+    // Objects: 1 catalog, 2 page tree, 3 info, 4.. fonts, then a page and a
+    // content stream per page.
+    let font_base = 4;
+    let page_base = font_base + Font::ALL.len();
+    let object_count = page_base + pages.len() * 2;
+
+    let mut objects: Vec<Vec<u8>> = vec![Vec::new(); object_count];
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|index| format!("{} 0 R", page_base + index * 2))
+        .collect();
+    let fonts: String = Font::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, font)| format!("/{} {} 0 R", font.resource(), font_base + index))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    objects[1] = b"<< /Type /Catalog /Pages 2 0 R >>".to_vec();
+    objects[2] = format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    )
+    .into_bytes();
+    objects[3] = format!(
+        "<< /Title {} /Producer (Pioneer Work Suite) >>",
+        pdf_string(if title.is_empty() {
+            "Untitled document"
+        } else {
+            title
+        })
+    )
+    .into_bytes();
+
+    for (index, font) in Font::ALL.iter().enumerate() {
+        objects[font_base + index] = format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+            font.base_font()
+        )
+        .into_bytes();
+    }
+
+    for (index, page) in pages.iter().enumerate() {
+        let page_id = page_base + index * 2;
+        let content_id = page_id + 1;
+
+        objects[page_id] = format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << {fonts} >> >> /Contents {content_id} 0 R >>"
+        )
+        .into_bytes();
+
+        let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+        stream.extend_from_slice(page.content.as_bytes());
+        stream.extend_from_slice(b"\nendstream");
+        objects[content_id] = stream;
+    }
+
+    let mut output = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = vec![0usize; object_count];
+
+    for (id, object) in objects.iter().enumerate().skip(1) {
+        offsets[id] = output.len();
+        output.extend_from_slice(format!("{id} 0 obj\n").as_bytes());
+        output.extend_from_slice(object);
+        output.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = output.len();
+    output.extend_from_slice(format!("xref\n0 {object_count}\n0000000000 65535 f \n").as_bytes());
+
+    for offset in offsets.iter().skip(1) {
+        output.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+
+    output.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {object_count} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{xref}\n%%EOF\n"
+        )
+        .as_bytes(),
+    );
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::blocks::parse_html;
+
+    fn hex(text: &str) -> String {
+        text.bytes().map(|byte| format!("{byte:02X}")).collect()
+    }
+
+    /// The file as a string with the same byte offsets; the binary marker
+    /// on the second line is not UTF-8.
+    fn ascii(pdf: &[u8]) -> String {
+        pdf.iter()
+            .map(|byte| if byte.is_ascii() { *byte as char } else { '?' })
+            .collect()
+    }
+
+    fn count(haystack: &str, needle: &str) -> usize {
+        haystack.matches(needle).count()
+    }
+
+    #[test]
+    fn writes_a_well_formed_file() {
+        let pdf = to_pdf(
+            "Notes",
+            &parse_html("<h2>Agenda</h2><p>Hello <b>world</b></p><ul><li>Item</li></ul>"),
+        )
+        .unwrap();
+        let text = ascii(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains(&format!("<{}>", hex("Agenda"))));
+        assert!(text.contains("/F2 11.0 Tf"));
+        assert!(text.contains("/Title (Notes)"));
+
+        // Every xref entry points at the start of its object.
+        let xref = text.rfind("\nxref\n").unwrap() + 1;
+        let startxref: usize = text[text.rfind("startxref\n").unwrap() + 10..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(startxref, xref);
+
+        for (id, entry) in text[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .enumerate()
+        {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj\n", id + 1)));
+        }
+    }
+
+    #[test]
+    fn long_documents_flow_onto_more_pages() {
+        let paragraph = format!("<p>{}</p>", "Lorem ipsum dolor sit amet. ".repeat(40));
+        let pdf = to_pdf("Long", &parse_html(&paragraph.repeat(20))).unwrap();
+        let text = ascii(&pdf);
+
+        assert!(count(&text, "/Type /Page ") > 1);
+        assert!(text.contains(&format!("/Count {}", count(&text, "/Type /Page "))));
+    }
+
+    #[test]
+    fn wraps_within_the_content_width() {
+        let content = [Inline::Text(
+            "word ".repeat(200) + &"x".repeat(400),
+            Style::default(),
+        )];
+
+        for line in wrap(&content, BODY_SIZE, CONTENT_WIDTH, false) {
+            let end = line
+                .runs
+                .iter()
+                .map(|run| run.x + text_width(&run.bytes, run.font, line.size))
+                .fold(0.0, f32::max);
+            assert!(end <= CONTENT_WIDTH + 0.01);
+        }
+    }
+
+    #[test]
+    fn encodes_text_as_win_ansi() {
+        assert_eq!(win_ansi("café – “ok”"), b"caf\xE9 \x96 \x93ok\x94".to_vec());
+        assert_eq!(pdf_string("a (b) \\ é"), "(a \\(b\\) \\\\ \\351)");
+    }
+
+    #[test]
+    fn refuses_text_outside_win_ansi() {
+        assert!(to_pdf("Café", &parse_html("<p>Line<br>break</p><pre>a\nb</pre>")).is_ok());
+
+        let error = to_pdf("Notes", &parse_html("<p>Done ✓</p>")).unwrap_err();
+        assert!(error.to_string().contains("U+2713"));
+        assert!(to_pdf("日本語", &[]).is_err());
+        assert!(to_pdf("Notes", &parse_html("<pre>x = π</pre>")).is_err());
+    }
+}
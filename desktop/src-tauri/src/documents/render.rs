@@ -0,0 +1,669 @@
+// desktop/src-tauri/src/documents/render.rs
+
+/*
+ * Markdown, plain text and standalone HTML renderings of a document, and
+ * the editor's own HTML for imported files.
+ */
+
+use super::blocks::{inline_text, Block, Inline, ListKind, Style};
+
+/// The document title, unless the content already opens with it as a
+/// heading.
+fn leading_title<'a>(title: &'a str, blocks: &[Block]) -> Option<&'a str> {
+    let title = title.trim();
+
+    if title.is_empty() {
+        return None;
+    }
+
+    match blocks.first() {
+        Some(Block::Heading { content, .. }) if inline_text(content).trim() == title => None,
+        _ => Some(title),
+    }
+}
+
+fn same_list(a: ListKind, b: ListKind) -> bool {
+    (a == ListKind::Ordered) == (b == ListKind::Ordered)
+}
+
+/// What goes before each block: nothing before the first, a line break
+/// between items of one list, and a blank line everywhere else.
+fn separators(blocks: &[Block]) -> Vec<&'static str> {
+    let mut separators = Vec::with_capacity(blocks.len());
+    let mut list: Option<ListKind> = None;
+
+    for (index, block) in blocks.iter().enumerate() {
+        let separator = match block {
+            _ if index == 0 => "",
+            Block::ListItem { kind, depth, .. }
+                if matches!(blocks[index - 1], Block::ListItem { .. })
+                    && (*depth > 0 || list.is_some_and(|list| same_list(list, *kind))) =>
+            {
+                "\n"
+            }
+            _ => "\n\n",
+        };
+
+        list = match block {
+            Block::ListItem { kind, depth: 0, .. } => Some(*kind),
+            Block::ListItem { .. } => list,
+            _ => None,
+        };
+
+        separators.push(separator);
+    }
+
+    separators
+}
+
+/* Markdown */
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        if matches!(
+            character,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+
+    escaped
+}
+
+/// Escapes what would otherwise start a heading, quote, list or rule.
+fn escape_line_start(line: String) -> String {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+
+    if line.starts_with(['#', '-', '+', '=']) {
+        format!("\\{line}")
+    } else if digits > 0 && line[digits..].starts_with(['.', ')']) {
+        format!("{}\\{}", &line[..digits], &line[digits..])
+    } else {
+        line
+    }
+}
+
+fn code_span(text: &str) -> String {
+    let longest_run = text
+        .split(|character| character != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+
+    format!("{fence}{padding}{text}{padding}{fence}")
+}
+
+/// Wraps `text` in emphasis markers, keeping surrounding whitespace outside
+/// them as Markdown requires.
+fn emphasize(text: &str, style: &Style) -> String {
+    let inner = text.trim();
+
+    if inner.is_empty() {
+        return text.to_string();
+    }
+
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+
+    let mut rendered = if style.code {
+        code_span(inner)
+    } else {
+        escape_markdown(inner)
+    };
+
+    if style.strike {
+        rendered = format!("~~{rendered}~~");
+    }
+    if style.italic {
+        rendered = format!("*{rendered}*");
+    }
+    if style.bold {
+        rendered = format!("**{rendered}**");
+    }
+
+    format!("{leading}{rendered}{trailing}")
+}
+
+fn markdown_inlines(content: &[Inline], continuation: &str) -> String {
+    let mut rendered = String::new();
+    let mut index = 0;
+
+    while index < content.len() {
+        match &content[index] {
+            Inline::Text(_, style) => {
+                // Adjacent runs with the same style render as one, so
+                // `<b>a</b><b>b</b>` does not become `**a****b**`.
+                let mut text = String::new();
+
+                while let Some(Inline::Text(next, next_style)) = content.get(index) {
+                    if next_style != style {
+                        break;
+                    }
+                    text.push_str(next);
+                    index += 1;
+                }
+
+                let emphasized = emphasize(&text, style);
+
+                match &style.link {
+                    Some(href) if !href.is_empty() => {
+                        let leading =
+                            &emphasized[..emphasized.len() - emphasized.trim_start().len()];
+                        let trailing = &emphasized[emphasized.trim_end().len()..];
+                        rendered.push_str(&format!(
+                            "{leading}[{}](<{href}>){trailing}",
+                            emphasized.trim()
+                        ));
+                    }
+                    _ => rendered.push_str(&emphasized),
+                }
+
+                continue;
+            }
+            Inline::Break => {
+                rendered.push_str("  \n");
+                rendered.push_str(continuation);
+            }
+            Inline::Image { src, alt } => {
+                rendered.push_str(&format!("![{}](<{src}>)", escape_markdown(alt)));
+            }
+        }
+
+        index += 1;
+    }
+
+    rendered
+}
+
+fn code_fence(code: &str) -> String {
+    let longest_run = code
+        .split(|character| character != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+pub fn to_markdown(title: &str, blocks: &[Block]) -> String {
+    let mut output = String::new();
+
+    if let Some(title) = leading_title(title, blocks) {
+        output.push_str(&format!("# {}\n\n", escape_markdown(title)));
+    }
+
+    for (block, separator) in blocks.iter().zip(separators(blocks)) {
+        output.push_str(separator);
+
+        match block {
+            Block::Heading { level, content } => {
+                output.push_str(&"#".repeat((*level).clamp(1, 6) as usize));
+                output.push(' ');
+                output.push_str(&markdown_inlines(content, ""));
+            }
+            Block::Paragraph(content) => {
+                output.push_str(&escape_line_start(markdown_inlines(content, "")));
+            }
+            Block::ListItem {
+                kind,
+                number,
+                depth,
+                content,
+            } => {
+                let indent = "    ".repeat(*depth);
+                let marker = match kind {
+                    ListKind::Bullet => "- ".to_string(),
+                    ListKind::Ordered => format!("{number}. "),
+                    ListKind::Checked(true) => "- [x] ".to_string(),
+                    ListKind::Checked(false) => "- [ ] ".to_string(),
+                };
+                let continuation = format!("{indent}{}", " ".repeat(marker.len()));
+
+                output.push_str(&indent);
+                output.push_str(&marker);
+                output.push_str(&markdown_inlines(content, &continuation));
+            }
+            Block::Quote(content) => {
+                output.push_str("> ");
+                output.push_str(&escape_line_start(markdown_inlines(content, "> ")));
+            }
+            Block::Code(code) => {
+                let fence = code_fence(code);
+                output.push_str(&format!("{fence}\n{code}\n{fence}"));
+            }
+            Block::Rule => output.push_str("---"),
+        }
+    }
+
+    output.push('\n');
+    output
+}
+
+/* Plain text */
+
+fn text_inlines(content: &[Inline], continuation: &str) -> String {
+    let mut rendered = String::new();
+
+    let mut link_text = String::new();
+
+    for (index, inline) in content.iter().enumerate() {
+        match inline {
+            Inline::Text(text, style) => {
+                rendered.push_str(text);
+
+                let Some(href) = style.link.as_deref().filter(|href| !href.is_empty()) else {
+                    continue;
+                };
+
+                // The target follows the last run of the link.
+                link_text.push_str(text);
+                let continues = matches!(
+                    content.get(index + 1),
+                    Some(Inline::Text(_, next)) if next.link.as_deref() == Some(href)
+                );
+
+                if !continues {
+                    if href != link_text.trim() {
+                        rendered.push_str(&format!(" ({href})"));
+                    }
+                    link_text.clear();
+                }
+            }
+            Inline::Break => {
+                rendered.push('\n');
+                rendered.push_str(continuation);
+            }
+            Inline::Image { alt, .. } if !alt.is_empty() => {
+                rendered.push_str(&format!("[Image: {alt}]"));
+            }
+            Inline::Image { .. } => rendered.push_str("[Image]"),
+        }
+    }
+
+    rendered
+}
+
+/// Plain text in the same layout as the webview's TXT download: the title
+/// underlined with `=`, then the content.
+pub fn to_text(title: &str, blocks: &[Block]) -> String {
+    let mut output = String::new();
+
+    if let Some(title) = leading_title(title, blocks) {
+        let underline = "=".repeat(title.chars().count().min(80));
+        output.push_str(&format!("{title}\n{underline}\n\n"));
+    }
+
+    for (block, separator) in blocks.iter().zip(separators(blocks)) {
+        output.push_str(separator);
+
+        match block {
+            Block::Heading { content, .. } | Block::Paragraph(content) => {
+                output.push_str(&text_inlines(content, ""));
+            }
+            Block::ListItem {
+                kind,
+                number,
+                depth,
+                content,
+            } => {
+                let indent = "  ".repeat(*depth);
+                let marker = match kind {
+                    ListKind::Bullet => "• ".to_string(),
+                    ListKind::Ordered => format!("{number}. "),
+                    ListKind::Checked(true) => "[x] ".to_string(),
+                    ListKind::Checked(false) => "[ ] ".to_string(),
+                };
+                let continuation = format!("{indent}{}", " ".repeat(marker.chars().count()));
+
+                output.push_str(&indent);
+                output.push_str(&marker);
+                output.push_str(&text_inlines(content, &continuation));
+            }
+            Block::Quote(content) => {
+                output.push_str("> ");
+                output.push_str(&text_inlines(content, "> "));
+            }
+            Block::Code(code) => output.push_str(code),
+            Block::Rule => output.push_str(&"-".repeat(40)),
+        }
+    }
+
+    output.push('\n');
+    output
+}
+
+/* HTML */
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#039;")
+}
+
+/// A standalone page around the editor HTML, styled like the webview's HTML
+/// download.
+pub fn to_html(title: &str, content: &str) -> String {
+    let title = escape_html(match title.trim() {
+        "" => "Untitled document",
+        title => title,
+    });
+    let content = if content.trim().is_empty() {
+        "<p></p>"
+    } else {
+        content
+    };
+
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>{title}</title>
+  <style>
+    body {{
+      max-width: 820px;
+      margin: 48px auto;
+      padding: 0 24px;
+      color: #171a2b;
+      font-family: system-ui, -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif;
+      font-size: 16px;
+      line-height: 1.65;
+    }}
+    img {{ max-width: 100%; height: auto; }}
+    blockquote {{
+      margin-left: 0;
+      padding-left: 16px;
+      border-left: 3px solid #aab2d5;
+      color: #545d7d;
+    }}
+    pre {{
+      overflow-x: auto;
+      padding: 16px;
+      border-radius: 8px;
+      background: #f1f3f8;
+    }}
+    .ql-align-center {{ text-align: center; }}
+    .ql-align-right {{ text-align: right; }}
+    .ql-align-justify {{ text-align: justify; }}
+  </style>
+</head>
+<body>
+  <h1>{title}</h1>
+  {content}
+</body>
+</html>
+"#
+    )
+}
+
+/* Editor HTML */
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn scheme(url: &str) -> Option<String> {
+    let (scheme, _) = url.trim().split_once(':')?;
+
+    // A colon after a path or query separator is not a scheme.
+    (!scheme.contains(['/', '?', '#'])).then(|| scheme.to_ascii_lowercase())
+}
+
+fn is_safe_link(href: &str) -> bool {
+    scheme(href).is_none_or(|scheme| matches!(scheme.as_str(), "http" | "https" | "mailto" | "tel"))
+}
+
+fn is_safe_image(src: &str) -> bool {
+    match scheme(src).as_deref() {
+        None | Some("http" | "https") => true,
+        Some("data") => src.trim()[5..].to_ascii_lowercase().starts_with("image/"),
+        Some(_) => false,
+    }
+}
+
+fn editor_inlines(content: &[Inline]) -> String {
+    let mut html = String::new();
+
+    for inline in content {
+        match inline {
+            Inline::Text(text, style) => {
+                let mut rendered = escape_text(text);
+
+                if style.code {
+                    rendered = format!("<code>{rendered}</code>");
+                }
+                if style.strike {
+                    rendered = format!("<s>{rendered}</s>");
+                }
+                if style.italic {
+                    rendered = format!("<em>{rendered}</em>");
+                }
+                if style.bold {
+                    rendered = format!("<strong>{rendered}</strong>");
+                }
+
+                match &style.link {
+                    Some(href) if !href.is_empty() && is_safe_link(href) => {
+                        html.push_str(&format!(
+                            "<a href=\"{}\" rel=\"noopener noreferrer\" target=\"_blank\">{rendered}</a>",
+                            escape_html(href)
+                        ));
+                    }
+                    _ => html.push_str(&rendered),
+                }
+            }
+            Inline::Break => html.push_str("<br>"),
+            Inline::Image { src, alt } if is_safe_image(src) => {
+                html.push_str(&format!(
+                    "<img src=\"{}\" alt=\"{}\">",
+                    escape_html(src),
+                    escape_html(alt)
+                ));
+            }
+            Inline::Image { alt, .. } => html.push_str(&escape_text(alt)),
+        }
+    }
+
+    html
+}
+
+fn list_tag(kind: ListKind) -> (&'static str, &'static str) {
+    match kind {
+        ListKind::Bullet => ("<ul>", "</ul>"),
+        ListKind::Ordered => ("<ol>", "</ol>"),
+        ListKind::Checked(true) => ("<ul data-checked=\"true\">", "</ul>"),
+        ListKind::Checked(false) => ("<ul data-checked=\"false\">", "</ul>"),
+    }
+}
+
+/// Renders blocks as the HTML the editor stores: Quill 1 markup, with
+/// nesting as `ql-indent-N` classes on flat lists. Unsafe link and image
+/// targets are dropped.
+pub fn to_editor_html(blocks: &[Block]) -> String {
+    let mut html = String::new();
+    let mut open_list: Option<(&str, &str)> = None;
+
+    for block in blocks {
+        let tag = match block {
+            Block::ListItem { kind, .. } => Some(list_tag(*kind)),
+            _ => None,
+        };
+
+        if open_list != tag {
+            if let Some((_, close)) = open_list {
+                html.push_str(close);
+            }
+            if let Some((open, _)) = tag {
+                html.push_str(open);
+            }
+            open_list = tag;
+        }
+
+        match block {
+            Block::Heading { level, content } => {
+                let level = (*level).clamp(1, 6);
+                html.push_str(&format!("<h{level}>{}</h{level}>", editor_inlines(content)));
+            }
+            Block::Paragraph(content) => {
+                html.push_str(&format!("<p>{}</p>", editor_inlines(content)));
+            }
+            Block::ListItem { depth, content, .. } => {
+                if *depth > 0 {
+                    html.push_str(&format!("<li class=\"ql-indent-{depth}\">"));
+                } else {
+                    html.push_str("<li>");
+                }
+                html.push_str(&editor_inlines(content));
+                html.push_str("</li>");
+            }
+            Block::Quote(content) => {
+                html.push_str(&format!(
+                    "<blockquote>{}</blockquote>",
+                    editor_inlines(content)
+                ));
+            }
+            Block::Code(code) => {
+                html.push_str(&format!(
+                    "<pre class=\"ql-syntax\" spellcheck=\"false\">{}</pre>",
+                    escape_text(code)
+                ));
+            }
+            // The editor has no rule; an empty line keeps the separation.
+            Block::Rule => html.push_str("<p><br></p>"),
+        }
+    }
+
+    if let Some((_, close)) = open_list {
+        html.push_str(close);
+    }
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::blocks::parse_html;
+
+    const SAMPLE: &str = concat!(
+        "<h2>Plan</h2>",
+        "<p>Ship the <strong>desktop</strong> build and <em>tell</em> ",
+        "<a href=\"https://example.com/notes\">the team</a>.</p>",
+        "<p><br></p>",
+        "<ul><li>Draft <code>v2</code> notes</li><li>Review *stars*",
+        "<ul><li>Nested item</li></ul></li></ul>",
+        "<ol><li>First</li><li>Second</li></ol>",
+        "<blockquote>Quoted line<br>and more</blockquote>",
+        "<pre class=\"ql-syntax\">fn main() {}\n</pre>",
+        "<p>1. Not a list</p>",
+    );
+
+    #[test]
+    fn markdown_covers_the_editor_formats() {
+        let markdown = to_markdown("Weekly plan", &parse_html(SAMPLE));
+
+        assert_eq!(
+            markdown,
+            concat!(
+                "# Weekly plan\n\n",
+                "## Plan\n\n",
+                "Ship the **desktop** build and *tell* [the team](<https://example.com/notes>).\n\n",
+                "- Draft `v2` notes\n",
+                "- Review \\*stars\\*\n",
+                "    - Nested item\n\n",
+                "1. First\n",
+                "2. Second\n\n",
+                "> Quoted line  \n",
+                "> and more\n\n",
+                "```\nfn main() {}\n```\n\n",
+                "1\\. Not a list\n",
+            )
+        );
+    }
+
+    #[test]
+    fn quill_indent_lists_nest_and_number() {
+        let html = concat!(
+            "<ol>",
+            "<li data-list=\"ordered\">One</li>",
+            "<li data-list=\"bullet\" class=\"ql-indent-1\">Detail</li>",
+            "<li data-list=\"ordered\">Two</li>",
+            "<li data-list=\"checked\">Done</li>",
+            "<li data-list=\"unchecked\">Open</li>",
+            "</ol>",
+            "<div class=\"ql-code-block-container\">",
+            "<div class=\"ql-code-block\">let a = 1;</div>",
+            "<div class=\"ql-code-block\">let b = 2;</div>",
+            "</div>",
+        );
+
+        assert_eq!(
+            to_markdown("", &parse_html(html)),
+            concat!(
+                "1. One\n",
+                "    - Detail\n",
+                "2. Two\n\n",
+                "- [x] Done\n",
+                "- [ ] Open\n\n",
+                "```\nlet a = 1;\nlet b = 2;\n```\n",
+            )
+        );
+    }
+
+    #[test]
+    fn plain_text_matches_the_webview_layout() {
+        let text = to_text("Weekly plan", &parse_html(SAMPLE));
+
+        assert_eq!(
+            text,
+            concat!(
+                "Weekly plan\n===========\n\n",
+                "Plan\n\n",
+                "Ship the desktop build and tell the team (https://example.com/notes).\n\n",
+                "• Draft v2 notes\n",
+                "• Review *stars*\n",
+                "  • Nested item\n\n",
+                "1. First\n",
+                "2. Second\n\n",
+                "> Quoted line\n",
+                "> and more\n\n",
+                "fn main() {}\n\n",
+                "1. Not a list\n",
+            )
+        );
+    }
+
+    #[test]
+    fn a_leading_heading_with_the_title_is_not_repeated() {
+        let blocks = parse_html("<h1>Notes</h1><p>Body</p>");
+
+        assert_eq!(to_markdown("Notes", &blocks), "# Notes\n\nBody\n");
+        assert_eq!(to_text("Notes", &blocks), "Notes\n\nBody\n");
+    }
+
+    #[test]
+    fn html_escapes_the_title_and_keeps_the_content() {
+        let html = to_html("<Q&A>", "<p>Body</p>");
+
+        assert!(html.contains("<title>&lt;Q&amp;A&gt;</title>"));
+        assert!(html.contains("<h1>&lt;Q&amp;A&gt;</h1>"));
+        assert!(html.contains("<p>Body</p>"));
+    }
+}
@@ -0,0 +1,60 @@
+// desktop/src-tauri/src/error.rs
+
+use serde::{Serialize, Serializer};
+
+/// Errors returned by the desktop commands.
+///
+/// The webview only ever sees the display string, so every variant should
+/// read well when shown in a toast or the developer console.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Local storage error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("File system error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("The app data directory is unavailable on this system.")]
+    AppDataDirUnavailable,
+
+    #[error("The app config directory is unavailable on this system.")]
+    AppConfigDirUnavailable,
+
+    #[error("{0}")]
+    Crypto(&'static str),
+
+    #[error("The credential vault is locked. Unlock it with your passphrase first.")]
+    VaultLocked,
+
+    #[error("The local workspace is locked. Unlock it with your PIN first.")]
+    StoreLocked,
+
+    #[error("Too many incorrect PIN attempts. Try again in {0} seconds.")]
+    TooManyAttempts(u64),
+
+    #[error("Pioneer is already running but does not respond. Quit it and try again.")]
+    InstanceUnresponsive,
+
+    #[error("Backup archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
+
+    #[error("This backup cannot be restored: {0}")]
+    InvalidBackup(String),
+
+    #[error("{0}")]
+    InvalidInput(String),
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
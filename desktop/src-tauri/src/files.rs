@@ -0,0 +1,28 @@
+// desktop/src-tauri/src/files.rs
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::Result;
+
+/// Writes `contents` to a sibling temp file, flushes it to disk and renames
+/// it over `path`, so a crash mid-write leaves either the old or the new
+/// file but never a truncated one.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    {
+        let mut file = fs::File::create(&temporary)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
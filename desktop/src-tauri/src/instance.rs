@@ -0,0 +1,231 @@
+// desktop/src-tauri/src/instance.rs
+
+/*
+ * One running app per user.
+ *
+ * The first instance holds an exclusive lock on `instance.lock` in the app
+ * config directory and listens on a loopback port, which it writes to
+ * `instance.json` together with a random token. A later launch that finds
+ * the lock taken sends its command line to that port and exits; the first
+ * instance brings its window forward and follows any `pioneer://` link in
+ * the arguments.
+ *
+ * The command-line mode uses the same channel to tell a running app which
+ * stores it changed, so the views reload and the sync worker picks up the
+ * new ops.
+ *
+ * The token keeps other local users from poking the port. A launch that
+ * finds the lock taken but cannot reach the instance holding it gives up
+ * with an error rather than open the same store twice. Only when locking
+ * itself fails, e.g. on a file system without locks, does the app start
+ * anyway, as it did before.
+ */
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Config, Manager};
+
+use crate::crypto;
+use crate::deep_link;
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+use crate::sync::{engine::Resource, SyncService};
+use crate::tray;
+
+pub const INSTANCE_LOCK_FILE_NAME: &str = "instance.lock";
+pub const INSTANCE_FILE_NAME: &str = "instance.json";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+/// The first instance may still be starting up when a second one is
+/// launched, e.g. by a double click.
+const FORWARD_ATTEMPTS: u32 = 10;
+const FORWARD_RETRY_DELAY: Duration = Duration::from_millis(300);
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+const ACCEPTED: &str = "ok";
+
+#[derive(Serialize, Deserialize)]
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Launch {
+    token: String,
+    args: Vec<String>,
+    /// Stores written by the command-line mode. A launch that changed
+    /// something is not a request to show the window.
+    #[serde(default)]
+    changed: Vec<Resource>,
+}
+
+pub enum Acquired {
+    /// This is the only instance.
+    Primary(Instance),
+    /// Another instance is running and has been handed the arguments.
+    Forwarded,
+}
+
+pub struct Instance {
+    // Held for the life of the process; the OS releases the lock when it
+    // exits, even after a crash.
+    _lock: File,
+    listener: TcpListener,
+    token: String,
+}
+
+fn token() -> String {
+    crypto::random_bytes::<16>()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn forward(endpoint_path: &Path, args: &[String], changed: &[Resource]) -> Result<()> {
+    let endpoint: Endpoint = serde_json::from_slice(&fs::read(endpoint_path)?)?;
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let launch = Launch {
+        token: endpoint.token,
+        args: args.to_vec(),
+        changed: changed.to_vec(),
+    };
+    let mut message = serde_json::to_vec(&launch)?;
+    message.push(b'\n');
+    stream.write_all(&message)?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+
+    if reply.trim() == ACCEPTED {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(
+            "The running instance did not accept the launch.".to_string(),
+        ))
+    }
+}
+
+/// Takes the instance lock, or hands `args` to the instance holding it.
+pub fn acquire(config: &Config, args: &[String]) -> Result<Acquired> {
+    let directory: PathBuf = paths::app_config_dir_for(config)?;
+    let endpoint_path = directory.join(INSTANCE_FILE_NAME);
+
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(directory.join(INSTANCE_LOCK_FILE_NAME))?;
+
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            let mut attempt = 1;
+            while forward(&endpoint_path, args, &[]).is_err() {
+                if attempt == FORWARD_ATTEMPTS {
+                    return Err(Error::InstanceUnresponsive);
+                }
+                attempt += 1;
+                thread::sleep(FORWARD_RETRY_DELAY);
+            }
+            return Ok(Acquired::Forwarded);
+        }
+        Err(TryLockError::Error(error)) => return Err(error.into()),
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let endpoint = Endpoint {
+        port: listener.local_addr()?.port(),
+        token: token(),
+    };
+    files::write_atomic(&endpoint_path, &serde_json::to_vec(&endpoint)?)?;
+
+    Ok(Acquired::Primary(Instance {
+        _lock: lock,
+        listener,
+        token: endpoint.token,
+    }))
+}
+
+/// Shows `error` in a native dialog. For failures before the app, and so
+/// Tauri's own dialogs, can start.
+pub fn show_error(error: &Error) {
+    eprintln!("{error}");
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("Pioneer")
+        .set_description(&error.to_string())
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
+
+/// Tells the running instance, if there is one, that `changed` were written
+/// behind its back.
+pub fn notify_changed(config: &Config, changed: &[Resource]) -> Result<()> {
+    forward(
+        &paths::app_config_dir_for(config)?.join(INSTANCE_FILE_NAME),
+        &[],
+        changed,
+    )
+}
+
+fn receive(stream: TcpStream, token: &str) -> Result<Launch> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_MESSAGE_BYTES)).read_line(&mut line)?;
+    let launch: Launch = serde_json::from_str(&line)?;
+
+    if launch.token != token {
+        return Err(Error::InvalidInput("Unknown launch token.".to_string()));
+    }
+
+    (&stream).write_all(format!("{ACCEPTED}\n").as_bytes())?;
+    Ok(launch)
+}
+
+/// Shows the main window for a forwarded launch and follows its link, or
+/// reloads what the command-line mode changed.
+fn relaunched(app: &AppHandle, launch: Launch) {
+    if !launch.changed.is_empty() {
+        for resource in launch.changed {
+            let _ = app.emit_all(resource.changed_event(), ());
+        }
+        app.state::<SyncService>().wake();
+        return;
+    }
+
+    match deep_link::find_link(launch.args) {
+        Some(link) => deep_link::open(app, &link),
+        None => tray::show_main_window(app),
+    }
+}
+
+impl Instance {
+    /// Accepts launches forwarded by later instances for as long as the app
+    /// runs.
+    pub fn listen(self, app: &AppHandle) {
+        let app = app.clone();
+
+        thread::spawn(move || {
+            for stream in self.listener.incoming().flatten() {
+                if let Ok(launch) = receive(stream, &self.token) {
+                    relaunched(&app, launch);
+                }
+            }
+        });
+    }
+}
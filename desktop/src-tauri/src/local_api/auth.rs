@@ -0,0 +1,155 @@
+// desktop/src-tauri/src/local_api/auth.rs
+
+/*
+ * Accounts on the embedded server, mirroring apps/api/src/auth.ts.
+ *
+ * Passwords are kept as Argon2id hashes. Instead of a JWT each account has
+ * one random token, handed out on register and login, that stays valid for
+ * as long as the account exists.
+ */
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::routes::{self, failure, new_id, text, truthy, Method, Request, Response};
+use crate::crypto;
+use crate::error::Result;
+use crate::storage::{LocalStore, RecordStore};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub id: String,
+    pub email: String,
+    pub name: String,
+    pub role: String,
+    password_hash: String,
+    token: String,
+}
+
+impl Account {
+    /// The `User` the API returns, without the secrets.
+    pub fn user(&self) -> Value {
+        json!({
+            "id": self.id,
+            "email": self.email,
+            "name": self.name,
+            "role": self.role,
+        })
+    }
+}
+
+fn accounts(store: &LocalStore) -> Result<Vec<Account>> {
+    store
+        .read_records(RecordStore::LocalApiUsers)?
+        .into_iter()
+        .map(|value| Ok(serde_json::from_value(value)?))
+        .collect()
+}
+
+pub fn find_by_email(store: &LocalStore, email: &str) -> Result<Option<Account>> {
+    Ok(accounts(store)?
+        .into_iter()
+        .find(|account| account.email == email))
+}
+
+/// The account a request's bearer token belongs to, or the 401 the API
+/// would send.
+pub fn authenticate(store: &LocalStore, request: &Request) -> Result<Account, Response> {
+    let Some(token) = request
+        .authorization
+        .as_deref()
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .map(str::trim)
+    else {
+        return Err(Response::error(
+            401,
+            "Missing or invalid Authorization header",
+        ));
+    };
+
+    accounts(store)
+        .map_err(failure)?
+        .into_iter()
+        .find(|account| !token.is_empty() && account.token == token)
+        .ok_or_else(|| Response::error(401, "Invalid or expired token"))
+}
+
+pub fn handle(store: &LocalStore, request: &Request, rest: &[&str]) -> Result<Response> {
+    match (request.method, rest) {
+        (Method::Post, ["register"]) => register(store, request),
+        (Method::Post, ["login"]) => login(store, request),
+        (Method::Get, ["me"]) => Ok(match authenticate(store, request) {
+            Ok(account) => Response::ok(json!({ "user": account.user() })),
+            Err(response) => response,
+        }),
+        _ => Ok(Response::no_route()),
+    }
+}
+
+fn signed_in(status: u16, account: &Account) -> Response {
+    Response::json(
+        status,
+        json!({ "user": account.user(), "token": account.token }),
+    )
+}
+
+fn register(store: &LocalStore, request: &Request) -> Result<Response> {
+    let (email, password, name) = (
+        request.field("email"),
+        request.field("password"),
+        request.field("name"),
+    );
+    if !truthy(email) || !truthy(password) || !truthy(name) {
+        return Ok(Response::error(400, "Missing email, password, or name"));
+    }
+
+    let email = text(email).unwrap_or_default().to_lowercase();
+    let password = text(password).unwrap_or_default();
+    let name = text(name).unwrap_or_default();
+
+    // Hashing is slow on purpose, so it happens outside the transaction.
+    let password_hash = crypto::hash_secret(password.as_bytes())?;
+
+    routes::update(
+        store,
+        RecordStore::LocalApiUsers,
+        |accounts: &mut Vec<Account>| {
+            if accounts.iter().any(|account| account.email == email) {
+                return Ok(Response::error(400, "Email already in use"));
+            }
+
+            let account = Account {
+                id: new_id(),
+                email,
+                name,
+                role: "student".to_string(),
+                password_hash,
+                token: BASE64.encode(crypto::random_bytes::<32>()),
+            };
+            let response = signed_in(201, &account);
+            accounts.push(account);
+
+            Ok(response)
+        },
+    )
+}
+
+fn login(store: &LocalStore, request: &Request) -> Result<Response> {
+    let (email, password) = (request.field("email"), request.field("password"));
+    if !truthy(email) || !truthy(password) {
+        return Ok(Response::error(400, "Missing email or password"));
+    }
+
+    let email = text(email).unwrap_or_default().to_lowercase();
+    let password = text(password).unwrap_or_default();
+
+    match find_by_email(store, &email)? {
+        Some(account) if crypto::verify_secret(password.as_bytes(), &account.password_hash)? => {
+            Ok(signed_in(200, &account))
+        }
+        _ => Ok(Response::error(401, "Invalid credentials")),
+    }
+}
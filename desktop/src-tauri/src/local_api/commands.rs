@@ -0,0 +1,21 @@
+// desktop/src-tauri/src/local_api/commands.rs
+
+use tauri::{AppHandle, State};
+
+use super::{LocalApi, LocalApiSettings, LocalApiStatus};
+use crate::error::Result;
+
+#[tauri::command]
+pub fn get_local_api_status(local_api: State<'_, LocalApi>) -> LocalApiStatus {
+    local_api.status()
+}
+
+#[tauri::command]
+pub fn set_local_api_settings(
+    app: AppHandle,
+    local_api: State<'_, LocalApi>,
+    settings: LocalApiSettings,
+) -> Result<LocalApiStatus> {
+    local_api.set_settings(&app, settings)?;
+    Ok(local_api.status())
+}
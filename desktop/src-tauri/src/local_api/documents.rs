@@ -0,0 +1,127 @@
+// desktop/src-tauri/src/local_api/documents.rs
+
+/* `/documents`, as in apps/api/src/documents.ts. */
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::auth::Account;
+use super::routes::{
+    self, new_id, non_blank, now, owned_by, to_json, Method, Owned, Request, Response,
+};
+use crate::error::Result;
+use crate::storage::{LocalStore, RecordStore};
+
+const UNTITLED: &str = "Untitled document";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+pub fn handle(
+    store: &LocalStore,
+    account: &Account,
+    request: &Request,
+    rest: &[&str],
+) -> Result<Response> {
+    match (request.method, rest) {
+        (Method::Get, []) => list(store, account),
+        (Method::Post, []) => create(store, account, request),
+        (Method::Get, [id]) => get(store, account, id),
+        (Method::Put, [id]) => update(store, account, id, request),
+        (Method::Delete, [id]) => delete(store, account, id),
+        _ => Ok(Response::no_route()),
+    }
+}
+
+fn not_found() -> Response {
+    Response::error(404, "Document not found")
+}
+
+fn list(store: &LocalStore, account: &Account) -> Result<Response> {
+    let mut documents: Vec<Document> =
+        owned_by(store, RecordStore::LocalApiDocuments, &account.id)?;
+    documents.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+    Ok(Response::ok(to_json(&documents)?))
+}
+
+fn create(store: &LocalStore, account: &Account, request: &Request) -> Result<Response> {
+    let created_at = now();
+    let document = Document {
+        id: new_id(),
+        title: non_blank(request.field("title")).unwrap_or_else(|| UNTITLED.to_string()),
+        content: String::new(),
+        created_at: created_at.clone(),
+        updated_at: created_at,
+    };
+
+    let response = Response::json(201, to_json(&document)?);
+    routes::update(store, RecordStore::LocalApiDocuments, |documents| {
+        documents.push(Owned {
+            user_id: account.id.clone(),
+            item: document,
+        });
+        Ok(())
+    })?;
+
+    Ok(response)
+}
+
+fn get(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    let documents: Vec<Document> = owned_by(store, RecordStore::LocalApiDocuments, &account.id)?;
+
+    match documents.iter().find(|document| document.id == id) {
+        Some(document) => Ok(Response::ok(to_json(document)?)),
+        None => Ok(not_found()),
+    }
+}
+
+fn update(store: &LocalStore, account: &Account, id: &str, request: &Request) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiDocuments,
+        |documents: &mut Vec<Owned<Document>>| {
+            let Some(Owned { item: document, .. }) = documents
+                .iter_mut()
+                .find(|document| document.user_id == account.id && document.item.id == id)
+            else {
+                return Ok(not_found());
+            };
+
+            if let Some(title) = non_blank(request.field("title")) {
+                document.title = title;
+            }
+            if let Some(content) = request.field("content").and_then(Value::as_str) {
+                document.content = content.to_string();
+            }
+            document.updated_at = now();
+
+            Ok(Response::ok(to_json(document)?))
+        },
+    )
+}
+
+fn delete(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiDocuments,
+        |documents: &mut Vec<Owned<Document>>| {
+            let before = documents.len();
+            documents
+                .retain(|document| !(document.user_id == account.id && document.item.id == id));
+
+            Ok(if documents.len() < before {
+                Response::no_content()
+            } else {
+                not_found()
+            })
+        },
+    )
+}
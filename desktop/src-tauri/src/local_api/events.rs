@@ -0,0 +1,229 @@
+// desktop/src-tauri/src/local_api/events.rs
+
+/* `/events`, as in apps/api/src/events.ts. */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::auth::Account;
+use super::routes::{
+    self, new_id, non_blank, now, owned_by, parse_date, to_iso, to_json, truthy, Method, Owned,
+    Request, Response,
+};
+use crate::error::Result;
+use crate::storage::{LocalStore, RecordStore};
+
+const DEFAULT_KIND: &str = "event";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub start: String,
+    pub end: String,
+    pub all_day: bool,
+    pub kind: String,
+    pub urgency: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Event {
+    fn start(&self) -> Option<DateTime<Utc>> {
+        parse_date(Some(&Value::String(self.start.clone())))
+    }
+}
+
+fn normalize_urgency(value: Option<&Value>) -> Option<String> {
+    match value.and_then(Value::as_str) {
+        Some(urgency @ ("critical" | "high" | "medium" | "low")) => Some(urgency.to_string()),
+        _ => None,
+    }
+}
+
+/// Missing, null and empty urgencies clear it; anything else must be valid.
+fn has_valid_urgency(value: Option<&Value>) -> bool {
+    matches!(value, None | Some(Value::Null))
+        || value.and_then(Value::as_str) == Some("")
+        || normalize_urgency(value).is_some()
+}
+
+/// `parseDateParam`: falsy values are no date at all.
+fn date_param(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    if !truthy(value) {
+        return None;
+    }
+    parse_date(value)
+}
+
+pub fn handle(
+    store: &LocalStore,
+    account: &Account,
+    request: &Request,
+    rest: &[&str],
+) -> Result<Response> {
+    match (request.method, rest) {
+        (Method::Get, []) => list(store, account, request),
+        (Method::Post, []) => create(store, account, request),
+        (Method::Get, [id]) => get(store, account, id),
+        (Method::Put, [id]) => update(store, account, id, request),
+        (Method::Delete, [id]) => delete(store, account, id),
+        _ => Ok(Response::no_route()),
+    }
+}
+
+fn not_found() -> Response {
+    Response::error(404, "Event not found")
+}
+
+/// `?from=` and `?to=` bound the start, inclusive and exclusive.
+fn list(store: &LocalStore, account: &Account, request: &Request) -> Result<Response> {
+    let query = |name: &str| {
+        request
+            .query
+            .get(name)
+            .and_then(|value| date_param(Some(&Value::String(value.clone()))))
+    };
+    let (from, to) = (query("from"), query("to"));
+
+    let mut events: Vec<(Option<DateTime<Utc>>, Event)> =
+        owned_by::<Event>(store, RecordStore::LocalApiEvents, &account.id)?
+            .into_iter()
+            .map(|event| (event.start(), event))
+            .filter(|(start, _)| {
+                from.is_none_or(|from| start.is_some_and(|start| start >= from))
+                    && to.is_none_or(|to| start.is_some_and(|start| start < to))
+            })
+            .collect();
+    events.sort_by_key(|(start, _)| *start);
+
+    let events: Vec<Event> = events.into_iter().map(|(_, event)| event).collect();
+    Ok(Response::ok(to_json(&events)?))
+}
+
+fn create(store: &LocalStore, account: &Account, request: &Request) -> Result<Response> {
+    let title = match request.field("title") {
+        Some(Value::String(title)) if !title.is_empty() => title.trim().to_string(),
+        _ => return Ok(Response::error(400, "Title is required")),
+    };
+
+    let Some(start) = date_param(request.field("start")) else {
+        return Ok(Response::error(400, "Valid start date is required"));
+    };
+    let end = date_param(request.field("end")).unwrap_or(start).max(start);
+
+    if !has_valid_urgency(request.field("urgency")) {
+        return Ok(Response::error(400, "Invalid event urgency"));
+    }
+
+    let created_at = now();
+    let event = Event {
+        id: new_id(),
+        title,
+        description: request
+            .field("description")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        start: to_iso(start),
+        end: to_iso(end),
+        all_day: truthy(request.field("allDay")),
+        kind: non_blank(request.field("kind")).unwrap_or_else(|| DEFAULT_KIND.to_string()),
+        urgency: normalize_urgency(request.field("urgency")),
+        created_at: created_at.clone(),
+        updated_at: created_at,
+    };
+
+    let response = Response::json(201, to_json(&event)?);
+    routes::update(store, RecordStore::LocalApiEvents, |events| {
+        events.push(Owned {
+            user_id: account.id.clone(),
+            item: event,
+        });
+        Ok(())
+    })?;
+
+    Ok(response)
+}
+
+fn get(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    let events: Vec<Event> = owned_by(store, RecordStore::LocalApiEvents, &account.id)?;
+
+    match events.iter().find(|event| event.id == id) {
+        Some(event) => Ok(Response::ok(to_json(event)?)),
+        None => Ok(not_found()),
+    }
+}
+
+fn update(store: &LocalStore, account: &Account, id: &str, request: &Request) -> Result<Response> {
+    // As in the API, a bad urgency is rejected before the event is looked up.
+    let urgency = request.field("urgency");
+    if urgency.is_some() && !has_valid_urgency(urgency) {
+        return Ok(Response::error(400, "Invalid event urgency"));
+    }
+
+    let start = date_param(request.field("start"));
+    let end = date_param(request.field("end"));
+
+    routes::update(
+        store,
+        RecordStore::LocalApiEvents,
+        |events: &mut Vec<Owned<Event>>| {
+            let Some(Owned { item: event, .. }) = events
+                .iter_mut()
+                .find(|event| event.user_id == account.id && event.item.id == id)
+            else {
+                return Ok(not_found());
+            };
+
+            if let Some(title) = non_blank(request.field("title")) {
+                event.title = title;
+            }
+            if let Some(description) = request.field("description").and_then(Value::as_str) {
+                event.description = description.to_string();
+            }
+            // Only when both move together is the end kept after the start.
+            match (start, end) {
+                (Some(start), Some(end)) => {
+                    event.start = to_iso(start);
+                    event.end = to_iso(end.max(start));
+                }
+                (Some(start), None) => event.start = to_iso(start),
+                (None, Some(end)) => event.end = to_iso(end),
+                (None, None) => {}
+            }
+            if request.field("allDay").is_some() {
+                event.all_day = truthy(request.field("allDay"));
+            }
+            if let Some(kind) = non_blank(request.field("kind")) {
+                event.kind = kind;
+            }
+            if urgency.is_some() {
+                event.urgency = normalize_urgency(urgency);
+            }
+            event.updated_at = now();
+
+            Ok(Response::ok(to_json(event)?))
+        },
+    )
+}
+
+fn delete(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiEvents,
+        |events: &mut Vec<Owned<Event>>| {
+            let before = events.len();
+            events.retain(|event| !(event.user_id == account.id && event.item.id == id));
+
+            Ok(if events.len() < before {
+                Response::no_content()
+            } else {
+                not_found()
+            })
+        },
+    )
+}
@@ -0,0 +1,256 @@
+// desktop/src-tauri/src/local_api/mail.rs
+
+/*
+ * `/mail/accounts` and `/mail/messages`, as in apps/api/src/mail.ts.
+ *
+ * Every account has one internal mailbox. Sending files the message under
+ * the sender's "sent" folder and delivers a copy to the inbox of any account
+ * on this server with the recipient's address; nothing goes out over SMTP.
+ */
+
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::auth::{self, Account};
+use super::routes::{
+    self, new_id, now, owned_by, text, to_json, truthy, Method, Owned, Request, Response,
+};
+use crate::error::Result;
+use crate::storage::{LocalStore, RecordStore};
+
+const FOLDERS: [&str; 4] = ["inbox", "sent", "draft", "archive"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub id: String,
+    pub account_id: String,
+    pub folder: String,
+    pub subject: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub cc_address: Option<String>,
+    pub bcc_address: Option<String>,
+    pub body_html: String,
+    pub body_text: String,
+    pub is_read: bool,
+    pub is_starred: bool,
+    pub sent_at: Option<String>,
+    pub received_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// The one mailbox of `account`.
+fn mail_account_id(account: &Account) -> String {
+    format!("mail-{}", account.id)
+}
+
+fn mailbox(account: &Account) -> Value {
+    json!({
+        "id": mail_account_id(account),
+        "provider": "internal",
+        "emailAddress": account.email.to_lowercase(),
+        "displayName": if account.name.is_empty() { &account.email } else { &account.name },
+    })
+}
+
+/// `html.replace(/<[^>]+>/g, " ")`.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        match rest[open + 1..].find('>') {
+            Some(0) | None => {
+                text.push('<');
+                rest = &rest[open + 1..];
+            }
+            Some(close) => {
+                text.push(' ');
+                rest = &rest[open + 1 + close + 1..];
+            }
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
+
+pub fn handle(
+    store: &LocalStore,
+    account: &Account,
+    request: &Request,
+    rest: &[&str],
+) -> Result<Response> {
+    match (request.method, rest) {
+        (Method::Get, ["accounts"]) => Ok(Response::ok(json!({ "accounts": [mailbox(account)] }))),
+        (Method::Get, ["messages"]) => list(store, account, request),
+        (Method::Post, ["messages"]) => send(store, account, request),
+        (Method::Get, ["messages", id]) => get(store, account, id),
+        (Method::Patch, ["messages", id]) => update(store, account, id, request),
+        (Method::Delete, ["messages", id]) => delete(store, account, id),
+        _ => Ok(Response::no_route()),
+    }
+}
+
+fn not_found() -> Response {
+    Response::error(404, "Message not found")
+}
+
+/// Newest first by received, then sent, then updated time. Like the
+/// Postgres default for descending order, a missing time sorts first.
+fn list(store: &LocalStore, account: &Account, request: &Request) -> Result<Response> {
+    let folder = request
+        .query
+        .get("folder")
+        .map(String::as_str)
+        .filter(|folder| FOLDERS.contains(folder))
+        .unwrap_or("inbox");
+
+    let mut messages: Vec<Message> = owned_by(store, RecordStore::LocalApiMail, &account.id)?;
+    messages.retain(|message| message.folder == folder);
+
+    let newest = |time: &Option<String>| Reverse((time.is_none(), time.clone()));
+    messages.sort_by_key(|message| {
+        (
+            newest(&message.received_at),
+            newest(&message.sent_at),
+            Reverse(message.updated_at.clone()),
+        )
+    });
+
+    Ok(Response::ok(json!({ "messages": to_json(&messages)? })))
+}
+
+fn send(store: &LocalStore, account: &Account, request: &Request) -> Result<Response> {
+    let (subject, to_address) = (request.field("subject"), request.field("toAddress"));
+    if !truthy(subject) || !truthy(to_address) {
+        return Ok(Response::error(400, "Subject and toAddress are required"));
+    }
+
+    let subject = text(subject).unwrap_or_default();
+    let to_address = text(to_address).unwrap_or_default().trim().to_lowercase();
+    let folder = if request.field("folder").and_then(Value::as_str) == Some("draft") {
+        "draft"
+    } else {
+        "sent"
+    };
+
+    let html = if truthy(request.field("bodyHtml")) {
+        text(request.field("bodyHtml")).unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let body_text = if truthy(request.field("bodyText")) {
+        text(request.field("bodyText")).unwrap_or_default()
+    } else {
+        strip_tags(&html)
+    };
+
+    let sent_at = now();
+    let sender_copy = Message {
+        id: new_id(),
+        account_id: mail_account_id(account),
+        folder: folder.to_string(),
+        subject,
+        from_address: account.email.to_lowercase(),
+        to_address: to_address.clone(),
+        cc_address: None,
+        bcc_address: None,
+        body_html: html,
+        body_text,
+        is_read: true,
+        is_starred: false,
+        sent_at: (folder == "sent").then(|| sent_at.clone()),
+        received_at: None,
+        created_at: sent_at.clone(),
+        updated_at: sent_at.clone(),
+    };
+
+    let recipient = match folder {
+        "sent" => auth::find_by_email(store, &to_address)?,
+        _ => None,
+    };
+    let response = Response::json(201, json!({ "message": to_json(&sender_copy)? }));
+
+    routes::update(store, RecordStore::LocalApiMail, |messages| {
+        if let Some(recipient) = recipient {
+            messages.push(Owned {
+                user_id: recipient.id.clone(),
+                item: Message {
+                    id: new_id(),
+                    account_id: mail_account_id(&recipient),
+                    folder: "inbox".to_string(),
+                    is_read: false,
+                    received_at: Some(sent_at),
+                    ..sender_copy.clone()
+                },
+            });
+        }
+        messages.push(Owned {
+            user_id: account.id.clone(),
+            item: sender_copy,
+        });
+        Ok(())
+    })?;
+
+    Ok(response)
+}
+
+fn get(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    let messages: Vec<Message> = owned_by(store, RecordStore::LocalApiMail, &account.id)?;
+
+    match messages.iter().find(|message| message.id == id) {
+        Some(message) => Ok(Response::ok(json!({ "message": to_json(message)? }))),
+        None => Ok(not_found()),
+    }
+}
+
+/// Updates the read and starred flags or moves the message to a folder.
+fn update(store: &LocalStore, account: &Account, id: &str, request: &Request) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiMail,
+        |messages: &mut Vec<Owned<Message>>| {
+            let Some(Owned { item: message, .. }) = messages
+                .iter_mut()
+                .find(|message| message.user_id == account.id && message.item.id == id)
+            else {
+                return Ok(not_found());
+            };
+
+            if let Some(is_read) = request.field("isRead").and_then(Value::as_bool) {
+                message.is_read = is_read;
+            }
+            if let Some(is_starred) = request.field("isStarred").and_then(Value::as_bool) {
+                message.is_starred = is_starred;
+            }
+            if let Some(folder) = request
+                .field("folder")
+                .and_then(Value::as_str)
+                .filter(|folder| FOLDERS.contains(folder))
+            {
+                message.folder = folder.to_string();
+            }
+            message.updated_at = now();
+
+            Ok(Response::ok(json!({ "message": to_json(message)? })))
+        },
+    )
+}
+
+/// A message that is already gone counts as deleted.
+fn delete(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiMail,
+        |messages: &mut Vec<Owned<Message>>| {
+            messages.retain(|message| !(message.user_id == account.id && message.item.id == id));
+            Ok(Response::no_content())
+        },
+    )
+}
@@ -0,0 +1,380 @@
+// desktop/src-tauri/src/local_api/mod.rs
+
+/*
+ * An optional API server on this computer.
+ *
+ * When it is turned on, the crate answers the Pioneer API's auth, tasks,
+ * documents, events and mail routes on 127.0.0.1 from the local store, with
+ * the same JSON and status codes as apps/api. The app then works with no
+ * cloud at all, and integration tests can run against it without Postgres.
+ *
+ * The server shows up among the server profiles as "This computer" and is
+ * chosen like any self-hosted server. Its accounts and data live in record
+ * stores of their own, so the webview caches and syncs them exactly as it
+ * would a remote server's. It only answers while the workspace is unlocked.
+ */
+
+mod auth;
+pub mod commands;
+mod documents;
+mod events;
+mod mail;
+mod routes;
+mod tasks;
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use hyper::body::HttpBody;
+use hyper::header::{self, HeaderValue};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Server};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Url};
+use tokio::sync::oneshot;
+
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+use crate::servers::{ServerProfile, Servers, DEFAULT_TIMEOUT_SECONDS, SERVER_CHANGED_EVENT};
+use crate::storage::LocalStore;
+use routes::{Method, Request, Response};
+
+pub const LOCAL_API_SETTINGS_FILE_NAME: &str = "local-api.json";
+
+/// The server profile pointing at this server.
+pub const LOCAL_SERVER_ID: &str = "this-computer";
+
+/// apps/api listens on 4000 in development, so the default stays clear of it.
+pub const DEFAULT_PORT: u16 = 4100;
+
+/// Generous next to Express's 100 kB, since documents are sent whole.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for LocalApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+impl LocalApiSettings {
+    fn validate(&self) -> Result<()> {
+        if self.port < 1024 {
+            return Err(Error::InvalidInput(
+                "Choose a port between 1024 and 65535.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn base_url(&self) -> String {
+        format!("http://{}:{}", Ipv4Addr::LOCALHOST, self.port)
+    }
+
+    fn profile(&self) -> ServerProfile {
+        ServerProfile {
+            id: LOCAL_SERVER_ID.to_string(),
+            name: "This computer".to_string(),
+            base_url: self.base_url(),
+            ca_certificate: None,
+            timeout_seconds: DEFAULT_TIMEOUT_SECONDS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiStatus {
+    pub enabled: bool,
+    pub port: u16,
+    pub running: bool,
+    pub base_url: String,
+    /// Why the server is not running although it is enabled, e.g. because
+    /// another program holds the port.
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Running {
+    shutdown: Option<oneshot::Sender<()>>,
+    error: Option<String>,
+}
+
+pub struct LocalApi {
+    settings_path: PathBuf,
+    settings: Mutex<LocalApiSettings>,
+    running: Mutex<Running>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl LocalApi {
+    pub fn open(settings_path: impl Into<PathBuf>) -> Result<Self> {
+        let settings_path = settings_path.into();
+        let settings = if settings_path.exists() {
+            serde_json::from_slice(&fs::read(&settings_path)?)?
+        } else {
+            LocalApiSettings::default()
+        };
+
+        Ok(Self {
+            settings_path,
+            settings: Mutex::new(settings),
+            running: Mutex::new(Running::default()),
+        })
+    }
+
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Self::open(paths::app_config_dir(app)?.join(LOCAL_API_SETTINGS_FILE_NAME))
+    }
+
+    pub fn settings(&self) -> LocalApiSettings {
+        *lock(&self.settings)
+    }
+
+    pub fn status(&self) -> LocalApiStatus {
+        let settings = self.settings();
+        let running = lock(&self.running);
+
+        LocalApiStatus {
+            enabled: settings.enabled,
+            port: settings.port,
+            running: running.shutdown.is_some(),
+            base_url: settings.base_url(),
+            error: running.error.clone(),
+        }
+    }
+
+    fn stop(&self) {
+        let mut running = lock(&self.running);
+        if let Some(shutdown) = running.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        running.error = None;
+    }
+
+    /// Starts serving on `port`, remembering why when that fails.
+    fn launch(&self, app: &AppHandle, port: u16) -> Result<()> {
+        let mut running = lock(&self.running);
+
+        let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+            Ok(listener) => listener,
+            Err(error) => {
+                let message = format!("Unable to listen on port {port}: {error}");
+                running.error = Some(message.clone());
+                return Err(Error::InvalidInput(message));
+            }
+        };
+
+        let (shutdown, stopped) = oneshot::channel();
+        running.shutdown = Some(shutdown);
+        running.error = None;
+
+        tauri::async_runtime::spawn(serve(app.clone(), listener, stopped));
+        Ok(())
+    }
+
+    /// Saves new settings and starts, moves or stops the server to match.
+    /// The "This computer" profile is added or removed along with it.
+    pub fn set_settings(&self, app: &AppHandle, settings: LocalApiSettings) -> Result<()> {
+        settings.validate()?;
+
+        let current = self.settings();
+        let servers = app.state::<Servers>();
+        let in_use = servers.active().id == LOCAL_SERVER_ID;
+
+        if in_use && !settings.enabled {
+            return Err(Error::InvalidInput(
+                "Switch to another server before turning off the server on this computer."
+                    .to_string(),
+            ));
+        }
+
+        // Saving unchanged settings retries a server that failed to start.
+        let stopped = lock(&self.running).shutdown.is_none();
+        if settings != current || (settings.enabled && stopped) {
+            self.stop();
+            if settings.enabled {
+                if let Err(error) = self.launch(app, settings.port) {
+                    if current.enabled {
+                        let _ = self.launch(app, current.port);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+
+        {
+            let mut saved = lock(&self.settings);
+            files::write_atomic(&self.settings_path, &serde_json::to_vec_pretty(&settings)?)?;
+            *saved = settings;
+        }
+
+        if settings.enabled {
+            servers.save_profile(settings.profile())?;
+            if in_use && settings.port != current.port {
+                let _ = app.emit_all(SERVER_CHANGED_EVENT, servers.active());
+            }
+        } else {
+            servers.delete_profile(LOCAL_SERVER_ID)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Starts the server when it is enabled.
+pub fn start(app: &AppHandle) {
+    let local_api = app.state::<LocalApi>();
+    let settings = local_api.settings();
+
+    if settings.enabled {
+        let _ = app.state::<Servers>().save_profile(settings.profile());
+        let _ = local_api.launch(app, settings.port);
+    }
+}
+
+async fn serve(app: AppHandle, listener: TcpListener, stopped: oneshot::Receiver<()>) {
+    let make_service = make_service_fn(move |_| {
+        let app = app.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| respond(app.clone(), request))) }
+    });
+
+    let Ok(builder) = Server::from_tcp(listener) else {
+        return;
+    };
+
+    let _ = builder
+        .serve(make_service)
+        .with_graceful_shutdown(async {
+            let _ = stopped.await;
+        })
+        .await;
+}
+
+async fn respond(
+    app: AppHandle,
+    request: hyper::Request<Body>,
+) -> std::result::Result<hyper::Response<Body>, Infallible> {
+    // The API allows any origin, like `cors()` in apps/api; the bearer
+    // token is what keeps other pages out.
+    if request.method() == hyper::Method::OPTIONS {
+        let allowed_headers = request
+            .headers()
+            .get(header::ACCESS_CONTROL_REQUEST_HEADERS)
+            .cloned()
+            .unwrap_or(HeaderValue::from_static("Authorization, Content-Type"));
+
+        let mut response = encode(Response {
+            status: 204,
+            body: None,
+        });
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET,HEAD,PUT,PATCH,POST,DELETE"),
+        );
+        headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+        return Ok(response);
+    }
+
+    let response = match parse(request).await {
+        Ok(request) => tauri::async_runtime::spawn_blocking(move || {
+            routes::handle(&app.state::<LocalStore>(), &request)
+        })
+        .await
+        .unwrap_or_else(|_| Response::error(500, "Internal server error")),
+        Err(response) => response,
+    };
+
+    Ok(encode(response))
+}
+
+async fn parse(request: hyper::Request<Body>) -> std::result::Result<Request, Response> {
+    let (parts, mut body) = request.into_parts();
+
+    let method = match parts.method {
+        hyper::Method::GET => Method::Get,
+        hyper::Method::POST => Method::Post,
+        hyper::Method::PUT => Method::Put,
+        hyper::Method::PATCH => Method::Patch,
+        hyper::Method::DELETE => Method::Delete,
+        _ => return Err(Response::no_route()),
+    };
+
+    let url = Url::parse(&format!("http://{}{}", Ipv4Addr::LOCALHOST, parts.uri))
+        .map_err(|_| Response::no_route())?;
+    let mut query = HashMap::new();
+    for (name, value) in url.query_pairs() {
+        query.entry(name.into_owned()).or_insert(value.into_owned());
+    }
+
+    let header = |name| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .map(str::to_string)
+    };
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk =
+            chunk.map_err(|_| Response::error(400, "The request body could not be read"))?;
+        if bytes.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err(Response::error(413, "The request body is too large"));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    // Like `express.json()`, bodies of other types are ignored.
+    let is_json = header(header::CONTENT_TYPE).is_some_and(|kind| kind.contains("json"));
+    let body = if is_json && !bytes.is_empty() {
+        serde_json::from_slice(&bytes).map_err(|_| Response::error(400, "Invalid JSON body"))?
+    } else {
+        Value::Null
+    };
+
+    Ok(Request {
+        method,
+        path: url.path().to_string(),
+        query,
+        authorization: header(header::AUTHORIZATION),
+        body,
+    })
+}
+
+fn encode(response: Response) -> hyper::Response<Body> {
+    let mut builder = hyper::Response::builder()
+        .status(response.status)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+    let body = match response.body {
+        Some(body) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json; charset=utf-8");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+
+    builder.body(body).unwrap_or_default()
+}
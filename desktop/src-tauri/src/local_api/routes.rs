@@ -0,0 +1,462 @@
+// desktop/src-tauri/src/local_api/routes.rs
+
+/*
+ * Request dispatch for the embedded API server, kept apart from the HTTP
+ * library: a parsed request goes in, a status and JSON body come out.
+ *
+ * The handlers follow the route files in apps/api/src one by one, down to
+ * the loose JavaScript checks on request bodies (`!title`,
+ * `Boolean(allDay)`, ...), so the web client cannot tell the servers apart.
+ */
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{auth, documents, events, mail, tasks};
+use crate::crypto;
+use crate::error::{Error, Result};
+use crate::storage::{LocalStore, RecordStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub authorization: Option<String>,
+    /// The JSON body, or `null` when the request had none.
+    pub body: Value,
+}
+
+impl Request {
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.body.get(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Option<Value>,
+}
+
+impl Response {
+    pub fn json(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            body: Some(body),
+        }
+    }
+
+    pub fn ok(body: Value) -> Self {
+        Self::json(200, body)
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, json!({ "error": message }))
+    }
+
+    pub fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: None,
+        }
+    }
+
+    /// What Express answers for a path or method no router handles.
+    pub fn no_route() -> Self {
+        Self::error(404, "Not found")
+    }
+}
+
+/// A record together with the account it belongs to. The owner is stored
+/// but never sent back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Owned<T> {
+    pub user_id: String,
+    #[serde(flatten)]
+    pub item: T,
+}
+
+pub fn handle(store: &LocalStore, request: &Request) -> Response {
+    let segments: Vec<&str> = request
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let Some((&resource, rest)) = segments.split_first() else {
+        return Response::no_route();
+    };
+
+    let result = match resource {
+        "health" if rest.is_empty() && request.method == Method::Get => Ok(Response::ok(json!({
+            "status": "ok",
+            "service": "pioneer-api",
+            "timestamp": now(),
+        }))),
+        "auth" => auth::handle(store, request, rest),
+        "tasks" | "documents" | "events" | "mail" => {
+            let user = match auth::authenticate(store, request) {
+                Ok(user) => user,
+                Err(response) => return response,
+            };
+
+            match resource {
+                "tasks" => tasks::handle(store, &user, request, rest),
+                "documents" => documents::handle(store, &user, request, rest),
+                "events" => events::handle(store, &user, request, rest),
+                _ => mail::handle(store, &user, request, rest),
+            }
+        }
+        _ => Ok(Response::no_route()),
+    };
+
+    result.unwrap_or_else(failure)
+}
+
+/// A locked workspace is worth telling the client about; anything else is
+/// the API's generic 500.
+pub fn failure(error: Error) -> Response {
+    match error {
+        Error::StoreLocked => Response::error(503, &error.to_string()),
+        _ => Response::error(500, "Internal server error"),
+    }
+}
+
+/* Records */
+
+/// The records in `records` that belong to `user_id`.
+pub fn owned_by<T: DeserializeOwned>(
+    store: &LocalStore,
+    records: RecordStore,
+    user_id: &str,
+) -> Result<Vec<T>> {
+    store
+        .read_records(records)?
+        .into_iter()
+        .map(serde_json::from_value::<Owned<T>>)
+        .filter(|record| {
+            record
+                .as_ref()
+                .map_or(true, |record| record.user_id == user_id)
+        })
+        .map(|record| Ok(record?.item))
+        .collect()
+}
+
+/// Reads, changes and writes back a record store in one transaction. When
+/// `change` fails the records are written back as they were.
+pub fn update<T, R>(
+    store: &LocalStore,
+    records: RecordStore,
+    change: impl FnOnce(&mut Vec<T>) -> Result<R>,
+) -> Result<R>
+where
+    T: Serialize + DeserializeOwned,
+{
+    store.update_records(records, |values| {
+        let mut items = values
+            .iter()
+            .cloned()
+            .map(serde_json::from_value)
+            .collect::<serde_json::Result<Vec<T>>>()?;
+
+        let result = change(&mut items)?;
+
+        *values = items
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<_>>()?;
+        Ok(result)
+    })?
+}
+
+pub fn to_json(value: &impl Serialize) -> Result<Value> {
+    Ok(serde_json::to_value(value)?)
+}
+
+/// A random id in the shape of a UUID, like the ones Prisma hands out.
+pub fn new_id() -> String {
+    let mut bytes = crypto::random_bytes::<16>();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/* JavaScript semantics */
+
+/// `Boolean(value)`.
+pub fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(value)) => *value,
+        Some(Value::Number(number)) => number.as_f64().is_some_and(|n| n != 0.0),
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(_) => true,
+    }
+}
+
+/// `String(value)` for the values a JSON body can hold; `None` when the
+/// field is missing.
+pub fn text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// A string field, trimmed, when it is not blank.
+pub fn non_blank(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// `new Date(value)` for the formats the web client sends: full ISO
+/// timestamps, dates (midnight UTC) and date-times without an offset (local
+/// time). Anything else, including blank values, is `None`.
+pub fn parse_date(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    let value = value?.as_str()?.trim();
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// `Date.prototype.toISOString`.
+pub fn to_iso(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn now() -> String {
+    to_iso(Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::UnlockSecret;
+
+    fn open_store(directory: &tempfile::TempDir) -> LocalStore {
+        let store = LocalStore::open(directory.path().join("store.sqlite3")).unwrap();
+        store.unlock(UnlockSecret::Pin("test")).unwrap();
+        store
+    }
+
+    fn request(method: Method, path: &str, token: Option<&str>, body: Value) -> Request {
+        Request {
+            method,
+            path: path.to_string(),
+            query: HashMap::new(),
+            authorization: token.map(|token| format!("Bearer {token}")),
+            body,
+        }
+    }
+
+    fn register(store: &LocalStore, email: &str) -> String {
+        let response = handle(
+            store,
+            &request(
+                Method::Post,
+                "/auth/register",
+                None,
+                json!({ "email": email, "password": "secret", "name": "Ada" }),
+            ),
+        );
+        assert_eq!(response.status, 201);
+        response.body.unwrap()["token"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn data_routes_need_a_valid_token() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+
+        let missing = handle(&store, &request(Method::Get, "/tasks", None, Value::Null));
+        assert_eq!(missing.status, 401);
+
+        let wrong = handle(
+            &store,
+            &request(Method::Get, "/tasks", Some("nope"), Value::Null),
+        );
+        assert_eq!(wrong.status, 401);
+
+        let unknown = handle(&store, &request(Method::Get, "/nothing", None, Value::Null));
+        assert_eq!(unknown.status, 404);
+
+        store.lock();
+        let locked = handle(
+            &store,
+            &request(Method::Get, "/tasks", Some("x"), Value::Null),
+        );
+        assert_eq!(locked.status, 503);
+    }
+
+    #[test]
+    fn tasks_round_trip_like_the_api() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+        let token = register(&store, "ada@example.com");
+        let other = register(&store, "grace@example.com");
+
+        let blank = handle(
+            &store,
+            &request(
+                Method::Post,
+                "/tasks",
+                Some(&token),
+                json!({ "title": "  " }),
+            ),
+        );
+        assert_eq!(blank.status, 400);
+
+        let created = handle(
+            &store,
+            &request(
+                Method::Post,
+                "/tasks",
+                Some(&token),
+                json!({
+                    "title": " Read ",
+                    "priority": "High",
+                    "tags": ["a", "a", " b "],
+                    "dueDate": "2024-03-01",
+                    "status": "done",
+                }),
+            ),
+        );
+        assert_eq!(created.status, 201);
+        let task = created.body.unwrap();
+        assert_eq!(task["title"], "Read");
+        assert_eq!(task["priority"], "high");
+        assert_eq!(task["tags"], json!(["a", "b"]));
+        assert_eq!(task["dueDate"], "2024-03-01T00:00:00.000Z");
+        assert!(task["completedAt"].is_string());
+        assert!(task.get("userId").is_none());
+
+        let id = task["id"].as_str().unwrap();
+        let reopened = handle(
+            &store,
+            &request(
+                Method::Put,
+                &format!("/tasks/{id}"),
+                Some(&token),
+                json!({ "status": "todo", "dueDate": null }),
+            ),
+        );
+        assert_eq!(reopened.status, 200);
+        let task = reopened.body.unwrap();
+        assert_eq!(task["completedAt"], Value::Null);
+        assert_eq!(task["dueDate"], Value::Null);
+
+        let foreign = handle(
+            &store,
+            &request(
+                Method::Delete,
+                &format!("/tasks/{id}"),
+                Some(&other),
+                Value::Null,
+            ),
+        );
+        assert_eq!(foreign.status, 404);
+
+        let deleted = handle(
+            &store,
+            &request(
+                Method::Delete,
+                &format!("/tasks/{id}"),
+                Some(&token),
+                Value::Null,
+            ),
+        );
+        assert_eq!(deleted, Response::no_content());
+    }
+
+    #[test]
+    fn sent_mail_reaches_the_recipient_inbox() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+        let sender = register(&store, "ada@example.com");
+        let recipient = register(&store, "grace@example.com");
+
+        let sent = handle(
+            &store,
+            &request(
+                Method::Post,
+                "/mail/messages",
+                Some(&sender),
+                json!({
+                    "subject": "Hello",
+                    "toAddress": " Grace@Example.com ",
+                    "bodyHtml": "<p>Hi</p>",
+                }),
+            ),
+        );
+        assert_eq!(sent.status, 201);
+        let message = &sent.body.unwrap()["message"];
+        assert_eq!(message["folder"], "sent");
+        assert_eq!(message["bodyText"], " Hi ");
+
+        let mut inbox = request(Method::Get, "/mail/messages", Some(&recipient), Value::Null);
+        inbox
+            .query
+            .insert("folder".to_string(), "inbox".to_string());
+        let messages = handle(&store, &inbox).body.unwrap()["messages"].clone();
+        assert_eq!(messages.as_array().unwrap().len(), 1);
+        assert_eq!(messages[0]["fromAddress"], "ada@example.com");
+        assert_eq!(messages[0]["isRead"], false);
+    }
+
+    #[test]
+    fn parses_dates_like_javascript() {
+        assert_eq!(
+            parse_date(Some(&json!("2024-01-02"))).map(to_iso),
+            Some("2024-01-02T00:00:00.000Z".to_string())
+        );
+        assert_eq!(
+            parse_date(Some(&json!("2024-01-02T03:04:05+02:00"))).map(to_iso),
+            Some("2024-01-02T01:04:05.000Z".to_string())
+        );
+        assert!(parse_date(Some(&json!("2024-01-02T09:30"))).is_some());
+        assert_eq!(parse_date(Some(&json!(""))), None);
+        assert_eq!(parse_date(Some(&json!("soon"))), None);
+        assert_eq!(parse_date(Some(&json!(12))), None);
+    }
+}
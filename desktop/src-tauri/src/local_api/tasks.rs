@@ -0,0 +1,201 @@
+// desktop/src-tauri/src/local_api/tasks.rs
+
+/* `/tasks`, as in apps/api/src/tasks.ts. */
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::auth::Account;
+use super::routes::{
+    self, new_id, non_blank, now, owned_by, parse_date, to_iso, to_json, Method, Owned, Request,
+    Response,
+};
+use crate::error::Result;
+use crate::storage::{LocalStore, RecordStore};
+
+const MAX_TAGS: usize = 50;
+const MAX_TAG_LENGTH: usize = 40;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub priority: String,
+    pub tags: Vec<String>,
+    pub due_date: Option<String>,
+    pub completed_at: Option<String>,
+    pub archived_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Unknown priorities, including the old "normal", become "medium".
+fn normalize_priority(value: Option<&Value>) -> String {
+    let priority = value
+        .and_then(Value::as_str)
+        .map(|priority| priority.trim().to_lowercase())
+        .unwrap_or_default();
+
+    match priority.as_str() {
+        "critical" | "high" | "low" => priority,
+        _ => "medium".to_string(),
+    }
+}
+
+fn is_status(value: Option<&Value>) -> bool {
+    matches!(
+        value.and_then(Value::as_str),
+        Some("todo" | "in_progress" | "done")
+    )
+}
+
+fn normalize_status(value: Option<&Value>) -> String {
+    match value.and_then(Value::as_str) {
+        Some(status @ ("in_progress" | "done")) => status.to_string(),
+        _ => "todo".to_string(),
+    }
+}
+
+fn normalize_tags(value: Option<&Value>) -> Vec<String> {
+    let Some(Value::Array(values)) = value else {
+        return Vec::new();
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in values.iter().filter_map(|tag| routes::text(Some(tag))) {
+        let tag: String = tag.trim().chars().take(MAX_TAG_LENGTH).collect();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_TAGS);
+    tags
+}
+
+fn date(value: Option<&Value>) -> Option<String> {
+    parse_date(value).map(to_iso)
+}
+
+pub fn handle(
+    store: &LocalStore,
+    account: &Account,
+    request: &Request,
+    rest: &[&str],
+) -> Result<Response> {
+    match (request.method, rest) {
+        (Method::Get, []) => list(store, account),
+        (Method::Post, []) => create(store, account, request),
+        (Method::Put, [id]) => update(store, account, id, request),
+        (Method::Delete, [id]) => delete(store, account, id),
+        _ => Ok(Response::no_route()),
+    }
+}
+
+fn list(store: &LocalStore, account: &Account) -> Result<Response> {
+    let mut tasks: Vec<Task> = owned_by(store, RecordStore::LocalApiTasks, &account.id)?;
+    tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(Response::ok(to_json(&tasks)?))
+}
+
+fn create(store: &LocalStore, account: &Account, request: &Request) -> Result<Response> {
+    let Some(title) = non_blank(request.field("title")) else {
+        return Ok(Response::error(400, "Title is required"));
+    };
+
+    let status = normalize_status(request.field("status"));
+    let created_at = now();
+    let task = Task {
+        id: new_id(),
+        title,
+        description: request
+            .field("description")
+            .and_then(Value::as_str)
+            .map(|description| description.trim().to_string())
+            .unwrap_or_default(),
+        priority: normalize_priority(request.field("priority")),
+        tags: normalize_tags(request.field("tags")),
+        due_date: date(request.field("dueDate")),
+        completed_at: (status == "done").then(|| created_at.clone()),
+        archived_at: None,
+        status,
+        created_at: created_at.clone(),
+        updated_at: created_at,
+    };
+
+    let response = Response::json(201, to_json(&task)?);
+    routes::update(store, RecordStore::LocalApiTasks, |tasks| {
+        tasks.push(Owned {
+            user_id: account.id.clone(),
+            item: task,
+        });
+        Ok(())
+    })?;
+
+    Ok(response)
+}
+
+fn update(store: &LocalStore, account: &Account, id: &str, request: &Request) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiTasks,
+        |tasks: &mut Vec<Owned<Task>>| {
+            let Some(Owned { item: task, .. }) = tasks
+                .iter_mut()
+                .find(|task| task.user_id == account.id && task.item.id == id)
+            else {
+                return Ok(Response::error(404, "Task not found"));
+            };
+
+            if let Some(title) = non_blank(request.field("title")) {
+                task.title = title;
+            }
+            if let Some(description) = request.field("description").and_then(Value::as_str) {
+                task.description = description.trim().to_string();
+            }
+            if let Some(priority) = request.field("priority") {
+                task.priority = normalize_priority(Some(priority));
+            }
+            if let Some(tags) = request.field("tags") {
+                task.tags = normalize_tags(Some(tags));
+            }
+            if let Some(due_date) = request.field("dueDate") {
+                task.due_date = date(Some(due_date));
+            }
+            if is_status(request.field("status")) {
+                task.status = normalize_status(request.field("status"));
+                task.completed_at = if task.status == "done" {
+                    task.completed_at.take().or_else(|| Some(now()))
+                } else {
+                    None
+                };
+            }
+            if let Some(archived_at) = request.field("archivedAt") {
+                task.archived_at = date(Some(archived_at));
+            }
+            task.updated_at = now();
+
+            Ok(Response::ok(to_json(task)?))
+        },
+    )
+}
+
+fn delete(store: &LocalStore, account: &Account, id: &str) -> Result<Response> {
+    routes::update(
+        store,
+        RecordStore::LocalApiTasks,
+        |tasks: &mut Vec<Owned<Task>>| {
+            let before = tasks.len();
+            tasks.retain(|task| !(task.user_id == account.id && task.item.id == id));
+
+            Ok(if tasks.len() < before {
+                Response::no_content()
+            } else {
+                Response::error(404, "Task not found")
+            })
+        },
+    )
+}
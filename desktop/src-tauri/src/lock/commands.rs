@@ -0,0 +1,90 @@
+// desktop/src-tauri/src/lock/commands.rs
+
+use tauri::{AppHandle, Manager, State};
+
+use super::{LockReason, WorkspaceLock, WorkspaceLockState};
+use crate::blocking;
+use crate::error::Result;
+use crate::storage::{LocalStore, UnlockSecret};
+use crate::sync::{engine::Resource, SyncService};
+
+#[tauri::command]
+pub fn get_workspace_lock_state(lock: State<'_, WorkspaceLock>) -> WorkspaceLockState {
+    lock.state()
+}
+
+/// Sets or changes the PIN, or removes it when `pin` is null. The local
+/// store is re-encrypted under the new PIN (or the machine key) as part of
+/// the change.
+#[tauri::command]
+pub async fn set_local_pin(
+    app: AppHandle,
+    current_pin: Option<String>,
+    pin: Option<String>,
+) -> Result<WorkspaceLockState> {
+    blocking::run(app, move |app| {
+        let lock = app.state::<WorkspaceLock>();
+        let store = app.state::<LocalStore>();
+
+        lock.set_pin(current_pin.as_deref(), pin.as_deref(), |pin| {
+            store.rekey(pin.map_or(UnlockSecret::Machine, UnlockSecret::Pin))
+        })?;
+
+        Ok(lock.state())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn verify_local_pin(app: AppHandle, pin: String) -> Result<WorkspaceLockState> {
+    blocking::run(app, move |app| {
+        let lock = app.state::<WorkspaceLock>();
+        let store = app.state::<LocalStore>();
+
+        let was_locked = lock.state().locked;
+        let state = lock.verify(&pin, |pin| store.pin_unlocks(pin))?;
+
+        if state.locked || !was_locked {
+            return Ok(state);
+        }
+
+        if let Err(error) = store.unlock(UnlockSecret::Pin(&pin)) {
+            lock.lock(LockReason::Startup);
+            return Err(error);
+        }
+
+        super::emit_unlocked(app);
+
+        // Views read while locked saw nothing; have them reload, and let the
+        // sync worker catch up on anything queued meanwhile.
+        for resource in Resource::ALL {
+            let _ = app.emit_all(resource.changed_event(), ());
+        }
+        app.state::<SyncService>().wake();
+
+        Ok(state)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn lock_workspace(app: AppHandle) -> WorkspaceLockState {
+    super::lock_now(&app, LockReason::Manual).await;
+    app.state::<WorkspaceLock>().state()
+}
+
+/// Called by the webview (throttled) on keyboard and pointer input.
+#[tauri::command]
+pub fn record_workspace_activity(lock: State<'_, WorkspaceLock>) {
+    lock.record_activity();
+}
+
+/// Zero disables the idle lock.
+#[tauri::command]
+pub fn set_idle_lock_timeout(
+    lock: State<'_, WorkspaceLock>,
+    seconds: u64,
+) -> Result<WorkspaceLockState> {
+    lock.set_idle_timeout(seconds)?;
+    Ok(lock.state())
+}
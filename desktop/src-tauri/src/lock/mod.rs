@@ -0,0 +1,576 @@
+// desktop/src-tauri/src/lock/mod.rs
+
+/*
+ * PIN lock for the local workspace.
+ *
+ * The PIN is stored as an Argon2id hash next to the credential vault. Failed
+ * attempts are persisted so restarting the app does not reset the lockout,
+ * and a background timer locks the workspace after a period without
+ * activity reported by the webview.
+ *
+ * The PIN is also the unlock secret of the encrypted local store, so locking
+ * the workspace drops the store's key, once any sync pass has finished, and
+ * unlocking hands the PIN back to it.
+ * Whether a PIN is set is the store's answer (`LocalStore::requires_pin`),
+ * not this file's: a lost or unreadable lock file leaves the workspace
+ * locked, and the PIN is then checked against the store's keyring and
+ * hashed here again.
+ */
+
+pub mod commands;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Config, Manager};
+use tokio::sync::Notify;
+
+use crate::crypto;
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+use crate::storage::LocalStore;
+use crate::sync;
+
+pub const LOCK_FILE_NAME: &str = "workspace-lock.json";
+
+pub const WORKSPACE_LOCKED_EVENT: &str = "pioneer:workspace-locked";
+pub const WORKSPACE_UNLOCKED_EVENT: &str = "pioneer:workspace-unlocked";
+
+const MIN_PIN_LENGTH: usize = 4;
+const MAX_PIN_LENGTH: usize = 64;
+
+// The first few mistakes are free; after that each failure doubles the wait.
+const FREE_ATTEMPTS: u32 = 5;
+const LOCKOUT_BASE_SECONDS: u64 = 30;
+const LOCKOUT_MAX_SECONDS: u64 = 15 * 60;
+
+const DEFAULT_IDLE_TIMEOUT_SECONDS: u64 = 15 * 60;
+
+fn default_idle_timeout_seconds() -> u64 {
+    DEFAULT_IDLE_TIMEOUT_SECONDS
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockFile {
+    #[serde(default)]
+    pin_hash: Option<String>,
+    #[serde(default)]
+    failed_attempts: u32,
+    /// Unix timestamp (seconds) before which no attempt is checked.
+    #[serde(default)]
+    retry_after: Option<i64>,
+    /// Zero disables the idle lock.
+    #[serde(default = "default_idle_timeout_seconds")]
+    idle_timeout_seconds: u64,
+}
+
+impl Default for LockFile {
+    fn default() -> Self {
+        Self {
+            pin_hash: None,
+            failed_attempts: 0,
+            retry_after: None,
+            idle_timeout_seconds: DEFAULT_IDLE_TIMEOUT_SECONDS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LockReason {
+    Startup,
+    Manual,
+    Idle,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLockState {
+    pub pin_set: bool,
+    pub locked: bool,
+    pub reason: Option<LockReason>,
+    pub failed_attempts: u32,
+    pub retry_after_seconds: Option<u64>,
+    pub idle_timeout_seconds: u64,
+}
+
+struct LockStatus {
+    file: LockFile,
+    pin_required: bool,
+    locked: Option<LockReason>,
+    last_activity: Instant,
+}
+
+pub struct WorkspaceLock {
+    path: PathBuf,
+    status: Mutex<LockStatus>,
+    changed: Notify,
+}
+
+fn now_seconds() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn lockout_seconds(failed_attempts: u32) -> Option<u64> {
+    let excess = failed_attempts.checked_sub(FREE_ATTEMPTS)?;
+    let seconds = LOCKOUT_BASE_SECONDS.saturating_mul(1 << excess.min(16));
+
+    Some(seconds.min(LOCKOUT_MAX_SECONDS))
+}
+
+fn validate_pin(pin: &str) -> Result<()> {
+    let length = pin.chars().count();
+
+    if !(MIN_PIN_LENGTH..=MAX_PIN_LENGTH).contains(&length) {
+        return Err(Error::InvalidInput(format!(
+            "A PIN must be between {MIN_PIN_LENGTH} and {MAX_PIN_LENGTH} characters."
+        )));
+    }
+
+    Ok(())
+}
+
+impl WorkspaceLock {
+    /// Opens the lock state at `path` for a store that does or does not
+    /// need a PIN. A workspace with a PIN always starts out locked, even
+    /// when the file is missing or cannot be read.
+    pub fn open(path: impl Into<PathBuf>, pin_required: bool) -> Self {
+        let path = path.into();
+        let file: LockFile = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        let locked = pin_required.then_some(LockReason::Startup);
+
+        Self {
+            path,
+            status: Mutex::new(LockStatus {
+                file,
+                pin_required,
+                locked,
+                last_activity: Instant::now(),
+            }),
+            changed: Notify::new(),
+        }
+    }
+
+    pub fn open_in_app_config(app: &AppHandle, pin_required: bool) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir(app)?.join(LOCK_FILE_NAME),
+            pin_required,
+        ))
+    }
+
+    pub fn open_for_config(config: &Config, pin_required: bool) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir_for(config)?.join(LOCK_FILE_NAME),
+            pin_required,
+        ))
+    }
+
+    fn status(&self) -> MutexGuard<'_, LockStatus> {
+        self.status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn save(&self, file: &LockFile) -> Result<()> {
+        files::write_atomic(&self.path, &serde_json::to_vec_pretty(file)?)
+    }
+
+    fn snapshot(status: &LockStatus) -> WorkspaceLockState {
+        let retry_after_seconds = status
+            .file
+            .retry_after
+            .map(|retry_after| retry_after - now_seconds())
+            .filter(|remaining| *remaining > 0)
+            .map(|remaining| remaining as u64);
+
+        WorkspaceLockState {
+            pin_set: status.pin_required,
+            locked: status.locked.is_some(),
+            reason: status.locked,
+            failed_attempts: status.file.failed_attempts,
+            retry_after_seconds,
+            idle_timeout_seconds: status.file.idle_timeout_seconds,
+        }
+    }
+
+    pub fn state(&self) -> WorkspaceLockState {
+        Self::snapshot(&self.status())
+    }
+
+    /// Checks `pin` against the stored hash, enforcing and updating the
+    /// attempt throttle. Returns `true` when there is no PIN. Without a hash
+    /// `unlocks_store` decides, and a PIN it accepts is hashed again.
+    fn check_pin(
+        &self,
+        status: &mut LockStatus,
+        pin: &str,
+        unlocks_store: impl FnOnce(&str) -> Result<bool>,
+    ) -> Result<bool> {
+        if !status.pin_required {
+            return Ok(true);
+        }
+
+        if let Some(retry_after) = status.file.retry_after {
+            let remaining = retry_after - now_seconds();
+
+            if remaining > 0 {
+                return Err(Error::TooManyAttempts(remaining as u64));
+            }
+        }
+
+        let verified = match &status.file.pin_hash {
+            Some(hash) => crypto::verify_secret(pin.as_bytes(), hash)?,
+            None => unlocks_store(pin)?,
+        };
+
+        if verified && status.file.pin_hash.is_none() {
+            status.file.pin_hash = Some(crypto::hash_secret(pin.as_bytes())?);
+        }
+
+        if verified {
+            status.file.failed_attempts = 0;
+            status.file.retry_after = None;
+        } else {
+            status.file.failed_attempts = status.file.failed_attempts.saturating_add(1);
+            status.file.retry_after = lockout_seconds(status.file.failed_attempts)
+                .map(|seconds| now_seconds() + seconds as i64);
+        }
+
+        self.save(&status.file)?;
+        Ok(verified)
+    }
+
+    /// Unlocks the workspace when `pin` matches. A wrong PIN is not an
+    /// error; the returned state stays locked and counts the attempt.
+    /// `unlocks_store` checks the PIN when the lock file lost its hash.
+    pub fn verify(
+        &self,
+        pin: &str,
+        unlocks_store: impl FnOnce(&str) -> Result<bool>,
+    ) -> Result<WorkspaceLockState> {
+        let mut status = self.status();
+
+        if self.check_pin(&mut status, pin, unlocks_store)? {
+            status.locked = None;
+            status.last_activity = Instant::now();
+            self.changed.notify_one();
+        }
+
+        Ok(Self::snapshot(&status))
+    }
+
+    fn confirm_current_pin(
+        &self,
+        status: &mut LockStatus,
+        current_pin: Option<&str>,
+    ) -> Result<()> {
+        if !status.pin_required {
+            return Ok(());
+        }
+
+        let current_pin = current_pin
+            .ok_or_else(|| Error::InvalidInput("Enter your current PIN first.".to_string()))?;
+
+        // Only reached unlocked, and `verify` has restored the hash by then.
+        let unlocks_store = |_: &str| Err(Error::StoreLocked);
+
+        if !self.check_pin(status, current_pin, unlocks_store)? {
+            return Err(Error::InvalidInput(
+                "The current PIN is not correct.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the current PIN (if one is set) under the attempt throttle.
+    pub fn confirm_pin(&self, current_pin: Option<&str>) -> Result<()> {
+        self.confirm_current_pin(&mut self.status(), current_pin)
+    }
+
+    /// Sets, changes or (with `pin: None`) removes the PIN. Changing an
+    /// existing PIN requires the current one. The new hash is saved before
+    /// `rekey` re-encrypts the store under the new PIN, and the old one is
+    /// put back if that fails, so the two never name different PINs.
+    pub fn set_pin(
+        &self,
+        current_pin: Option<&str>,
+        pin: Option<&str>,
+        rekey: impl FnOnce(Option<&str>) -> Result<()>,
+    ) -> Result<()> {
+        if let Some(pin) = pin {
+            validate_pin(pin)?;
+        }
+
+        let mut status = self.status();
+
+        self.confirm_current_pin(&mut status, current_pin)?;
+
+        let pin_hash = match pin {
+            Some(pin) => Some(crypto::hash_secret(pin.as_bytes())?),
+            None => None,
+        };
+
+        let previous_hash = std::mem::replace(&mut status.file.pin_hash, pin_hash);
+        status.file.failed_attempts = 0;
+        status.file.retry_after = None;
+
+        if let Err(error) = self.save(&status.file) {
+            status.file.pin_hash = previous_hash;
+            return Err(error);
+        }
+
+        if let Err(error) = rekey(pin) {
+            status.file.pin_hash = previous_hash;
+            self.save(&status.file)?;
+            return Err(error);
+        }
+
+        status.pin_required = pin.is_some();
+        status.locked = None;
+        status.last_activity = Instant::now();
+        self.changed.notify_one();
+
+        Ok(())
+    }
+
+    /// Locks the workspace. Returns `false` when there is no PIN to unlock
+    /// it with again, or when it is already locked.
+    pub fn lock(&self, reason: LockReason) -> bool {
+        let mut status = self.status();
+
+        if !status.pin_required || status.locked.is_some() {
+            return false;
+        }
+
+        status.locked = Some(reason);
+        true
+    }
+
+    pub fn record_activity(&self) {
+        self.status().last_activity = Instant::now();
+    }
+
+    pub fn set_idle_timeout(&self, seconds: u64) -> Result<()> {
+        let mut status = self.status();
+
+        status.file.idle_timeout_seconds = seconds;
+        status.last_activity = Instant::now();
+
+        self.save(&status.file)?;
+        self.changed.notify_one();
+
+        Ok(())
+    }
+
+    /// When the idle lock should fire, if it is armed at all.
+    fn idle_deadline(&self) -> Option<Instant> {
+        let status = self.status();
+
+        if !status.pin_required || status.locked.is_some() || status.file.idle_timeout_seconds == 0
+        {
+            return None;
+        }
+
+        Some(status.last_activity + Duration::from_secs(status.file.idle_timeout_seconds))
+    }
+}
+
+/// Locks the workspace and the local store together. The store's key is
+/// only dropped between sync passes: a pass that has replayed an op still
+/// has to take it off the queue, or the next pass would send it again.
+pub async fn lock_now(app: &AppHandle, reason: LockReason) {
+    let lock = app.state::<WorkspaceLock>();
+
+    if lock.lock(reason) {
+        emit_locked(app);
+
+        sync::between_passes(app, || {
+            // Unless the PIN was entered again in the meantime.
+            if lock.state().locked {
+                app.state::<LocalStore>().lock();
+            }
+        })
+        .await;
+    }
+}
+
+fn emit_locked(app: &AppHandle) {
+    let state = app.state::<WorkspaceLock>().state();
+    let _ = app.emit_all(WORKSPACE_LOCKED_EVENT, state);
+}
+
+pub fn emit_unlocked(app: &AppHandle) {
+    let state = app.state::<WorkspaceLock>().state();
+    let _ = app.emit_all(WORKSPACE_UNLOCKED_EVENT, state);
+}
+
+/// Spawns the idle timer. It sleeps until the current deadline and re-checks
+/// it on waking, since activity reported in the meantime moves it later.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let lock = app.state::<WorkspaceLock>();
+
+            let Some(deadline) = lock.idle_deadline() else {
+                lock.changed.notified().await;
+                continue;
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline.into()) => {
+                    if lock.idle_deadline().is_some_and(|deadline| deadline <= Instant::now()) {
+                        lock_now(&app, LockReason::Idle).await;
+                    }
+                }
+                _ = lock.changed.notified() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_path(directory: &tempfile::TempDir) -> PathBuf {
+        directory.path().join(LOCK_FILE_NAME)
+    }
+
+    fn read_file(directory: &tempfile::TempDir) -> LockFile {
+        serde_json::from_slice(&fs::read(lock_path(directory)).unwrap()).unwrap()
+    }
+
+    /// For locks whose file still has the hash.
+    fn no_store(_: &str) -> Result<bool> {
+        panic!("the PIN should be checked against the hash");
+    }
+
+    #[test]
+    fn lockouts_double_after_the_free_attempts_up_to_fifteen_minutes() {
+        assert_eq!(lockout_seconds(0), None);
+        assert_eq!(lockout_seconds(FREE_ATTEMPTS - 1), None);
+        assert_eq!(lockout_seconds(FREE_ATTEMPTS), Some(30));
+        assert_eq!(lockout_seconds(FREE_ATTEMPTS + 1), Some(60));
+        assert_eq!(lockout_seconds(FREE_ATTEMPTS + 4), Some(480));
+        assert_eq!(
+            lockout_seconds(FREE_ATTEMPTS + 5),
+            Some(LOCKOUT_MAX_SECONDS)
+        );
+        assert_eq!(lockout_seconds(u32::MAX), Some(LOCKOUT_MAX_SECONDS));
+    }
+
+    #[test]
+    fn pins_are_set_and_verified_across_restarts() {
+        let directory = tempfile::tempdir().unwrap();
+        let lock = WorkspaceLock::open(lock_path(&directory), false);
+        assert!(!lock.state().locked);
+
+        assert!(lock.set_pin(None, Some("12"), |_| Ok(())).is_err());
+        lock.set_pin(None, Some("2468"), |_| Ok(())).unwrap();
+        assert!(lock.state().pin_set);
+        assert!(!lock.state().locked);
+        assert!(lock.set_pin(None, Some("1357"), |_| Ok(())).is_err());
+
+        let lock = WorkspaceLock::open(lock_path(&directory), true);
+        assert!(lock.state().locked);
+        assert_eq!(lock.state().reason, Some(LockReason::Startup));
+
+        let state = lock.verify("1111", no_store).unwrap();
+        assert!(state.locked);
+        assert_eq!(state.failed_attempts, 1);
+
+        let state = lock.verify("2468", no_store).unwrap();
+        assert!(!state.locked);
+        assert_eq!(state.failed_attempts, 0);
+    }
+
+    #[test]
+    fn failed_attempts_and_lockouts_survive_restarts() {
+        let directory = tempfile::tempdir().unwrap();
+        let file = LockFile {
+            pin_hash: Some(crypto::hash_secret(b"2468").unwrap()),
+            failed_attempts: FREE_ATTEMPTS - 1,
+            ..LockFile::default()
+        };
+        fs::write(lock_path(&directory), serde_json::to_vec(&file).unwrap()).unwrap();
+
+        let lock = WorkspaceLock::open(lock_path(&directory), true);
+        let state = lock.verify("0000", no_store).unwrap();
+        assert_eq!(state.failed_attempts, FREE_ATTEMPTS);
+        assert!(state
+            .retry_after_seconds
+            .is_some_and(|seconds| seconds <= 30));
+
+        let lock = WorkspaceLock::open(lock_path(&directory), true);
+        assert_eq!(lock.state().failed_attempts, FREE_ATTEMPTS);
+        assert!(matches!(
+            lock.verify("2468", no_store),
+            Err(Error::TooManyAttempts(_))
+        ));
+    }
+
+    #[test]
+    fn a_failed_rekey_leaves_no_pin_behind() {
+        let directory = tempfile::tempdir().unwrap();
+        let lock = WorkspaceLock::open(lock_path(&directory), false);
+
+        let result = lock.set_pin(None, Some("2468"), |_| {
+            Err(Error::Crypto("The store could not be re-encrypted."))
+        });
+
+        assert!(result.is_err());
+        assert!(!lock.state().pin_set);
+        assert!(read_file(&directory).pin_hash.is_none());
+    }
+
+    #[test]
+    fn a_lost_lock_file_still_asks_for_the_store_pin() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(lock_path(&directory), b"{ not json").unwrap();
+
+        let lock = WorkspaceLock::open(lock_path(&directory), true);
+        assert!(lock.state().pin_set);
+        assert!(lock.state().locked);
+
+        let store_pin = |pin: &str| Ok(pin == "2468");
+        assert!(lock.verify("0000", store_pin).unwrap().locked);
+        assert!(!lock.verify("2468", store_pin).unwrap().locked);
+
+        // The hash is back, so the store is not needed again.
+        let lock = WorkspaceLock::open(lock_path(&directory), true);
+        assert!(!lock.verify("2468", no_store).unwrap().locked);
+    }
+
+    #[test]
+    fn the_idle_lock_is_armed_only_while_unlocked_with_a_pin() {
+        let directory = tempfile::tempdir().unwrap();
+        let lock = WorkspaceLock::open(lock_path(&directory), false);
+        assert!(lock.idle_deadline().is_none());
+
+        lock.status().pin_required = true;
+        lock.set_idle_timeout(60).unwrap();
+        let remaining = lock.idle_deadline().unwrap() - Instant::now();
+        assert!(remaining <= Duration::from_secs(60));
+        assert!(remaining > Duration::from_secs(50));
+
+        lock.set_idle_timeout(0).unwrap();
+        assert!(lock.idle_deadline().is_none());
+
+        lock.set_idle_timeout(60).unwrap();
+        assert!(lock.lock(LockReason::Manual));
+        assert!(lock.idle_deadline().is_none());
+        assert!(!lock.lock(LockReason::Idle));
+    }
+}
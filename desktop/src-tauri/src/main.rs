@@ -2,9 +2,225 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod backup;
+mod blocking;
+mod calendar;
+mod capture;
+mod cli;
+mod close_guard;
+mod crypto;
+mod deep_link;
+mod documents;
+mod error;
+mod files;
+mod instance;
+mod local_api;
+mod lock;
+mod menu;
+mod network;
+mod paths;
+mod popout;
+mod reachability;
+mod reminders;
+mod servers;
+mod session;
+mod snapshots;
+mod storage;
+mod sync;
+mod tray;
+mod vault;
+mod window_state;
+
+use tauri::Manager;
+
 fn main() {
+    let context = tauri::generate_context!();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Scripted commands run against the store and exit without a window,
+    // whether or not the app is running.
+    if let Some(command) = cli::parse(&args) {
+        std::process::exit(cli::run(context.config(), command));
+    }
+
+    let instance = match instance::acquire(context.config(), &args) {
+        Ok(instance::Acquired::Primary(instance)) => Some(instance),
+        Ok(instance::Acquired::Forwarded) => return,
+        Err(error @ error::Error::InstanceUnresponsive) => {
+            instance::show_error(&error);
+            std::process::exit(1);
+        }
+        // Without the lock the app starts as it always has.
+        Err(_) => None,
+    };
+
+    // The menu bar exists before `setup` runs, so its catalogue is loaded
+    // from the config rather than through the app handle.
+    let native_menu = menu::NativeMenu::open_for_config(context.config()).unwrap_or_default();
+
+    // reqwest reads the proxy variables when the first client is built, so
+    // they are set before the updater or the sync worker can build one.
+    let network = network::Network::open_for_config(context.config())
+        .expect("error while reading the network settings");
+    network.apply_to_environment();
+
     tauri::Builder::default()
-        // If you add custom commands later, they’ll be attached here
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
\ No newline at end of file
+        .setup(|app| {
+            let store = storage::LocalStore::open_in_app_data(&app.handle())?;
+            let workspace_lock =
+                lock::WorkspaceLock::open_in_app_config(&app.handle(), store.requires_pin()?)?;
+
+            // Without a PIN the store is keyed to this computer and opens
+            // straight away; otherwise it waits for `verify_local_pin`.
+            if !workspace_lock.state().pin_set {
+                store.unlock(storage::UnlockSecret::Machine)?;
+            }
+
+            app.manage(workspace_lock);
+            app.manage(store);
+            app.manage(sync::SyncService::default());
+            app.manage(reachability::Reachability::default());
+            app.manage(tray::Tray::default());
+            app.manage(deep_link::DeepLinks::default());
+            app.manage(close_guard::CloseGuard::default());
+            app.manage(servers::Servers::open_in_app_config(&app.handle())?);
+            app.manage(session::Session::open_in_app_config(&app.handle())?);
+            app.manage(local_api::LocalApi::open_in_app_config(&app.handle())?);
+
+            let vault = vault::Vault::open_in_app_config(&app.handle())?;
+            // A passphrase-protected vault stays locked until it is unlocked
+            // in Settings; until then the worker simply has no token.
+            if let Ok(Some(session)) = vault.read() {
+                app.state::<sync::SyncService>()
+                    .set_session(Some(session.token), false);
+            }
+            app.manage(vault);
+
+            app.manage(snapshots::Snapshots::open_in_app_config(&app.handle())?);
+            app.manage(reminders::Reminders::open_in_app_dirs(&app.handle())?);
+            app.manage(capture::Capture::open_in_app_config(&app.handle())?);
+            app.manage(window_state::WindowStates::open_in_app_config(
+                &app.handle(),
+            )?);
+
+            window_state::start(&app.handle());
+
+            lock::start(&app.handle());
+
+            sync::start(&app.handle());
+
+            reachability::start(&app.handle());
+
+            snapshots::start(&app.handle());
+
+            reminders::start(&app.handle());
+
+            tray::start(&app.handle());
+
+            capture::start(&app.handle());
+
+            deep_link::start(&app.handle());
+
+            local_api::start(&app.handle());
+
+            if let Some(instance) = instance {
+                instance.listen(&app.handle());
+            }
+
+            Ok(())
+        })
+        .menu(native_menu.menu())
+        .on_menu_event(menu::handle_event)
+        .manage(native_menu)
+        .manage(network)
+        .system_tray(tray::system_tray())
+        .on_system_tray_event(tray::handle_event)
+        .on_window_event(window_state::handle_window_event)
+        .on_window_event(session::handle_window_event)
+        .on_window_event(close_guard::handle_window_event)
+        .on_window_event(tray::handle_window_event)
+        .invoke_handler(tauri::generate_handler![
+            storage::commands::read_stored_tasks,
+            storage::commands::write_stored_tasks,
+            storage::commands::read_stored_task_queue,
+            storage::commands::read_stored_documents,
+            storage::commands::write_stored_documents,
+            storage::commands::read_stored_document_queue,
+            storage::commands::enqueue_stored_op,
+            storage::commands::merge_stored_record,
+            storage::commands::remove_stored_record,
+            storage::commands::import_stored_resource,
+            storage::commands::read_stored_document_recovery,
+            storage::commands::write_stored_document_recovery,
+            storage::commands::delete_stored_document_recovery,
+            storage::commands::read_stored_events,
+            storage::commands::write_stored_events,
+            storage::commands::read_stored_event_queue,
+            storage::commands::read_storage_meta,
+            storage::commands::write_storage_meta,
+            storage::commands::get_storage_path,
+            storage::commands::rekey_local_store,
+            sync::commands::sync_set_session,
+            sync::commands::sync_now,
+            sync::commands::get_sync_snapshot,
+            vault::commands::vault_store_token,
+            vault::commands::vault_read_token,
+            vault::commands::vault_clear,
+            vault::commands::vault_status,
+            vault::commands::vault_unlock,
+            vault::commands::vault_set_passphrase,
+            backup::commands::export_workspace,
+            backup::commands::import_workspace,
+            documents::commands::export_document,
+            documents::commands::import_documents,
+            calendar::commands::import_ics,
+            calendar::commands::export_ics,
+            lock::commands::get_workspace_lock_state,
+            lock::commands::set_local_pin,
+            lock::commands::verify_local_pin,
+            lock::commands::lock_workspace,
+            lock::commands::record_workspace_activity,
+            lock::commands::set_idle_lock_timeout,
+            snapshots::commands::list_snapshots,
+            snapshots::commands::create_snapshot,
+            snapshots::commands::restore_snapshot,
+            snapshots::commands::get_snapshot_settings,
+            snapshots::commands::set_snapshot_settings,
+            reminders::commands::list_reminders,
+            reminders::commands::snooze_reminder,
+            reminders::commands::dismiss_reminder,
+            reminders::commands::get_reminder_settings,
+            reminders::commands::set_reminder_settings,
+            capture::commands::get_capture_shortcuts,
+            capture::commands::set_capture_shortcuts,
+            capture::commands::finish_capture,
+            menu::commands::set_native_menu,
+            deep_link::commands::take_pending_deep_link,
+            popout::commands::open_popout,
+            popout::commands::broadcast_change,
+            popout::commands::show_in_main_window,
+            session::commands::get_previous_session_state,
+            session::commands::set_session_path,
+            close_guard::commands::report_unsaved_drafts,
+            servers::commands::list_servers,
+            servers::commands::save_server,
+            servers::commands::delete_server,
+            servers::commands::check_server,
+            servers::commands::get_active_server,
+            servers::commands::set_active_server,
+            local_api::commands::get_local_api_status,
+            local_api::commands::set_local_api_settings,
+            network::commands::get_network_settings,
+            network::commands::set_proxy_settings,
+            network::commands::import_root_certificate,
+            network::commands::remove_root_certificate,
+            reachability::commands::get_network_status,
+        ])
+        .build(context)
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                close_guard::shutdown(app);
+            }
+        });
+}
@@ -0,0 +1,17 @@
+// desktop/src-tauri/src/menu/commands.rs
+
+use tauri::{AppHandle, State};
+
+use super::manifest::CommandState;
+use super::NativeMenu;
+use crate::error::Result;
+
+/// Receives every command currently in the webview's registry.
+#[tauri::command]
+pub fn set_native_menu(
+    app: AppHandle,
+    menu: State<'_, NativeMenu>,
+    commands: Vec<CommandState>,
+) -> Result<()> {
+    menu.update(&app, &commands)
+}
@@ -0,0 +1,331 @@
+// desktop/src-tauri/src/menu/manifest.rs
+
+/*
+ * The command manifest sent by the webview, and the catalogue of commands
+ * the native menu is built from.
+ *
+ * The catalogue starts from the web app's menuCommands.json, embedded at
+ * compile time, so the commands it lists have menu items from the first
+ * start.
+ *
+ * Commands are grouped into submenus by category. Shortcuts use the
+ * command registry's notation, e.g. `["Ctrl", "Shift", "N"]`, and become
+ * Tauri accelerators; when two commands share a shortcut only the first one
+ * in menu order gets the accelerator.
+ */
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// At most this many commands are remembered.
+const MAX_COMMANDS: usize = 256;
+
+const BUILT_IN_COMMANDS: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../apps/web/src/commands/menuCommands.json"
+));
+
+/// Submenus in menu order, with the categories they hold. Commands in a
+/// category that is not listed go to "Workspace".
+const SECTIONS: [(&str, &str); 7] = [
+    ("File", "Create"),
+    ("Go", "Navigation"),
+    ("Workspace", "Workspace"),
+    ("Tasks", "Tasks"),
+    ("Documents", "Documents"),
+    ("Calendar", "Calendar"),
+    ("Mail", "Mail"),
+];
+
+/// How a command appears in the menu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuCommand {
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    #[serde(default)]
+    pub shortcut: Vec<String>,
+    /// Whether the command shows a check mark.
+    #[serde(default)]
+    pub checkable: bool,
+}
+
+/// A command as it is currently registered in the webview.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandState {
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    #[serde(default)]
+    pub shortcut: Vec<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub checked: Option<bool>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl CommandState {
+    fn menu_command(&self) -> MenuCommand {
+        MenuCommand {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            category: self.category.clone(),
+            shortcut: self.shortcut.clone(),
+            checkable: self.checked.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuEntry {
+    pub command: MenuCommand,
+    pub accelerator: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuSection {
+    pub title: &'static str,
+    pub entries: Vec<MenuEntry>,
+}
+
+fn key(name: &str) -> Option<String> {
+    let key = match name {
+        "/" => "Slash",
+        "\\" => "Backslash",
+        "," => "Comma",
+        "." => "Period",
+        ";" => "Semicolon",
+        "'" => "Quote",
+        "[" => "BracketLeft",
+        "]" => "BracketRight",
+        "-" => "Minus",
+        "=" => "Equal",
+        "`" => "Backquote",
+        "ArrowUp" | "Up" => "Up",
+        "ArrowDown" | "Down" => "Down",
+        "ArrowLeft" | "Left" => "Left",
+        "ArrowRight" | "Right" => "Right",
+        "Enter" | "Return" => "Enter",
+        "Esc" | "Escape" => "Escape",
+        "Space" | " " => "Space",
+        "Tab" => "Tab",
+        "Backspace" => "Backspace",
+        "Delete" | "Del" => "Delete",
+        "Home" => "Home",
+        "End" => "End",
+        "PageUp" => "PageUp",
+        "PageDown" => "PageDown",
+        name if name.len() == 1 && name.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            return Some(name.to_ascii_uppercase());
+        }
+        name if name.len() <= 3
+            && name.starts_with('F')
+            && name[1..].parse::<u8>().is_ok_and(|n| (1..=24).contains(&n)) =>
+        {
+            return Some(name.to_string());
+        }
+        _ => return None,
+    };
+
+    Some(key.to_string())
+}
+
+/// Converts a registry shortcut to a Tauri accelerator. `Ctrl` becomes
+/// `CmdOrCtrl`, matching how the webview treats it as the primary modifier.
+pub fn accelerator(shortcut: &[String]) -> Option<String> {
+    let (last, modifiers) = shortcut.split_last()?;
+    let mut parts = Vec::new();
+
+    for modifier in modifiers {
+        let modifier = match modifier.as_str() {
+            "Ctrl" | "Cmd" | "Mod" | "CmdOrCtrl" => "CmdOrCtrl",
+            "Shift" => "Shift",
+            "Alt" | "Option" => "Alt",
+            _ => return None,
+        };
+
+        if !parts.contains(&modifier) {
+            parts.push(modifier);
+        }
+    }
+
+    Some(
+        parts
+            .into_iter()
+            .map(str::to_string)
+            .chain([key(last)?])
+            .collect::<Vec<_>>()
+            .join("+"),
+    )
+}
+
+/// The commands the web app declares in menuCommands.json.
+pub fn built_in() -> Vec<MenuCommand> {
+    serde_json::from_str(BUILT_IN_COMMANDS).unwrap_or_default()
+}
+
+/// Adds remembered commands the built-in catalogue does not list, keeping
+/// the built-in ones first.
+pub fn seed(remembered: Vec<MenuCommand>) -> Vec<MenuCommand> {
+    let mut catalogue = built_in();
+
+    for command in remembered {
+        if catalogue.len() < MAX_COMMANDS && !catalogue.iter().any(|known| known.id == command.id) {
+            catalogue.push(command);
+        }
+    }
+
+    catalogue
+}
+
+/// Adds commands that were not seen before and refreshes the rest.
+/// Returns whether the catalogue changed.
+pub fn merge(catalogue: &mut Vec<MenuCommand>, commands: &[CommandState]) -> bool {
+    let mut changed = false;
+
+    for state in commands {
+        let command = state.menu_command();
+
+        match catalogue.iter().position(|known| known.id == command.id) {
+            Some(index) if catalogue[index] == command => {}
+            Some(index) => {
+                catalogue[index] = command;
+                changed = true;
+            }
+            None if catalogue.len() < MAX_COMMANDS => {
+                catalogue.push(command);
+                changed = true;
+            }
+            None => {}
+        }
+    }
+
+    changed
+}
+
+/// Groups the catalogue into submenus, in menu order.
+pub fn sections(catalogue: &[MenuCommand]) -> Vec<MenuSection> {
+    let section_of = |category: &str| {
+        SECTIONS
+            .iter()
+            .find(|(_, listed)| *listed == category)
+            .map_or("Workspace", |(title, _)| *title)
+    };
+    let mut taken = HashSet::new();
+
+    SECTIONS
+        .iter()
+        .map(|(title, _)| MenuSection {
+            title,
+            entries: catalogue
+                .iter()
+                .filter(|command| section_of(&command.category) == *title)
+                .map(|command| MenuEntry {
+                    command: command.clone(),
+                    accelerator: accelerator(&command.shortcut)
+                        .filter(|accelerator| taken.insert(accelerator.to_lowercase())),
+                })
+                .collect(),
+        })
+        .filter(|section| !section.entries.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcut(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    fn state(id: &str, category: &str, keys: &[&str]) -> CommandState {
+        CommandState {
+            id: id.to_string(),
+            title: id.to_string(),
+            category: category.to_string(),
+            shortcut: shortcut(keys),
+            enabled: true,
+            checked: None,
+        }
+    }
+
+    #[test]
+    fn converts_registry_shortcuts() {
+        assert_eq!(
+            accelerator(&shortcut(&["Ctrl", "Shift", "n"])).as_deref(),
+            Some("CmdOrCtrl+Shift+N")
+        );
+        assert_eq!(
+            accelerator(&shortcut(&["Ctrl", "/"])).as_deref(),
+            Some("CmdOrCtrl+Slash")
+        );
+        assert_eq!(
+            accelerator(&shortcut(&["Alt", "ArrowUp"])).as_deref(),
+            Some("Alt+Up")
+        );
+        assert_eq!(accelerator(&shortcut(&["F5"])).as_deref(), Some("F5"));
+        assert_eq!(accelerator(&shortcut(&["Hyper", "K"])), None);
+        assert_eq!(accelerator(&shortcut(&["Ctrl", "Pause"])), None);
+        assert_eq!(accelerator(&[]), None);
+    }
+
+    #[test]
+    fn built_in_commands_are_listed_once() {
+        let built_in = built_in();
+        assert!(!built_in.is_empty());
+
+        let ids: HashSet<&str> = built_in.iter().map(|command| command.id.as_str()).collect();
+        assert_eq!(ids.len(), built_in.len());
+        assert!(built_in.iter().all(|command| SECTIONS
+            .iter()
+            .any(|(_, category)| *category == command.category)));
+
+        let extra = MenuCommand {
+            id: "beta".to_string(),
+            title: "Beta".to_string(),
+            category: "Labs".to_string(),
+            shortcut: Vec::new(),
+            checkable: false,
+        };
+        let catalogue = seed(vec![built_in[0].clone(), extra.clone()]);
+        assert_eq!(catalogue.len(), built_in.len() + 1);
+        assert_eq!(catalogue.last(), Some(&extra));
+    }
+
+    #[test]
+    fn merges_and_groups_commands() {
+        let mut catalogue = Vec::new();
+        let first = [
+            state("create-document", "Create", &["Ctrl", "Shift", "N"]),
+            state("go-tasks", "Navigation", &[]),
+        ];
+        assert!(merge(&mut catalogue, &first));
+        assert!(!merge(&mut catalogue, &first));
+
+        let mut later = vec![
+            state("documents-create", "Documents", &["Ctrl", "Shift", "N"]),
+            state("beta", "Labs", &[]),
+        ];
+        later[0].checked = Some(false);
+        assert!(merge(&mut catalogue, &later));
+        assert!(catalogue[2].checkable);
+
+        let sections = sections(&catalogue);
+        let titles: Vec<&str> = sections.iter().map(|section| section.title).collect();
+        assert_eq!(titles, ["File", "Go", "Workspace", "Documents"]);
+        assert_eq!(
+            sections[0].entries[0].accelerator.as_deref(),
+            Some("CmdOrCtrl+Shift+N")
+        );
+        assert_eq!(sections[3].entries[0].accelerator, None);
+        assert_eq!(sections[2].entries[0].command.id, "beta");
+    }
+}
@@ -0,0 +1,218 @@
+// desktop/src-tauri/src/menu/mod.rs
+
+/*
+ * Native application menu.
+ *
+ * The commands live in the webview's command registry, which sends the
+ * commands it currently has through `set_native_menu` whenever they change.
+ * Choosing a menu item sends `pioneer:menu-command` with the command id
+ * back to the window, which runs it through the registry.
+ *
+ * Tauri cannot replace a window menu once the window exists, so the menu is
+ * built at startup from a catalogue: the commands the web app lists in
+ * menuCommands.json, built into the binary, followed by any other command
+ * seen so far, kept in the app config directory. Afterwards the manifest
+ * only updates titles, enabled and checked state; commands that are not
+ * registered at the moment, such as those of another page, are shown
+ * disabled. A command missing from menuCommands.json joins the saved
+ * catalogue when it is first seen and shows up from the next start.
+ */
+
+pub mod commands;
+pub mod manifest;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::{AppHandle, Config, CustomMenuItem, Manager, Menu, MenuItem, Submenu, WindowMenuEvent};
+
+use crate::close_guard;
+use crate::error::Result;
+use crate::files;
+use crate::paths;
+use manifest::{CommandState, MenuCommand};
+
+pub const MENU_CATALOGUE_FILE_NAME: &str = "menu-commands.json";
+
+pub const MENU_COMMAND_EVENT: &str = "pioneer:menu-command";
+
+const MAIN_WINDOW_LABEL: &str = "main";
+
+const COMMAND_ID_PREFIX: &str = "command:";
+const QUIT_ID: &str = "app:quit";
+
+pub struct NativeMenu {
+    catalogue_path: Option<PathBuf>,
+    catalogue: Mutex<Vec<MenuCommand>>,
+    /// The commands the menu was built with.
+    built: Vec<MenuCommand>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The built-in commands only, for when there is nowhere to save more.
+impl Default for NativeMenu {
+    fn default() -> Self {
+        let catalogue = manifest::built_in();
+
+        Self {
+            catalogue_path: None,
+            catalogue: Mutex::new(catalogue.clone()),
+            built: catalogue,
+        }
+    }
+}
+
+impl NativeMenu {
+    /// A saved catalogue that cannot be read is treated as empty; the
+    /// webview fills it in again.
+    pub fn open(catalogue_path: impl Into<PathBuf>) -> Self {
+        let catalogue_path = catalogue_path.into();
+
+        let catalogue = manifest::seed(
+            fs::read(&catalogue_path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .unwrap_or_default(),
+        );
+
+        Self {
+            catalogue_path: Some(catalogue_path),
+            catalogue: Mutex::new(catalogue.clone()),
+            built: catalogue,
+        }
+    }
+
+    pub fn open_for_config(config: &Config) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir_for(config)?.join(MENU_CATALOGUE_FILE_NAME),
+        ))
+    }
+
+    /// Builds the menu bar. Command items start disabled until the webview
+    /// reports them.
+    pub fn menu(&self) -> Menu {
+        let mut menu = Menu::new();
+
+        #[cfg(target_os = "macos")]
+        {
+            menu = menu.add_submenu(Submenu::new(
+                "Pioneer Work Suite",
+                Menu::new()
+                    .add_native_item(MenuItem::Hide)
+                    .add_native_item(MenuItem::HideOthers)
+                    .add_native_item(MenuItem::ShowAll)
+                    .add_native_item(MenuItem::Separator)
+                    .add_item(CustomMenuItem::new(QUIT_ID, "Quit").accelerator("CmdOrCtrl+Q")),
+            ));
+        }
+
+        let sections = manifest::sections(&self.built);
+        let file = sections.iter().find(|section| section.title == "File");
+
+        let mut file_menu = file.map_or_else(Menu::new, |section| command_menu(&section.entries));
+        if cfg!(not(target_os = "macos")) {
+            if file.is_some() {
+                file_menu = file_menu.add_native_item(MenuItem::Separator);
+            }
+            file_menu = file_menu.add_item(CustomMenuItem::new(QUIT_ID, "Quit"));
+        }
+        if !file_menu.items.is_empty() {
+            menu = menu.add_submenu(Submenu::new("File", file_menu));
+        }
+
+        menu = menu.add_submenu(Submenu::new(
+            "Edit",
+            Menu::new()
+                .add_native_item(MenuItem::Undo)
+                .add_native_item(MenuItem::Redo)
+                .add_native_item(MenuItem::Separator)
+                .add_native_item(MenuItem::Cut)
+                .add_native_item(MenuItem::Copy)
+                .add_native_item(MenuItem::Paste)
+                .add_native_item(MenuItem::SelectAll),
+        ));
+
+        for section in sections.iter().filter(|section| section.title != "File") {
+            menu = menu.add_submenu(Submenu::new(section.title, command_menu(&section.entries)));
+        }
+
+        menu.add_submenu(Submenu::new(
+            "Window",
+            Menu::new()
+                .add_native_item(MenuItem::Minimize)
+                .add_native_item(MenuItem::Zoom)
+                .add_native_item(MenuItem::Separator)
+                .add_native_item(MenuItem::CloseWindow),
+        ))
+    }
+
+    /// Brings the main window's menu in line with the registered commands
+    /// and remembers commands it has not seen before.
+    pub fn update(&self, app: &AppHandle, commands: &[CommandState]) -> Result<()> {
+        if let Some(window) = app.get_window(MAIN_WINDOW_LABEL) {
+            let handle = window.menu_handle();
+
+            for command in &self.built {
+                let Some(item) = handle.try_get_item(&item_id(&command.id)) else {
+                    continue;
+                };
+                let state = commands.iter().find(|state| state.id == command.id);
+
+                let _ = item.set_enabled(state.is_some_and(|state| state.enabled));
+                if let Some(state) = state {
+                    let _ = item.set_title(&state.title);
+                }
+                if command.checkable {
+                    let _ = item.set_selected(state.and_then(|state| state.checked) == Some(true));
+                }
+            }
+        }
+
+        let mut catalogue = lock(&self.catalogue);
+        if manifest::merge(&mut catalogue, commands) {
+            if let Some(path) = &self.catalogue_path {
+                files::write_atomic(path, &serde_json::to_vec_pretty(&*catalogue)?)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn item_id(command_id: &str) -> String {
+    format!("{COMMAND_ID_PREFIX}{command_id}")
+}
+
+fn command_menu(entries: &[manifest::MenuEntry]) -> Menu {
+    entries.iter().fold(Menu::new(), |menu, entry| {
+        let mut item =
+            CustomMenuItem::new(item_id(&entry.command.id), &entry.command.title).disabled();
+
+        if let Some(accelerator) = &entry.accelerator {
+            item = item.accelerator(accelerator);
+        }
+        // GTK only makes an item checkable if it starts out checked; the
+        // first manifest sets the real state.
+        if entry.command.checkable {
+            item = item.selected();
+        }
+
+        menu.add_item(item)
+    })
+}
+
+pub fn handle_event(event: WindowMenuEvent) {
+    let id = event.menu_item_id();
+
+    if id == QUIT_ID {
+        close_guard::request_quit(&event.window().app_handle());
+    } else if let Some(command_id) = id.strip_prefix(COMMAND_ID_PREFIX) {
+        let _ = event.window().emit(MENU_COMMAND_EVENT, command_id);
+    }
+}
@@ -0,0 +1,39 @@
+// desktop/src-tauri/src/network/commands.rs
+
+use std::path::PathBuf;
+
+use tauri::State;
+
+use super::{Network, NetworkSettings, ProxySettings};
+use crate::error::Result;
+
+#[tauri::command]
+pub fn get_network_settings(network: State<'_, Network>) -> NetworkSettings {
+    network.settings()
+}
+
+/// Leaving `password` out keeps the saved one.
+#[tauri::command]
+pub async fn set_proxy_settings(
+    network: State<'_, Network>,
+    proxy: ProxySettings,
+    password: Option<String>,
+) -> Result<NetworkSettings> {
+    network.set_proxy(proxy, password)?;
+    Ok(network.settings())
+}
+
+#[tauri::command]
+pub fn import_root_certificate(
+    network: State<'_, Network>,
+    path: PathBuf,
+) -> Result<NetworkSettings> {
+    network.import_certificate(&path)?;
+    Ok(network.settings())
+}
+
+#[tauri::command]
+pub fn remove_root_certificate(network: State<'_, Network>, id: String) -> Result<NetworkSettings> {
+    network.remove_certificate(&id)?;
+    Ok(network.settings())
+}
@@ -0,0 +1,16 @@
+// desktop/src-tauri/src/network/config.rs
+
+use std::path::PathBuf;
+
+use zeroize::Zeroizing;
+
+use super::proxy::ProxySettings;
+
+/// What a client needs from the network settings, resolved once per client:
+/// the proxy with its unsealed password and the imported root certificates.
+#[derive(Clone, Default)]
+pub struct NetworkConfig {
+    pub proxy: ProxySettings,
+    pub proxy_password: Option<Zeroizing<String>>,
+    pub root_certificates: Vec<PathBuf>,
+}
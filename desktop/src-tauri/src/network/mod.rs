@@ -0,0 +1,261 @@
+// desktop/src-tauri/src/network/mod.rs
+
+/*
+ * Proxy and trust settings for the HTTP the crate does natively.
+ *
+ * The settings live in `network.json` in the app config directory. The sync
+ * worker and the server check build their clients from `Network::config`,
+ * so a change applies from the next request. The Tauri updater builds its
+ * own client, so it is pointed at a manual proxy through the environment
+ * before the first client exists. The environment only carries the proxy
+ * address, so the updater follows a changed proxy after a restart, does not
+ * sign in to it, and trusts only the operating system's roots; Settings
+ * tells the user so. Mail and the rest of the webview's API traffic go
+ * through the webview, which uses the operating system's proxy and trust
+ * settings.
+ *
+ * The proxy password is sealed with a key derived from the machine id, like
+ * a vault without a passphrase, and never returned to the webview.
+ */
+
+pub mod certificates;
+pub mod commands;
+mod config;
+mod proxy;
+
+pub use certificates::CertificateInfo;
+pub use config::NetworkConfig;
+pub use proxy::{ProxyMode, ProxySettings};
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::Config;
+use zeroize::Zeroizing;
+
+use crate::crypto::{self, machine_secret, SALT_LEN};
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+
+pub const NETWORK_FILE_NAME: &str = "network.json";
+
+const PASSWORD_CONTEXT: &[u8] = b"pioneer-work-suite/network/proxy-password/v1";
+
+const MAX_CERTIFICATES: usize = 32;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedPassword {
+    salt: String,
+    sealed: String,
+}
+
+impl SealedPassword {
+    fn seal(password: &str) -> Result<Self> {
+        let salt = crypto::random_bytes::<SALT_LEN>();
+        let key = crypto::derive_key(&machine_secret()?, &salt)?;
+
+        Ok(Self {
+            salt: BASE64.encode(salt),
+            sealed: BASE64.encode(crypto::seal(&key, password.as_bytes(), PASSWORD_CONTEXT)?),
+        })
+    }
+
+    fn open(&self) -> Result<Zeroizing<String>> {
+        let damaged = || Error::Crypto("The saved proxy password is damaged.");
+
+        let salt = BASE64.decode(&self.salt).map_err(|_| damaged())?;
+        let sealed = BASE64.decode(&self.sealed).map_err(|_| damaged())?;
+        let key = crypto::derive_key(&machine_secret()?, &salt)?;
+        let password = crypto::open(&key, &sealed, PASSWORD_CONTEXT)?;
+
+        String::from_utf8(password)
+            .map(Zeroizing::new)
+            .map_err(|_| damaged())
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkFile {
+    #[serde(default)]
+    proxy: ProxySettings,
+    #[serde(default)]
+    proxy_password: Option<SealedPassword>,
+    #[serde(default)]
+    certificates: Vec<CertificateInfo>,
+}
+
+/// The settings as the webview sees them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSettings {
+    pub proxy: ProxySettings,
+    pub has_proxy_password: bool,
+    pub certificates: Vec<CertificateInfo>,
+}
+
+struct State {
+    file: NetworkFile,
+    /// The unsealed proxy password, or `None` when there is none or it was
+    /// sealed on another computer.
+    password: Option<Zeroizing<String>>,
+}
+
+pub struct Network {
+    path: PathBuf,
+    certificates_dir: PathBuf,
+    state: Mutex<State>,
+}
+
+impl Network {
+    /// Settings that cannot be read are replaced by the defaults: the system
+    /// proxy and no extra certificates.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let certificates_dir = path
+            .parent()
+            .map(|parent| parent.join(certificates::CERTIFICATES_DIR_NAME))
+            .unwrap_or_else(|| PathBuf::from(certificates::CERTIFICATES_DIR_NAME));
+
+        let file = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<NetworkFile>(&bytes).ok())
+            .unwrap_or_default();
+        let password = file
+            .proxy_password
+            .as_ref()
+            .and_then(|sealed| sealed.open().ok());
+
+        Self {
+            path,
+            certificates_dir,
+            state: Mutex::new(State { file, password }),
+        }
+    }
+
+    /// Like the menu catalogue, the settings are read before the app is
+    /// built, so the proxy environment is in place before any client is.
+    pub fn open_for_config(config: &Config) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir_for(config)?.join(NETWORK_FILE_NAME),
+        ))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn settings(&self) -> NetworkSettings {
+        let state = self.lock();
+
+        NetworkSettings {
+            proxy: state.file.proxy.clone(),
+            has_proxy_password: state.password.is_some(),
+            certificates: state.file.certificates.clone(),
+        }
+    }
+
+    pub fn config(&self) -> NetworkConfig {
+        let state = self.lock();
+
+        NetworkConfig {
+            proxy: state.file.proxy.clone(),
+            proxy_password: state.password.clone(),
+            root_certificates: state
+                .file
+                .certificates
+                .iter()
+                .map(|certificate| certificates::path_for(&self.certificates_dir, &certificate.id))
+                .collect(),
+        }
+    }
+
+    /// Applies `change` and writes the result, leaving the settings as they
+    /// were if either fails.
+    fn update(&self, change: impl FnOnce(&mut NetworkFile) -> Result<()>) -> Result<()> {
+        let mut state = self.lock();
+        let mut next = state.file.clone();
+
+        change(&mut next)?;
+        files::write_atomic(&self.path, &serde_json::to_vec_pretty(&next)?)?;
+
+        state.file = next;
+        Ok(())
+    }
+
+    /// Saves the proxy settings. `password` replaces the saved password when
+    /// given, and an empty one or a blank username removes it.
+    pub fn set_proxy(&self, proxy: ProxySettings, password: Option<String>) -> Result<()> {
+        let proxy = proxy.normalized()?;
+
+        // `None` keeps the saved password and `Some(None)` removes it.
+        let password = if proxy.username.is_empty() {
+            Some(None)
+        } else {
+            password.map(|password| Some(Zeroizing::new(password)).filter(|p| !p.is_empty()))
+        };
+        let sealed = match &password {
+            Some(Some(password)) => Some(Some(SealedPassword::seal(password)?)),
+            Some(None) => Some(None),
+            None => None,
+        };
+
+        self.update(|file| {
+            file.proxy = proxy;
+            if let Some(sealed) = sealed {
+                file.proxy_password = sealed;
+            }
+            Ok(())
+        })?;
+
+        if let Some(password) = password {
+            self.lock().password = password;
+        }
+        Ok(())
+    }
+
+    pub fn import_certificate(&self, source: &Path) -> Result<()> {
+        if self.lock().file.certificates.len() >= MAX_CERTIFICATES {
+            return Err(Error::InvalidInput(format!(
+                "Import at most {MAX_CERTIFICATES} root certificates."
+            )));
+        }
+
+        let imported = certificates::import(&self.certificates_dir, source)?;
+
+        self.update(|file| {
+            file.certificates
+                .retain(|certificate| certificate.id != imported.id);
+            file.certificates.push(imported);
+            Ok(())
+        })
+    }
+
+    pub fn remove_certificate(&self, id: &str) -> Result<()> {
+        self.update(|file| {
+            file.certificates.retain(|certificate| certificate.id != id);
+            Ok(())
+        })?;
+
+        certificates::remove(&self.certificates_dir, id)
+    }
+
+    /// Points reqwest's system proxy lookup, and with it the updater, at
+    /// the saved proxy, without its credentials. Only takes full effect
+    /// before the first client is built, since reqwest reads the proxy
+    /// variables once per process.
+    pub fn apply_to_environment(&self) {
+        for (name, value) in self.lock().file.proxy.environment() {
+            env::set_var(name, value);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// desktop/src-tauri/src/paths.rs
+
+use std::fs;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Config};
+
+use crate::error::{Error, Result};
+
+/// Returns the per-user app data directory, creating it if necessary.
+///
+/// Everything the desktop client owns on disk (the local store, snapshots,
+/// reminder state) lives below this directory so it can be backed up as a
+/// single folder.
+pub fn app_data_dir(app: &AppHandle) -> Result<PathBuf> {
+    let directory = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or(Error::AppDataDirUnavailable)?;
+
+    fs::create_dir_all(&directory)?;
+    Ok(directory)
+}
+
+/// Returns the per-user app config directory, creating it if necessary.
+pub fn app_config_dir(app: &AppHandle) -> Result<PathBuf> {
+    let directory = app
+        .path_resolver()
+        .app_config_dir()
+        .ok_or(Error::AppConfigDirUnavailable)?;
+
+    fs::create_dir_all(&directory)?;
+    Ok(directory)
+}
+
+/// Like [`app_config_dir`], for code that runs before the app is built and
+/// only has its config, such as the native menu.
+pub fn app_config_dir_for(config: &Config) -> Result<PathBuf> {
+    let directory =
+        tauri::api::path::app_config_dir(config).ok_or(Error::AppConfigDirUnavailable)?;
+
+    fs::create_dir_all(&directory)?;
+    Ok(directory)
+}
+
+/// Like [`app_data_dir`], for the command-line mode, which never builds
+/// the app.
+pub fn app_data_dir_for(config: &Config) -> Result<PathBuf> {
+    let directory = tauri::api::path::app_data_dir(config).ok_or(Error::AppDataDirUnavailable)?;
+
+    fs::create_dir_all(&directory)?;
+    Ok(directory)
+}
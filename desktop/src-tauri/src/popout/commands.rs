@@ -0,0 +1,34 @@
+// desktop/src-tauri/src/popout/commands.rs
+
+use tauri::{AppHandle, Manager, Window};
+
+use super::Popout;
+use crate::deep_link;
+use crate::error::{Error, Result};
+use crate::sync::engine::Resource;
+
+#[tauri::command]
+pub fn open_popout(app: AppHandle, popout: Popout) -> Result<()> {
+    super::open(&app, &popout)
+}
+
+/// Tells every other window that `resource` changed in `window`.
+#[tauri::command]
+pub fn broadcast_change(app: AppHandle, window: Window, resource: Resource) {
+    for (label, other) in app.windows() {
+        if label != window.label() {
+            let _ = other.emit(resource.changed_event(), ());
+        }
+    }
+}
+
+/// Opens `route` in the main window, e.g. a task linked from a pop-out.
+#[tauri::command]
+pub fn show_in_main_window(app: AppHandle, route: String) -> Result<()> {
+    if !route.starts_with('/') {
+        return Err(Error::InvalidInput(format!("{route} is not an app route.")));
+    }
+
+    deep_link::open_route(&app, route);
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// desktop/src-tauri/src/popout/mod.rs
+
+/*
+ * Documents and the calendar in windows of their own.
+ *
+ * Each pop-out is a labeled webview loading the same bundle at
+ * `#/popout/...`. There is one window per document and one calendar
+ * window; asking for one that is already open brings it forward. Pop-outs
+ * remember their geometry like the main window.
+ *
+ * Every window keeps its own view of the data, so a window that changes
+ * something calls `broadcast_change` and the others receive the same
+ * `pioneer:*-changed` event the sync worker sends after a pass.
+ */
+
+pub mod commands;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+
+use crate::error::{Error, Result};
+use crate::window_state::WindowStates;
+
+/// Labels of document windows start with this, followed by the id.
+pub const DOCUMENT_LABEL_PREFIX: &str = "document-";
+
+const MAX_ID_LENGTH: usize = 128;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Popout {
+    Document { id: String },
+    Calendar,
+}
+
+impl Popout {
+    fn validate(&self) -> Result<()> {
+        if let Popout::Document { id } = self {
+            let valid = !id.is_empty()
+                && id.len() <= MAX_ID_LENGTH
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+            if !valid {
+                return Err(Error::InvalidInput(
+                    "This document cannot be opened in a new window.".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Popout::Document { id } => format!("{DOCUMENT_LABEL_PREFIX}{id}"),
+            Popout::Calendar => "calendar".to_string(),
+        }
+    }
+
+    fn route(&self) -> String {
+        match self {
+            Popout::Document { id } => format!("/popout/document/{id}"),
+            Popout::Calendar => "/popout/calendar".to_string(),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Popout::Document { .. } => "Document",
+            Popout::Calendar => "Calendar",
+        }
+    }
+
+    fn size(&self) -> (f64, f64) {
+        match self {
+            Popout::Document { .. } => (820.0, 900.0),
+            Popout::Calendar => (1000.0, 760.0),
+        }
+    }
+}
+
+/// Shows the pop-out, creating its window if it is not open yet.
+pub fn open(app: &AppHandle, popout: &Popout) -> Result<()> {
+    popout.validate()?;
+
+    let label = popout.label();
+
+    if let Some(window) = app.get_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let (width, height) = popout.size();
+    let url = WindowUrl::App(format!("index.html#{}", popout.route()).into());
+
+    let window = WindowBuilder::new(app, label, url)
+        .title(popout.title())
+        .inner_size(width, height)
+        .min_inner_size(480.0, 360.0)
+        .visible(false)
+        .build()
+        .map_err(|error| Error::InvalidInput(format!("Unable to open the window: {error}")))?;
+
+    // Pop-outs have no command registry behind the app menu bar.
+    let _ = window.menu_handle().hide();
+    app.state::<WindowStates>().restore(&window);
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    Ok(())
+}
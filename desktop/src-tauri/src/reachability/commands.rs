@@ -0,0 +1,10 @@
+// desktop/src-tauri/src/reachability/commands.rs
+
+use tauri::State;
+
+use super::{NetworkStatus, Reachability};
+
+#[tauri::command]
+pub fn get_network_status(reachability: State<'_, Reachability>) -> NetworkStatus {
+    reachability.status()
+}
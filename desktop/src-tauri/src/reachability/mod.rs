@@ -0,0 +1,188 @@
+// desktop/src-tauri/src/reachability/mod.rs
+
+/*
+ * Whether the active API server can be reached, for the sync layer.
+ *
+ * `navigator.onLine` only knows whether some interface is up, and inside a
+ * webview it is often simply wrong. Instead a monitor ticks every few
+ * seconds and probes the server through the same proxy and certificates as
+ * the sync worker: once a minute while online, more often while offline,
+ * and straight away after the computer wakes from sleep or the route to
+ * the internet moves to another interface.
+ *
+ * Any HTTP answer counts as online, as does a failed TLS handshake, since
+ * that is a trust problem the sync worker reports on its own. Every change
+ * is emitted as `pioneer:network-status`; the webview answers reconnects
+ * and wake-ups with a sync.
+ */
+
+pub mod commands;
+mod signals;
+
+use std::net::{IpAddr, UdpSocket};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::network::Network;
+use crate::servers::{Servers, HEALTH_CHECK_PATH};
+use crate::sync::{self, client};
+use signals::{Reason, Trigger};
+
+pub const NETWORK_STATUS_EVENT: &str = "pioneer:network-status";
+
+const TICK: Duration = Duration::from_secs(5);
+const ONLINE_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(15);
+const PROBE_TIMEOUT_SECONDS: u64 = 10;
+
+// Documentation addresses: connecting a UDP socket to them sends nothing,
+// but makes the OS pick the interface and source address it would use.
+const ROUTE_TARGETS: [&str; 2] = ["192.0.2.1:9", "[2001:db8::1]:9"];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    /// `None` until the first probe has finished.
+    pub online: Option<bool>,
+    pub reason: Option<Reason>,
+    pub checked_at: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Reachability {
+    status: Mutex<NetworkStatus>,
+    wake: Notify,
+}
+
+impl Reachability {
+    fn lock(&self) -> MutexGuard<'_, NetworkStatus> {
+        self.status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn status(&self) -> NetworkStatus {
+        self.lock().clone()
+    }
+
+    /// Probes again without waiting for the next scheduled probe.
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+/// The source address for each route target, which changes whenever the
+/// default route moves to another interface or goes away.
+fn local_routes() -> Vec<Option<IpAddr>> {
+    ROUTE_TARGETS
+        .iter()
+        .map(|target| {
+            let any = if target.starts_with('[') {
+                "[::]:0"
+            } else {
+                "0.0.0.0:0"
+            };
+            let socket = UdpSocket::bind(any).ok()?;
+            socket.connect(target).ok()?;
+            socket.local_addr().ok().map(|address| address.ip())
+        })
+        .collect()
+}
+
+async fn probe(app: &AppHandle) -> bool {
+    let mut profile = app.state::<Servers>().active();
+    profile.timeout_seconds = profile.timeout_seconds.min(PROBE_TIMEOUT_SECONDS);
+
+    // A client that cannot be built says nothing about the network; the
+    // sync worker reports the broken setting.
+    let Ok(http) = client::http_client(&profile, &app.state::<Network>().config()) else {
+        return true;
+    };
+
+    match http
+        .get(format!("{}{HEALTH_CHECK_PATH}", profile.base_url))
+        .send()
+        .await
+    {
+        Ok(_) => true,
+        Err(error) => matches!(
+            client::ApiError::from_request(error),
+            client::ApiError::Tls(_)
+        ),
+    }
+}
+
+fn record(app: &AppHandle, online: bool, trigger: Trigger) {
+    let reachability = app.state::<Reachability>();
+
+    let (previous, status) = {
+        let mut status = reachability.lock();
+        let previous = status.online;
+        let reason = signals::announce(previous, online, trigger);
+
+        status.online = Some(online);
+        status.checked_at = Some(chrono::Utc::now().to_rfc3339());
+        if reason.is_some() {
+            status.reason = reason;
+        }
+
+        (previous, reason.map(|_| status.clone()))
+    };
+
+    if previous != Some(online) {
+        sync::set_reachable(app, online);
+    }
+    if let Some(status) = status {
+        let _ = app.emit_all(NETWORK_STATUS_EVENT, status);
+    }
+}
+
+/// Spawns the monitor, which probes once at startup.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let reachability = app.state::<Reachability>();
+        let mut routes = local_routes();
+        let mut trigger = Some(Trigger::Scheduled);
+        let mut next_probe = Instant::now();
+
+        loop {
+            if trigger.is_some() || Instant::now() >= next_probe {
+                let online = probe(&app).await;
+                record(&app, online, trigger.take().unwrap_or(Trigger::Scheduled));
+
+                next_probe = Instant::now()
+                    + if online {
+                        ONLINE_PROBE_INTERVAL
+                    } else {
+                        OFFLINE_PROBE_INTERVAL
+                    };
+            }
+
+            let (ticked_at, ticked_at_wall) = (Instant::now(), SystemTime::now());
+            let requested = tokio::select! {
+                _ = tokio::time::sleep(TICK) => false,
+                _ = reachability.wake.notified() => true,
+            };
+
+            let wall = ticked_at_wall.elapsed().unwrap_or_default();
+            let current_routes = local_routes();
+
+            trigger = if signals::slept(TICK, wall, ticked_at.elapsed()) {
+                Some(Trigger::Resumed)
+            } else if current_routes != routes {
+                Some(Trigger::NetworkChanged)
+            } else if requested {
+                Some(Trigger::Requested)
+            } else {
+                None
+            };
+            routes = current_routes;
+        }
+    });
+}
@@ -0,0 +1,102 @@
+// desktop/src-tauri/src/reachability/signals.rs
+
+/*
+ * When a probe result is worth telling the webview about.
+ *
+ * Sleep is noticed from the gap between two ticks of the monitor: a laptop
+ * that slept for ten minutes wakes up to a tick that is ten minutes late on
+ * the wall clock. Monotonic clocks stop during sleep on some platforms and
+ * keep going on others, so the larger of the two gaps is used.
+ */
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+// Far longer than a busy runtime ever delays a timer.
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// What made the monitor probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Scheduled,
+    Requested,
+    Resumed,
+    NetworkChanged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reason {
+    /// The first probe since startup.
+    Checked,
+    Reconnected,
+    Disconnected,
+    Resumed,
+    NetworkChanged,
+}
+
+/// True when a tick scheduled `interval` after the previous one arrived so
+/// late that the computer must have been asleep in between.
+pub fn slept(interval: Duration, wall: Duration, monotonic: Duration) -> bool {
+    wall.max(monotonic).saturating_sub(interval) > SLEEP_THRESHOLD
+}
+
+/// The reason to announce a probe that found the server `online`, or `None`
+/// when nothing changed that the webview would act on.
+pub fn announce(previous: Option<bool>, online: bool, trigger: Trigger) -> Option<Reason> {
+    match previous {
+        None => Some(Reason::Checked),
+        Some(false) if online => Some(Reason::Reconnected),
+        Some(true) if !online => Some(Reason::Disconnected),
+        Some(_) if !online => None,
+        Some(_) => match trigger {
+            Trigger::Resumed => Some(Reason::Resumed),
+            Trigger::NetworkChanged => Some(Reason::NetworkChanged),
+            Trigger::Scheduled | Trigger::Requested => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn a_late_tick_on_either_clock_means_sleep() {
+        let on_time = Duration::from_secs(6);
+        let late = Duration::from_secs(10 * 60);
+
+        assert!(!slept(TICK, on_time, on_time));
+        assert!(slept(TICK, late, on_time));
+        assert!(slept(TICK, on_time, late));
+        // A wall clock set back by NTP is not sleep.
+        assert!(!slept(TICK, Duration::ZERO, on_time));
+    }
+
+    #[test]
+    fn announces_changes_and_wake_ups_but_not_steady_state() {
+        use Trigger::*;
+
+        assert_eq!(announce(None, false, Scheduled), Some(Reason::Checked));
+        assert_eq!(
+            announce(Some(false), true, Scheduled),
+            Some(Reason::Reconnected)
+        );
+        assert_eq!(
+            announce(Some(true), false, Resumed),
+            Some(Reason::Disconnected)
+        );
+
+        assert_eq!(announce(Some(true), true, Resumed), Some(Reason::Resumed));
+        assert_eq!(
+            announce(Some(true), true, NetworkChanged),
+            Some(Reason::NetworkChanged)
+        );
+        assert_eq!(announce(Some(true), true, Scheduled), None);
+        assert_eq!(announce(Some(true), true, Requested), None);
+        assert_eq!(announce(Some(false), false, Resumed), None);
+    }
+}
@@ -0,0 +1,42 @@
+// desktop/src-tauri/src/reminders/commands.rs
+
+use tauri::State;
+
+use super::schedule::ReminderSettings;
+use super::{ReminderNotice, Reminders};
+use crate::error::Result;
+use crate::storage::LocalStore;
+
+#[tauri::command]
+pub fn list_reminders(
+    reminders: State<'_, Reminders>,
+    store: State<'_, LocalStore>,
+) -> Result<Vec<ReminderNotice>> {
+    reminders.refresh(&store)?;
+    Ok(reminders.list(&store))
+}
+
+#[tauri::command]
+pub fn snooze_reminder(reminders: State<'_, Reminders>, key: String, minutes: u32) -> Result<()> {
+    reminders.snooze(&key, minutes)
+}
+
+#[tauri::command]
+pub fn dismiss_reminder(reminders: State<'_, Reminders>, key: String) -> Result<()> {
+    reminders.dismiss(&key)
+}
+
+#[tauri::command]
+pub fn get_reminder_settings(reminders: State<'_, Reminders>) -> ReminderSettings {
+    reminders.settings()
+}
+
+#[tauri::command]
+pub fn set_reminder_settings(
+    reminders: State<'_, Reminders>,
+    store: State<'_, LocalStore>,
+    settings: ReminderSettings,
+) -> Result<ReminderSettings> {
+    reminders.set_settings(&store, settings)?;
+    Ok(reminders.settings())
+}
@@ -0,0 +1,303 @@
+// desktop/src-tauri/src/reminders/mod.rs
+
+/*
+ * Reminders for due tasks and upcoming events.
+ *
+ * The scheduler re-plans reminders from the local store every minute and
+ * sleeps until the next one is due. Each reminder raises a native
+ * notification when the main window is not focused and is always emitted
+ * to the webview as `pioneer:reminder`, where it can be snoozed or
+ * dismissed. Reminder state lives in the app data directory so snoozes,
+ * dismissals and reminders missed while the app was closed survive a
+ * restart.
+ *
+ * While the workspace is locked the store cannot be read; reminders that
+ * were already planned still go off, without their titles.
+ */
+
+pub mod commands;
+pub mod schedule;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::api::notification::Notification;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+use crate::storage::{LocalStore, RecordStore};
+use schedule::{Reminder, ReminderKind, ReminderSettings, ReminderState};
+
+pub const REMINDER_SETTINGS_FILE_NAME: &str = "reminder-settings.json";
+pub const REMINDERS_FILE_NAME: &str = "reminders.json";
+
+pub const REMINDER_EVENT: &str = "pioneer:reminder";
+
+// How often reminders are re-planned from the store, and the longest the
+// scheduler sleeps in any case.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+const MAX_SNOOZE_MINUTES: u32 = 24 * 60;
+
+const LOCKED_TITLE: &str = "Reminder";
+const LOCKED_BODY: &str = "Unlock Pioneer Work Suite to see it.";
+
+/// A reminder as the webview sees it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderNotice {
+    pub key: String,
+    pub kind: ReminderKind,
+    pub record_id: String,
+    pub title: String,
+    pub body: String,
+    pub due_at: String,
+    pub fire_at: String,
+    pub state: ReminderState,
+    pub snoozed_until: Option<String>,
+}
+
+impl ReminderNotice {
+    fn of(reminder: &Reminder, unlocked: bool) -> Self {
+        let now = Utc::now();
+        let (title, body) = if unlocked && !reminder.title.is_empty() {
+            (reminder.title.clone(), reminder.body(now))
+        } else {
+            (LOCKED_TITLE.to_string(), LOCKED_BODY.to_string())
+        };
+
+        Self {
+            key: reminder.key.clone(),
+            kind: reminder.kind,
+            record_id: reminder.record_id.clone(),
+            title,
+            body,
+            due_at: reminder.due_at.to_rfc3339(),
+            fire_at: reminder.fire_at.to_rfc3339(),
+            state: reminder.state,
+            snoozed_until: reminder.snoozed_until.map(|until| until.to_rfc3339()),
+        }
+    }
+}
+
+pub struct Reminders {
+    settings_path: PathBuf,
+    state_path: PathBuf,
+    settings: Mutex<ReminderSettings>,
+    reminders: Mutex<Vec<Reminder>>,
+    changed: Notify,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Reminders {
+    pub fn open(settings_path: impl Into<PathBuf>, state_path: impl Into<PathBuf>) -> Result<Self> {
+        let settings_path = settings_path.into();
+        let state_path = state_path.into();
+
+        let settings = if settings_path.exists() {
+            serde_json::from_slice(&fs::read(&settings_path)?)?
+        } else {
+            ReminderSettings::default()
+        };
+        // Unreadable state is only lost snoozes, so it does not stop startup.
+        let reminders = fs::read(&state_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Ok(Self {
+            settings_path,
+            state_path,
+            settings: Mutex::new(settings),
+            reminders: Mutex::new(reminders),
+            changed: Notify::new(),
+        })
+    }
+
+    pub fn open_in_app_dirs(app: &AppHandle) -> Result<Self> {
+        Self::open(
+            paths::app_config_dir(app)?.join(REMINDER_SETTINGS_FILE_NAME),
+            paths::app_data_dir(app)?.join(REMINDERS_FILE_NAME),
+        )
+    }
+
+    pub fn settings(&self) -> ReminderSettings {
+        lock(&self.settings).clone()
+    }
+
+    pub fn set_settings(&self, store: &LocalStore, settings: ReminderSettings) -> Result<()> {
+        settings.validate()?;
+
+        {
+            let mut current = lock(&self.settings);
+            files::write_atomic(&self.settings_path, &serde_json::to_vec_pretty(&settings)?)?;
+            *current = settings;
+        }
+
+        self.refresh(store)?;
+        self.changed.notify_one();
+        Ok(())
+    }
+
+    fn save(&self, reminders: &[Reminder]) -> Result<()> {
+        files::write_atomic(&self.state_path, &serde_json::to_vec(reminders)?)
+    }
+
+    /// Re-plans reminders from the store. Does nothing while it is locked.
+    pub fn refresh(&self, store: &LocalStore) -> Result<()> {
+        if !store.is_unlocked() {
+            return Ok(());
+        }
+
+        let tasks = store.read_records(RecordStore::Tasks)?;
+        let events = store.read_records(RecordStore::Events)?;
+        let planned = schedule::plan(&tasks, &events, &self.settings(), Utc::now());
+
+        let mut reminders = lock(&self.reminders);
+        let merged = schedule::merge(&reminders, planned);
+
+        if merged != *reminders {
+            self.save(&merged)?;
+            *reminders = merged;
+        }
+
+        Ok(())
+    }
+
+    /// Upcoming, snoozed and fired reminders, soonest first.
+    pub fn list(&self, store: &LocalStore) -> Vec<ReminderNotice> {
+        let unlocked = store.is_unlocked();
+
+        lock(&self.reminders)
+            .iter()
+            .filter(|reminder| reminder.state != ReminderState::Dismissed)
+            .map(|reminder| ReminderNotice::of(reminder, unlocked))
+            .collect()
+    }
+
+    fn update(&self, key: &str, change: impl FnOnce(&mut Reminder)) -> Result<()> {
+        let mut reminders = lock(&self.reminders);
+        let reminder = reminders
+            .iter_mut()
+            .find(|reminder| reminder.key == key)
+            .ok_or_else(|| Error::InvalidInput("That reminder no longer exists.".to_string()))?;
+
+        change(reminder);
+        self.save(&reminders)?;
+        drop(reminders);
+
+        self.changed.notify_one();
+        Ok(())
+    }
+
+    pub fn snooze(&self, key: &str, minutes: u32) -> Result<()> {
+        if !(1..=MAX_SNOOZE_MINUTES).contains(&minutes) {
+            return Err(Error::InvalidInput(
+                "Snooze for between a minute and a day.".to_string(),
+            ));
+        }
+
+        self.update(key, |reminder| {
+            reminder.state = ReminderState::Snoozed;
+            reminder.snoozed_until = Some(Utc::now() + chrono::Duration::minutes(minutes.into()));
+        })
+    }
+
+    pub fn dismiss(&self, key: &str) -> Result<()> {
+        self.update(key, |reminder| {
+            reminder.state = ReminderState::Dismissed;
+            reminder.snoozed_until = None;
+        })
+    }
+
+    /// Marks the reminders that are due as fired and returns them.
+    fn take_due(&self) -> Result<Vec<Reminder>> {
+        let now = Utc::now();
+        let mut reminders = lock(&self.reminders);
+        let mut due = Vec::new();
+
+        for reminder in reminders.iter_mut() {
+            if reminder.next_fire().is_some_and(|at| at <= now) {
+                reminder.state = ReminderState::Fired;
+                reminder.snoozed_until = None;
+                due.push(reminder.clone());
+            }
+        }
+
+        if !due.is_empty() {
+            self.save(&reminders)?;
+        }
+
+        Ok(due)
+    }
+
+    /// How long until the next reminder is due.
+    fn next_due(&self) -> Option<Duration> {
+        let now = Utc::now();
+
+        lock(&self.reminders)
+            .iter()
+            .filter_map(Reminder::next_fire)
+            .min()
+            .map(|at| (at - now).to_std().unwrap_or_default())
+    }
+}
+
+fn notify(app: &AppHandle, notice: &ReminderNotice) {
+    let focused = app
+        .get_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false);
+
+    if !focused {
+        let _ = Notification::new(&app.config().tauri.bundle.identifier)
+            .title(&notice.title)
+            .body(&notice.body)
+            .show();
+    }
+
+    let _ = app.emit_all(REMINDER_EVENT, notice);
+}
+
+/// Spawns the reminder scheduler.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let reminders = app.state::<Reminders>();
+            let store = app.state::<LocalStore>();
+
+            let _ = reminders.refresh(&store);
+
+            if let Ok(due) = reminders.take_due() {
+                let unlocked = store.is_unlocked();
+
+                for reminder in &due {
+                    notify(&app, &ReminderNotice::of(reminder, unlocked));
+                }
+            }
+
+            let delay = reminders.next_due().map_or(REFRESH_INTERVAL, |remaining| {
+                remaining.min(REFRESH_INTERVAL)
+            });
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = reminders.changed.notified() => {}
+            }
+        }
+    });
+}
@@ -0,0 +1,446 @@
+// desktop/src-tauri/src/reminders/schedule.rs
+
+/*
+ * Reminder planning.
+ *
+ * Reminders are derived from the cached task and event records: one per
+ * lead time before each open task's due date and each event's start. Task
+ * due dates and all-day events have no time of day, so they are due at the
+ * configured `allDayTime`. A reminder's key names its record, due time and
+ * lead time, which lets snoozes and dismissals carry over when the plan is
+ * rebuilt and drop away once the record changes.
+ */
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+const MAX_LEAD_MINUTES: u32 = 7 * 24 * 60;
+const MAX_LEAD_TIMES: usize = 5;
+
+/// Reminders are planned this far ahead of now.
+const HORIZON: chrono::Duration = chrono::Duration::days(8);
+
+/// A reminder missed while the app was closed still fires if it is at most
+/// this late.
+const MISSED_GRACE: chrono::Duration = chrono::Duration::hours(12);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderSettings {
+    pub enabled: bool,
+    /// `HH:MM`, local time.
+    pub all_day_time: String,
+    pub task_lead_minutes: Vec<u32>,
+    pub event_lead_minutes: Vec<u32>,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            all_day_time: "09:00".to_string(),
+            task_lead_minutes: vec![0],
+            event_lead_minutes: vec![15],
+        }
+    }
+}
+
+impl ReminderSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.all_day_time().is_none() {
+            return Err(Error::InvalidInput(
+                "Choose a valid time for all-day reminders.".to_string(),
+            ));
+        }
+
+        for leads in [&self.task_lead_minutes, &self.event_lead_minutes] {
+            if leads.len() > MAX_LEAD_TIMES {
+                return Err(Error::InvalidInput(format!(
+                    "Choose at most {MAX_LEAD_TIMES} reminder times."
+                )));
+            }
+
+            if leads.iter().any(|lead| *lead > MAX_LEAD_MINUTES) {
+                return Err(Error::InvalidInput(
+                    "Reminders can be at most a week ahead.".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn all_day_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(&self.all_day_time, "%H:%M").ok()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderKind {
+    Task,
+    Event,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderState {
+    Pending,
+    Snoozed,
+    Fired,
+    Dismissed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub key: String,
+    pub kind: ReminderKind,
+    pub record_id: String,
+    pub due_at: DateTime<Utc>,
+    pub all_day: bool,
+    pub fire_at: DateTime<Utc>,
+    pub state: ReminderState,
+    #[serde(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// Only kept in memory, so the state file holds no record contents.
+    #[serde(skip)]
+    pub title: String,
+}
+
+impl Reminder {
+    /// When the reminder should go off next, if it still should.
+    pub fn next_fire(&self) -> Option<DateTime<Utc>> {
+        match self.state {
+            ReminderState::Pending => Some(self.fire_at),
+            ReminderState::Snoozed => self.snoozed_until,
+            ReminderState::Fired | ReminderState::Dismissed => None,
+        }
+    }
+
+    /// The notification text, e.g. "Starts at 14:00" or "Due tomorrow".
+    pub fn body(&self, now: DateTime<Utc>) -> String {
+        let due = self.due_at.with_timezone(&Local);
+        let today = now.with_timezone(&Local).date_naive();
+        let day = match (due.date_naive() - today).num_days() {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            _ => due.format("on %a %-d %b").to_string(),
+        };
+
+        match (self.kind, self.all_day) {
+            (ReminderKind::Task, _) => format!("Due {day}"),
+            (ReminderKind::Event, true) => format!("All day {day}"),
+            (ReminderKind::Event, false) if day == "today" => {
+                format!("Starts at {}", due.format("%H:%M"))
+            }
+            (ReminderKind::Event, false) => format!("Starts {day} at {}", due.format("%H:%M")),
+        }
+    }
+}
+
+fn local_time(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Reads a `YYYY-MM-DD` due date, or the local date of a full timestamp,
+/// like `getDueDateKey` in the web client.
+pub(crate) fn due_date(value: &str) -> Option<NaiveDate> {
+    value
+        .get(..10)
+        .and_then(|key| NaiveDate::parse_from_str(key, "%Y-%m-%d").ok())
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|time| time.with_timezone(&Local).date_naive())
+        })
+}
+
+fn string<'a>(record: &'a Value, field: &str) -> Option<&'a str> {
+    record
+        .get(field)?
+        .as_str()
+        .filter(|value| !value.is_empty())
+}
+
+struct Due {
+    kind: ReminderKind,
+    record_id: String,
+    title: String,
+    due_at: DateTime<Utc>,
+    all_day: bool,
+}
+
+/// Plans the reminders for `tasks` and `events` around `now`, all pending.
+pub fn plan(
+    tasks: &[Value],
+    events: &[Value],
+    settings: &ReminderSettings,
+    now: DateTime<Utc>,
+) -> Vec<Reminder> {
+    let Some(all_day_time) = settings.all_day_time().filter(|_| settings.enabled) else {
+        return Vec::new();
+    };
+
+    let task_due = tasks
+        .iter()
+        .filter(|task| string(task, "status") != Some("done"))
+        .filter_map(|task| {
+            let date = due_date(string(task, "dueDate")?)?;
+            Some(Due {
+                kind: ReminderKind::Task,
+                record_id: string(task, "id")?.to_string(),
+                title: string(task, "title").unwrap_or("Untitled task").to_string(),
+                due_at: local_time(date, all_day_time)?,
+                all_day: true,
+            })
+        });
+
+    let event_due = events.iter().filter_map(|event| {
+        let start = DateTime::parse_from_rfc3339(string(event, "start")?).ok()?;
+        let all_day = event
+            .get("allDay")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let due_at = if all_day {
+            local_time(start.with_timezone(&Local).date_naive(), all_day_time)?
+        } else {
+            start.with_timezone(&Utc)
+        };
+
+        Some(Due {
+            kind: ReminderKind::Event,
+            record_id: string(event, "id")?.to_string(),
+            title: string(event, "title")
+                .unwrap_or("Untitled event")
+                .to_string(),
+            due_at,
+            all_day,
+        })
+    });
+
+    let mut reminders = Vec::new();
+
+    for due in task_due.chain(event_due) {
+        let leads = match due.kind {
+            ReminderKind::Task => &settings.task_lead_minutes,
+            ReminderKind::Event => &settings.event_lead_minutes,
+        };
+
+        for lead in leads {
+            let fire_at = due.due_at - chrono::Duration::minutes(i64::from(*lead));
+
+            if fire_at < now - MISSED_GRACE || fire_at > now + HORIZON {
+                continue;
+            }
+
+            let kind = match due.kind {
+                ReminderKind::Task => "task",
+                ReminderKind::Event => "event",
+            };
+
+            reminders.push(Reminder {
+                key: format!("{kind}:{}:{}:{lead}", due.record_id, due.due_at.timestamp()),
+                kind: due.kind,
+                record_id: due.record_id.clone(),
+                due_at: due.due_at,
+                all_day: due.all_day,
+                fire_at,
+                state: ReminderState::Pending,
+                snoozed_until: None,
+                title: due.title.clone(),
+            });
+        }
+    }
+
+    reminders.sort_by(|a, b| a.fire_at.cmp(&b.fire_at).then_with(|| a.key.cmp(&b.key)));
+    reminders.dedup_by(|a, b| a.key == b.key);
+    reminders
+}
+
+/// Carries the state of `existing` reminders over to a new plan. Reminders
+/// that are no longer planned are dropped.
+///
+/// Records created offline get a new id when they first sync, so a reminder
+/// for an `offline-` id that disappeared also carries over to an otherwise
+/// identical reminder; without that it would go off again.
+pub fn merge(existing: &[Reminder], planned: Vec<Reminder>) -> Vec<Reminder> {
+    let renamed = |previous: &Reminder, reminder: &Reminder| {
+        previous.record_id.starts_with("offline-")
+            && previous.kind == reminder.kind
+            && previous.due_at == reminder.due_at
+            && previous.fire_at == reminder.fire_at
+            && !planned
+                .iter()
+                .any(|other| other.record_id == previous.record_id)
+    };
+
+    planned
+        .iter()
+        .cloned()
+        .map(|mut reminder| {
+            let previous = existing
+                .iter()
+                .find(|previous| previous.key == reminder.key)
+                .or_else(|| {
+                    existing
+                        .iter()
+                        .find(|previous| renamed(previous, &reminder))
+                });
+
+            if let Some(previous) = previous {
+                reminder.state = previous.state;
+                reminder.snoozed_until = previous.snoozed_until;
+            }
+            reminder
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn settings() -> ReminderSettings {
+        ReminderSettings {
+            task_lead_minutes: vec![0, 24 * 60],
+            ..ReminderSettings::default()
+        }
+    }
+
+    #[test]
+    fn plans_lead_times_for_open_tasks_and_events() {
+        let now = at("2024-03-04T08:00:00Z");
+        let tasks = [
+            json!({ "id": "t1", "title": "Essay", "status": "todo", "dueDate": "2024-03-06" }),
+            json!({ "id": "t2", "title": "Done", "status": "done", "dueDate": "2024-03-06" }),
+            json!({ "id": "t3", "title": "Someday", "status": "todo", "dueDate": null }),
+        ];
+        let events = [
+            json!({ "id": "e1", "title": "Standup", "start": "2024-03-04T09:00:00.000Z", "allDay": false }),
+            json!({ "id": "e2", "title": "Next month", "start": "2024-04-04T09:00:00.000Z", "allDay": false }),
+        ];
+
+        let reminders = plan(&tasks, &events, &settings(), now);
+        let summary: Vec<(&str, String)> = reminders
+            .iter()
+            .map(|reminder| (reminder.record_id.as_str(), reminder.fire_at.to_rfc3339()))
+            .collect();
+
+        let nine_local = |date: &str| {
+            local_time(
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+                NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            )
+            .unwrap()
+            .to_rfc3339()
+        };
+        let mut expected = vec![
+            ("e1", "2024-03-04T08:45:00+00:00".to_string()),
+            ("t1", nine_local("2024-03-05")),
+            ("t1", nine_local("2024-03-06")),
+        ];
+        expected.sort_by(|a, b| a.1.cmp(&b.1));
+
+        assert_eq!(summary, expected);
+        assert!(reminders
+            .iter()
+            .all(|reminder| reminder.state == ReminderState::Pending));
+        assert_eq!(reminders[0].title, "Standup");
+    }
+
+    #[test]
+    fn missed_reminders_fire_within_the_grace_period_only() {
+        let events = [
+            json!({ "id": "recent", "start": "2024-03-04T07:00:00Z" }),
+            json!({ "id": "stale", "start": "2024-03-03T07:00:00Z" }),
+        ];
+        let reminders = plan(&[], &events, &settings(), at("2024-03-04T12:00:00Z"));
+
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].record_id, "recent");
+        assert_eq!(reminders[0].title, "Untitled event");
+    }
+
+    #[test]
+    fn merge_keeps_state_until_the_record_moves() {
+        let now = at("2024-03-04T08:00:00Z");
+        let event = |start: &str| [json!({ "id": "e1", "title": "Review", "start": start })];
+
+        let mut first = plan(&[], &event("2024-03-04T10:00:00Z"), &settings(), now);
+        first[0].state = ReminderState::Snoozed;
+        first[0].snoozed_until = Some(at("2024-03-04T09:50:00Z"));
+
+        let same = merge(
+            &first,
+            plan(&[], &event("2024-03-04T10:00:00Z"), &settings(), now),
+        );
+        assert_eq!(same[0].state, ReminderState::Snoozed);
+        assert_eq!(same[0].next_fire(), Some(at("2024-03-04T09:50:00Z")));
+
+        let moved = merge(
+            &first,
+            plan(&[], &event("2024-03-04T11:00:00Z"), &settings(), now),
+        );
+        assert_eq!(moved[0].state, ReminderState::Pending);
+        assert_eq!(moved[0].next_fire(), Some(at("2024-03-04T10:45:00Z")));
+    }
+
+    #[test]
+    fn merge_follows_offline_records_to_their_synced_id() {
+        let now = at("2024-03-04T08:00:00Z");
+        let event = |id: &str| [json!({ "id": id, "start": "2024-03-04T10:00:00Z" })];
+
+        let mut first = plan(&[], &event("offline-event-1-a"), &settings(), now);
+        first[0].state = ReminderState::Fired;
+
+        let synced = merge(&first, plan(&[], &event("server-id"), &settings(), now));
+        assert_eq!(synced[0].record_id, "server-id");
+        assert_eq!(synced[0].state, ReminderState::Fired);
+    }
+
+    #[test]
+    fn persisted_reminders_leave_out_titles() {
+        let reminders = plan(
+            &[],
+            &[json!({ "id": "e1", "title": "Private", "start": "2024-03-04T10:00:00Z" })],
+            &settings(),
+            at("2024-03-04T08:00:00Z"),
+        );
+        let saved = serde_json::to_string(&reminders).unwrap();
+
+        assert!(!saved.contains("Private"));
+        let restored: Vec<Reminder> = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored[0].key, reminders[0].key);
+    }
+
+    #[test]
+    fn settings_are_validated() {
+        assert!(ReminderSettings::default().validate().is_ok());
+        assert!(ReminderSettings {
+            all_day_time: "25:00".to_string(),
+            ..ReminderSettings::default()
+        }
+        .validate()
+        .is_err());
+        assert!(ReminderSettings {
+            event_lead_minutes: vec![MAX_LEAD_MINUTES + 1],
+            ..ReminderSettings::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,63 @@
+// desktop/src-tauri/src/servers/commands.rs
+
+use tauri::{AppHandle, Manager, State};
+
+use super::{ServerCheck, ServerProfile, ServerSettings, Servers, SERVER_CHANGED_EVENT};
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::reachability::Reachability;
+use crate::sync::SyncService;
+
+#[tauri::command]
+pub fn list_servers(servers: State<'_, Servers>) -> ServerSettings {
+    servers.settings()
+}
+
+#[tauri::command]
+pub fn save_server(servers: State<'_, Servers>, profile: ServerProfile) -> Result<ServerSettings> {
+    servers.save_profile(profile)?;
+    Ok(servers.settings())
+}
+
+#[tauri::command]
+pub fn delete_server(servers: State<'_, Servers>, id: String) -> Result<ServerSettings> {
+    servers.delete_profile(&id)?;
+    Ok(servers.settings())
+}
+
+/// Checks a profile, saved or not, without making it active.
+#[tauri::command]
+pub async fn check_server(app: AppHandle, profile: ServerProfile) -> ServerCheck {
+    let network = app.state::<Network>().config();
+    super::check(&profile, &network).await
+}
+
+#[tauri::command]
+pub fn get_active_server(servers: State<'_, Servers>) -> ServerProfile {
+    servers.active()
+}
+
+/// Switches to the server `id` once it passes the health check. The cloud
+/// session belonged to the previous server, so the sync worker drops it;
+/// every window is told to point its HTTP client at the new server.
+#[tauri::command]
+pub async fn set_active_server(
+    app: AppHandle,
+    servers: State<'_, Servers>,
+    network: State<'_, Network>,
+    id: String,
+) -> Result<ServerProfile> {
+    let profile = servers.profile(&id)?;
+
+    let check = super::check(&profile, &network.config()).await;
+    if !check.reachable {
+        return Err(Error::InvalidInput(check.message));
+    }
+
+    let active = servers.set_active(&id)?;
+    app.state::<SyncService>().set_session(None, false);
+    app.state::<Reachability>().wake();
+    let _ = app.emit_all(SERVER_CHANGED_EVENT, active.clone());
+
+    Ok(active)
+}
@@ -0,0 +1,185 @@
+// desktop/src-tauri/src/servers/mod.rs
+
+/*
+ * Which API server the app talks to.
+ *
+ * The profiles live in `servers.json` in the app config directory. The sync
+ * worker builds its client from the active profile, and the webview points
+ * `http.ts` at it on startup and whenever `pioneer:server-changed` arrives.
+ *
+ * A server is checked before it becomes active: `GET /auth/me` without a
+ * token must come back from the Pioneer API as 200 or 401 with a JSON body.
+ * That proves the URL, the TLS setup and the timeout all work, without
+ * needing an account on the server yet.
+ */
+
+pub mod commands;
+mod profile;
+
+pub use profile::{ServerProfile, ServerSettings, DEFAULT_TIMEOUT_SECONDS};
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::error::Result;
+use crate::files;
+use crate::network::NetworkConfig;
+use crate::paths;
+use crate::sync::client::{self, ApiError};
+
+pub const SERVERS_FILE_NAME: &str = "servers.json";
+
+pub const SERVER_CHANGED_EVENT: &str = "pioneer:server-changed";
+
+pub const HEALTH_CHECK_PATH: &str = "/auth/me";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCheck {
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub message: String,
+}
+
+pub struct Servers {
+    path: PathBuf,
+    settings: Mutex<ServerSettings>,
+}
+
+impl Servers {
+    /// Settings that cannot be read are replaced by the defaults, which
+    /// only know Pioneer Cloud.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        let settings = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<ServerSettings>(&bytes).ok())
+            .map(ServerSettings::repaired)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir(app)?.join(SERVERS_FILE_NAME),
+        ))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ServerSettings> {
+        self.settings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn settings(&self) -> ServerSettings {
+        self.lock().clone()
+    }
+
+    pub fn active(&self) -> ServerProfile {
+        self.lock().active().clone()
+    }
+
+    pub fn profile(&self, id: &str) -> Result<ServerProfile> {
+        self.lock().profile(id).cloned()
+    }
+
+    /// Applies `change` and writes the result, leaving the settings as they
+    /// were if either fails.
+    fn update(&self, change: impl FnOnce(&mut ServerSettings) -> Result<()>) -> Result<()> {
+        let mut settings = self.lock();
+        let mut next = settings.clone();
+
+        change(&mut next)?;
+        files::write_atomic(&self.path, &serde_json::to_vec_pretty(&next)?)?;
+
+        *settings = next;
+        Ok(())
+    }
+
+    pub fn save_profile(&self, profile: ServerProfile) -> Result<()> {
+        self.update(|settings| settings.upsert(profile))
+    }
+
+    pub fn delete_profile(&self, id: &str) -> Result<()> {
+        self.update(|settings| settings.remove(id))
+    }
+
+    pub fn set_active(&self, id: &str) -> Result<ServerProfile> {
+        self.update(|settings| {
+            settings.profile(id)?;
+            settings.active = id.to_string();
+            Ok(())
+        })?;
+
+        Ok(self.active())
+    }
+}
+
+fn is_pioneer_api(status: u16, body: &str) -> bool {
+    matches!(status, 200 | 401)
+        && serde_json::from_str::<Value>(body).is_ok_and(|value| value.is_object())
+}
+
+/// Checks that `profile` answers like the Pioneer API.
+pub async fn check(profile: &ServerProfile, network: &NetworkConfig) -> ServerCheck {
+    let started = Instant::now();
+    let elapsed = || started.elapsed().as_millis().try_into().unwrap_or(u64::MAX);
+
+    let http = match client::http_client(profile, network) {
+        Ok(http) => http,
+        Err(error) => {
+            return ServerCheck {
+                reachable: false,
+                status: None,
+                latency_ms: 0,
+                message: error.to_string(),
+            }
+        }
+    };
+
+    let response = match http
+        .get(format!("{}{HEALTH_CHECK_PATH}", profile.base_url))
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(error) => {
+            return ServerCheck {
+                reachable: false,
+                status: None,
+                latency_ms: elapsed(),
+                message: ApiError::from_request(error).to_string(),
+            }
+        }
+    };
+
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    let reachable = is_pioneer_api(status, &body);
+
+    ServerCheck {
+        reachable,
+        status: Some(status),
+        latency_ms: elapsed(),
+        message: if reachable {
+            format!("{} is reachable.", profile.name)
+        } else {
+            format!(
+                "{} answered with status {status}, which does not look like the Pioneer API.",
+                profile.base_url
+            )
+        },
+    }
+}
@@ -0,0 +1,283 @@
+// desktop/src-tauri/src/servers/profile.rs
+
+/*
+ * Named API servers.
+ *
+ * Pioneer Cloud is always available as the first profile and cannot be
+ * edited or removed; self-hosted deployments of apps/api are added next to
+ * it. Exactly one profile is active at a time.
+ */
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::Url;
+
+use crate::error::{Error, Result};
+use crate::sync::client::DEFAULT_API_BASE_URL;
+
+pub const DEFAULT_SERVER_ID: &str = "pioneer-cloud";
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+const MAX_TIMEOUT_SECONDS: u64 = 300;
+const MAX_NAME_LENGTH: usize = 80;
+const MAX_ID_LENGTH: usize = 64;
+const MAX_PROFILES: usize = 32;
+
+fn default_timeout_seconds() -> u64 {
+    DEFAULT_TIMEOUT_SECONDS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerProfile {
+    pub id: String,
+    pub name: String,
+    pub base_url: String,
+    /// A PEM file with extra root certificates, for servers whose TLS
+    /// certificate is issued by a private CA.
+    #[serde(default)]
+    pub ca_certificate: Option<PathBuf>,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+impl ServerProfile {
+    pub fn pioneer_cloud() -> Self {
+        Self {
+            id: DEFAULT_SERVER_ID.to_string(),
+            name: "Pioneer Cloud".to_string(),
+            base_url: DEFAULT_API_BASE_URL.to_string(),
+            ca_certificate: None,
+            timeout_seconds: DEFAULT_TIMEOUT_SECONDS,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds)
+    }
+
+    /// Checks the profile and trims the trailing slash off its URL.
+    fn normalized(mut self) -> Result<Self> {
+        let valid_id = !self.id.is_empty()
+            && self.id.len() <= MAX_ID_LENGTH
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_id {
+            return Err(Error::InvalidInput(
+                "A server id may only contain letters, digits, - and _.".to_string(),
+            ));
+        }
+
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() || self.name.chars().count() > MAX_NAME_LENGTH {
+            return Err(Error::InvalidInput(format!(
+                "A server name must be between 1 and {MAX_NAME_LENGTH} characters."
+            )));
+        }
+
+        let url = Url::parse(self.base_url.trim()).map_err(|_| {
+            Error::InvalidInput(format!("{} is not a valid URL.", self.base_url.trim()))
+        })?;
+        if !matches!(url.scheme(), "http" | "https")
+            || url.host_str().is_none()
+            || url.query().is_some()
+            || url.fragment().is_some()
+        {
+            return Err(Error::InvalidInput(
+                "The server URL must be an http:// or https:// address without a query."
+                    .to_string(),
+            ));
+        }
+        self.base_url = url.as_str().trim_end_matches('/').to_string();
+
+        if !(1..=MAX_TIMEOUT_SECONDS).contains(&self.timeout_seconds) {
+            return Err(Error::InvalidInput(format!(
+                "The timeout must be between 1 and {MAX_TIMEOUT_SECONDS} seconds."
+            )));
+        }
+
+        if let Some(path) = &self.ca_certificate {
+            if !path.is_absolute() {
+                return Err(Error::InvalidInput(
+                    "The CA certificate must be given as a full path.".to_string(),
+                ));
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerSettings {
+    pub active: String,
+    pub profiles: Vec<ServerProfile>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_SERVER_ID.to_string(),
+            profiles: vec![ServerProfile::pioneer_cloud()],
+        }
+    }
+}
+
+impl ServerSettings {
+    /// Puts Pioneer Cloud back in front, drops profiles that no longer
+    /// validate, and falls back to Pioneer Cloud when the active profile is
+    /// gone. Used on settings read from disk.
+    pub fn repaired(self) -> Self {
+        let mut profiles = vec![ServerProfile::pioneer_cloud()];
+
+        for profile in self.profiles {
+            if profile.id == DEFAULT_SERVER_ID
+                || profiles.iter().any(|existing| existing.id == profile.id)
+            {
+                continue;
+            }
+            if let Ok(profile) = profile.normalized() {
+                profiles.push(profile);
+            }
+        }
+
+        let active = if profiles.iter().any(|profile| profile.id == self.active) {
+            self.active
+        } else {
+            DEFAULT_SERVER_ID.to_string()
+        };
+
+        Self { active, profiles }
+    }
+
+    pub fn active(&self) -> &ServerProfile {
+        self.profiles
+            .iter()
+            .find(|profile| profile.id == self.active)
+            .unwrap_or(&self.profiles[0])
+    }
+
+    pub fn profile(&self, id: &str) -> Result<&ServerProfile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.id == id)
+            .ok_or_else(|| Error::InvalidInput(format!("There is no server called {id}.")))
+    }
+
+    /// Adds `profile`, or replaces the one with the same id.
+    pub fn upsert(&mut self, profile: ServerProfile) -> Result<()> {
+        if profile.id == DEFAULT_SERVER_ID {
+            return Err(Error::InvalidInput(
+                "Pioneer Cloud cannot be changed.".to_string(),
+            ));
+        }
+        let profile = profile.normalized()?;
+
+        match self
+            .profiles
+            .iter()
+            .position(|existing| existing.id == profile.id)
+        {
+            Some(index) => self.profiles[index] = profile,
+            None if self.profiles.len() >= MAX_PROFILES => {
+                return Err(Error::InvalidInput(format!(
+                    "Keep at most {MAX_PROFILES} servers."
+                )));
+            }
+            None => self.profiles.push(profile),
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<()> {
+        if id == DEFAULT_SERVER_ID {
+            return Err(Error::InvalidInput(
+                "Pioneer Cloud cannot be removed.".to_string(),
+            ));
+        }
+        if id == self.active {
+            return Err(Error::InvalidInput(
+                "Switch to another server before removing this one.".to_string(),
+            ));
+        }
+
+        self.profiles.retain(|profile| profile.id != id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campus() -> ServerProfile {
+        ServerProfile {
+            id: "campus".to_string(),
+            name: " Campus ".to_string(),
+            base_url: "https://pioneer.example.edu/api/".to_string(),
+            ca_certificate: None,
+            timeout_seconds: 10,
+        }
+    }
+
+    #[test]
+    fn normalizes_added_profiles() {
+        let mut settings = ServerSettings::default();
+        settings.upsert(campus()).unwrap();
+
+        let added = settings.profile("campus").unwrap();
+        assert_eq!(added.name, "Campus");
+        assert_eq!(added.base_url, "https://pioneer.example.edu/api");
+
+        for base_url in [
+            "ftp://example.edu",
+            "https://example.edu/?a=1",
+            "example.edu",
+        ] {
+            let profile = ServerProfile {
+                base_url: base_url.to_string(),
+                ..campus()
+            };
+            assert!(settings.upsert(profile).is_err(), "{base_url}");
+        }
+    }
+
+    #[test]
+    fn keeps_pioneer_cloud() {
+        let mut settings = ServerSettings::default();
+
+        assert!(settings
+            .upsert(ServerProfile {
+                base_url: "http://localhost".to_string(),
+                ..ServerProfile::pioneer_cloud()
+            })
+            .is_err());
+        assert!(settings.remove(DEFAULT_SERVER_ID).is_err());
+    }
+
+    #[test]
+    fn repairs_settings_read_from_disk() {
+        let settings = ServerSettings {
+            active: "gone".to_string(),
+            profiles: vec![
+                campus(),
+                ServerProfile {
+                    timeout_seconds: 0,
+                    id: "broken".to_string(),
+                    ..campus()
+                },
+            ],
+        }
+        .repaired();
+
+        let ids: Vec<&str> = settings.profiles.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, [DEFAULT_SERVER_ID, "campus"]);
+        assert_eq!(settings.active().id, DEFAULT_SERVER_ID);
+    }
+}
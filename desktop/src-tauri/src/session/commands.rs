@@ -0,0 +1,16 @@
+// desktop/src-tauri/src/session/commands.rs
+
+use tauri::State;
+
+use super::{PreviousSession, Session};
+use crate::error::Result;
+
+#[tauri::command]
+pub fn get_previous_session_state(session: State<'_, Session>) -> PreviousSession {
+    session.previous()
+}
+
+#[tauri::command]
+pub fn set_session_path(session: State<'_, Session>, path: String) -> Result<()> {
+    session.set_path(path)
+}
@@ -0,0 +1,169 @@
+// desktop/src-tauri/src/session/marker.rs
+
+/*
+ * The session marker file.
+ *
+ * A marker is written as soon as the app starts and says that a session is
+ * running. It is only marked as ended when the app exits normally, so a
+ * marker that is still running at the next launch means the last session
+ * was interrupted: the process crashed, was killed, or the computer lost
+ * power.
+ */
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::files;
+
+const MAX_PATH_LENGTH: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Marker {
+    started_at: DateTime<Utc>,
+    /// The last time a window of the session was focused or closed.
+    last_seen_at: DateTime<Utc>,
+    /// The page the main window last showed.
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    ended: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviousSession {
+    pub interrupted: bool,
+    pub path: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+pub struct Session {
+    path: PathBuf,
+    previous: PreviousSession,
+    current: Mutex<Marker>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Session {
+    /// Reads the previous session's marker and starts a new session, which
+    /// keeps the page the previous one was on until the webview reports
+    /// another. A marker that cannot be read counts as no previous session.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        let previous: Option<Marker> = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+
+        let now = Utc::now();
+        let session = Self {
+            path,
+            previous: previous
+                .as_ref()
+                .map(|marker| PreviousSession {
+                    interrupted: !marker.ended,
+                    path: marker.path.clone(),
+                    started_at: Some(marker.started_at),
+                    last_seen_at: Some(marker.last_seen_at),
+                })
+                .unwrap_or_default(),
+            current: Mutex::new(Marker {
+                started_at: now,
+                last_seen_at: now,
+                path: previous.and_then(|marker| marker.path),
+                ended: false,
+            }),
+        };
+
+        let _ = session.save(&lock(&session.current));
+        session
+    }
+
+    pub fn previous(&self) -> PreviousSession {
+        self.previous.clone()
+    }
+
+    fn save(&self, marker: &Marker) -> Result<()> {
+        files::write_atomic(&self.path, &serde_json::to_vec_pretty(marker)?)
+    }
+
+    pub fn set_path(&self, path: String) -> Result<()> {
+        if !path.starts_with('/') || path.len() > MAX_PATH_LENGTH {
+            return Err(Error::InvalidInput(format!("{path} is not an app route.")));
+        }
+
+        let mut marker = lock(&self.current);
+        marker.path = Some(path);
+        marker.last_seen_at = Utc::now();
+        self.save(&marker)
+    }
+
+    pub fn touch(&self) -> Result<()> {
+        let mut marker = lock(&self.current);
+        marker.last_seen_at = Utc::now();
+        self.save(&marker)
+    }
+
+    /// Records that the session ended normally.
+    pub fn end(&self) -> Result<()> {
+        let mut marker = lock(&self.current);
+        marker.last_seen_at = Utc::now();
+        marker.ended = true;
+        self.save(&marker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_first_launch_was_not_interrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = Session::open(dir.path().join("session.json"));
+
+        assert!(!session.previous().interrupted);
+        assert!(session.previous().started_at.is_none());
+    }
+
+    #[test]
+    fn a_session_that_did_not_end_was_interrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+
+        let first = Session::open(&path);
+        first.set_path("/tasks?task=t1".to_string()).unwrap();
+        drop(first);
+
+        let second = Session::open(&path);
+        assert!(second.previous().interrupted);
+        assert_eq!(second.previous().path.as_deref(), Some("/tasks?task=t1"));
+
+        second.end().unwrap();
+
+        let third = Session::open(&path);
+        assert!(!third.previous().interrupted);
+        assert_eq!(third.previous().path.as_deref(), Some("/tasks?task=t1"));
+    }
+
+    #[test]
+    fn rejects_paths_that_are_not_routes() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = Session::open(dir.path().join("session.json"));
+
+        assert!(session.set_path("https://example.com".to_string()).is_err());
+        assert!(session.set_path("/".repeat(600)).is_err());
+    }
+}
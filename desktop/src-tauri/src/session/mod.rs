@@ -0,0 +1,55 @@
+// desktop/src-tauri/src/session/mod.rs
+
+/*
+ * Whether the last session ended normally.
+ *
+ * The webview used to keep a `cleanExit` flag in localStorage and set it on
+ * `pagehide`, which never fires when the webview is killed. The marker is
+ * kept here instead: it is written when the app starts, refreshed as the
+ * main window is focused and closed, and marked as ended from the native
+ * exit hook. The webview asks `get_previous_session_state` once at startup
+ * and reports the page it is on with `set_session_path`.
+ */
+
+pub mod commands;
+mod marker;
+
+pub use marker::{PreviousSession, Session};
+
+use tauri::{AppHandle, GlobalWindowEvent, Manager, WindowEvent};
+
+use crate::error::Result;
+use crate::paths;
+
+pub const SESSION_FILE_NAME: &str = "session.json";
+
+impl Session {
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir(app)?.join(SESSION_FILE_NAME),
+        ))
+    }
+}
+
+pub fn handle_window_event(event: GlobalWindowEvent) {
+    if event.window().label() != "main" {
+        return;
+    }
+    let Some(session) = event.window().try_state::<Session>() else {
+        return;
+    };
+
+    match event.event() {
+        WindowEvent::Focused(true) | WindowEvent::CloseRequested { .. } => {
+            let _ = session.touch();
+        }
+        _ => {}
+    }
+}
+
+/// Marks the session as ended, e.g. when the app exits.
+pub fn end(app: &AppHandle) {
+    if let Some(session) = app.try_state::<Session>() {
+        let _ = session.end();
+    }
+}
@@ -0,0 +1,61 @@
+// desktop/src-tauri/src/snapshots/commands.rs
+
+use tauri::{AppHandle, Manager, State};
+
+use super::{SnapshotInfo, SnapshotSettings, Snapshots};
+use crate::backup::WorkspaceCounts;
+use crate::error::Result;
+use crate::storage::LocalStore;
+use crate::sync::{engine::Resource, SyncService};
+
+#[tauri::command]
+pub fn list_snapshots(
+    snapshots: State<'_, Snapshots>,
+    store: State<'_, LocalStore>,
+) -> Result<Vec<SnapshotInfo>> {
+    snapshots.list(&store)
+}
+
+#[tauri::command]
+pub async fn create_snapshot(
+    snapshots: State<'_, Snapshots>,
+    store: State<'_, LocalStore>,
+) -> Result<SnapshotInfo> {
+    snapshots.create(&store)
+}
+
+/// Replaces the workspace with the snapshot `id`, after snapshotting the
+/// current state. `pin` is only needed for a snapshot taken under a PIN the
+/// workspace no longer has.
+#[tauri::command]
+pub async fn restore_snapshot(
+    app: AppHandle,
+    snapshots: State<'_, Snapshots>,
+    store: State<'_, LocalStore>,
+    id: String,
+    pin: Option<String>,
+) -> Result<WorkspaceCounts> {
+    let contents = snapshots.restore(&store, &id, pin.as_deref())?;
+
+    for resource in Resource::ALL {
+        let _ = app.emit_all(resource.changed_event(), ());
+    }
+    app.state::<SyncService>().wake();
+
+    Ok(WorkspaceCounts::of(&contents))
+}
+
+#[tauri::command]
+pub fn get_snapshot_settings(snapshots: State<'_, Snapshots>) -> SnapshotSettings {
+    snapshots.settings()
+}
+
+#[tauri::command]
+pub fn set_snapshot_settings(
+    snapshots: State<'_, Snapshots>,
+    store: State<'_, LocalStore>,
+    settings: SnapshotSettings,
+) -> Result<SnapshotSettings> {
+    snapshots.set_settings(&store, settings)?;
+    Ok(snapshots.settings())
+}
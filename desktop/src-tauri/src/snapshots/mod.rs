@@ -0,0 +1,572 @@
+// desktop/src-tauri/src/snapshots/mod.rs
+
+/*
+ * Scheduled snapshots of the local store.
+ *
+ * Snapshots are copies of the encrypted database in the `snapshots` folder
+ * next to it, taken hourly or daily while the workspace is unlocked. Each
+ * keeps the wrapped data key, so it opens with this computer or the PIN
+ * even after the database itself was lost and recreated with a new key.
+ *
+ * Retention keeps the newest snapshot of each of the last `keep_hourly`
+ * hours and of each of the last `keep_daily` days that have one; everything
+ * else is deleted after each new snapshot. The snapshot last restored and
+ * the one taken just before that restore, or before a backup was imported,
+ * are listed in `kept.json` and left alone until the next restore or
+ * import, so either can still be undone.
+ */
+
+pub mod commands;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+use crate::storage::{LocalStore, WorkspaceContents, SNAPSHOT_EXTENSION};
+
+pub const SNAPSHOT_SETTINGS_FILE_NAME: &str = "snapshot-settings.json";
+
+// Ids are UTC timestamps to the millisecond; ids from before that have
+// whole seconds.
+const SNAPSHOT_ID_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+const LEGACY_SNAPSHOT_ID_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+const KEPT_FILE_NAME: &str = "kept.json";
+
+const MAX_KEEP_HOURLY: u32 = 7 * 24;
+const MAX_KEEP_DAILY: u32 = 365;
+
+// How often the scheduler re-checks while a snapshot is due but the
+// workspace is locked, and the longest it sleeps in any case.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SnapshotFrequency {
+    Off,
+    Hourly,
+    Daily,
+}
+
+impl SnapshotFrequency {
+    fn interval(self) -> Option<chrono::Duration> {
+        match self {
+            SnapshotFrequency::Off => None,
+            SnapshotFrequency::Hourly => Some(chrono::Duration::hours(1)),
+            SnapshotFrequency::Daily => Some(chrono::Duration::days(1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSettings {
+    pub frequency: SnapshotFrequency,
+    pub keep_hourly: u32,
+    pub keep_daily: u32,
+}
+
+impl Default for SnapshotSettings {
+    fn default() -> Self {
+        Self {
+            frequency: SnapshotFrequency::Daily,
+            keep_hourly: 24,
+            keep_daily: 7,
+        }
+    }
+}
+
+impl SnapshotSettings {
+    fn validate(&self) -> Result<()> {
+        if self.keep_hourly > MAX_KEEP_HOURLY || self.keep_daily > MAX_KEEP_DAILY {
+            return Err(Error::InvalidInput(format!(
+                "Keep at most {MAX_KEEP_HOURLY} hourly and {MAX_KEEP_DAILY} daily snapshots."
+            )));
+        }
+
+        if self.keep_hourly == 0 && self.keep_daily == 0 {
+            return Err(Error::InvalidInput(
+                "Keep at least one hourly or daily snapshot.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+struct SnapshotFile {
+    id: String,
+    created_at: DateTime<Utc>,
+    path: PathBuf,
+}
+
+pub struct Snapshots {
+    settings_path: PathBuf,
+    settings: Mutex<SnapshotSettings>,
+    changed: Notify,
+}
+
+fn parse_id(id: &str) -> Option<DateTime<Utc>> {
+    [SNAPSHOT_ID_FORMAT, LEGACY_SNAPSHOT_ID_FORMAT]
+        .into_iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(id, format).ok())
+        .map(|created_at| created_at.and_utc())
+}
+
+/// Ids of the snapshots retention must not delete. An unreadable list
+/// protects nothing rather than stopping retention.
+fn kept_ids(directory: &Path) -> HashSet<String> {
+    fs::read(directory.join(KEPT_FILE_NAME))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Snapshots in `directory`, newest first.
+fn snapshot_files(directory: &Path) -> Result<Vec<SnapshotFile>> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+
+        if path
+            .extension()
+            .is_none_or(|extension| extension != SNAPSHOT_EXTENSION)
+        {
+            continue;
+        }
+
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        if let Some(created_at) = parse_id(id) {
+            snapshots.push(SnapshotFile {
+                id: id.to_string(),
+                created_at,
+                path: path.clone(),
+            });
+        }
+    }
+
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
+
+/// Picks the snapshots to delete: everything that is not in `kept` or the
+/// newest of one of the kept hours or days.
+fn expired<'a>(
+    snapshots: &'a [SnapshotFile],
+    settings: &SnapshotSettings,
+    kept: &HashSet<String>,
+) -> Vec<&'a SnapshotFile> {
+    let mut hours = HashSet::new();
+    let mut days = HashSet::new();
+
+    snapshots
+        .iter()
+        .filter(|snapshot| !kept.contains(&snapshot.id))
+        .filter(|snapshot| {
+            let hour = snapshot.created_at.format("%Y%m%d%H").to_string();
+            let day = snapshot.created_at.format("%Y%m%d").to_string();
+
+            let keep_for_hour = hours.len() < settings.keep_hourly as usize && hours.insert(hour);
+            let keep_for_day = days.len() < settings.keep_daily as usize && days.insert(day);
+
+            !keep_for_hour && !keep_for_day
+        })
+        .collect()
+}
+
+impl Snapshots {
+    pub fn open(settings_path: impl Into<PathBuf>) -> Result<Self> {
+        let settings_path = settings_path.into();
+        let settings = if settings_path.exists() {
+            serde_json::from_slice(&fs::read(&settings_path)?)?
+        } else {
+            SnapshotSettings::default()
+        };
+
+        Ok(Self {
+            settings_path,
+            settings: Mutex::new(settings),
+            changed: Notify::new(),
+        })
+    }
+
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Self::open(paths::app_config_dir(app)?.join(SNAPSHOT_SETTINGS_FILE_NAME))
+    }
+
+    fn lock_settings(&self) -> MutexGuard<'_, SnapshotSettings> {
+        self.settings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn settings(&self) -> SnapshotSettings {
+        *self.lock_settings()
+    }
+
+    /// Saves new settings and applies the retention limits straight away.
+    pub fn set_settings(&self, store: &LocalStore, settings: SnapshotSettings) -> Result<()> {
+        settings.validate()?;
+
+        {
+            let mut current = self.lock_settings();
+            files::write_atomic(&self.settings_path, &serde_json::to_vec_pretty(&settings)?)?;
+            *current = settings;
+        }
+
+        self.changed.notify_one();
+        self.prune(store)
+    }
+
+    pub fn list(&self, store: &LocalStore) -> Result<Vec<SnapshotInfo>> {
+        snapshot_files(&store.snapshot_dir())?
+            .into_iter()
+            .map(|snapshot| {
+                Ok(SnapshotInfo {
+                    size_bytes: fs::metadata(&snapshot.path)?.len(),
+                    created_at: snapshot.created_at.to_rfc3339(),
+                    id: snapshot.id,
+                })
+            })
+            .collect()
+    }
+
+    /// Takes a snapshot now, then applies retention.
+    pub fn create(&self, store: &LocalStore) -> Result<SnapshotInfo> {
+        let snapshot = Self::take(store)?;
+        self.prune(store)?;
+
+        Ok(snapshot)
+    }
+
+    /// Takes a snapshot under an id no other snapshot has.
+    fn take(store: &LocalStore) -> Result<SnapshotInfo> {
+        let mut created_at = Utc::now().trunc_subsecs(3);
+        let (id, path) = loop {
+            let id = created_at.format(SNAPSHOT_ID_FORMAT).to_string();
+            let path = store
+                .snapshot_dir()
+                .join(format!("{id}.{SNAPSHOT_EXTENSION}"));
+
+            if !path.exists() {
+                break (id, path);
+            }
+
+            created_at += chrono::Duration::milliseconds(1);
+        };
+
+        store.snapshot_to(&path)?;
+
+        Ok(SnapshotInfo {
+            size_bytes: fs::metadata(&path)?.len(),
+            created_at: created_at.to_rfc3339(),
+            id,
+        })
+    }
+
+    fn prune(&self, store: &LocalStore) -> Result<()> {
+        let settings = self.settings();
+        let directory = store.snapshot_dir();
+        let snapshots = snapshot_files(&directory)?;
+
+        for snapshot in expired(&snapshots, &settings, &kept_ids(&directory)) {
+            fs::remove_file(&snapshot.path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the snapshot `id` and replaces the workspace with it. The
+    /// current state is snapshotted first, so a restore can be undone, and
+    /// both snapshots are kept from retention until the next restore.
+    /// `pin` opens a snapshot taken under a PIN the store no longer uses.
+    pub fn restore(
+        &self,
+        store: &LocalStore,
+        id: &str,
+        pin: Option<&str>,
+    ) -> Result<WorkspaceContents> {
+        let missing = || Error::InvalidInput("That snapshot no longer exists.".to_string());
+
+        parse_id(id).ok_or_else(missing)?;
+
+        let path = store
+            .snapshot_dir()
+            .join(format!("{id}.{SNAPSHOT_EXTENSION}"));
+
+        if !path.exists() {
+            return Err(missing());
+        }
+
+        let contents = store.read_snapshot(&path, pin)?;
+
+        self.take_before_replacing(store, Some(id))?;
+        store.replace_contents(&contents)?;
+
+        Ok(contents)
+    }
+
+    /// Snapshots the workspace before something replaces it, and keeps that
+    /// snapshot and `source` from retention until the next replacement.
+    pub fn take_before_replacing(
+        &self,
+        store: &LocalStore,
+        source: Option<&str>,
+    ) -> Result<SnapshotInfo> {
+        let before = Self::take(store)?;
+        let kept: Vec<&str> = source.into_iter().chain([before.id.as_str()]).collect();
+
+        files::write_atomic(
+            &store.snapshot_dir().join(KEPT_FILE_NAME),
+            &serde_json::to_vec_pretty(&kept)?,
+        )?;
+        self.prune(store)?;
+
+        Ok(before)
+    }
+
+    /// How long until the next scheduled snapshot, or `None` when scheduling
+    /// is off.
+    fn next_due(&self, store: &LocalStore) -> Result<Option<Duration>> {
+        let Some(interval) = self.settings().frequency.interval() else {
+            return Ok(None);
+        };
+
+        let latest = snapshot_files(&store.snapshot_dir())?
+            .first()
+            .map(|snapshot| snapshot.created_at);
+
+        let remaining = match latest {
+            Some(latest) => (latest + interval - Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+            None => Duration::ZERO,
+        };
+
+        Ok(Some(remaining))
+    }
+}
+
+/// Spawns the snapshot scheduler. A snapshot that comes due while the
+/// workspace is locked is taken once it is unlocked again.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let snapshots = app.state::<Snapshots>();
+            let store = app.state::<LocalStore>();
+
+            let delay = match snapshots.next_due(&store) {
+                Ok(None) => {
+                    snapshots.changed.notified().await;
+                    continue;
+                }
+                Ok(Some(remaining)) if remaining.is_zero() => {
+                    if store.is_unlocked() && snapshots.create(&store).is_ok() {
+                        continue;
+                    }
+
+                    CHECK_INTERVAL
+                }
+                Ok(Some(remaining)) => remaining.min(CHECK_INTERVAL),
+                Err(_) => CHECK_INTERVAL,
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = snapshots.changed.notified() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::storage::{RecordStore, UnlockSecret};
+
+    fn snapshot(id: &str) -> SnapshotFile {
+        SnapshotFile {
+            id: id.to_string(),
+            created_at: parse_id(id).unwrap(),
+            path: PathBuf::from(id),
+        }
+    }
+
+    fn ids<'a>(snapshots: impl IntoIterator<Item = &'a SnapshotFile>) -> Vec<&'a str> {
+        snapshots
+            .into_iter()
+            .map(|snapshot| snapshot.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn ids_parse_with_and_without_milliseconds() {
+        let half_past_nine = Utc.with_ymd_and_hms(2026, 10, 17, 9, 30, 0).unwrap();
+
+        assert_eq!(
+            parse_id("20261017T093000250Z"),
+            Some(half_past_nine + chrono::Duration::milliseconds(250))
+        );
+        assert_eq!(parse_id("20261017T093000Z"), Some(half_past_nine));
+        assert_eq!(parse_id("20261017T0930Z"), None);
+        assert_eq!(parse_id("kept"), None);
+
+        let id = half_past_nine.format(SNAPSHOT_ID_FORMAT).to_string();
+        assert_eq!(id, "20261017T093000000Z");
+        assert_eq!(parse_id(&id), Some(half_past_nine));
+    }
+
+    #[test]
+    fn retention_keeps_the_newest_of_each_hour_and_day() {
+        // Newest first, as `snapshot_files` lists them.
+        let snapshots = [
+            snapshot("20261017T103000000Z"),
+            snapshot("20261017T101500000Z"),
+            snapshot("20261017T093000000Z"),
+            snapshot("20261016T220000000Z"),
+            snapshot("20261015T080000Z"),
+        ];
+        let settings = SnapshotSettings {
+            frequency: SnapshotFrequency::Hourly,
+            keep_hourly: 2,
+            keep_daily: 2,
+        };
+        let kept = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect();
+
+        assert_eq!(
+            ids(expired(&snapshots, &settings, &HashSet::new())),
+            vec!["20261017T101500000Z", "20261015T080000Z"]
+        );
+        assert_eq!(
+            ids(expired(&snapshots, &settings, &kept(&["20261015T080000Z"]))),
+            vec!["20261017T101500000Z"]
+        );
+
+        // A kept snapshot does not use up its hour.
+        assert_eq!(
+            ids(expired(
+                &snapshots,
+                &settings,
+                &kept(&["20261017T103000000Z"])
+            )),
+            vec!["20261015T080000Z"]
+        );
+    }
+
+    #[test]
+    fn restores_keep_their_source_and_the_state_they_replaced() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(directory.path().join("store.sqlite3")).unwrap();
+        store.unlock(UnlockSecret::Pin("2468")).unwrap();
+
+        let snapshots =
+            Snapshots::open(directory.path().join(SNAPSHOT_SETTINGS_FILE_NAME)).unwrap();
+        let settings = SnapshotSettings {
+            frequency: SnapshotFrequency::Off,
+            keep_hourly: 1,
+            keep_daily: 0,
+        };
+        snapshots.set_settings(&store, settings).unwrap();
+
+        store
+            .replace_records(RecordStore::Tasks, &[json!({ "id": "t1" })])
+            .unwrap();
+        let source = snapshots.create(&store).unwrap();
+        store
+            .replace_records(RecordStore::Tasks, &[json!({ "id": "t2" })])
+            .unwrap();
+
+        let contents = snapshots.restore(&store, &source.id, None).unwrap();
+        assert_eq!(contents.tasks, vec![json!({ "id": "t1" })]);
+
+        // Taken within the same hour, and maybe the same millisecond.
+        let latest = snapshots.create(&store).unwrap();
+        let listed = snapshots.list(&store).unwrap();
+
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[0].id, latest.id);
+        assert!(listed.iter().any(|info| info.id == source.id));
+        assert_eq!(
+            kept_ids(&store.snapshot_dir()),
+            HashSet::from([source.id.clone(), listed[1].id.clone()])
+        );
+
+        let ids: HashSet<&str> = listed.iter().map(|info| info.id.as_str()).collect();
+        assert_eq!(ids.len(), 3);
+    }
+
+    #[test]
+    fn snapshots_outlive_the_database() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("store.sqlite3");
+        let snapshots =
+            Snapshots::open(directory.path().join(SNAPSHOT_SETTINGS_FILE_NAME)).unwrap();
+
+        let store = LocalStore::open(&path).unwrap();
+        store.unlock(UnlockSecret::Pin("2468")).unwrap();
+        store
+            .replace_records(RecordStore::Tasks, &[json!({ "id": "t1" })])
+            .unwrap();
+        let source = snapshots.create(&store).unwrap();
+        drop(store);
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+
+        // A fresh database, with a key of its own.
+        let store = LocalStore::open(&path).unwrap();
+        store.unlock(UnlockSecret::Machine).unwrap();
+
+        assert!(matches!(
+            snapshots.restore(&store, &source.id, None),
+            Err(Error::Crypto(_))
+        ));
+        assert!(matches!(
+            snapshots.restore(&store, &source.id, Some("1357")),
+            Err(Error::Crypto(_))
+        ));
+
+        let contents = snapshots.restore(&store, &source.id, Some("2468")).unwrap();
+        assert_eq!(contents.tasks, vec![json!({ "id": "t1" })]);
+        assert_eq!(
+            store.read_records(RecordStore::Tasks).unwrap(),
+            vec![json!({ "id": "t1" })]
+        );
+
+        // Setting a PIN now leaves the old snapshot under its own key.
+        store.rekey(UnlockSecret::Pin("1357")).unwrap();
+        assert!(snapshots.restore(&store, &source.id, Some("2468")).is_ok());
+    }
+}
@@ -0,0 +1,177 @@
+// desktop/src-tauri/src/storage/commands.rs
+
+/*
+ * Tauri commands mirroring the exports of apps/web/src/api/storage.ts.
+ * Command names are the snake_case form of the matching web function.
+ */
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use super::{document_recovery_key, LocalStore, QueueStore, RecordStore, UnlockSecret};
+use crate::blocking;
+use crate::error::Result;
+use crate::lock::WorkspaceLock;
+use crate::sync::changes;
+use crate::sync::engine::{QueueOp, Resource};
+
+/* Tasks */
+
+#[tauri::command]
+pub fn read_stored_tasks(store: State<'_, LocalStore>) -> Result<Vec<Value>> {
+    store.read_records(RecordStore::Tasks)
+}
+
+/// Replaces the cache with a list the webview built from the server's,
+/// keeping what the queue still needs.
+#[tauri::command]
+pub fn write_stored_tasks(store: State<'_, LocalStore>, tasks: Vec<Value>) -> Result<()> {
+    changes::replace_records(&store, Resource::Tasks, tasks)
+}
+
+#[tauri::command]
+pub fn read_stored_task_queue(store: State<'_, LocalStore>) -> Result<Vec<Value>> {
+    store.read_queue(QueueStore::Tasks)
+}
+
+/* Documents */
+
+#[tauri::command]
+pub fn read_stored_documents(store: State<'_, LocalStore>) -> Result<Vec<Value>> {
+    store.read_records(RecordStore::Documents)
+}
+
+#[tauri::command]
+pub fn write_stored_documents(store: State<'_, LocalStore>, documents: Vec<Value>) -> Result<()> {
+    changes::replace_records(&store, Resource::Documents, documents)
+}
+
+#[tauri::command]
+pub fn read_stored_document_queue(store: State<'_, LocalStore>) -> Result<Vec<Value>> {
+    store.read_queue(QueueStore::Documents)
+}
+
+/* Single changes, shared by all three resources */
+
+/// Adds `op` to the queue of `resource`, coalesced with the ops waiting
+/// there. Returns the new queue length.
+#[tauri::command]
+pub fn enqueue_stored_op(
+    store: State<'_, LocalStore>,
+    resource: Resource,
+    op: QueueOp,
+) -> Result<usize> {
+    changes::enqueue(&store, resource, op)
+}
+
+#[tauri::command]
+pub fn merge_stored_record(
+    store: State<'_, LocalStore>,
+    resource: Resource,
+    record: Value,
+) -> Result<()> {
+    changes::save_record(&store, resource, record)
+}
+
+#[tauri::command]
+pub fn remove_stored_record(
+    store: State<'_, LocalStore>,
+    resource: Resource,
+    id: String,
+) -> Result<()> {
+    changes::remove_record(&store, resource, &id)
+}
+
+#[tauri::command]
+pub fn import_stored_resource(
+    store: State<'_, LocalStore>,
+    resource: Resource,
+    records: Vec<Value>,
+    queue: Vec<Value>,
+) -> Result<()> {
+    changes::import(&store, resource, records, queue)
+}
+
+/* Document recovery */
+
+#[tauri::command]
+pub fn read_stored_document_recovery(
+    store: State<'_, LocalStore>,
+    document_id: String,
+) -> Result<Option<Value>> {
+    store.read_meta(&document_recovery_key(&document_id))
+}
+
+#[tauri::command]
+pub fn write_stored_document_recovery(
+    store: State<'_, LocalStore>,
+    document_id: String,
+    value: Value,
+) -> Result<()> {
+    store.write_meta(&document_recovery_key(&document_id), &value)
+}
+
+#[tauri::command]
+pub fn delete_stored_document_recovery(
+    store: State<'_, LocalStore>,
+    document_id: String,
+) -> Result<()> {
+    store.delete_meta(&document_recovery_key(&document_id))
+}
+
+/* Calendar events */
+
+#[tauri::command]
+pub fn read_stored_events(store: State<'_, LocalStore>) -> Result<Vec<Value>> {
+    store.read_records(RecordStore::Events)
+}
+
+#[tauri::command]
+pub fn write_stored_events(store: State<'_, LocalStore>, events: Vec<Value>) -> Result<()> {
+    changes::replace_records(&store, Resource::Events, events)
+}
+
+#[tauri::command]
+pub fn read_stored_event_queue(store: State<'_, LocalStore>) -> Result<Vec<Value>> {
+    store.read_queue(QueueStore::Events)
+}
+
+/* Meta */
+
+#[tauri::command]
+pub fn read_storage_meta(store: State<'_, LocalStore>, key: String) -> Result<Option<Value>> {
+    store.read_meta(&key)
+}
+
+#[tauri::command]
+pub fn write_storage_meta(store: State<'_, LocalStore>, key: String, value: Value) -> Result<()> {
+    store.write_meta(&key, &value)
+}
+
+/// Absolute path of the SQLite file, shown in Settings so the data can be
+/// located for manual backups.
+#[tauri::command]
+pub fn get_storage_path(store: State<'_, LocalStore>) -> String {
+    store.path().display().to_string()
+}
+
+/* Encryption */
+
+/// Re-encrypts the whole store under a fresh data key, from Settings.
+/// `pin` is the current workspace PIN, or null when none is set.
+#[tauri::command]
+pub async fn rekey_local_store(app: AppHandle, pin: Option<String>) -> Result<()> {
+    blocking::run(app, move |app| {
+        let lock = app.state::<WorkspaceLock>();
+        lock.confirm_pin(pin.as_deref())?;
+
+        let secret = if lock.state().pin_set {
+            UnlockSecret::Pin(pin.as_deref().unwrap_or_default())
+        } else {
+            UnlockSecret::Machine
+        };
+
+        app.state::<LocalStore>().rekey(secret)
+    })
+    .await
+}
@@ -0,0 +1,112 @@
+// desktop/src-tauri/src/storage/keyring.rs
+
+/*
+ * Key management for the encrypted local store.
+ *
+ * Values are sealed with a random data key. The data key itself is stored in
+ * the `keyring` table wrapped by a key derived from the unlock secret: the
+ * workspace PIN when one is set, otherwise this computer's machine id.
+ */
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::crypto::{self, SecretKey, SALT_LEN};
+use crate::error::{Error, Result};
+
+const WRAP_CONTEXT: &[u8] = b"pioneer-work-suite/keyring/v1";
+
+/// What the data key is wrapped with.
+#[derive(Clone, Copy)]
+pub enum UnlockSecret<'a> {
+    Machine,
+    Pin(&'a str),
+}
+
+impl UnlockSecret<'_> {
+    fn source(self) -> &'static str {
+        match self {
+            UnlockSecret::Machine => "machine",
+            UnlockSecret::Pin(_) => "pin",
+        }
+    }
+
+    fn derive(self, salt: &[u8]) -> Result<SecretKey> {
+        match self {
+            UnlockSecret::Machine => crypto::derive_key(&crypto::machine_secret()?, salt),
+            UnlockSecret::Pin(pin) => crypto::derive_key(pin.as_bytes(), salt),
+        }
+    }
+}
+
+pub(super) struct WrappedKey {
+    pub source: String,
+    salt: Vec<u8>,
+    wrapped: Vec<u8>,
+}
+
+impl WrappedKey {
+    pub fn unwrap_with(&self, secret: UnlockSecret<'_>) -> Result<SecretKey> {
+        let wrapping_key = secret.derive(&self.salt)?;
+        let key = crypto::open(&wrapping_key, &self.wrapped, WRAP_CONTEXT)
+            .map_err(|_| Error::Crypto("The unlock secret does not match the local store."))?;
+
+        SecretKey::from_slice(&key)
+    }
+}
+
+pub(super) fn read(connection: &Connection) -> Result<Option<WrappedKey>> {
+    Ok(connection
+        .query_row(
+            "SELECT source, salt, wrapped_key FROM keyring WHERE id = 1",
+            [],
+            |row| {
+                Ok(WrappedKey {
+                    source: row.get(0)?,
+                    salt: row.get(1)?,
+                    wrapped: row.get(2)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// Wraps `key` under `secret` with a fresh salt and stores it.
+pub(super) fn write(
+    connection: &Connection,
+    key: &SecretKey,
+    secret: UnlockSecret<'_>,
+) -> Result<()> {
+    let salt = crypto::random_bytes::<SALT_LEN>();
+    let wrapping_key = secret.derive(&salt)?;
+    let wrapped = crypto::seal(&wrapping_key, key.as_bytes(), WRAP_CONTEXT)?;
+
+    insert(
+        connection,
+        &WrappedKey {
+            source: secret.source().to_string(),
+            salt: salt.to_vec(),
+            wrapped,
+        },
+    )
+}
+
+fn insert(connection: &Connection, wrapped: &WrappedKey) -> Result<()> {
+    connection.execute(
+        "INSERT OR REPLACE INTO keyring (id, source, salt, wrapped_key) VALUES (1, ?1, ?2, ?3)",
+        params![wrapped.source, wrapped.salt, wrapped.wrapped],
+    )?;
+
+    Ok(())
+}
+
+/// Copies the wrapped key of `from` into `to`, e.g. a snapshot.
+pub(super) fn copy(from: &Connection, to: &Connection) -> Result<()> {
+    match read(from)? {
+        Some(wrapped) => insert(to, &wrapped),
+        None => Ok(()),
+    }
+}
+
+pub(super) fn is_source(wrapped: &WrappedKey, secret: UnlockSecret<'_>) -> bool {
+    wrapped.source == secret.source()
+}
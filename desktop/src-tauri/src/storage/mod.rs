@@ -0,0 +1,1002 @@
+// desktop/src-tauri/src/storage/mod.rs
+
+/*
+ * Native replacement for the webview's IndexedDB database.
+ *
+ * The layout deliberately mirrors apps/web/src/api/storage.ts: three record
+ * stores keyed by `id`, three ordered op queues, and a key/value meta store
+ * that also holds document recovery drafts. Values are kept as opaque JSON so
+ * the web layer stays the single owner of the task/document/event shapes.
+ *
+ * Every value is sealed with XChaCha20-Poly1305 before it is written (see
+ * keyring.rs for where the key comes from), so the store has to be unlocked
+ * before anything can be read or written. Schema version 1 stored plaintext
+ * JSON; those rows are encrypted in place the first time the store is
+ * unlocked.
+ *
+ * The embedded API server (see local_api) keeps its accounts and data in
+ * record stores of its own, apart from the webview's cache.
+ */
+
+pub mod commands;
+pub mod keyring;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, TransactionBehavior};
+use serde_json::Value;
+use tauri::{AppHandle, Config};
+use zeroize::Zeroizing;
+
+use crate::crypto::{self, SecretKey};
+use crate::error::{Error, Result};
+use crate::paths;
+pub use keyring::UnlockSecret;
+
+pub const DATABASE_FILE_NAME: &str = "pioneer-work-suite.sqlite3";
+
+pub const SNAPSHOT_DIR_NAME: &str = "snapshots";
+pub const SNAPSHOT_EXTENSION: &str = "sqlite3";
+
+const SCHEMA_VERSION: i64 = 2;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS records (
+        store TEXT NOT NULL,
+        id TEXT NOT NULL,
+        value BLOB NOT NULL,
+        PRIMARY KEY (store, id)
+    );
+
+    CREATE TABLE IF NOT EXISTS queue (
+        store TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        value BLOB NOT NULL,
+        PRIMARY KEY (store, position)
+    );
+
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value BLOB NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS keyring (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        source TEXT NOT NULL,
+        salt BLOB NOT NULL,
+        wrapped_key BLOB NOT NULL
+    );
+";
+
+const CONTEXT_PREFIX: &str = "pioneer-work-suite/storage/v1/";
+
+/// Each table holding sealed values, with the SQL expression for the
+/// context a value is bound to. These must agree with the `*_context`
+/// helpers below.
+const VALUE_TABLES: [(&str, &str); 3] = [
+    ("records", "'records/' || store || '/' || id"),
+    ("queue", "'queue/' || store"),
+    ("meta", "'meta/' || key"),
+];
+
+fn record_context(store: RecordStore, id: &str) -> String {
+    format!("records/{}/{id}", store.name())
+}
+
+fn queue_context(store: QueueStore) -> String {
+    format!("queue/{}", store.name())
+}
+
+fn meta_context(key: &str) -> String {
+    format!("meta/{key}")
+}
+
+/// Record stores keyed by the entity `id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordStore {
+    Tasks,
+    Documents,
+    Events,
+    LocalApiUsers,
+    LocalApiTasks,
+    LocalApiDocuments,
+    LocalApiEvents,
+    LocalApiMail,
+}
+
+impl RecordStore {
+    pub fn name(self) -> &'static str {
+        match self {
+            RecordStore::Tasks => "tasks",
+            RecordStore::Documents => "documents",
+            RecordStore::Events => "events",
+            RecordStore::LocalApiUsers => "localApiUsers",
+            RecordStore::LocalApiTasks => "localApiTasks",
+            RecordStore::LocalApiDocuments => "localApiDocuments",
+            RecordStore::LocalApiEvents => "localApiEvents",
+            RecordStore::LocalApiMail => "localApiMail",
+        }
+    }
+}
+
+/// Ordered offline op queues, replayed front to back by the sync layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueStore {
+    Tasks,
+    Documents,
+    Events,
+}
+
+impl QueueStore {
+    pub fn name(self) -> &'static str {
+        match self {
+            QueueStore::Tasks => "taskQueue",
+            QueueStore::Documents => "documentQueue",
+            QueueStore::Events => "eventQueue",
+        }
+    }
+}
+
+const DOCUMENT_RECOVERY_PREFIX: &str = "documentRecovery:";
+
+pub fn document_recovery_key(document_id: &str) -> String {
+    format!("{DOCUMENT_RECOVERY_PREFIX}{document_id}")
+}
+
+/// Everything the web layer keeps in the store, moved as one unit by
+/// backups and snapshots.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceContents {
+    pub tasks: Vec<Value>,
+    pub documents: Vec<Value>,
+    pub events: Vec<Value>,
+    pub task_queue: Vec<Value>,
+    pub document_queue: Vec<Value>,
+    pub event_queue: Vec<Value>,
+    /// Document recovery drafts keyed by document id.
+    pub recovery_drafts: BTreeMap<String, Value>,
+}
+
+pub struct LocalStore {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    key: Mutex<Option<SecretKey>>,
+}
+
+impl LocalStore {
+    /// Opens the database. The store starts out locked; call `unlock`
+    /// before reading or writing values.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut connection = Connection::open(&path)?;
+
+        // WAL keeps reads available while a long write is in progress.
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        // Overwrite deleted content instead of leaving it in free pages.
+        connection.pragma_update(None, "secure_delete", true)?;
+        // The command-line mode may write while the app is running. A
+        // deferred transaction that reads and then writes fails outright
+        // when the other process wrote in between; taking the write lock
+        // up front makes it wait for the busy timeout instead.
+        connection.set_transaction_behavior(TransactionBehavior::Immediate);
+
+        let version: i64 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        connection.execute_batch(SCHEMA)?;
+
+        // Older databases keep their version until `unlock` has encrypted
+        // their plaintext rows.
+        if version == 0 {
+            connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+
+        Ok(Self {
+            path,
+            connection: Mutex::new(connection),
+            key: Mutex::new(None),
+        })
+    }
+
+    pub fn open_in_app_data(app: &AppHandle) -> Result<Self> {
+        Self::open(paths::app_data_dir(app)?.join(DATABASE_FILE_NAME))
+    }
+
+    pub fn open_for_config(config: &Config) -> Result<Self> {
+        Self::open(paths::app_data_dir_for(config)?.join(DATABASE_FILE_NAME))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        // A panic while holding the lock cannot leave SQLite half-written:
+        // every write below runs inside its own transaction.
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn key(&self) -> MutexGuard<'_, Option<SecretKey>> {
+        self.key
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The data key. Always taken after `connection()` so the two locks are
+    /// acquired in a consistent order.
+    fn current_key(&self) -> Result<SecretKey> {
+        self.key().clone().ok_or(Error::StoreLocked)
+    }
+
+    /* Keys */
+
+    pub fn is_unlocked(&self) -> bool {
+        self.key().is_some()
+    }
+
+    /// Whether the data key is wrapped by a PIN, which `unlock` then needs.
+    pub fn requires_pin(&self) -> Result<bool> {
+        let connection = self.connection();
+
+        Ok(keyring::read(&connection)?
+            .is_some_and(|wrapped| keyring::is_source(&wrapped, UnlockSecret::Pin(""))))
+    }
+
+    /// Whether `pin` unwraps the data key. The store stays as it is.
+    pub fn pin_unlocks(&self, pin: &str) -> Result<bool> {
+        let connection = self.connection();
+        let secret = UnlockSecret::Pin(pin);
+
+        match keyring::read(&connection)? {
+            Some(wrapped) if keyring::is_source(&wrapped, secret) => {
+                match wrapped.unwrap_with(secret) {
+                    Ok(_) => Ok(true),
+                    Err(Error::Crypto(_)) => Ok(false),
+                    Err(error) => Err(error),
+                }
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Unwraps the data key with `secret`, creating one on first use, and
+    /// encrypts any rows left over from schema version 1.
+    pub fn unlock(&self, secret: UnlockSecret<'_>) -> Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let key = match keyring::read(&transaction)? {
+            None => {
+                let key = SecretKey::generate();
+                keyring::write(&transaction, &key, secret)?;
+                key
+            }
+            Some(wrapped) if keyring::is_source(&wrapped, secret) => wrapped.unwrap_with(secret)?,
+            // Data written before a PIN was set is moved under the PIN.
+            Some(wrapped) if keyring::is_source(&wrapped, UnlockSecret::Machine) => {
+                let key = wrapped.unwrap_with(UnlockSecret::Machine)?;
+                keyring::write(&transaction, &key, secret)?;
+                key
+            }
+            Some(_) => return Err(Error::StoreLocked),
+        };
+
+        let encrypted = rewrite_values(&transaction, |context, value| match value {
+            SqlValue::Text(json) => Ok(Some(seal_value(
+                &key,
+                context,
+                &serde_json::from_str(&json)?,
+            )?)),
+            _ => Ok(None),
+        })?;
+
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        transaction.commit()?;
+
+        if encrypted > 0 {
+            compact(&connection)?;
+        }
+
+        *self.key() = Some(key);
+        Ok(())
+    }
+
+    /// Forgets the data key until the next `unlock`.
+    pub fn lock(&self) {
+        *self.key() = None;
+    }
+
+    /// Replaces the data key, re-encrypting every value, and wraps the new
+    /// key under `secret`. Used when the PIN is set, changed or removed.
+    ///
+    /// Snapshots under the old key are re-encrypted too and take over the
+    /// new wrapped key. Any other snapshot, e.g. one from before the
+    /// database was recreated, keeps the key it was taken with.
+    pub fn rekey(&self, secret: UnlockSecret<'_>) -> Result<()> {
+        let mut connection = self.connection();
+        let old_key = self.current_key()?;
+        let new_key = SecretKey::generate();
+
+        let transaction = connection.transaction()?;
+
+        reseal_values(&transaction, &old_key, &new_key)?;
+        keyring::write(&transaction, &new_key, secret)?;
+
+        transaction.commit()?;
+
+        *self.key() = Some(new_key.clone());
+
+        for path in self.snapshot_files()? {
+            let _ = reseal_snapshot(&path, &connection, &old_key, &new_key);
+        }
+
+        Ok(())
+    }
+
+    /* Snapshots */
+
+    /// Directory holding snapshots of this store, next to the database.
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(SNAPSHOT_DIR_NAME)
+    }
+
+    fn snapshot_files(&self) -> Result<Vec<PathBuf>> {
+        let directory = self.snapshot_dir();
+
+        if !directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+
+            if path
+                .extension()
+                .is_some_and(|extension| extension == SNAPSHOT_EXTENSION)
+            {
+                files.push(path);
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Copies the database to `path`. Values stay sealed, and the wrapped
+    /// key goes along, so the copy can be opened without this database.
+    pub fn snapshot_to(&self, path: &Path) -> Result<()> {
+        let connection = self.connection();
+
+        // Only an unlocked store is guaranteed to hold no plaintext rows.
+        self.current_key()?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+
+        let _ = fs::remove_file(&temporary);
+        connection.execute(
+            "VACUUM INTO ?1",
+            params![temporary.to_string_lossy().into_owned()],
+        )?;
+
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Reads the contents of a snapshot made by `snapshot_to`. A snapshot
+    /// under another key than this store's, e.g. one taken before the
+    /// database was lost, is opened with the key wrapped inside it, which
+    /// needs `pin` if it was taken under a PIN.
+    pub fn read_snapshot(&self, path: &Path, pin: Option<&str>) -> Result<WorkspaceContents> {
+        let snapshot = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let key = self.current_key()?;
+
+        match select_contents(&snapshot, &key) {
+            Err(Error::Crypto(_)) => {}
+            result => return result,
+        }
+
+        let unreadable =
+            || Error::Crypto("This snapshot was made with a different key and cannot be read.");
+        let wrapped = keyring::read(&snapshot)?.ok_or_else(unreadable)?;
+
+        let key = if keyring::is_source(&wrapped, UnlockSecret::Machine) {
+            wrapped
+                .unwrap_with(UnlockSecret::Machine)
+                .map_err(|_| unreadable())?
+        } else {
+            let pin = pin.ok_or(Error::Crypto(
+                "This snapshot was taken under an earlier PIN. Enter that PIN to restore it.",
+            ))?;
+            wrapped
+                .unwrap_with(UnlockSecret::Pin(pin))
+                .map_err(|_| Error::Crypto("That PIN does not open this snapshot."))?
+        };
+
+        select_contents(&snapshot, &key).map_err(|error| match error {
+            Error::Crypto(_) => unreadable(),
+            error => error,
+        })
+    }
+
+    /* Records */
+
+    pub fn read_records(&self, store: RecordStore) -> Result<Vec<Value>> {
+        let connection = self.connection();
+        select_records(&connection, &self.current_key()?, store)
+    }
+
+    /// Seeds tests. The app changes records through `update_records` and
+    /// `sync::changes`, which see what the sync worker wrote meanwhile.
+    #[cfg(test)]
+    pub fn replace_records(&self, store: RecordStore, values: &[Value]) -> Result<()> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        write_records(&transaction, &key, store, values)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Reads, modifies and writes a record store in one transaction so a
+    /// concurrent write from the webview cannot interleave.
+    pub fn update_records<R>(
+        &self,
+        store: RecordStore,
+        update: impl FnOnce(&mut Vec<Value>) -> R,
+    ) -> Result<R> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        let mut values = select_records(&transaction, &key, store)?;
+        let result = update(&mut values);
+
+        write_records(&transaction, &key, store, &values)?;
+        transaction.commit()?;
+        Ok(result)
+    }
+
+    /* Queues */
+
+    pub fn read_queue(&self, store: QueueStore) -> Result<Vec<Value>> {
+        let connection = self.connection();
+        select_queue(&connection, &self.current_key()?, store)
+    }
+
+    #[cfg(test)]
+    pub fn replace_queue(&self, store: QueueStore, queue: &[Value]) -> Result<()> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        write_queue(&transaction, &key, store, queue)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Transactional read-modify-write of an op queue; see `update_records`.
+    pub fn update_queue<R>(
+        &self,
+        store: QueueStore,
+        update: impl FnOnce(&mut Vec<Value>) -> R,
+    ) -> Result<R> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        let mut queue = select_queue(&transaction, &key, store)?;
+        let result = update(&mut queue);
+
+        write_queue(&transaction, &key, store, &queue)?;
+        transaction.commit()?;
+        Ok(result)
+    }
+
+    /// Updates a record store and its queue in one transaction, so a record
+    /// and the op that syncs it are written together.
+    pub fn update_records_and_queue<R>(
+        &self,
+        records: RecordStore,
+        queue: QueueStore,
+        update: impl FnOnce(&mut Vec<Value>, &mut Vec<Value>) -> R,
+    ) -> Result<R> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        let mut values = select_records(&transaction, &key, records)?;
+        let mut ops = select_queue(&transaction, &key, queue)?;
+        let result = update(&mut values, &mut ops);
+
+        write_records(&transaction, &key, records, &values)?;
+        write_queue(&transaction, &key, queue, &ops)?;
+        transaction.commit()?;
+        Ok(result)
+    }
+
+    /* Meta */
+
+    pub fn read_meta(&self, key: &str) -> Result<Option<Value>> {
+        let connection = self.connection();
+        let data_key = self.current_key()?;
+        let sealed: Option<Vec<u8>> = connection
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match sealed {
+            Some(sealed) => Ok(Some(open_value(&data_key, &meta_context(key), &sealed)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn write_meta(&self, key: &str, value: &Value) -> Result<()> {
+        let connection = self.connection();
+        let sealed = seal_value(&self.current_key()?, &meta_context(key), value)?;
+
+        connection.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![key, sealed],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn delete_meta(&self, key: &str) -> Result<()> {
+        self.connection()
+            .execute("DELETE FROM meta WHERE key = ?1", params![key])?;
+
+        Ok(())
+    }
+
+    /* Whole workspace */
+
+    /// Reads every store inside one transaction, so the result is a
+    /// consistent picture even while the sync worker is running.
+    pub fn read_contents(&self) -> Result<WorkspaceContents> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        let contents = select_contents(&transaction, &key)?;
+
+        transaction.commit()?;
+        Ok(contents)
+    }
+
+    /// Replaces every store with `contents` in one transaction. Other meta
+    /// keys are left alone.
+    pub fn replace_contents(&self, contents: &WorkspaceContents) -> Result<()> {
+        let mut connection = self.connection();
+        let key = self.current_key()?;
+        let transaction = connection.transaction()?;
+
+        write_records(&transaction, &key, RecordStore::Tasks, &contents.tasks)?;
+        write_records(
+            &transaction,
+            &key,
+            RecordStore::Documents,
+            &contents.documents,
+        )?;
+        write_records(&transaction, &key, RecordStore::Events, &contents.events)?;
+        write_queue(&transaction, &key, QueueStore::Tasks, &contents.task_queue)?;
+        write_queue(
+            &transaction,
+            &key,
+            QueueStore::Documents,
+            &contents.document_queue,
+        )?;
+        write_queue(
+            &transaction,
+            &key,
+            QueueStore::Events,
+            &contents.event_queue,
+        )?;
+
+        transaction.execute(
+            "DELETE FROM meta WHERE substr(key, 1, length(?1)) = ?1",
+            params![DOCUMENT_RECOVERY_PREFIX],
+        )?;
+
+        for (document_id, draft) in &contents.recovery_drafts {
+            let meta_key = document_recovery_key(document_id);
+
+            transaction.execute(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)",
+                params![meta_key, seal_value(&key, &meta_context(&meta_key), draft)?],
+            )?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+fn seal_value(key: &SecretKey, context: &str, value: &Value) -> Result<Vec<u8>> {
+    let plaintext = Zeroizing::new(serde_json::to_vec(value)?);
+    crypto::seal(
+        key,
+        &plaintext,
+        format!("{CONTEXT_PREFIX}{context}").as_bytes(),
+    )
+}
+
+fn open_value(key: &SecretKey, context: &str, sealed: &[u8]) -> Result<Value> {
+    let plaintext = Zeroizing::new(crypto::open(
+        key,
+        sealed,
+        format!("{CONTEXT_PREFIX}{context}").as_bytes(),
+    )?);
+
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+/// Passes every stored value to `rewrite` along with its context, writing
+/// back whatever it returns. Returns how many values were rewritten.
+fn rewrite_values(
+    connection: &Connection,
+    mut rewrite: impl FnMut(&str, SqlValue) -> Result<Option<Vec<u8>>>,
+) -> Result<usize> {
+    let mut rewritten = 0;
+
+    for (table, context) in VALUE_TABLES {
+        let rows = connection
+            .prepare(&format!("SELECT rowid, {context}, value FROM {table}"))?
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, SqlValue>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut update =
+            connection.prepare(&format!("UPDATE {table} SET value = ?1 WHERE rowid = ?2"))?;
+
+        for (rowid, context, value) in rows {
+            if let Some(sealed) = rewrite(&context, value)? {
+                update.execute(params![sealed, rowid])?;
+                rewritten += 1;
+            }
+        }
+    }
+
+    Ok(rewritten)
+}
+
+/// Re-encrypts every value from `old_key` to `new_key`. Plaintext rows left
+/// from schema version 1 are sealed along the way.
+fn reseal_values(
+    connection: &Connection,
+    old_key: &SecretKey,
+    new_key: &SecretKey,
+) -> Result<usize> {
+    rewrite_values(connection, |context, value| {
+        let value = match value {
+            SqlValue::Text(json) => serde_json::from_str(&json)?,
+            SqlValue::Blob(sealed) => open_value(old_key, context, &sealed)?,
+            _ => return Err(Error::Crypto("The local store contains a damaged value.")),
+        };
+
+        Ok(Some(seal_value(new_key, context, &value)?))
+    })
+}
+
+/// Moves the snapshot at `path` from `old_key` to `new_key` and the wrapped
+/// key of `store`. Leaves it as it is when `old_key` does not open it.
+fn reseal_snapshot(
+    path: &Path,
+    store: &Connection,
+    old_key: &SecretKey,
+    new_key: &SecretKey,
+) -> Result<()> {
+    let mut snapshot = Connection::open(path)?;
+    snapshot.pragma_update(None, "secure_delete", true)?;
+
+    let transaction = snapshot.transaction()?;
+    reseal_values(&transaction, old_key, new_key)?;
+    keyring::copy(store, &transaction)?;
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Rebuilds the database file and empties the WAL so pages that held
+/// plaintext before encryption do not linger on disk.
+fn compact(connection: &Connection) -> Result<()> {
+    connection.execute_batch("VACUUM")?;
+    connection.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+
+    Ok(())
+}
+
+fn select_contents(connection: &Connection, key: &SecretKey) -> Result<WorkspaceContents> {
+    Ok(WorkspaceContents {
+        tasks: select_records(connection, key, RecordStore::Tasks)?,
+        documents: select_records(connection, key, RecordStore::Documents)?,
+        events: select_records(connection, key, RecordStore::Events)?,
+        task_queue: select_queue(connection, key, QueueStore::Tasks)?,
+        document_queue: select_queue(connection, key, QueueStore::Documents)?,
+        event_queue: select_queue(connection, key, QueueStore::Events)?,
+        recovery_drafts: select_recovery_drafts(connection, key)?,
+    })
+}
+
+fn select_recovery_drafts(
+    connection: &Connection,
+    key: &SecretKey,
+) -> Result<BTreeMap<String, Value>> {
+    let mut statement = connection.prepare(
+        "SELECT key, value FROM meta WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+    )?;
+
+    let rows = statement.query_map(params![DOCUMENT_RECOVERY_PREFIX], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+    })?;
+
+    rows.map(|row| {
+        let (meta_key, sealed) = row?;
+        let draft = open_value(key, &meta_context(&meta_key), &sealed)?;
+
+        Ok((
+            meta_key[DOCUMENT_RECOVERY_PREFIX.len()..].to_string(),
+            draft,
+        ))
+    })
+    .collect()
+}
+
+fn select_records(
+    connection: &Connection,
+    key: &SecretKey,
+    store: RecordStore,
+) -> Result<Vec<Value>> {
+    let mut statement =
+        connection.prepare("SELECT id, value FROM records WHERE store = ?1 ORDER BY id")?;
+
+    let rows = statement.query_map(params![store.name()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+    })?;
+
+    rows.map(|row| {
+        let (id, sealed) = row?;
+        open_value(key, &record_context(store, &id), &sealed)
+    })
+    .collect()
+}
+
+fn write_records(
+    connection: &Connection,
+    key: &SecretKey,
+    store: RecordStore,
+    values: &[Value],
+) -> Result<()> {
+    connection.execute(
+        "DELETE FROM records WHERE store = ?1",
+        params![store.name()],
+    )?;
+
+    let mut insert = connection
+        .prepare("INSERT OR REPLACE INTO records (store, id, value) VALUES (?1, ?2, ?3)")?;
+
+    for value in values {
+        let id = value.get("id").and_then(Value::as_str).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Every record written to {} needs a string id.",
+                store.name()
+            ))
+        })?;
+
+        let sealed = seal_value(key, &record_context(store, id), value)?;
+        insert.execute(params![store.name(), id, sealed])?;
+    }
+
+    Ok(())
+}
+
+fn select_queue(connection: &Connection, key: &SecretKey, store: QueueStore) -> Result<Vec<Value>> {
+    let mut statement =
+        connection.prepare("SELECT value FROM queue WHERE store = ?1 ORDER BY position")?;
+
+    let rows = statement.query_map(params![store.name()], |row| row.get::<_, Vec<u8>>(0))?;
+    let context = queue_context(store);
+
+    rows.map(|row| open_value(key, &context, &row?)).collect()
+}
+
+fn write_queue(
+    connection: &Connection,
+    key: &SecretKey,
+    store: QueueStore,
+    queue: &[Value],
+) -> Result<()> {
+    connection.execute("DELETE FROM queue WHERE store = ?1", params![store.name()])?;
+
+    let mut insert =
+        connection.prepare("INSERT INTO queue (store, position, value) VALUES (?1, ?2, ?3)")?;
+    let context = queue_context(store);
+
+    for (index, value) in queue.iter().enumerate() {
+        insert.execute(params![
+            store.name(),
+            index as i64 + 1,
+            seal_value(key, &context, value)?
+        ])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    const MARKER: &str = "plaintext-marker-4f1d9c";
+
+    fn database_path(directory: &tempfile::TempDir) -> PathBuf {
+        directory.path().join("store.sqlite3")
+    }
+
+    /// True when `MARKER` appears in the database or its WAL/SHM files.
+    fn marker_on_disk(directory: &tempfile::TempDir) -> bool {
+        fs::read_dir(directory.path()).unwrap().any(|entry| {
+            let bytes = fs::read(entry.unwrap().path()).unwrap();
+            bytes
+                .windows(MARKER.len())
+                .any(|window| window == MARKER.as_bytes())
+        })
+    }
+
+    fn write_everything(store: &LocalStore) {
+        store
+            .replace_records(
+                RecordStore::Documents,
+                &[json!({ "id": "doc-1", "title": "Notes", "content": MARKER })],
+            )
+            .unwrap();
+        store
+            .replace_queue(
+                QueueStore::Documents,
+                &[json!({ "kind": "update", "id": "doc-1", "patch": { "content": MARKER } })],
+            )
+            .unwrap();
+        store
+            .write_meta(
+                &document_recovery_key("doc-1"),
+                &json!({ "documentId": "doc-1", "content": MARKER }),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn plaintext_never_reaches_disk() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(database_path(&directory)).unwrap();
+        store.unlock(UnlockSecret::Pin("2468")).unwrap();
+
+        write_everything(&store);
+
+        assert!(!marker_on_disk(&directory));
+        assert_eq!(
+            store.read_records(RecordStore::Documents).unwrap()[0]["content"],
+            MARKER
+        );
+        assert_eq!(
+            store.read_queue(QueueStore::Documents).unwrap()[0]["patch"]["content"],
+            MARKER
+        );
+        assert_eq!(
+            store
+                .read_meta(&document_recovery_key("doc-1"))
+                .unwrap()
+                .unwrap()["content"],
+            MARKER
+        );
+    }
+
+    #[test]
+    fn version_one_plaintext_is_encrypted_on_unlock() {
+        let directory = tempfile::tempdir().unwrap();
+
+        {
+            let connection = Connection::open(database_path(&directory)).unwrap();
+            let record = json!({ "id": "task-1", "title": MARKER }).to_string();
+
+            connection
+                .execute_batch(
+                    "CREATE TABLE records (store TEXT NOT NULL, id TEXT NOT NULL, value TEXT NOT NULL, PRIMARY KEY (store, id));
+                     CREATE TABLE queue (store TEXT NOT NULL, position INTEGER NOT NULL, value TEXT NOT NULL, PRIMARY KEY (store, position));
+                     CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                     PRAGMA user_version = 1;",
+                )
+                .unwrap();
+            connection
+                .execute(
+                    "INSERT INTO records (store, id, value) VALUES ('tasks', 'task-1', ?1)",
+                    params![record],
+                )
+                .unwrap();
+        }
+
+        assert!(marker_on_disk(&directory));
+
+        let store = LocalStore::open(database_path(&directory)).unwrap();
+        store.unlock(UnlockSecret::Pin("2468")).unwrap();
+
+        assert!(!marker_on_disk(&directory));
+        assert_eq!(
+            store.read_records(RecordStore::Tasks).unwrap()[0]["title"],
+            MARKER
+        );
+    }
+
+    #[test]
+    fn rekey_replaces_the_unlock_secret() {
+        let directory = tempfile::tempdir().unwrap();
+
+        {
+            let store = LocalStore::open(database_path(&directory)).unwrap();
+            store.unlock(UnlockSecret::Pin("2468")).unwrap();
+            write_everything(&store);
+            store.rekey(UnlockSecret::Pin("1357")).unwrap();
+        }
+
+        assert!(!marker_on_disk(&directory));
+
+        let store = LocalStore::open(database_path(&directory)).unwrap();
+
+        assert!(store.requires_pin().unwrap());
+        assert!(!store.pin_unlocks("2468").unwrap());
+        assert!(store.pin_unlocks("1357").unwrap());
+        assert!(!store.is_unlocked());
+
+        assert!(store.unlock(UnlockSecret::Pin("2468")).is_err());
+        store.unlock(UnlockSecret::Pin("1357")).unwrap();
+        assert_eq!(
+            store.read_records(RecordStore::Documents).unwrap()[0]["content"],
+            MARKER
+        );
+    }
+
+    #[test]
+    fn locked_store_refuses_reads_and_writes() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(database_path(&directory)).unwrap();
+
+        assert!(matches!(
+            store.read_records(RecordStore::Tasks),
+            Err(Error::StoreLocked)
+        ));
+
+        store.unlock(UnlockSecret::Pin("2468")).unwrap();
+        write_everything(&store);
+        store.lock();
+
+        assert!(matches!(
+            store.write_meta("key", &json!(true)),
+            Err(Error::StoreLocked)
+        ));
+    }
+}
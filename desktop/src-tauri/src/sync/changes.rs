@@ -0,0 +1,315 @@
+// desktop/src-tauri/src/sync/changes.rs
+
+/*
+ * Local edits to the caches and op queues, one transaction each.
+ *
+ * The sync worker removes replayed ops and swaps temporary ids for server
+ * ids while the webview keeps editing, so the webview cannot read a whole
+ * queue or record list and write it back later. Instead it sends single
+ * changes: an op, which is coalesced with the queue as it is at that moment
+ * (the rules of `enqueueCreate`/`enqueueUpdate`/`enqueueDelete` in
+ * apps/web/src/api), or a record to merge or remove.
+ *
+ * Whole record lists, as written after a fetch from the server, are
+ * reconciled with the current queue first: a temporary record whose create
+ * has already been replayed stays gone, and records with changes still
+ * waiting keep their local version, as `fetchTasks` intends.
+ */
+
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use super::engine::{QueueOp, Resource};
+use crate::error::Result;
+use crate::storage::LocalStore;
+
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a str> {
+    value.get(name).and_then(Value::as_str)
+}
+
+fn is_op(entry: &Value, kind: &str, id_field: &str, id: &str) -> bool {
+    field(entry, "kind") == Some(kind) && field(entry, id_field) == Some(id)
+}
+
+/// Shallow merge, as with `{ ...target, ...updates }`.
+fn merge_fields(target: &mut Value, updates: Value) {
+    match (target.as_object_mut(), updates) {
+        (Some(fields), Value::Object(updates)) => fields.extend(updates),
+        (_, updates) => *target = updates,
+    }
+}
+
+/// Adds `op` to `queue`, folding it into the ops already waiting for the
+/// same record.
+pub fn enqueue_op(queue: &mut Vec<Value>, resource: Resource, op: QueueOp) {
+    match op {
+        QueueOp::Create { .. } => queue.push(json!(op)),
+        QueueOp::Update {
+            id,
+            patch,
+            timestamp,
+        } => {
+            if let Some(create) = queue
+                .iter_mut()
+                .find(|entry| is_op(entry, "create", "tempId", &id))
+            {
+                merge_fields(&mut create["payload"], patch);
+                create["timestamp"] = json!(timestamp);
+                return;
+            }
+
+            if queue.iter().any(|entry| is_op(entry, "delete", "id", &id)) {
+                return;
+            }
+
+            match queue
+                .iter_mut()
+                .find(|entry| is_op(entry, "update", "id", &id))
+            {
+                Some(update) => {
+                    merge_fields(&mut update["patch"], patch);
+                    update["timestamp"] = json!(timestamp);
+                }
+                None => queue.push(json!(QueueOp::Update {
+                    id,
+                    patch,
+                    timestamp
+                })),
+            }
+        }
+        QueueOp::Delete { id, timestamp } => {
+            queue.retain(|entry| {
+                !(is_op(entry, "create", "tempId", &id)
+                    || is_op(entry, "update", "id", &id)
+                    || is_op(entry, "delete", "id", &id))
+            });
+
+            // A record the server never saw only has to disappear locally.
+            if !resource.is_offline_id(&id) {
+                queue.push(json!(QueueOp::Delete { id, timestamp }));
+            }
+        }
+    }
+}
+
+/// Merges `record` into the entry with its id, or adds it at the front.
+pub fn merge_record(records: &mut Vec<Value>, record: Value) {
+    let id = field(&record, "id").map(str::to_string);
+
+    match records
+        .iter_mut()
+        .find(|existing| id.is_some() && field(existing, "id") == id.as_deref())
+    {
+        Some(existing) => merge_fields(existing, record),
+        None => records.insert(0, record),
+    }
+}
+
+/// `incoming`, written by the webview from what it read earlier, adjusted
+/// to `current` and `queue` as they are now.
+pub fn reconcile(
+    resource: Resource,
+    incoming: Vec<Value>,
+    current: &[Value],
+    queue: &[Value],
+) -> Vec<Value> {
+    let ids_of = |kind: &str, id_field: &str| -> HashSet<String> {
+        queue
+            .iter()
+            .filter(|entry| field(entry, "kind") == Some(kind))
+            .filter_map(|entry| field(entry, id_field).map(str::to_string))
+            .collect()
+    };
+    let deletes = ids_of("delete", "id");
+    let updates = ids_of("update", "id");
+    let current_ids: HashSet<&str> = current
+        .iter()
+        .filter_map(|record| field(record, "id"))
+        .collect();
+
+    let mut records: Vec<Value> = incoming
+        .into_iter()
+        .filter(|record| match field(record, "id") {
+            Some(id) => {
+                !deletes.contains(id) && (!resource.is_offline_id(id) || current_ids.contains(id))
+            }
+            None => true,
+        })
+        .collect();
+
+    for record in current {
+        let Some(id) = field(record, "id") else {
+            continue;
+        };
+
+        if deletes.contains(id) || !(resource.is_offline_id(id) || updates.contains(id)) {
+            continue;
+        }
+
+        match records
+            .iter_mut()
+            .find(|existing| field(existing, "id") == Some(id))
+        {
+            Some(existing) => *existing = record.clone(),
+            None => records.insert(0, record.clone()),
+        }
+    }
+
+    records
+}
+
+/// Adds `op` to the queue of `resource`. Returns the queue length.
+pub fn enqueue(store: &LocalStore, resource: Resource, op: QueueOp) -> Result<usize> {
+    store.update_queue(resource.queue_store(), |queue| {
+        enqueue_op(queue, resource, op);
+        queue.len()
+    })
+}
+
+pub fn save_record(store: &LocalStore, resource: Resource, record: Value) -> Result<()> {
+    store.update_records(resource.record_store(), |records| {
+        merge_record(records, record)
+    })
+}
+
+pub fn remove_record(store: &LocalStore, resource: Resource, id: &str) -> Result<()> {
+    store.update_records(resource.record_store(), |records| {
+        records.retain(|record| field(record, "id") != Some(id));
+    })
+}
+
+/// Replaces the cache of `resource` with `records`, reconciled with the
+/// current queue.
+pub fn replace_records(store: &LocalStore, resource: Resource, records: Vec<Value>) -> Result<()> {
+    store.update_records_and_queue(
+        resource.record_store(),
+        resource.queue_store(),
+        |current, queue| {
+            *current = reconcile(resource, records, current, queue);
+        },
+    )
+}
+
+/// Adds the webview's IndexedDB cache and queue of `resource` as they are,
+/// for the one-time import on the first desktop start.
+pub fn import(
+    store: &LocalStore,
+    resource: Resource,
+    records: Vec<Value>,
+    ops: Vec<Value>,
+) -> Result<()> {
+    store.update_records_and_queue(
+        resource.record_store(),
+        resource.queue_store(),
+        |current, queue| {
+            for record in records.into_iter().rev() {
+                merge_record(current, record);
+            }
+            queue.extend(ops);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(id: &str, patch: Value) -> QueueOp {
+        QueueOp::Update {
+            id: id.to_string(),
+            patch,
+            timestamp: 2,
+        }
+    }
+
+    fn delete(id: &str) -> QueueOp {
+        QueueOp::Delete {
+            id: id.to_string(),
+            timestamp: 3,
+        }
+    }
+
+    #[test]
+    fn ops_coalesce_like_the_web_client() {
+        let mut queue = vec![json!(QueueOp::Create {
+            temp_id: "offline-task-1-a".to_string(),
+            payload: json!({ "title": "Draft", "status": "todo" }),
+            timestamp: 1,
+        })];
+
+        enqueue_op(
+            &mut queue,
+            Resource::Tasks,
+            update("offline-task-1-a", json!({ "status": "done" })),
+        );
+        assert_eq!(queue.len(), 1);
+        assert_eq!(
+            queue[0]["payload"],
+            json!({ "title": "Draft", "status": "done" })
+        );
+
+        enqueue_op(&mut queue, Resource::Tasks, update("t1", json!({ "a": 1 })));
+        enqueue_op(&mut queue, Resource::Tasks, update("t1", json!({ "b": 2 })));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[1]["patch"], json!({ "a": 1, "b": 2 }));
+
+        // Deleting a record the server never saw drops its create.
+        enqueue_op(&mut queue, Resource::Tasks, delete("offline-task-1-a"));
+        enqueue_op(&mut queue, Resource::Tasks, delete("t1"));
+        assert_eq!(queue, vec![json!(delete("t1"))]);
+
+        // Nothing to update once a delete is waiting.
+        enqueue_op(&mut queue, Resource::Tasks, update("t1", json!({ "c": 3 })));
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn stale_lists_cannot_bring_back_replayed_or_deleted_records() {
+        // The webview read this before the worker replayed the create.
+        let incoming = vec![
+            json!({ "id": "offline-doc-1-a", "title": "Plan" }),
+            json!({ "id": "d1", "title": "Server copy" }),
+            json!({ "id": "d2", "title": "Deleted" }),
+        ];
+        let current = vec![
+            json!({ "id": "d9", "title": "Plan" }),
+            json!({ "id": "d1", "title": "Edited offline" }),
+            json!({ "id": "offline-doc-2-b", "title": "Imported" }),
+        ];
+        let queue = vec![
+            json!(update("d1", json!({ "title": "Edited offline" }))),
+            json!(delete("d2")),
+            json!(QueueOp::Create {
+                temp_id: "offline-doc-2-b".to_string(),
+                payload: json!({ "title": "Imported" }),
+                timestamp: 4,
+            }),
+        ];
+
+        let records = reconcile(Resource::Documents, incoming, &current, &queue);
+        let ids: Vec<&str> = records
+            .iter()
+            .filter_map(|record| field(record, "id"))
+            .collect();
+
+        assert_eq!(ids, vec!["offline-doc-2-b", "d1"]);
+        assert_eq!(records[1]["title"], "Edited offline");
+    }
+
+    #[test]
+    fn merged_records_keep_fields_the_change_does_not_mention() {
+        let mut records = vec![json!({ "id": "e1", "title": "Standup", "allDay": false })];
+
+        merge_record(&mut records, json!({ "id": "e1", "title": "Retro" }));
+        merge_record(&mut records, json!({ "id": "e2", "title": "Review" }));
+
+        assert_eq!(
+            records,
+            vec![
+                json!({ "id": "e2", "title": "Review" }),
+                json!({ "id": "e1", "title": "Retro", "allDay": false }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+// desktop/src-tauri/src/sync/client.rs
+
+use std::error::Error as _;
+use std::fs;
+use std::path::Path;
+
+use reqwest::{Certificate, ClientBuilder, Method, StatusCode};
+use serde_json::Value;
+
+use super::engine::Resource;
+use crate::network::{NetworkConfig, ProxyMode};
+use crate::servers::ServerProfile;
+
+// Matches API_BASE_URL in apps/web/src/api/http.ts.
+pub const DEFAULT_API_BASE_URL: &str = "https://pioneer-work-suite.onrender.com";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Network request failed: {0}")]
+    Network(#[source] reqwest::Error),
+
+    #[error("Server responded with {status}: {message}")]
+    Status { status: u16, message: String },
+
+    #[error("Unexpected response from the server: {0}")]
+    Decode(String),
+
+    #[error("The server's CA certificate cannot be used: {0}")]
+    Certificate(String),
+
+    #[error(
+        "The secure connection to the server failed ({0}). If this network inspects \
+         HTTPS traffic, import its root certificate in Settings."
+    )]
+    Tls(String),
+
+    #[error("The proxy settings cannot be used: {0}")]
+    Proxy(String),
+}
+
+impl ApiError {
+    /// Mirrors `isRecoverableOfflineError` in apps/web/src/api/syncSupport.ts:
+    /// connection failures, timeouts and gateway errors are retried later.
+    /// Authentication failures are not recoverable; they require a reconnect.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            ApiError::Network(_) => true,
+            ApiError::Status { status, .. } => matches!(status, 500 | 502 | 503 | 504),
+            // The network may let the handshake through later, or the user
+            // may import its root; either way the op itself is fine.
+            ApiError::Tls(_) => true,
+            ApiError::Decode(_) | ApiError::Certificate(_) | ApiError::Proxy(_) => false,
+        }
+    }
+
+    pub fn is_auth_failure(&self) -> bool {
+        matches!(
+            self,
+            ApiError::Status {
+                status: 401 | 403,
+                ..
+            }
+        )
+    }
+
+    /// True when the request never produced an HTTP response at all.
+    pub fn is_offline(&self) -> bool {
+        matches!(self, ApiError::Network(_))
+    }
+
+    /// Wraps a failed request, telling TLS failures apart from the network
+    /// simply being down: they need the user to act, so they are reported
+    /// rather than treated as offline.
+    pub fn from_request(error: reqwest::Error) -> Self {
+        match tls_failure(&error) {
+            Some(detail) => ApiError::Tls(detail),
+            None => ApiError::Network(error),
+        }
+    }
+}
+
+/// The innermost cause of `error` if any cause mentions a certificate or
+/// the TLS handshake. The backends word these differently: OpenSSL says
+/// "certificate verify failed", Schannel "The certificate chain was issued
+/// by an authority that is not trusted", Secure Transport "bad certificate".
+fn tls_failure(error: &reqwest::Error) -> Option<String> {
+    let mut causes = Vec::new();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+
+    let is_tls = causes.iter().any(|cause| {
+        let cause = cause.to_lowercase();
+        ["certificate", "handshake", "ssl", "tls"]
+            .iter()
+            .any(|marker| cause.contains(marker))
+    });
+
+    is_tls.then(|| causes.pop()).flatten()
+}
+
+pub struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+fn add_root_certificates(
+    mut builder: ClientBuilder,
+    path: &Path,
+) -> Result<ClientBuilder, ApiError> {
+    let pem = fs::read(path)
+        .map_err(|error| ApiError::Certificate(format!("{}: {error}", path.display())))?;
+    let certificates = Certificate::from_pem_bundle(&pem)
+        .map_err(|error| ApiError::Certificate(error.to_string()))?;
+    if certificates.is_empty() {
+        return Err(ApiError::Certificate(format!(
+            "{} contains no PEM certificates",
+            path.display()
+        )));
+    }
+    for certificate in certificates {
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    Ok(builder)
+}
+
+/// An HTTP client that honours the profile's timeout, goes through the
+/// configured proxy and trusts the imported and the profile's extra root
+/// certificates.
+pub fn http_client(
+    profile: &ServerProfile,
+    network: &NetworkConfig,
+) -> Result<reqwest::Client, ApiError> {
+    let mut builder = reqwest::Client::builder().timeout(profile.timeout());
+
+    match network.proxy.mode {
+        ProxyMode::System => {}
+        ProxyMode::None => builder = builder.no_proxy(),
+        ProxyMode::Manual => {
+            let password = network
+                .proxy_password
+                .as_ref()
+                .map(|password| password.as_str());
+            let proxy = network
+                .proxy
+                .manual_proxy(password)
+                .map_err(|error| ApiError::Proxy(error.to_string()))?;
+            builder = builder.no_proxy().proxy(proxy);
+        }
+    }
+
+    for path in network
+        .root_certificates
+        .iter()
+        .chain(&profile.ca_certificate)
+    {
+        builder = add_root_certificates(builder, path)?;
+    }
+
+    builder.build().map_err(ApiError::Network)
+}
+
+impl ApiClient {
+    pub fn for_server(
+        profile: &ServerProfile,
+        network: &NetworkConfig,
+        token: &str,
+    ) -> Result<Self, ApiError> {
+        Ok(Self {
+            http: http_client(profile, network)?,
+            base_url: profile.base_url.clone(),
+            token: token.to_string(),
+        })
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<Value, ApiError> {
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .bearer_auth(&self.token);
+
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await.map_err(ApiError::from_request)?;
+        let status = response.status();
+        let text = response.text().await.map_err(ApiError::Network)?;
+
+        if !status.is_success() {
+            return Err(ApiError::Status {
+                status: status.as_u16(),
+                message: server_error_message(status, &text),
+            });
+        }
+
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        serde_json::from_str(&text).map_err(|error| ApiError::Decode(error.to_string()))
+    }
+
+    /// The API wraps single records as `{ task: {...} }` on some routes and
+    /// returns them bare on others; both shapes are accepted, as in the web
+    /// client's `data?.task ?? data`.
+    fn unwrap_record(resource: Resource, data: Value) -> Result<Value, ApiError> {
+        let record = match data.get(resource.response_key()) {
+            Some(record) => record.clone(),
+            None => data,
+        };
+
+        if record.get("id").and_then(Value::as_str).is_none() {
+            return Err(ApiError::Decode(format!(
+                "{} response is missing an id",
+                resource.path()
+            )));
+        }
+
+        Ok(record)
+    }
+
+    pub async fn create(&self, resource: Resource, payload: &Value) -> Result<Value, ApiError> {
+        let data = self
+            .send(Method::POST, resource.path(), Some(payload))
+            .await?;
+        Self::unwrap_record(resource, data)
+    }
+
+    pub async fn update(
+        &self,
+        resource: Resource,
+        id: &str,
+        patch: &Value,
+    ) -> Result<Value, ApiError> {
+        let path = format!("{}/{}", resource.path(), id);
+        let data = self.send(Method::PUT, &path, Some(patch)).await?;
+        Self::unwrap_record(resource, data)
+    }
+
+    pub async fn delete(&self, resource: Resource, id: &str) -> Result<(), ApiError> {
+        let path = format!("{}/{}", resource.path(), id);
+        self.send(Method::DELETE, &path, None).await?;
+        Ok(())
+    }
+}
+
+fn server_error_message(status: StatusCode, body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("error")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .filter(|message| !message.trim().is_empty())
+        .unwrap_or_else(|| {
+            status
+                .canonical_reason()
+                .unwrap_or("Request failed")
+                .to_string()
+        })
+}
@@ -0,0 +1,25 @@
+// desktop/src-tauri/src/sync/commands.rs
+
+use tauri::{AppHandle, State};
+
+use super::{SyncService, SyncSnapshot};
+
+/// Called by the web session layer whenever the cloud token changes.
+#[tauri::command]
+pub fn sync_set_session(
+    service: State<'_, SyncService>,
+    token: Option<String>,
+    reconnect_required: bool,
+) {
+    service.set_session(token, reconnect_required);
+}
+
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> SyncSnapshot {
+    super::sync_now(&app).await
+}
+
+#[tauri::command]
+pub fn get_sync_snapshot(app: AppHandle) -> SyncSnapshot {
+    super::current_snapshot(&app)
+}
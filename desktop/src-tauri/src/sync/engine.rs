@@ -0,0 +1,591 @@
+// desktop/src-tauri/src/sync/engine.rs
+
+/*
+ * Replays the offline op queues against the API.
+ *
+ * This is a port of the `syncOffline*Queue` loops in apps/web/src/api: ops are
+ * replayed in order, a successful create rewrites the temporary id in the
+ * cache and in every later op, and the first recoverable failure stops the
+ * pass so the remaining ops keep their order for the next attempt.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::client::{ApiClient, ApiError};
+use crate::crypto;
+use crate::error::Result;
+use crate::storage::{LocalStore, QueueStore, RecordStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resource {
+    Tasks,
+    Documents,
+    Events,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 3] = [Resource::Tasks, Resource::Documents, Resource::Events];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            Resource::Tasks => "/tasks",
+            Resource::Documents => "/documents",
+            Resource::Events => "/events",
+        }
+    }
+
+    pub fn response_key(self) -> &'static str {
+        match self {
+            Resource::Tasks => "task",
+            Resource::Documents => "document",
+            Resource::Events => "event",
+        }
+    }
+
+    /// Prefix of ids minted by the web client for records that have never
+    /// reached the server.
+    pub fn offline_id_prefix(self) -> &'static str {
+        match self {
+            Resource::Tasks => "offline-task-",
+            Resource::Documents => "offline-doc-",
+            Resource::Events => "offline-event-",
+        }
+    }
+
+    /// Window event the web client listens to for cache invalidation.
+    pub fn changed_event(self) -> &'static str {
+        match self {
+            Resource::Tasks => "pioneer:tasks-changed",
+            Resource::Documents => "pioneer:documents-changed",
+            Resource::Events => "pioneer:calendar-events-changed",
+        }
+    }
+
+    pub fn record_store(self) -> RecordStore {
+        match self {
+            Resource::Tasks => RecordStore::Tasks,
+            Resource::Documents => RecordStore::Documents,
+            Resource::Events => RecordStore::Events,
+        }
+    }
+
+    pub fn queue_store(self) -> QueueStore {
+        match self {
+            Resource::Tasks => QueueStore::Tasks,
+            Resource::Documents => QueueStore::Documents,
+            Resource::Events => QueueStore::Events,
+        }
+    }
+
+    /// A new offline id in the shape of `makeOfflineId` in the web client.
+    pub fn offline_id(self, timestamp: i64) -> String {
+        const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+        let mut value = u64::from_le_bytes(crypto::random_bytes::<8>());
+        let mut suffix = Vec::new();
+
+        while value > 0 {
+            suffix.push(DIGITS[(value % 36) as usize]);
+            value /= 36;
+        }
+        suffix.reverse();
+
+        format!(
+            "{}{timestamp}-{}",
+            self.offline_id_prefix(),
+            String::from_utf8_lossy(&suffix)
+        )
+    }
+
+    pub fn is_offline_id(self, id: &str) -> bool {
+        id.starts_with(self.offline_id_prefix())
+    }
+}
+
+/// Queue entries as written by `enqueueCreate`/`enqueueUpdate`/`enqueueDelete`
+/// in the web client. Payloads stay opaque JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum QueueOp {
+    #[serde(rename_all = "camelCase")]
+    Create {
+        temp_id: String,
+        payload: Value,
+        #[serde(default)]
+        timestamp: i64,
+    },
+    Update {
+        id: String,
+        patch: Value,
+        #[serde(default)]
+        timestamp: i64,
+    },
+    Delete {
+        id: String,
+        #[serde(default)]
+        timestamp: i64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingCounts {
+    pub tasks: usize,
+    pub documents: usize,
+    pub events: usize,
+}
+
+impl PendingCounts {
+    pub fn read(store: &LocalStore) -> Result<Self> {
+        Ok(Self {
+            tasks: store.read_queue(QueueStore::Tasks)?.len(),
+            documents: store.read_queue(QueueStore::Documents)?.len(),
+            events: store.read_queue(QueueStore::Events)?.len(),
+        })
+    }
+
+    pub fn total(&self) -> usize {
+        self.tasks + self.documents + self.events
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PassReport {
+    /// Resources whose local cache was rewritten during the pass.
+    pub changed: Vec<Resource>,
+    /// The recoverable or authentication failure that stopped the pass.
+    pub halted: Option<ApiError>,
+    /// Ops the server refused outright. They stay queued, as in the web client.
+    pub rejected: usize,
+    pub pending: PendingCounts,
+}
+
+#[derive(Debug)]
+struct QueueReport {
+    changed: bool,
+    halted: Option<ApiError>,
+    rejected: usize,
+}
+
+pub async fn run_pass(store: &LocalStore, client: &ApiClient) -> Result<PassReport> {
+    let mut report = PassReport::default();
+
+    for resource in Resource::ALL {
+        let queue_report = replay_queue(store, client, resource).await?;
+
+        if queue_report.changed {
+            report.changed.push(resource);
+        }
+
+        report.rejected += queue_report.rejected;
+
+        if queue_report.halted.is_some() {
+            report.halted = queue_report.halted;
+            break;
+        }
+    }
+
+    report.pending = PendingCounts::read(store)?;
+    Ok(report)
+}
+
+fn record_id(record: &Value) -> Option<&str> {
+    record.get("id").and_then(Value::as_str)
+}
+
+/// Stores a server record in place of the cached entry `previous_id`, or at
+/// the front of the cache when there is none. A record with the same id is
+/// merged field by field, as in `mergeTaskIntoCache`; a created record
+/// replaces its temporary offline copy outright.
+fn merge_into_cache(
+    store: &LocalStore,
+    resource: Resource,
+    previous_id: &str,
+    record: Value,
+) -> Result<()> {
+    store.update_records(resource.record_store(), |records| {
+        match records
+            .iter_mut()
+            .find(|existing| record_id(existing) == Some(previous_id))
+        {
+            Some(existing) => match (existing.as_object_mut(), record) {
+                (Some(fields), Value::Object(updates))
+                    if updates.get("id").and_then(Value::as_str) == Some(previous_id) =>
+                {
+                    fields.extend(updates);
+                }
+                (_, record) => *existing = record,
+            },
+            None => records.insert(0, record),
+        }
+    })
+}
+
+fn remove_from_cache(store: &LocalStore, resource: Resource, id: &str) -> Result<()> {
+    store.update_records(resource.record_store(), |records| {
+        records.retain(|record| record_id(record) != Some(id));
+    })
+}
+
+/// Removes a replayed op from the stored queue.
+///
+/// The webview keeps enqueueing while a pass is running, so the queue is
+/// re-read here rather than overwritten with a snapshot taken before the
+/// request. If the web client merged a patch into a create that has just
+/// been sent, the merged create is turned into an update of the new record.
+fn complete_op(
+    store: &LocalStore,
+    resource: Resource,
+    position: usize,
+    original: &Value,
+    created: Option<(&str, &str)>,
+) -> Result<()> {
+    store.update_queue(resource.queue_store(), |queue| {
+        let index = if queue.get(position) == Some(original) {
+            Some(position)
+        } else {
+            queue.iter().position(|entry| entry == original)
+        };
+
+        match index {
+            Some(index) => {
+                queue.remove(index);
+            }
+            None => {
+                if let Some((temp_id, created_id)) = created {
+                    for entry in queue.iter_mut() {
+                        if let Ok(QueueOp::Create {
+                            temp_id: entry_temp_id,
+                            payload,
+                            timestamp,
+                        }) = serde_json::from_value::<QueueOp>(entry.clone())
+                        {
+                            if entry_temp_id == temp_id {
+                                *entry = json!(QueueOp::Update {
+                                    id: created_id.to_string(),
+                                    patch: payload,
+                                    timestamp,
+                                });
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some((temp_id, created_id)) = created {
+            for entry in queue.iter_mut() {
+                let is_follow_up = matches!(
+                    entry.get("kind").and_then(Value::as_str),
+                    Some("update" | "delete")
+                ) && entry.get("id").and_then(Value::as_str) == Some(temp_id);
+
+                if is_follow_up {
+                    entry["id"] = Value::String(created_id.to_string());
+                }
+            }
+        }
+    })
+}
+
+enum OpOutcome {
+    Done { created: Option<(String, String)> },
+    Deferred,
+}
+
+async fn replay_op(
+    store: &LocalStore,
+    client: &ApiClient,
+    resource: Resource,
+    operation: &QueueOp,
+) -> Result<Result<OpOutcome, ApiError>> {
+    let result = match operation {
+        QueueOp::Create {
+            temp_id, payload, ..
+        } => match client.create(resource, payload).await {
+            Ok(created) => {
+                let created_id = record_id(&created).unwrap_or_default().to_string();
+                merge_into_cache(store, resource, temp_id, created)?;
+
+                Ok(OpOutcome::Done {
+                    created: Some((temp_id.clone(), created_id)),
+                })
+            }
+            Err(error) => Err(error),
+        },
+        // Its create has not been accepted yet; keep it for a later pass.
+        QueueOp::Update { id, .. } if resource.is_offline_id(id) => Ok(OpOutcome::Deferred),
+        QueueOp::Update { id, patch, .. } => match client.update(resource, id, patch).await {
+            Ok(updated) => {
+                merge_into_cache(store, resource, id, updated)?;
+                Ok(OpOutcome::Done { created: None })
+            }
+            Err(error) => Err(error),
+        },
+        QueueOp::Delete { id, .. } if resource.is_offline_id(id) => {
+            remove_from_cache(store, resource, id)?;
+            Ok(OpOutcome::Done { created: None })
+        }
+        QueueOp::Delete { id, .. } => match client.delete(resource, id).await {
+            // Already gone on the server, which is the outcome we wanted.
+            Ok(()) | Err(ApiError::Status { status: 404, .. }) => {
+                remove_from_cache(store, resource, id)?;
+                Ok(OpOutcome::Done { created: None })
+            }
+            Err(error) => Err(error),
+        },
+    };
+
+    Ok(result)
+}
+
+async fn replay_queue(
+    store: &LocalStore,
+    client: &ApiClient,
+    resource: Resource,
+) -> Result<QueueReport> {
+    let mut report = QueueReport {
+        changed: false,
+        halted: None,
+        rejected: 0,
+    };
+
+    // Ops left in place at the head of the queue: deferred, rejected by the
+    // server, or unreadable.
+    let mut kept = 0;
+
+    loop {
+        let queue = store.read_queue(resource.queue_store())?;
+
+        let Some(original) = queue.get(kept) else {
+            break;
+        };
+
+        let Ok(operation) = serde_json::from_value::<QueueOp>(original.clone()) else {
+            kept += 1;
+            continue;
+        };
+
+        match replay_op(store, client, resource, &operation).await? {
+            Ok(OpOutcome::Done { created }) => {
+                let created = created
+                    .as_ref()
+                    .map(|(temp_id, created_id)| (temp_id.as_str(), created_id.as_str()));
+
+                complete_op(store, resource, kept, original, created)?;
+                report.changed = true;
+            }
+            Ok(OpOutcome::Deferred) => kept += 1,
+            Err(error) if error.is_recoverable() || error.is_auth_failure() => {
+                report.halted = Some(error);
+                break;
+            }
+            Err(_) => {
+                kept += 1;
+                report.rejected += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::NetworkConfig;
+    use crate::servers::ServerProfile;
+    use crate::storage::UnlockSecret;
+    use mockito::{Matcher, Server};
+
+    fn open_store(directory: &tempfile::TempDir) -> LocalStore {
+        let store = LocalStore::open(directory.path().join("store.sqlite3")).unwrap();
+        store.unlock(UnlockSecret::Pin("test")).unwrap();
+        store
+    }
+
+    fn client(server: &Server) -> ApiClient {
+        let profile = ServerProfile {
+            base_url: server.url(),
+            ..ServerProfile::pioneer_cloud()
+        };
+        ApiClient::for_server(&profile, &NetworkConfig::default(), "secret").unwrap()
+    }
+
+    fn queue_values(store: &LocalStore, resource: Resource) -> Vec<Value> {
+        store.read_queue(resource.queue_store()).unwrap()
+    }
+
+    #[test]
+    fn offline_ids_match_the_web_client() {
+        let id = Resource::Documents.offline_id(1_700_000_000_000);
+
+        assert!(id.starts_with("offline-doc-1700000000000-"));
+        assert!(Resource::Documents.is_offline_id(&id));
+        assert_ne!(id, Resource::Documents.offline_id(1_700_000_000_000));
+    }
+
+    #[tokio::test]
+    async fn create_rewrites_temp_id_for_later_ops_and_cache() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+        let mut server = Server::new_async().await;
+
+        store
+            .replace_records(
+                RecordStore::Tasks,
+                &[json!({ "id": "offline-task-1", "title": "Essay" })],
+            )
+            .unwrap();
+        store
+            .replace_queue(
+                QueueStore::Tasks,
+                &[
+                    json!({
+                        "kind": "create",
+                        "tempId": "offline-task-1",
+                        "payload": { "title": "Essay" },
+                        "timestamp": 1
+                    }),
+                    json!({
+                        "kind": "update",
+                        "id": "offline-task-1",
+                        "patch": { "status": "done" },
+                        "timestamp": 2
+                    }),
+                ],
+            )
+            .unwrap();
+
+        let create = server
+            .mock("POST", "/tasks")
+            .match_header("authorization", "Bearer secret")
+            .match_body(Matcher::Json(json!({ "title": "Essay" })))
+            .with_status(201)
+            .with_body(r#"{ "id": "task-9", "title": "Essay", "status": "todo" }"#)
+            .create_async()
+            .await;
+        let update = server
+            .mock("PUT", "/tasks/task-9")
+            .with_body(r#"{ "task": { "id": "task-9", "title": "Essay", "status": "done" } }"#)
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let report = run_pass(&store, &client).await.unwrap();
+
+        create.assert_async().await;
+        update.assert_async().await;
+        assert!(report.halted.is_none());
+        assert_eq!(report.changed, vec![Resource::Tasks]);
+        assert_eq!(report.pending.total(), 0);
+        assert_eq!(
+            store.read_records(RecordStore::Tasks).unwrap(),
+            vec![json!({ "id": "task-9", "title": "Essay", "status": "done" })]
+        );
+    }
+
+    #[tokio::test]
+    async fn recoverable_failure_keeps_remaining_ops_in_order() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+        let mut server = Server::new_async().await;
+
+        let queue = vec![
+            json!({ "kind": "delete", "id": "doc-1", "timestamp": 1 }),
+            json!({ "kind": "delete", "id": "doc-2", "timestamp": 2 }),
+        ];
+        store.replace_queue(QueueStore::Documents, &queue).unwrap();
+
+        server
+            .mock("DELETE", "/documents/doc-1")
+            .with_status(503)
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let report = run_pass(&store, &client).await.unwrap();
+
+        assert!(report.halted.as_ref().unwrap().is_recoverable());
+        assert_eq!(report.pending.documents, 2);
+        assert_eq!(queue_values(&store, Resource::Documents), queue);
+    }
+
+    #[tokio::test]
+    async fn rejected_op_stays_queued_while_later_ops_continue() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+        let mut server = Server::new_async().await;
+
+        store
+            .replace_queue(
+                QueueStore::Events,
+                &[
+                    json!({ "kind": "create", "tempId": "offline-event-1", "payload": {}, "timestamp": 1 }),
+                    json!({ "kind": "delete", "id": "event-2", "timestamp": 2 }),
+                ],
+            )
+            .unwrap();
+
+        server
+            .mock("POST", "/events")
+            .with_status(400)
+            .with_body(r#"{ "error": "Title is required" }"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/events/event-2")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let report = run_pass(&store, &client).await.unwrap();
+
+        delete.assert_async().await;
+        assert!(report.halted.is_none());
+        assert_eq!(report.rejected, 1);
+        assert_eq!(report.pending.events, 1);
+    }
+
+    #[tokio::test]
+    async fn auth_failure_halts_the_pass() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = open_store(&directory);
+        let mut server = Server::new_async().await;
+
+        store
+            .replace_queue(
+                QueueStore::Tasks,
+                &[json!({ "kind": "delete", "id": "task-1", "timestamp": 1 })],
+            )
+            .unwrap();
+        store
+            .replace_queue(
+                QueueStore::Documents,
+                &[json!({ "kind": "delete", "id": "doc-1", "timestamp": 1 })],
+            )
+            .unwrap();
+
+        server
+            .mock("DELETE", "/tasks/task-1")
+            .with_status(401)
+            .with_body(r#"{ "error": "Invalid token" }"#)
+            .create_async()
+            .await;
+        let documents = server
+            .mock("DELETE", "/documents/doc-1")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let report = run_pass(&store, &client).await.unwrap();
+
+        documents.assert_async().await;
+        assert!(report.halted.as_ref().unwrap().is_auth_failure());
+        assert_eq!(report.pending.total(), 2);
+    }
+}
@@ -0,0 +1,341 @@
+// desktop/src-tauri/src/sync/mod.rs
+
+/*
+ * Background sync worker.
+ *
+ * The desktop client drains the offline op queues from Rust so changes keep
+ * uploading while the window is hidden. The webview is kept informed through
+ * `SyncSnapshot` events shaped exactly like the type in
+ * apps/web/src/api/sync.ts.
+ */
+
+pub mod changes;
+pub mod client;
+pub mod commands;
+pub mod engine;
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::error::Error;
+use crate::network::Network;
+use crate::servers::Servers;
+use crate::storage::LocalStore;
+use crate::tray::{self, Tray};
+use client::ApiClient;
+use engine::{PassReport, PendingCounts};
+
+pub const SYNC_SNAPSHOT_EVENT: &str = "pioneer:sync-snapshot";
+pub const CLOUD_AUTH_REQUIRED_EVENT: &str = "pioneer:cloud-auth-required";
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5 * 60);
+
+const SYNC_FAILED_MESSAGE: &str = "Cloud synchronization failed. Local changes are safe.";
+const SYNC_INCOMPLETE_MESSAGE: &str =
+    "Some local changes could not be uploaded. They will be retried.";
+const SESSION_EXPIRED_MESSAGE: &str = "Your cloud session expired. Reconnect to resume syncing.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncPhase {
+    LocalOnly,
+    Offline,
+    ReconnectRequired,
+    Idle,
+    Pending,
+    Syncing,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSnapshot {
+    pub phase: SyncPhase,
+    pub cloud_connected: bool,
+    pub online: bool,
+    pub pending_tasks: usize,
+    pub pending_documents: usize,
+    pub pending_events: usize,
+    pub pending_total: usize,
+    pub last_successful_sync_at: Option<String>,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct SyncStatus {
+    token: Option<String>,
+    reconnect_required: bool,
+    offline: bool,
+    syncing: bool,
+    last_successful_sync_at: Option<String>,
+    error_message: Option<String>,
+    failed_attempts: u32,
+}
+
+#[derive(Default)]
+pub struct SyncService {
+    status: Mutex<SyncStatus>,
+    wake: Notify,
+    pass: tokio::sync::Mutex<()>,
+}
+
+impl SyncService {
+    fn status(&self) -> MutexGuard<'_, SyncStatus> {
+        self.status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Stores or clears the cloud token and schedules an immediate pass.
+    pub fn set_session(&self, token: Option<String>, reconnect_required: bool) {
+        {
+            let mut status = self.status();
+
+            status.reconnect_required = reconnect_required;
+            status.error_message = None;
+            status.failed_attempts = 0;
+            status.token = token.filter(|token| !token.trim().is_empty());
+        }
+
+        self.wake();
+    }
+
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    fn next_delay(&self) -> Duration {
+        match self.status().failed_attempts {
+            0 => SYNC_INTERVAL,
+            attempts => RETRY_BASE_DELAY
+                .saturating_mul(2u32.saturating_pow(attempts - 1))
+                .min(RETRY_MAX_DELAY),
+        }
+    }
+
+    fn snapshot(&self, pending: PendingCounts) -> SyncSnapshot {
+        let status = self.status();
+        let cloud_connected = status.token.is_some();
+        let online = !status.offline;
+        let pending_total = pending.total();
+
+        let phase = if status.reconnect_required {
+            SyncPhase::ReconnectRequired
+        } else if !cloud_connected {
+            SyncPhase::LocalOnly
+        } else if !online {
+            SyncPhase::Offline
+        } else if status.syncing {
+            SyncPhase::Syncing
+        } else if status.error_message.is_some() {
+            SyncPhase::Error
+        } else if pending_total > 0 {
+            SyncPhase::Pending
+        } else {
+            SyncPhase::Idle
+        };
+
+        SyncSnapshot {
+            phase,
+            cloud_connected,
+            online,
+            pending_tasks: pending.tasks,
+            pending_documents: pending.documents,
+            pending_events: pending.events,
+            pending_total,
+            last_successful_sync_at: status.last_successful_sync_at.clone(),
+            error_message: status.error_message.clone(),
+        }
+    }
+
+    /// Folds the result of a pass into the status, returning the message to
+    /// forward to the webview when the server rejected the token.
+    fn record_pass(&self, result: &crate::error::Result<PassReport>) -> Option<String> {
+        let mut status = self.status();
+
+        let report = match result {
+            Ok(report) => report,
+            Err(_) => {
+                status.error_message = Some(SYNC_FAILED_MESSAGE.to_string());
+                status.failed_attempts += 1;
+                return None;
+            }
+        };
+
+        match &report.halted {
+            Some(error) if error.is_auth_failure() => {
+                let message = match error {
+                    client::ApiError::Status { message, .. } if !message.trim().is_empty() => {
+                        message.clone()
+                    }
+                    _ => SESSION_EXPIRED_MESSAGE.to_string(),
+                };
+
+                status.token = None;
+                status.reconnect_required = true;
+                status.offline = false;
+                status.failed_attempts = 0;
+                status.error_message = Some(message.clone());
+
+                Some(message)
+            }
+            Some(error) => {
+                status.offline = error.is_offline();
+                status.failed_attempts += 1;
+                status.error_message = match error {
+                    _ if error.is_offline() => None,
+                    client::ApiError::Tls(_) => Some(error.to_string()),
+                    _ => Some(SYNC_INCOMPLETE_MESSAGE.to_string()),
+                };
+
+                None
+            }
+            None => {
+                status.offline = false;
+                status.failed_attempts = 0;
+
+                if report.rejected > 0 {
+                    status.error_message = Some(SYNC_INCOMPLETE_MESSAGE.to_string());
+                } else {
+                    status.error_message = None;
+                    status.last_successful_sync_at = Some(chrono::Utc::now().to_rfc3339());
+                }
+
+                None
+            }
+        }
+    }
+}
+
+fn read_pending(app: &AppHandle) -> PendingCounts {
+    let store = app.state::<LocalStore>();
+    let service = app.state::<SyncService>();
+
+    match PendingCounts::read(&store) {
+        Ok(pending) => pending,
+        Err(Error::StoreLocked) => PendingCounts::default(),
+        Err(_) => {
+            service.status().error_message =
+                Some("Unable to read the local sync queues.".to_string());
+            PendingCounts::default()
+        }
+    }
+}
+
+pub fn current_snapshot(app: &AppHandle) -> SyncSnapshot {
+    let pending = read_pending(app);
+    app.state::<SyncService>().snapshot(pending)
+}
+
+fn publish(app: &AppHandle) -> SyncSnapshot {
+    let snapshot = current_snapshot(app);
+    let _ = app.emit_all(SYNC_SNAPSHOT_EVENT, snapshot.clone());
+
+    // A pass may have changed today's agenda as well.
+    tray::show_sync_snapshot(app, &snapshot);
+    app.state::<Tray>().wake();
+
+    snapshot
+}
+
+/// Records what the reachability monitor found. Coming back online also
+/// clears the retry backoff left over from while the network was gone.
+pub fn set_reachable(app: &AppHandle, reachable: bool) {
+    {
+        let service = app.state::<SyncService>();
+        let mut status = service.status();
+
+        status.offline = !reachable;
+        if reachable {
+            status.failed_attempts = 0;
+        }
+    }
+
+    publish(app);
+}
+
+/// Runs one pass over every queue. Passes are serialised so an op is never
+/// replayed twice by overlapping callers.
+pub async fn sync_now(app: &AppHandle) -> SyncSnapshot {
+    let service = app.state::<SyncService>();
+    let store = app.state::<LocalStore>();
+
+    let _pass = service.pass.lock().await;
+
+    let (token, reconnect_required) = {
+        let status = service.status();
+        (status.token.clone(), status.reconnect_required)
+    };
+
+    // Nothing can be replayed while the workspace is locked; the unlock
+    // wakes the worker again.
+    let Some(token) = token.filter(|_| !reconnect_required && store.is_unlocked()) else {
+        return publish(app);
+    };
+
+    let client = match ApiClient::for_server(
+        &app.state::<Servers>().active(),
+        &app.state::<Network>().config(),
+        &token,
+    ) {
+        Ok(client) => client,
+        Err(error) => {
+            service.status().error_message = Some(error.to_string());
+            return publish(app);
+        }
+    };
+
+    service.status().syncing = true;
+    publish(app);
+
+    let result = engine::run_pass(&store, &client).await;
+
+    service.status().syncing = false;
+    let auth_message = service.record_pass(&result);
+
+    if let Ok(report) = &result {
+        for resource in &report.changed {
+            let _ = app.emit_all(resource.changed_event(), ());
+        }
+    }
+
+    if let Some(message) = auth_message {
+        let _ = app.emit_all(CLOUD_AUTH_REQUIRED_EVENT, message);
+    }
+
+    publish(app)
+}
+
+/// Runs `f` once no pass is in progress, holding off the next one until it
+/// returns.
+pub async fn between_passes<T>(app: &AppHandle, f: impl FnOnce() -> T) -> T {
+    let service = app.state::<SyncService>();
+    let _pass = service.pass.lock().await;
+    f()
+}
+
+/// Spawns the worker: one pass at startup, then one per interval, sooner
+/// when woken, with exponential backoff while the API is unreachable.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sync_now(&app).await;
+
+            let service = app.state::<SyncService>();
+            let delay = service.next_delay();
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = service.wake.notified() => {}
+            }
+        }
+    });
+}
@@ -0,0 +1,205 @@
+// desktop/src-tauri/src/tray/agenda.rs
+
+/*
+ * Today's agenda for the tray menu: the events that touch the local day,
+ * soonest first, then the open tasks that are due today or overdue.
+ */
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde_json::Value;
+
+use crate::calendar::CalendarEvent;
+use crate::reminders::schedule::due_date;
+
+const MAX_TITLE_CHARS: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgendaItem {
+    pub label: String,
+    /// Where the item opens in the webview.
+    pub route: String,
+}
+
+fn string<'a>(record: &'a Value, field: &str) -> Option<&'a str> {
+    record
+        .get(field)?
+        .as_str()
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn shorten(title: &str) -> String {
+    let title = title.trim();
+
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+
+    let mut short: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// The agenda for the local date `today`.
+pub fn for_day(tasks: &[Value], events: &[Value], today: NaiveDate) -> Vec<AgendaItem> {
+    let (Some(from), Some(to)) = (
+        local_midnight(today),
+        today.succ_opt().and_then(local_midnight),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut todays_events: Vec<(bool, DateTime<Utc>, CalendarEvent)> = events
+        .iter()
+        .filter_map(|event| serde_json::from_value::<CalendarEvent>(event.clone()).ok())
+        .filter_map(|event| {
+            let start = parse_time(&event.payload.start)?;
+            let end = parse_time(&event.payload.end)?;
+            // Events that end at midnight belong to the day before.
+            (start < to && end > from).then_some((!event.payload.all_day, start, event))
+        })
+        .collect();
+    todays_events.sort_by_key(|(timed, start, _)| (*timed, *start));
+
+    let event_items = todays_events.into_iter().map(|(timed, start, event)| {
+        let when = if !timed {
+            "All day".to_string()
+        } else if start < from {
+            "Ongoing".to_string()
+        } else {
+            start.with_timezone(&Local).format("%H:%M").to_string()
+        };
+        let title = match event.payload.title.trim() {
+            "" => "Untitled event",
+            title => title,
+        };
+
+        AgendaItem {
+            label: format!("{when}  {}", shorten(title)),
+            route: "/calendar".to_string(),
+        }
+    });
+
+    let mut due_tasks: Vec<(NaiveDate, &Value)> = tasks
+        .iter()
+        .filter(|task| string(task, "status") != Some("done"))
+        .filter(|task| string(task, "archivedAt").is_none())
+        .filter_map(|task| Some((due_date(string(task, "dueDate")?)?, task)))
+        .filter(|(due, _)| *due <= today)
+        .collect();
+    due_tasks.sort_by_key(|(due, _)| *due);
+
+    let task_items = due_tasks.into_iter().filter_map(|(due, task)| {
+        let id = string(task, "id")?;
+        let when = if due < today { "Overdue" } else { "Due" };
+
+        Some(AgendaItem {
+            label: format!(
+                "{when}  {}",
+                shorten(string(task, "title").unwrap_or("Untitled task"))
+            ),
+            route: format!("/tasks?task={}", encode(id)),
+        })
+    });
+
+    event_items.chain(task_items).collect()
+}
+
+/// Percent-encodes an id for a query string, like `encodeURIComponent`.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn local(date: NaiveDate, hour: u32, minute: u32) -> String {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, minute, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc)
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn lists_todays_events_then_due_tasks() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let tomorrow = today.succ_opt().unwrap();
+        let events = [
+            json!({ "id": "e1", "title": "Review", "start": local(today, 14, 0), "end": local(today, 15, 0) }),
+            json!({ "id": "e2", "title": "Standup", "start": local(today, 9, 30), "end": local(today, 9, 45) }),
+            json!({ "id": "e3", "title": "Offsite", "start": local(today, 0, 0), "end": local(tomorrow, 0, 0), "allDay": true }),
+            json!({ "id": "e4", "title": "Tomorrow", "start": local(tomorrow, 9, 0), "end": local(tomorrow, 10, 0) }),
+        ];
+        let tasks = [
+            json!({ "id": "t1", "title": "Essay", "status": "todo", "dueDate": "2024-03-04" }),
+            json!({ "id": "t2", "title": "Late", "status": "in-progress", "dueDate": "2024-03-01" }),
+            json!({ "id": "t3", "title": "Done", "status": "done", "dueDate": "2024-03-04" }),
+            json!({ "id": "t4", "title": "Later", "status": "todo", "dueDate": "2024-03-05" }),
+            json!({ "id": "t5", "title": "Archived", "status": "todo", "dueDate": "2024-03-04", "archivedAt": "2024-03-02T00:00:00Z" }),
+            json!({ "id": "offline task", "title": "Offline", "status": "todo", "dueDate": "2024-03-04" }),
+        ];
+
+        let labels: Vec<String> = for_day(&tasks, &events, today)
+            .into_iter()
+            .map(|item| item.label)
+            .collect();
+
+        assert_eq!(
+            labels,
+            [
+                "All day  Offsite",
+                "09:30  Standup",
+                "14:00  Review",
+                "Overdue  Late",
+                "Due  Essay",
+                "Due  Offline",
+            ]
+        );
+        assert_eq!(
+            for_day(&tasks[5..], &[], today)[0].route,
+            "/tasks?task=offline%20task"
+        );
+    }
+
+    #[test]
+    fn shortens_long_titles() {
+        let title = "x".repeat(60);
+        let short = shorten(&title);
+
+        assert_eq!(short.chars().count(), MAX_TITLE_CHARS);
+        assert!(short.ends_with('…'));
+    }
+}
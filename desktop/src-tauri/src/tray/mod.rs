@@ -0,0 +1,277 @@
+// desktop/src-tauri/src/tray/mod.rs
+
+/*
+ * System tray menu.
+ *
+ * The menu shows the sync state from `SyncSnapshot` and today's agenda,
+ * and offers quick actions. Actions that need the webview are sent to it as
+ * `pioneer:tray-action` after the main window is shown. Closing the main
+ * window only hides it; the app keeps running in the tray until "Quit".
+ *
+ * The agenda is rebuilt every minute and after every sync pass. While the
+ * workspace is locked it is left out.
+ */
+
+pub mod agenda;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{
+    AppHandle, CustomMenuItem, GlobalWindowEvent, Manager, SystemTray, SystemTrayEvent,
+    SystemTrayMenu, SystemTrayMenuItem, WindowEvent,
+};
+use tokio::sync::Notify;
+
+use crate::close_guard;
+use crate::storage::{LocalStore, RecordStore};
+use crate::sync::{self, SyncPhase, SyncSnapshot};
+use agenda::AgendaItem;
+
+pub const TRAY_ACTION_EVENT: &str = "pioneer:tray-action";
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+const MAX_AGENDA_ITEMS: usize = 8;
+
+const SYNC_STATUS_ID: &str = "sync-status";
+const QUICK_ADD_TASK_ID: &str = "quick-add-task";
+const SYNC_NOW_ID: &str = "sync-now";
+const OPEN_DOCUMENT_ID: &str = "open-document";
+const SHOW_ID: &str = "show";
+const QUIT_ID: &str = "quit";
+// Agenda item ids are `open:{index}:{route}`; the index keeps them unique.
+const OPEN_ROUTE_PREFIX: &str = "open:";
+
+/// What the webview should do after the tray brought it to the front.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum TrayAction {
+    QuickAddTask,
+    OpenDocument,
+    Open { route: String },
+}
+
+/// `None` while the workspace is locked.
+type Agenda = Option<Vec<AgendaItem>>;
+
+#[derive(Default)]
+pub struct Tray {
+    agenda: Mutex<Option<Agenda>>,
+    changed: Notify,
+}
+
+impl Tray {
+    /// Asks the tray to rebuild the agenda.
+    pub fn wake(&self) {
+        self.changed.notify_one();
+    }
+}
+
+fn sync_label(snapshot: &SyncSnapshot) -> String {
+    let pending = match snapshot.pending_total {
+        1 => "1 change".to_string(),
+        count => format!("{count} changes"),
+    };
+
+    match snapshot.phase {
+        SyncPhase::LocalOnly => "Local only".to_string(),
+        SyncPhase::Idle => "Synced".to_string(),
+        SyncPhase::Pending => format!("{pending} waiting to sync"),
+        SyncPhase::Syncing => format!("Syncing {pending}…"),
+        SyncPhase::Offline => format!("Offline · {pending} waiting"),
+        SyncPhase::ReconnectRequired => "Reconnect to resume syncing".to_string(),
+        SyncPhase::Error => format!("Sync failed · {pending} waiting"),
+    }
+}
+
+fn tooltip(snapshot: &SyncSnapshot) -> String {
+    format!("Pioneer Work Suite · {}", sync_label(snapshot))
+}
+
+fn menu(snapshot: &SyncSnapshot, agenda: &Agenda) -> SystemTrayMenu {
+    let mut menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(SYNC_STATUS_ID, sync_label(snapshot)).disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("agenda-heading", "Today").disabled());
+
+    match agenda {
+        None => {
+            menu = menu.add_item(
+                CustomMenuItem::new("agenda-locked", "Unlock to see today's agenda").disabled(),
+            );
+        }
+        Some(items) if items.is_empty() => {
+            menu =
+                menu.add_item(CustomMenuItem::new("agenda-empty", "Nothing due today").disabled());
+        }
+        Some(items) => {
+            for (index, item) in items.iter().take(MAX_AGENDA_ITEMS).enumerate() {
+                menu = menu.add_item(CustomMenuItem::new(
+                    format!("{OPEN_ROUTE_PREFIX}{index}:{}", item.route),
+                    &item.label,
+                ));
+            }
+
+            if items.len() > MAX_AGENDA_ITEMS {
+                menu = menu.add_item(CustomMenuItem::new(
+                    format!("{OPEN_ROUTE_PREFIX}{MAX_AGENDA_ITEMS}:/calendar"),
+                    format!("{} more…", items.len() - MAX_AGENDA_ITEMS),
+                ));
+            }
+        }
+    }
+
+    menu.add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(QUICK_ADD_TASK_ID, "Quick add task"))
+        .add_item(CustomMenuItem::new(SYNC_NOW_ID, "Sync now"))
+        .add_item(CustomMenuItem::new(OPEN_DOCUMENT_ID, "Open document…"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(SHOW_ID, "Show Pioneer Work Suite"))
+        .add_item(CustomMenuItem::new(QUIT_ID, "Quit"))
+}
+
+/// The tray as it is built at startup, before the store has been read.
+pub fn system_tray() -> SystemTray {
+    let snapshot = SyncSnapshot {
+        phase: SyncPhase::LocalOnly,
+        cloud_connected: false,
+        online: true,
+        pending_tasks: 0,
+        pending_documents: 0,
+        pending_events: 0,
+        pending_total: 0,
+        last_successful_sync_at: None,
+        error_message: None,
+    };
+
+    SystemTray::new()
+        .with_tooltip(&tooltip(&snapshot))
+        .with_menu(menu(&snapshot, &None))
+}
+
+fn read_agenda(app: &AppHandle) -> Agenda {
+    let store = app.state::<LocalStore>();
+
+    if !store.is_unlocked() {
+        return None;
+    }
+
+    let tasks = store.read_records(RecordStore::Tasks).ok()?;
+    let events = store.read_records(RecordStore::Events).ok()?;
+    Some(agenda::for_day(&tasks, &events, Local::now().date_naive()))
+}
+
+/// Rebuilds the menu when the agenda changed, and updates the sync status.
+fn refresh(app: &AppHandle) {
+    let snapshot = sync::current_snapshot(app);
+    let agenda = read_agenda(app);
+    let state = app.state::<Tray>();
+
+    {
+        let mut shown = state
+            .agenda
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if shown.as_ref() != Some(&agenda) {
+            let _ = app.tray_handle().set_menu(menu(&snapshot, &agenda));
+            *shown = Some(agenda);
+        }
+    }
+
+    show_sync_snapshot(app, &snapshot);
+}
+
+/// Shows a sync snapshot in the tray without rebuilding the menu.
+pub fn show_sync_snapshot(app: &AppHandle, snapshot: &SyncSnapshot) {
+    let tray = app.tray_handle();
+
+    if let Some(item) = tray.try_get_item(SYNC_STATUS_ID) {
+        let _ = item.set_title(sync_label(snapshot));
+    }
+    let _ = tray.set_tooltip(&tooltip(snapshot));
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn send(app: &AppHandle, action: TrayAction) {
+    show_main_window(app);
+    let _ = app.emit_all(TRAY_ACTION_EVENT, action);
+}
+
+pub fn handle_event(app: &AppHandle, event: SystemTrayEvent) {
+    let id = match event {
+        SystemTrayEvent::LeftClick { .. } => {
+            show_main_window(app);
+            return;
+        }
+        SystemTrayEvent::MenuItemClick { id, .. } => id,
+        _ => return,
+    };
+
+    match id.as_str() {
+        QUICK_ADD_TASK_ID => send(app, TrayAction::QuickAddTask),
+        OPEN_DOCUMENT_ID => send(app, TrayAction::OpenDocument),
+        SYNC_NOW_ID => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                sync::sync_now(&app).await;
+            });
+        }
+        SHOW_ID => show_main_window(app),
+        QUIT_ID => close_guard::request_quit(app),
+        id => {
+            let route = id
+                .strip_prefix(OPEN_ROUTE_PREFIX)
+                .and_then(|rest| rest.split_once(':'))
+                .map(|(_, route)| route);
+
+            if let Some(route) = route {
+                send(
+                    app,
+                    TrayAction::Open {
+                        route: route.to_string(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Hides the main window instead of closing it, so the app stays in the
+/// tray.
+pub fn handle_window_event(event: GlobalWindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event.event() {
+        if event.window().label() == "main" {
+            api.prevent_close();
+            let _ = event.window().hide();
+        }
+    }
+}
+
+/// Spawns the loop that keeps the agenda current.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh(&app);
+
+            let tray = app.state::<Tray>();
+
+            tokio::select! {
+                _ = tokio::time::sleep(REFRESH_INTERVAL) => {}
+                _ = tray.changed.notified() => {}
+            }
+        }
+    });
+}
@@ -0,0 +1,86 @@
+// desktop/src-tauri/src/vault/commands.rs
+
+/*
+ * Opening and sealing the vault derives a key with Argon2, so the commands
+ * that may do so run on a blocking thread.
+ */
+
+use tauri::{AppHandle, Manager};
+
+use super::{CloudSession, CloudUser, Vault, VaultStatus};
+use crate::blocking;
+use crate::error::Result;
+use crate::sync::SyncService;
+
+/// Saves the cloud session and hands the token to the sync worker.
+#[tauri::command]
+pub async fn vault_store_token(
+    app: AppHandle,
+    token: String,
+    user: Option<CloudUser>,
+) -> Result<()> {
+    blocking::run(app, move |app| {
+        app.state::<Vault>().store(&CloudSession {
+            token: token.clone(),
+            user,
+        })?;
+
+        app.state::<SyncService>().set_session(Some(token), false);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn vault_read_token(app: AppHandle) -> Result<Option<CloudSession>> {
+    blocking::run(app, |app| app.state::<Vault>().read()).await
+}
+
+#[tauri::command]
+pub async fn vault_clear(app: AppHandle) -> Result<()> {
+    blocking::run(app, |app| {
+        app.state::<Vault>().clear()?;
+        app.state::<SyncService>().set_session(None, false);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn vault_status(app: AppHandle) -> Result<VaultStatus> {
+    blocking::run(app, |app| app.state::<Vault>().status()).await
+}
+
+/// Unlocks a passphrase-protected vault for this run and hands its token to
+/// the sync worker.
+#[tauri::command]
+pub async fn vault_unlock(app: AppHandle, passphrase: String) -> Result<Option<CloudSession>> {
+    blocking::run(app, move |app| {
+        let vault = app.state::<Vault>();
+        vault.unlock(&passphrase)?;
+
+        let session = vault.read()?;
+        if let Some(session) = &session {
+            app.state::<SyncService>()
+                .set_session(Some(session.token.clone()), false);
+        }
+
+        Ok(session)
+    })
+    .await
+}
+
+/// Protects the vault with a passphrase, or removes the passphrase when
+/// `passphrase` is null or empty.
+#[tauri::command]
+pub async fn vault_set_passphrase(
+    app: AppHandle,
+    passphrase: Option<String>,
+) -> Result<VaultStatus> {
+    blocking::run(app, move |app| {
+        let vault = app.state::<Vault>();
+        vault.set_passphrase(passphrase.as_deref())?;
+        vault.status()
+    })
+    .await
+}
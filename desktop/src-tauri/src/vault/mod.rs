@@ -0,0 +1,374 @@
+// desktop/src-tauri/src/vault/mod.rs
+
+/*
+ * Encrypted file holding the cloud session (bearer token and the signed-in
+ * user) outside the webview profile.
+ *
+ * By default the key is derived from the operating system's machine id, so
+ * a vault file copied to another computer cannot be opened. A passphrase can
+ * be set instead, in which case the vault stays locked until it is unlocked
+ * with that passphrase for the current run.
+ */
+
+pub mod commands;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use zeroize::Zeroizing;
+
+use crate::crypto::{self, machine_secret, SecretKey, SALT_LEN};
+use crate::error::{Error, Result};
+use crate::files;
+use crate::paths;
+
+pub const VAULT_FILE_NAME: &str = "credentials.vault";
+
+const VAULT_VERSION: u32 = 1;
+const VAULT_CONTEXT: &[u8] = b"pioneer-work-suite/vault/v1";
+
+/// Signed-in user, matching `CloudUser` in apps/web/src/api/session.ts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudUser {
+    pub id: String,
+    pub email: String,
+    pub name: String,
+    pub role: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CloudSession {
+    pub token: String,
+    pub user: Option<CloudUser>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeySource {
+    Machine,
+    Passphrase,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    key_source: KeySource,
+    salt: String,
+    sealed: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub exists: bool,
+    pub key_source: Option<KeySource>,
+    pub locked: bool,
+}
+
+struct UnlockedKey {
+    source: KeySource,
+    salt: [u8; SALT_LEN],
+    key: SecretKey,
+}
+
+pub struct Vault {
+    path: PathBuf,
+    unlocked: Mutex<Option<UnlockedKey>>,
+}
+
+impl Vault {
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            unlocked: Mutex::new(None),
+        }
+    }
+
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir(app)?.join(VAULT_FILE_NAME),
+        ))
+    }
+
+    fn unlocked(&self) -> MutexGuard<'_, Option<UnlockedKey>> {
+        self.unlocked
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn read_file(&self) -> Result<Option<VaultFile>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let file: VaultFile = serde_json::from_slice(&fs::read(&self.path)?)?;
+
+        if file.version != VAULT_VERSION {
+            return Err(Error::InvalidInput(format!(
+                "Unsupported credential vault version {}.",
+                file.version
+            )));
+        }
+
+        Ok(Some(file))
+    }
+
+    fn decode_salt(file: &VaultFile) -> Result<[u8; SALT_LEN]> {
+        BASE64
+            .decode(&file.salt)
+            .ok()
+            .and_then(|salt| salt.try_into().ok())
+            .ok_or(Error::Crypto("The credential vault is damaged."))
+    }
+
+    /// Returns the key for the vault on disk, deriving the machine-bound key
+    /// on first use. Passphrase vaults must have been unlocked explicitly.
+    fn key_for(&self, file: &VaultFile) -> Result<SecretKey> {
+        let salt = Self::decode_salt(file)?;
+        let mut unlocked = self.unlocked();
+
+        if let Some(current) = unlocked.as_ref() {
+            if current.source == file.key_source && current.salt == salt {
+                return Ok(current.key.clone());
+            }
+        }
+
+        match file.key_source {
+            KeySource::Passphrase => Err(Error::VaultLocked),
+            KeySource::Machine => {
+                let key = crypto::derive_key(&machine_secret()?, &salt)?;
+
+                *unlocked = Some(UnlockedKey {
+                    source: KeySource::Machine,
+                    salt,
+                    key: key.clone(),
+                });
+
+                Ok(key)
+            }
+        }
+    }
+
+    fn write_sealed(&self, session: &CloudSession, unlocked: &UnlockedKey) -> Result<()> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(session)?);
+        let sealed = crypto::seal(&unlocked.key, &plaintext, VAULT_CONTEXT)?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            key_source: unlocked.source,
+            salt: BASE64.encode(unlocked.salt),
+            sealed: BASE64.encode(sealed),
+        };
+
+        files::write_atomic(&self.path, &serde_json::to_vec_pretty(&file)?)
+    }
+
+    pub fn status(&self) -> Result<VaultStatus> {
+        let Some(file) = self.read_file()? else {
+            return Ok(VaultStatus {
+                exists: false,
+                key_source: None,
+                locked: false,
+            });
+        };
+
+        let salt = Self::decode_salt(&file)?;
+        let locked = file.key_source == KeySource::Passphrase
+            && self
+                .unlocked()
+                .as_ref()
+                .is_none_or(|current| current.salt != salt);
+
+        Ok(VaultStatus {
+            exists: true,
+            key_source: Some(file.key_source),
+            locked,
+        })
+    }
+
+    pub fn read(&self) -> Result<Option<CloudSession>> {
+        let Some(file) = self.read_file()? else {
+            return Ok(None);
+        };
+
+        let key = self.key_for(&file)?;
+        let sealed = BASE64
+            .decode(&file.sealed)
+            .map_err(|_| Error::Crypto("The credential vault is damaged."))?;
+        let plaintext = Zeroizing::new(crypto::open(&key, &sealed, VAULT_CONTEXT)?);
+
+        Ok(Some(serde_json::from_slice(&plaintext)?))
+    }
+
+    /// Seals `session`, keeping the current key source. A new vault starts
+    /// out machine-bound.
+    pub fn store(&self, session: &CloudSession) -> Result<()> {
+        let file = self.read_file()?;
+
+        if let Some(file) = &file {
+            self.key_for(file)?;
+        } else {
+            let salt = crypto::random_bytes::<SALT_LEN>();
+            let key = crypto::derive_key(&machine_secret()?, &salt)?;
+
+            *self.unlocked() = Some(UnlockedKey {
+                source: KeySource::Machine,
+                salt,
+                key,
+            });
+        }
+
+        let unlocked = self.unlocked();
+        let current = unlocked.as_ref().ok_or(Error::VaultLocked)?;
+
+        self.write_sealed(session, current)
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<()> {
+        let Some(file) = self.read_file()? else {
+            return Ok(());
+        };
+
+        if file.key_source != KeySource::Passphrase {
+            return Ok(());
+        }
+
+        let salt = Self::decode_salt(&file)?;
+        let key = crypto::derive_key(passphrase.as_bytes(), &salt)?;
+        let sealed = BASE64
+            .decode(&file.sealed)
+            .map_err(|_| Error::Crypto("The credential vault is damaged."))?;
+
+        crypto::open(&key, &sealed, VAULT_CONTEXT)
+            .map_err(|_| Error::InvalidInput("That passphrase is not correct.".to_string()))?;
+
+        *self.unlocked() = Some(UnlockedKey {
+            source: KeySource::Passphrase,
+            salt,
+            key,
+        });
+
+        Ok(())
+    }
+
+    /// Re-seals the vault under a passphrase, or back under the machine key
+    /// when `passphrase` is `None`. The vault must be readable first.
+    pub fn set_passphrase(&self, passphrase: Option<&str>) -> Result<()> {
+        let Some(session) = self.read()? else {
+            return Err(Error::InvalidInput(
+                "There is no saved cloud session to protect.".to_string(),
+            ));
+        };
+
+        let salt = crypto::random_bytes::<SALT_LEN>();
+
+        let unlocked = match passphrase.filter(|passphrase| !passphrase.is_empty()) {
+            Some(passphrase) => UnlockedKey {
+                source: KeySource::Passphrase,
+                salt,
+                key: crypto::derive_key(passphrase.as_bytes(), &salt)?,
+            },
+            None => UnlockedKey {
+                source: KeySource::Machine,
+                salt,
+                key: crypto::derive_key(&machine_secret()?, &salt)?,
+            },
+        };
+
+        self.write_sealed(&session, &unlocked)?;
+        *self.unlocked() = Some(unlocked);
+
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        *self.unlocked() = None;
+
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn session(token: &str) -> CloudSession {
+        CloudSession {
+            token: token.to_string(),
+            user: Some(CloudUser {
+                id: "u1".to_string(),
+                email: "ada@example.com".to_string(),
+                name: "Ada".to_string(),
+                role: "member".to_string(),
+            }),
+        }
+    }
+
+    fn read_file(path: &Path) -> VaultFile {
+        serde_json::from_slice(&fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn sessions_round_trip_through_a_machine_vault() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(VAULT_FILE_NAME);
+
+        Vault::open(&path).store(&session("t1")).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("t1"));
+
+        // A fresh run derives the machine key again.
+        let vault = Vault::open(&path);
+        assert!(!vault.status().unwrap().locked);
+        assert_eq!(vault.read().unwrap().unwrap().token, "t1");
+        assert_eq!(
+            vault.read().unwrap().unwrap().user.unwrap().email,
+            "ada@example.com"
+        );
+    }
+
+    #[test]
+    fn passphrase_vaults_stay_locked_until_unlocked() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(VAULT_FILE_NAME);
+
+        let vault = Vault::open(&path);
+        vault.store(&session("t1")).unwrap();
+        let machine_salt = read_file(&path).salt;
+
+        vault.set_passphrase(Some("open sesame")).unwrap();
+        let file = read_file(&path);
+        assert_eq!(file.key_source, KeySource::Passphrase);
+        assert_ne!(file.salt, machine_salt);
+
+        let vault = Vault::open(&path);
+        assert!(vault.status().unwrap().locked);
+        assert!(matches!(vault.read(), Err(Error::VaultLocked)));
+        assert!(matches!(
+            vault.store(&session("t2")),
+            Err(Error::VaultLocked)
+        ));
+        assert!(vault.unlock("open says me").is_err());
+        assert!(vault.status().unwrap().locked);
+
+        vault.unlock("open sesame").unwrap();
+        vault.store(&session("t2")).unwrap();
+        assert_eq!(vault.read().unwrap().unwrap().token, "t2");
+
+        // Back to the machine key, readable without the passphrase.
+        vault.set_passphrase(None).unwrap();
+        assert_eq!(read_file(&path).key_source, KeySource::Machine);
+        assert_eq!(Vault::open(&path).read().unwrap().unwrap().token, "t2");
+    }
+}
@@ -0,0 +1,144 @@
+// desktop/src-tauri/src/window_state/geometry.rs
+
+/*
+ * Where a saved window goes on the screens that exist now.
+ *
+ * Everything is in physical pixels, as reported by the windowing system.
+ * A window returns to the monitor it was on when that monitor is still
+ * connected, otherwise to the one it overlaps most, otherwise to the
+ * primary monitor. It is then shrunk and moved as little as needed to fit
+ * that monitor.
+ */
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    fn right(&self) -> i64 {
+        i64::from(self.x) + i64::from(self.width)
+    }
+
+    fn bottom(&self) -> i64 {
+        i64::from(self.y) + i64::from(self.height)
+    }
+
+    fn overlap(&self, other: &Rect) -> i64 {
+        let width = self.right().min(other.right()) - i64::from(self.x.max(other.x));
+        let height = self.bottom().min(other.bottom()) - i64::from(self.y.max(other.y));
+        width.max(0) * height.max(0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Screen {
+    pub name: Option<String>,
+    pub area: Rect,
+}
+
+fn clamp(start: i32, length: u32, area_start: i32, area_length: u32) -> i32 {
+    let last = i64::from(area_start) + i64::from(area_length) - i64::from(length);
+    i64::from(start).clamp(i64::from(area_start), last.max(i64::from(area_start))) as i32
+}
+
+/// Fits `bounds`, last seen on the monitor called `monitor`, onto `screens`.
+/// The primary screen comes first. Returns `bounds` as they are when there
+/// are no screens to go by.
+pub fn place(bounds: Rect, monitor: Option<&str>, screens: &[Screen]) -> Rect {
+    let named = monitor.and_then(|monitor| {
+        screens
+            .iter()
+            .find(|screen| screen.name.as_deref() == Some(monitor))
+    });
+    let overlapping = || {
+        screens
+            .iter()
+            .filter(|screen| bounds.overlap(&screen.area) > 0)
+            .max_by_key(|screen| bounds.overlap(&screen.area))
+    };
+
+    let Some(screen) = named.or_else(overlapping).or(screens.first()) else {
+        return bounds;
+    };
+    let area = screen.area;
+
+    let width = bounds.width.min(area.width);
+    let height = bounds.height.min(area.height);
+
+    Rect {
+        x: clamp(bounds.x, width, area.x, area.width),
+        y: clamp(bounds.y, height, area.y, area.height),
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn screens() -> Vec<Screen> {
+        vec![
+            Screen {
+                name: Some("Built-in".to_string()),
+                area: rect(0, 0, 1920, 1080),
+            },
+            Screen {
+                name: Some("DELL U2720Q".to_string()),
+                area: rect(1920, -200, 2560, 1440),
+            },
+        ]
+    }
+
+    #[test]
+    fn keeps_windows_that_still_fit() {
+        let bounds = rect(2100, 0, 1200, 800);
+        assert_eq!(place(bounds, Some("DELL U2720Q"), &screens()), bounds);
+    }
+
+    #[test]
+    fn moves_windows_off_missing_monitors() {
+        let laptop = &screens()[..1];
+
+        assert_eq!(
+            place(rect(2100, 0, 1200, 800), Some("DELL U2720Q"), laptop),
+            rect(720, 0, 1200, 800)
+        );
+        assert_eq!(
+            place(rect(-3000, 5000, 2400, 1600), None, laptop),
+            rect(0, 0, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn follows_a_monitor_that_was_rearranged() {
+        let mut moved = screens();
+        moved[1].area = rect(-2560, 0, 2560, 1440);
+
+        assert_eq!(
+            place(rect(2100, 0, 1200, 800), Some("DELL U2720Q"), &moved),
+            rect(-1200, 0, 1200, 800)
+        );
+    }
+
+    #[test]
+    fn leaves_bounds_alone_without_screens() {
+        let bounds = rect(10, 10, 800, 600);
+        assert_eq!(place(bounds, None, &[]), bounds);
+    }
+}
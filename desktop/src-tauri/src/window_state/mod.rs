@@ -0,0 +1,220 @@
+// desktop/src-tauri/src/window_state/mod.rs
+
+/*
+ * Window size, position and state across launches.
+ *
+ * Windows opt in with `restore`, which puts them back where they were and
+ * from then on follows their moves and resizes. The last normal bounds are
+ * kept separately from the maximized and fullscreen flags, so a window
+ * that was maximized comes back maximized but un-maximizes to its old size.
+ * The state is written to the app config directory when a window closes
+ * and when the app exits.
+ *
+ * The main window starts hidden (see tauri.conf.json) and is shown once it
+ * has been placed, so it does not jump on screen.
+ */
+
+pub mod geometry;
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, GlobalWindowEvent, Manager, PhysicalPosition, PhysicalSize, Window, WindowEvent,
+};
+
+use crate::error::Result;
+use crate::files;
+use crate::paths;
+use geometry::{Rect, Screen};
+
+pub const WINDOW_STATE_FILE_NAME: &str = "window-state.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    /// Physical pixels, while neither maximized nor fullscreen.
+    pub bounds: Rect,
+    /// The name of the monitor the window was on.
+    #[serde(default)]
+    pub monitor: Option<String>,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+pub struct WindowStates {
+    path: PathBuf,
+    windows: Mutex<BTreeMap<String, WindowGeometry>>,
+    tracked: Mutex<HashSet<String>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn screens(window: &Window) -> Vec<Screen> {
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+
+    let mut screens: Vec<Screen> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|monitor| Screen {
+            name: monitor.name().cloned(),
+            area: Rect {
+                x: monitor.position().x,
+                y: monitor.position().y,
+                width: monitor.size().width,
+                height: monitor.size().height,
+            },
+        })
+        .collect();
+
+    screens.sort_by_key(|screen| screen.name != primary);
+    screens
+}
+
+impl WindowStates {
+    /// Saved state that cannot be read is dropped; windows then open at
+    /// their default size.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        let windows = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            windows: Mutex::new(windows),
+            tracked: Mutex::default(),
+        }
+    }
+
+    pub fn open_in_app_config(app: &AppHandle) -> Result<Self> {
+        Ok(Self::open(
+            paths::app_config_dir(app)?.join(WINDOW_STATE_FILE_NAME),
+        ))
+    }
+
+    /// Places `window` where it was last time and starts following it.
+    pub fn restore(&self, window: &Window) {
+        lock(&self.tracked).insert(window.label().to_string());
+
+        let Some(saved) = lock(&self.windows).get(window.label()).cloned() else {
+            return;
+        };
+
+        let bounds = geometry::place(saved.bounds, saved.monitor.as_deref(), &screens(window));
+        let _ = window.set_position(PhysicalPosition::new(bounds.x, bounds.y));
+        let _ = window.set_size(PhysicalSize::new(bounds.width, bounds.height));
+
+        if saved.maximized {
+            let _ = window.maximize();
+        }
+        if saved.fullscreen {
+            let _ = window.set_fullscreen(true);
+        }
+    }
+
+    fn record(&self, window: &Window) {
+        if !lock(&self.tracked).contains(window.label())
+            || window.is_minimized().unwrap_or(false)
+            || !window.is_visible().unwrap_or(false)
+        {
+            return;
+        }
+
+        let maximized = window.is_maximized().unwrap_or(false);
+        let fullscreen = window.is_fullscreen().unwrap_or(false);
+        let monitor = window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .and_then(|monitor| monitor.name().cloned());
+
+        let mut windows = lock(&self.windows);
+        let previous = windows.get(window.label()).map(|saved| saved.bounds);
+
+        let current = || {
+            let position = window.outer_position().ok()?;
+            let size = window.inner_size().ok()?;
+            Some(Rect {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            })
+        };
+        let bounds = if maximized || fullscreen {
+            previous.or_else(current)
+        } else {
+            current().or(previous)
+        };
+
+        if let Some(bounds) = bounds {
+            windows.insert(
+                window.label().to_string(),
+                WindowGeometry {
+                    bounds,
+                    monitor,
+                    maximized,
+                    fullscreen,
+                },
+            );
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let windows = lock(&self.windows);
+        files::write_atomic(&self.path, &serde_json::to_vec_pretty(&*windows)?)
+    }
+}
+
+pub fn handle_window_event(event: GlobalWindowEvent) {
+    let window = event.window();
+    // Windows from tauri.conf.json exist before `setup` manages the state.
+    let Some(states) = window.try_state::<WindowStates>() else {
+        return;
+    };
+
+    match event.event() {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => states.record(window),
+        WindowEvent::CloseRequested { .. } => {
+            states.record(window);
+            let _ = states.save();
+        }
+        _ => {}
+    }
+}
+
+/// Restores the main window and shows it.
+pub fn start(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        app.state::<WindowStates>().restore(&window);
+        let _ = window.show();
+    }
+}
+
+/// Saves every window's state, e.g. when the app exits.
+pub fn save(app: &AppHandle) {
+    let states = app.state::<WindowStates>();
+
+    for window in app.windows().values() {
+        states.record(window);
+    }
+
+    let _ = states.save();
+}